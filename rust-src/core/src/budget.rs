@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use uplc::builtins::DefaultFunction;
+
+use crate::protocol_params::ProtocolParameters;
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableBudget {
+    pub ex_units_spent: i64,
+    pub ex_units_available: i64,
+    pub memory_units_spent: i64,
+    pub memory_units_available: i64,
+    /// Fee for the units spent so far, at the protocol's execution unit prices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lovelace_spent: Option<i64>,
+    /// Fee for the units still available (negative once the budget is exceeded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lovelace_remaining: Option<i64>,
+    /// Share of the per-transaction limits (`max_tx_ex_steps` / `max_tx_ex_mem`) spent, in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ex_units_tx_limit_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_units_tx_limit_percent: Option<f64>,
+}
+
+/// Execution unit prices and per-transaction and per-block limits taken from the protocol parameters
+#[derive(Debug, Clone, Default)]
+pub struct BudgetPricing {
+    pub price_mem: Option<f64>,
+    pub price_step: Option<f64>,
+    pub max_tx_ex_mem: Option<i64>,
+    pub max_tx_ex_steps: Option<i64>,
+    pub max_block_ex_mem: Option<i64>,
+    pub max_block_ex_steps: Option<i64>,
+}
+
+impl BudgetPricing {
+    pub fn from_protocol_params(protocol_params: &ProtocolParameters) -> Self {
+        let parse = |value: &Option<String>| value.as_ref().and_then(|value| value.parse().ok());
+        BudgetPricing {
+            price_mem: protocol_params.price_mem,
+            price_step: protocol_params.price_step,
+            max_tx_ex_mem: parse(&protocol_params.max_tx_ex_mem),
+            max_tx_ex_steps: parse(&protocol_params.max_tx_ex_steps),
+            max_block_ex_mem: parse(&protocol_params.max_block_ex_mem),
+            max_block_ex_steps: parse(&protocol_params.max_block_ex_steps),
+        }
+    }
+
+    /// Lovelace cost of the given units, rounded up as the ledger does for script fees
+    pub fn lovelace(&self, cpu: i64, mem: i64) -> Option<i64> {
+        let (price_mem, price_step) = (self.price_mem?, self.price_step?);
+        Some((price_mem * mem as f64 + price_step * cpu as f64).ceil() as i64)
+    }
+}
+
+fn percent(spent: i64, limit: Option<i64>) -> Option<f64> {
+    limit.filter(|limit| *limit > 0).map(|limit| spent as f64 * 100.0 / limit as f64)
+}
+
+/// Spent units measured against one limit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct LimitCheck {
+    /// "declared" (the redeemer's ex units), "transaction" or "block"
+    pub limit: String,
+    /// None when the protocol parameters don't set the limit
+    pub cpu_limit: Option<i64>,
+    pub mem_limit: Option<i64>,
+    pub cpu_percent: Option<f64>,
+    pub mem_percent: Option<f64>,
+    pub exceeded: bool,
+}
+
+/// Spent units measured against the declared ex units and the protocol's
+/// per-transaction and per-block limits
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct BudgetProjection {
+    pub cpu_spent: i64,
+    pub mem_spent: i64,
+    pub checks: Vec<LimitCheck>,
+    /// Limits exceeded, in the order of `checks`
+    pub violated: Vec<String>,
+}
+
+fn limit_check(limit: &str, cpu_spent: i64, mem_spent: i64, cpu_limit: Option<i64>, mem_limit: Option<i64>) -> LimitCheck {
+    LimitCheck {
+        limit: limit.to_string(),
+        cpu_limit,
+        mem_limit,
+        cpu_percent: percent(cpu_spent, cpu_limit),
+        mem_percent: percent(mem_spent, mem_limit),
+        exceeded: cpu_limit.is_some_and(|limit| cpu_spent > limit) || mem_limit.is_some_and(|limit| mem_spent > limit),
+    }
+}
+
+impl BudgetPricing {
+    /// Measures spent units against the declared ex units and the transaction and
+    /// block limits. The protocol limits bound the sum over all redeemers of a
+    /// transaction, so a single redeemer within them can still add up past them.
+    pub fn project(&self, cpu_spent: i64, mem_spent: i64, declared_cpu: i64, declared_mem: i64) -> BudgetProjection {
+        let checks = vec![
+            limit_check("declared", cpu_spent, mem_spent, Some(declared_cpu), Some(declared_mem)),
+            limit_check("transaction", cpu_spent, mem_spent, self.max_tx_ex_steps, self.max_tx_ex_mem),
+            limit_check("block", cpu_spent, mem_spent, self.max_block_ex_steps, self.max_block_ex_mem),
+        ];
+        let violated = checks
+            .iter()
+            .filter(|check| check.exceeded)
+            .map(|check| check.limit.clone())
+            .collect();
+        BudgetProjection {
+            cpu_spent,
+            mem_spent,
+            checks,
+            violated,
+        }
+    }
+}
+
+/// `spent` raised by `margin_percent` percent, rounded up, for declaring ex units
+pub fn with_margin(spent: i64, margin_percent: u32) -> u64 {
+    let spent = spent.max(0) as u128;
+    let raised = (spent * (100 + margin_percent as u128)).div_ceil(100);
+    raised.min(u64::MAX as u128) as u64
+}
+
+impl SerializableBudget {
+    pub fn new(cpu_spent: i64, cpu_available: i64, mem_spent: i64, mem_available: i64, pricing: &BudgetPricing) -> Self {
+        SerializableBudget {
+            ex_units_spent: cpu_spent,
+            ex_units_available: cpu_available,
+            memory_units_spent: mem_spent,
+            memory_units_available: mem_available,
+            lovelace_spent: pricing.lovelace(cpu_spent, mem_spent),
+            lovelace_remaining: pricing.lovelace(cpu_available - cpu_spent, mem_available - mem_spent),
+            ex_units_tx_limit_percent: percent(cpu_spent, pricing.max_tx_ex_steps),
+            memory_units_tx_limit_percent: percent(mem_spent, pricing.max_tx_ex_mem),
+        }
+    }
+}
+/// Budget spent by the calls to one builtin function
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BuiltinBudget {
+    pub builtin: String,
+    pub calls: u64,
+    pub cpu: i64,
+    pub mem: i64,
+}
+
+/// Budget spent so far, split between builtin calls and machine steps
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BudgetBreakdown {
+    /// Builtins called so far, most CPU first
+    pub builtins: Vec<BuiltinBudget>,
+    pub builtin_cpu: i64,
+    pub builtin_mem: i64,
+    /// Spent on computing terms and returning values rather than in builtins
+    pub machine_cpu: i64,
+    pub machine_mem: i64,
+}
+
+#[derive(Debug, Clone)]
+struct BuiltinCall {
+    step: u64,
+    fun: DefaultFunction,
+    cpu: i64,
+    mem: i64,
+}
+
+/// Builtin calls made by a session with what each cost, charged at the step
+/// that applies the builtin to its last argument
+#[derive(Debug, Clone, Default)]
+pub struct BuiltinSpend {
+    calls: Vec<BuiltinCall>,
+}
+
+impl BuiltinSpend {
+    pub fn record(&mut self, step: u64, fun: DefaultFunction, cpu: i64, mem: i64) {
+        self.calls.push(BuiltinCall { step, fun, cpu, mem });
+    }
+
+    /// Drops the calls made after `step`, when the session steps back
+    pub fn truncate_after(&mut self, step: u64) {
+        while self.calls.last().is_some_and(|call| call.step > step) {
+            self.calls.pop();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.calls.clear();
+    }
+
+    /// Splits `spent_cpu` / `spent_mem`, everything the session spent, by builtin
+    pub fn breakdown(&self, spent_cpu: i64, spent_mem: i64) -> BudgetBreakdown {
+        let mut by_builtin: BTreeMap<String, BuiltinBudget> = BTreeMap::new();
+        for call in &self.calls {
+            let name = format!("{:?}", call.fun);
+            let builtin = by_builtin.entry(name.clone()).or_insert_with(|| BuiltinBudget {
+                builtin: name,
+                calls: 0,
+                cpu: 0,
+                mem: 0,
+            });
+            builtin.calls += 1;
+            builtin.cpu += call.cpu;
+            builtin.mem += call.mem;
+        }
+        let mut builtins: Vec<BuiltinBudget> = by_builtin.into_values().collect();
+        builtins.sort_by(|a, b| b.cpu.cmp(&a.cpu).then_with(|| a.builtin.cmp(&b.builtin)));
+        let builtin_cpu = builtins.iter().map(|builtin| builtin.cpu).sum();
+        let builtin_mem = builtins.iter().map(|builtin| builtin.mem).sum();
+        BudgetBreakdown {
+            builtins,
+            builtin_cpu,
+            builtin_mem,
+            machine_cpu: spent_cpu - builtin_cpu,
+            machine_mem: spent_mem - builtin_mem,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_projection() {
+        let pricing = BudgetPricing {
+            max_tx_ex_mem: Some(1_000),
+            max_tx_ex_steps: Some(100_000),
+            max_block_ex_mem: Some(5_000),
+            ..Default::default()
+        };
+        let projection = pricing.project(50_000, 2_000, 60_000, 2_500);
+        assert_eq!(projection.violated, vec!["transaction".to_string()]);
+        let block = &projection.checks[2];
+        assert_eq!((block.cpu_limit, block.mem_percent), (None, Some(40.0)));
+    }
+
+    #[test]
+    fn test_with_margin() {
+        assert_eq!(with_margin(1_000, 0), 1_000);
+        assert_eq!(with_margin(1_000, 5), 1_050);
+        assert_eq!(with_margin(333, 10), 367);
+        assert_eq!(with_margin(-1, 10), 0);
+    }
+
+    #[test]
+    fn test_builtin_spend_breakdown() {
+        let mut spend = BuiltinSpend::default();
+        spend.record(3, DefaultFunction::AddInteger, 100, 10);
+        spend.record(7, DefaultFunction::Sha2_256, 500, 4);
+        spend.record(9, DefaultFunction::AddInteger, 100, 10);
+
+        let breakdown = spend.breakdown(1_000, 50);
+        assert_eq!(breakdown.builtins[0].builtin, "Sha2_256");
+        assert_eq!((breakdown.builtins[1].calls, breakdown.builtins[1].cpu), (2, 200));
+        assert_eq!((breakdown.machine_cpu, breakdown.machine_mem), (300, 26));
+
+        spend.truncate_after(8);
+        let breakdown = spend.breakdown(700, 30);
+        assert_eq!(breakdown.builtins.len(), 2);
+        assert_eq!((breakdown.builtin_cpu, breakdown.machine_cpu), (600, 100));
+    }
+}
@@ -1,9 +1,13 @@
 use crate::{
-    SerializableValue, lazy_loading::{LazyLoadConfig, LazyLoadable, LazyLoadableEnv, LazyLoadableTermOrId, LazyLoadableValue, PathSegment, SupportsLazyLoading}, serializer::{EitherTermOrId, term_to_either_term_or_id}, value::SerializableEnv
+    SerializableValue, lazy_loading::{LazyLoadConfig, LazyLoadable, LazyLoadableEnv, LazyLoadableTermOrId, LazyLoadableValue, PathRoot, PathSegment, SupportsLazyLoading, embed_truncated_paths}, serializer::{EitherTermOrId, term_to_either_term_or_id}, value::SerializableEnv
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::debugger_engine::call_stack::describe_frame;
+use crate::debugger_engine::state_summary::frame_type;
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(tag = "context_type")]
@@ -94,6 +98,86 @@ impl SerializableMachineContext {
     }
 }
 
+/// One frame of the machine context stack. The full, paged and lazy context APIs
+/// all return frames in this shape, with the frame contents as `payload`.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SerializableFrame<P> {
+    /// Position counted from the outermost frame, unchanged while the frame stays on the stack
+    pub frame_id: usize,
+    /// Index of the frame, innermost first; also the index to use in lazy context paths
+    pub depth: usize,
+    /// Frame type, e.g. "FrameAwaitArg"
+    pub kind: String,
+    /// What the frame waits for, as in `get_call_stack`
+    pub summary: String,
+    /// Term the frame came from or continues with, when known
+    pub term_id: Option<i32>,
+    pub payload: P,
+}
+
+/// Wraps the frames of `contexts` (innermost first) at indices `range`, with
+/// `payload` serializing the contents of each frame
+pub fn serializable_frames<P>(
+    contexts: &[uplc::machine::Context],
+    range: Range<usize>,
+    case_branches: &HashMap<i32, (i32, usize)>,
+    payload: impl Fn(&uplc::machine::Context) -> P,
+) -> Vec<SerializableFrame<P>> {
+    let total_depth = contexts.len();
+    range
+        .filter_map(|depth| contexts.get(depth).map(|context| (depth, context)))
+        .map(|(depth, context)| {
+            let (term_id, _, summary) = describe_frame(context, case_branches);
+            SerializableFrame {
+                frame_id: total_depth - 1 - depth,
+                depth,
+                kind: frame_type(context).to_string(),
+                summary,
+                term_id,
+                payload: payload(context),
+            }
+        })
+        .collect()
+}
+
+/// Gives the truncated constants of serialized frames the paths
+/// `get_machine_context_lazy` fetches them from: the frame's depth, then the
+/// path within its payload
+pub fn embed_frame_paths(frames: &mut serde_json::Value) {
+    for frame in frames.as_array_mut().into_iter().flatten() {
+        let depth = frame.get("depth").and_then(|depth| depth.as_u64());
+        if let (Some(depth), Some(payload)) = (depth, frame.get_mut("payload")) {
+            embed_truncated_paths(payload, PathRoot::MachineContext, &[PathSegment::Index(depth as usize)]);
+        }
+    }
+}
+
+/// A window of the machine context stack, for stacks too deep to serialize whole
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SerializableMachineContextPage {
+    /// Number of frames in the whole stack
+    pub total_depth: usize,
+    /// Index of the first returned frame in the full stack, innermost first as in
+    /// `get_machine_context`; also the index to use in lazy context paths
+    pub start_index: usize,
+    /// Frames of the window, innermost first
+    pub frames: Vec<SerializableFrame<SerializableMachineContext>>,
+    /// Whether the stack has frames outside this window
+    pub truncated: bool,
+}
+
+/// Indices of the frames in a page of `limit` frames, skipping `offset` frames
+/// from the innermost end, or from the outermost end when `from_bottom` is set
+pub fn context_page_bounds(total_depth: usize, offset: usize, limit: usize, from_bottom: bool) -> Range<usize> {
+    let offset = offset.min(total_depth);
+    if from_bottom {
+        let end = total_depth - offset;
+        end.saturating_sub(limit)..end
+    } else {
+        offset..offset.saturating_add(limit).min(total_depth)
+    }
+}
+
 /// Convert a UPLC Context to JSON string
 pub fn context_to_json(context: &uplc::machine::Context) -> Result<String, serde_json::Error> {
     let serializable_context = SerializableMachineContext::from_uplc_context(context);
@@ -263,7 +347,7 @@ impl SerializableMachineContext {
                     };
                     LazyLoadable::Loaded(crate::value::from_uplc_value_lazy(v, term_ids, &value_config))
                 }, |v| match crate::value::get_value_type_only_new(v) {
-                    LazyLoadableValue::TypeOnly { type_name, kind, length } => LazyLoadable::TypeOnly { type_name, kind, length },
+                    LazyLoadableValue::TypeOnly { type_name, kind, length, hash } => LazyLoadable::TypeOnly { type_name, kind, length, hash },
                     _ => unreachable!()
                 })
                 .into_iter().map(|l| LazyLoadableValue::from(l)).collect();
@@ -357,18 +441,7 @@ pub fn navigate_context_to_any(
             crate::value::navigate_value_to_any(value, &path[1..], term_ids, return_full_object)
         }
         (Context::FrameAwaitFunTerm(env, ..), PathSegment::Field(field)) if field == "env" => {
-            // If path continues after 'env', navigate to values inside env
-            if path.len() > 1 {
-                crate::value::navigate_to_value_from_env(env, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v).unwrap())
-            } else {
-                // Just return the env itself
-            match crate::value::navigate_to_env_lazy(env, &path[1..], term_ids, return_full_object) {
-                NavigationResult::Found(env) => NavigationResult::Found(serde_json::to_value(env).unwrap()),
-                NavigationResult::InvalidPath(msg) => NavigationResult::InvalidPath(msg),
-                NavigationResult::Incomplete => NavigationResult::Incomplete,
-                }
-            }
+            crate::value::navigate_env_to_any(env, &path[1..], term_ids, return_full_object)
         }
         (Context::FrameAwaitFunValue(value, ..), PathSegment::Field(field)) if field == "value" => {
             crate::value::navigate_value_to_any(value, &path[1..], term_ids, return_full_object)
@@ -385,35 +458,42 @@ pub fn navigate_context_to_any(
             }
         }
         (Context::FrameConstr(env, ..), PathSegment::Field(field)) if field == "env" => {
-            // If path continues after 'env', navigate to values inside env
-            if path.len() > 1 {
-                crate::value::navigate_to_value_from_env(env, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v).unwrap())
-            } else {
-                // Just return the env itself
-            match crate::value::navigate_to_env_lazy(env, &path[1..], term_ids, return_full_object) {
-                NavigationResult::Found(env) => NavigationResult::Found(serde_json::to_value(env).unwrap()),
-                NavigationResult::InvalidPath(msg) => NavigationResult::InvalidPath(msg),
-                NavigationResult::Incomplete => NavigationResult::Incomplete,
-                }
-            }
+            crate::value::navigate_env_to_any(env, &path[1..], term_ids, return_full_object)
         }
         (Context::FrameCases(env, ..), PathSegment::Field(field)) if field == "env" => {
-            // If path continues after 'env', navigate to values inside env
-            if path.len() > 1 {
-                crate::value::navigate_to_value_from_env(env, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v).unwrap())
-            } else {
-                // Just return the env itself
-            match crate::value::navigate_to_env_lazy(env, &path[1..], term_ids, return_full_object) {
-                NavigationResult::Found(env) => NavigationResult::Found(serde_json::to_value(env).unwrap()),
-                NavigationResult::InvalidPath(msg) => NavigationResult::InvalidPath(msg),
-                NavigationResult::Incomplete => NavigationResult::Incomplete,
+            crate::value::navigate_env_to_any(env, &path[1..], term_ids, return_full_object)
+        }
+        (Context::FrameAwaitFunTerm(_, term, ..), PathSegment::Field(field)) if field == "term" => {
+            crate::serializer::navigate_to_term_lazy(term, &path[1..], term_ids, return_full_object)
+                .map(|v| serde_json::to_value(v).unwrap())
+        }
+        (Context::FrameConstr(_, _, terms, ..), PathSegment::Field(field))
+        | (Context::FrameCases(_, terms, ..), PathSegment::Field(field)) if field == "terms" => {
+            if let Some(PathSegment::Index(idx)) = path.get(1) {
+                if let Some(term) = terms.get(*idx) {
+                    crate::serializer::navigate_to_term_lazy(term, &path[2..], term_ids, return_full_object)
+                        .map(|v| serde_json::to_value(v).unwrap())
+                } else {
+                    NavigationResult::InvalidPath(format!("Index {} out of bounds in terms", idx))
                 }
+            } else {
+                NavigationResult::InvalidPath("Expected index after 'terms'".to_string())
             }
         }
         _ => NavigationResult::InvalidPath(format!("Invalid path for context: {:?}", path))
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn test_context_page_bounds() {
+        assert_eq!(context_page_bounds(50_000, 0, 20, false), 0..20);
+        assert_eq!(context_page_bounds(50_000, 0, 20, true), 49_980..50_000);
+        assert_eq!(context_page_bounds(50_000, 10, 20, true), 49_970..49_990);
+        assert_eq!(context_page_bounds(5, 3, 20, false), 3..5);
+        assert_eq!(context_page_bounds(5, 9, 20, true), 0..0);
+    }
+}
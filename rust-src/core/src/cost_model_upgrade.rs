@@ -0,0 +1,829 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use pallas_primitives::conway::Language;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+    machine::cost_model::{CostModel, StepKind},
+};
+
+use crate::debugger_engine::DebuggerError;
+use crate::protocol_params::CostModels;
+use crate::wasm_tools::{enveloped_json, wasm_bindgen, JsError};
+
+/// Parameter counts accepted by the machine for each language, oldest layout first
+const PLUTUS_V1_LENGTHS: [usize; 1] = [166];
+const PLUTUS_V2_LENGTHS: [usize; 2] = [175, 185];
+const PLUTUS_V3_LENGTHS: [usize; 2] = [251, 297];
+
+/// Builtins available to V1 and V2 scripts, a prefix of `DefaultFunction` order:
+/// V2 added serialiseData and the two secp256k1 signature checks, V3 everything after
+const PLUTUS_V1_BUILTINS: u8 = 51;
+const PLUTUS_V2_BUILTINS: u8 = 54;
+
+/// PlutusV2 parameter names in array order. The last ten were appended in Conway
+const PLUTUS_V2_PARAMS: [&str; 185] = [
+    "addInteger-cpu-arguments-intercept",
+    "addInteger-cpu-arguments-slope",
+    "addInteger-memory-arguments-intercept",
+    "addInteger-memory-arguments-slope",
+    "appendByteString-cpu-arguments-intercept",
+    "appendByteString-cpu-arguments-slope",
+    "appendByteString-memory-arguments-intercept",
+    "appendByteString-memory-arguments-slope",
+    "appendString-cpu-arguments-intercept",
+    "appendString-cpu-arguments-slope",
+    "appendString-memory-arguments-intercept",
+    "appendString-memory-arguments-slope",
+    "bData-cpu-arguments",
+    "bData-memory-arguments",
+    "blake2b_256-cpu-arguments-intercept",
+    "blake2b_256-cpu-arguments-slope",
+    "blake2b_256-memory-arguments",
+    "cekApplyCost-exBudgetCPU",
+    "cekApplyCost-exBudgetMemory",
+    "cekBuiltinCost-exBudgetCPU",
+    "cekBuiltinCost-exBudgetMemory",
+    "cekConstCost-exBudgetCPU",
+    "cekConstCost-exBudgetMemory",
+    "cekDelayCost-exBudgetCPU",
+    "cekDelayCost-exBudgetMemory",
+    "cekForceCost-exBudgetCPU",
+    "cekForceCost-exBudgetMemory",
+    "cekLamCost-exBudgetCPU",
+    "cekLamCost-exBudgetMemory",
+    "cekStartupCost-exBudgetCPU",
+    "cekStartupCost-exBudgetMemory",
+    "cekVarCost-exBudgetCPU",
+    "cekVarCost-exBudgetMemory",
+    "chooseData-cpu-arguments",
+    "chooseData-memory-arguments",
+    "chooseList-cpu-arguments",
+    "chooseList-memory-arguments",
+    "chooseUnit-cpu-arguments",
+    "chooseUnit-memory-arguments",
+    "consByteString-cpu-arguments-intercept",
+    "consByteString-cpu-arguments-slope",
+    "consByteString-memory-arguments-intercept",
+    "consByteString-memory-arguments-slope",
+    "constrData-cpu-arguments",
+    "constrData-memory-arguments",
+    "decodeUtf8-cpu-arguments-intercept",
+    "decodeUtf8-cpu-arguments-slope",
+    "decodeUtf8-memory-arguments-intercept",
+    "decodeUtf8-memory-arguments-slope",
+    "divideInteger-cpu-arguments-constant",
+    "divideInteger-cpu-arguments-model-arguments-intercept",
+    "divideInteger-cpu-arguments-model-arguments-slope",
+    "divideInteger-memory-arguments-intercept",
+    "divideInteger-memory-arguments-minimum",
+    "divideInteger-memory-arguments-slope",
+    "encodeUtf8-cpu-arguments-intercept",
+    "encodeUtf8-cpu-arguments-slope",
+    "encodeUtf8-memory-arguments-intercept",
+    "encodeUtf8-memory-arguments-slope",
+    "equalsByteString-cpu-arguments-constant",
+    "equalsByteString-cpu-arguments-intercept",
+    "equalsByteString-cpu-arguments-slope",
+    "equalsByteString-memory-arguments",
+    "equalsData-cpu-arguments-intercept",
+    "equalsData-cpu-arguments-slope",
+    "equalsData-memory-arguments",
+    "equalsInteger-cpu-arguments-intercept",
+    "equalsInteger-cpu-arguments-slope",
+    "equalsInteger-memory-arguments",
+    "equalsString-cpu-arguments-constant",
+    "equalsString-cpu-arguments-intercept",
+    "equalsString-cpu-arguments-slope",
+    "equalsString-memory-arguments",
+    "fstPair-cpu-arguments",
+    "fstPair-memory-arguments",
+    "headList-cpu-arguments",
+    "headList-memory-arguments",
+    "iData-cpu-arguments",
+    "iData-memory-arguments",
+    "ifThenElse-cpu-arguments",
+    "ifThenElse-memory-arguments",
+    "indexByteString-cpu-arguments",
+    "indexByteString-memory-arguments",
+    "lengthOfByteString-cpu-arguments",
+    "lengthOfByteString-memory-arguments",
+    "lessThanByteString-cpu-arguments-intercept",
+    "lessThanByteString-cpu-arguments-slope",
+    "lessThanByteString-memory-arguments",
+    "lessThanEqualsByteString-cpu-arguments-intercept",
+    "lessThanEqualsByteString-cpu-arguments-slope",
+    "lessThanEqualsByteString-memory-arguments",
+    "lessThanEqualsInteger-cpu-arguments-intercept",
+    "lessThanEqualsInteger-cpu-arguments-slope",
+    "lessThanEqualsInteger-memory-arguments",
+    "lessThanInteger-cpu-arguments-intercept",
+    "lessThanInteger-cpu-arguments-slope",
+    "lessThanInteger-memory-arguments",
+    "listData-cpu-arguments",
+    "listData-memory-arguments",
+    "mapData-cpu-arguments",
+    "mapData-memory-arguments",
+    "mkCons-cpu-arguments",
+    "mkCons-memory-arguments",
+    "mkNilData-cpu-arguments",
+    "mkNilData-memory-arguments",
+    "mkNilPairData-cpu-arguments",
+    "mkNilPairData-memory-arguments",
+    "mkPairData-cpu-arguments",
+    "mkPairData-memory-arguments",
+    "modInteger-cpu-arguments-constant",
+    "modInteger-cpu-arguments-model-arguments-intercept",
+    "modInteger-cpu-arguments-model-arguments-slope",
+    "modInteger-memory-arguments-intercept",
+    "modInteger-memory-arguments-minimum",
+    "modInteger-memory-arguments-slope",
+    "multiplyInteger-cpu-arguments-intercept",
+    "multiplyInteger-cpu-arguments-slope",
+    "multiplyInteger-memory-arguments-intercept",
+    "multiplyInteger-memory-arguments-slope",
+    "nullList-cpu-arguments",
+    "nullList-memory-arguments",
+    "quotientInteger-cpu-arguments-constant",
+    "quotientInteger-cpu-arguments-model-arguments-intercept",
+    "quotientInteger-cpu-arguments-model-arguments-slope",
+    "quotientInteger-memory-arguments-intercept",
+    "quotientInteger-memory-arguments-minimum",
+    "quotientInteger-memory-arguments-slope",
+    "remainderInteger-cpu-arguments-constant",
+    "remainderInteger-cpu-arguments-model-arguments-intercept",
+    "remainderInteger-cpu-arguments-model-arguments-slope",
+    "remainderInteger-memory-arguments-intercept",
+    "remainderInteger-memory-arguments-minimum",
+    "remainderInteger-memory-arguments-slope",
+    "serialiseData-cpu-arguments-intercept",
+    "serialiseData-cpu-arguments-slope",
+    "serialiseData-memory-arguments-intercept",
+    "serialiseData-memory-arguments-slope",
+    "sha2_256-cpu-arguments-intercept",
+    "sha2_256-cpu-arguments-slope",
+    "sha2_256-memory-arguments",
+    "sha3_256-cpu-arguments-intercept",
+    "sha3_256-cpu-arguments-slope",
+    "sha3_256-memory-arguments",
+    "sliceByteString-cpu-arguments-intercept",
+    "sliceByteString-cpu-arguments-slope",
+    "sliceByteString-memory-arguments-intercept",
+    "sliceByteString-memory-arguments-slope",
+    "sndPair-cpu-arguments",
+    "sndPair-memory-arguments",
+    "subtractInteger-cpu-arguments-intercept",
+    "subtractInteger-cpu-arguments-slope",
+    "subtractInteger-memory-arguments-intercept",
+    "subtractInteger-memory-arguments-slope",
+    "tailList-cpu-arguments",
+    "tailList-memory-arguments",
+    "trace-cpu-arguments",
+    "trace-memory-arguments",
+    "unBData-cpu-arguments",
+    "unBData-memory-arguments",
+    "unConstrData-cpu-arguments",
+    "unConstrData-memory-arguments",
+    "unIData-cpu-arguments",
+    "unIData-memory-arguments",
+    "unListData-cpu-arguments",
+    "unListData-memory-arguments",
+    "unMapData-cpu-arguments",
+    "unMapData-memory-arguments",
+    "verifyEcdsaSecp256k1Signature-cpu-arguments",
+    "verifyEcdsaSecp256k1Signature-memory-arguments",
+    "verifyEd25519Signature-cpu-arguments-intercept",
+    "verifyEd25519Signature-cpu-arguments-slope",
+    "verifyEd25519Signature-memory-arguments",
+    "verifySchnorrSecp256k1Signature-cpu-arguments-intercept",
+    "verifySchnorrSecp256k1Signature-cpu-arguments-slope",
+    "verifySchnorrSecp256k1Signature-memory-arguments",
+    "integerToByteString-cpu-arguments-c0",
+    "integerToByteString-cpu-arguments-c1",
+    "integerToByteString-cpu-arguments-c2",
+    "integerToByteString-memory-arguments-intercept",
+    "integerToByteString-memory-arguments-slope",
+    "byteStringToInteger-cpu-arguments-c0",
+    "byteStringToInteger-cpu-arguments-c1",
+    "byteStringToInteger-cpu-arguments-c2",
+    "byteStringToInteger-memory-arguments-intercept",
+    "byteStringToInteger-memory-arguments-slope",
+];
+
+/// PlutusV3 parameter names in array order (Chang layout). Later layouts only
+/// append parameters, which are taken positionally from the defaults
+const PLUTUS_V3_PARAMS: [&str; 251] = [
+    "addInteger-cpu-arguments-intercept",
+    "addInteger-cpu-arguments-slope",
+    "addInteger-memory-arguments-intercept",
+    "addInteger-memory-arguments-slope",
+    "appendByteString-cpu-arguments-intercept",
+    "appendByteString-cpu-arguments-slope",
+    "appendByteString-memory-arguments-intercept",
+    "appendByteString-memory-arguments-slope",
+    "appendString-cpu-arguments-intercept",
+    "appendString-cpu-arguments-slope",
+    "appendString-memory-arguments-intercept",
+    "appendString-memory-arguments-slope",
+    "bData-cpu-arguments",
+    "bData-memory-arguments",
+    "blake2b_256-cpu-arguments-intercept",
+    "blake2b_256-cpu-arguments-slope",
+    "blake2b_256-memory-arguments",
+    "cekApplyCost-exBudgetCPU",
+    "cekApplyCost-exBudgetMemory",
+    "cekBuiltinCost-exBudgetCPU",
+    "cekBuiltinCost-exBudgetMemory",
+    "cekConstCost-exBudgetCPU",
+    "cekConstCost-exBudgetMemory",
+    "cekDelayCost-exBudgetCPU",
+    "cekDelayCost-exBudgetMemory",
+    "cekForceCost-exBudgetCPU",
+    "cekForceCost-exBudgetMemory",
+    "cekLamCost-exBudgetCPU",
+    "cekLamCost-exBudgetMemory",
+    "cekStartupCost-exBudgetCPU",
+    "cekStartupCost-exBudgetMemory",
+    "cekVarCost-exBudgetCPU",
+    "cekVarCost-exBudgetMemory",
+    "chooseData-cpu-arguments",
+    "chooseData-memory-arguments",
+    "chooseList-cpu-arguments",
+    "chooseList-memory-arguments",
+    "chooseUnit-cpu-arguments",
+    "chooseUnit-memory-arguments",
+    "consByteString-cpu-arguments-intercept",
+    "consByteString-cpu-arguments-slope",
+    "consByteString-memory-arguments-intercept",
+    "consByteString-memory-arguments-slope",
+    "constrData-cpu-arguments",
+    "constrData-memory-arguments",
+    "decodeUtf8-cpu-arguments-intercept",
+    "decodeUtf8-cpu-arguments-slope",
+    "decodeUtf8-memory-arguments-intercept",
+    "decodeUtf8-memory-arguments-slope",
+    "divideInteger-cpu-arguments-constant",
+    "divideInteger-cpu-arguments-model-arguments-c00",
+    "divideInteger-cpu-arguments-model-arguments-c01",
+    "divideInteger-cpu-arguments-model-arguments-c02",
+    "divideInteger-cpu-arguments-model-arguments-c10",
+    "divideInteger-cpu-arguments-model-arguments-c11",
+    "divideInteger-cpu-arguments-model-arguments-c20",
+    "divideInteger-cpu-arguments-model-arguments-minimum",
+    "divideInteger-memory-arguments-intercept",
+    "divideInteger-memory-arguments-minimum",
+    "divideInteger-memory-arguments-slope",
+    "encodeUtf8-cpu-arguments-intercept",
+    "encodeUtf8-cpu-arguments-slope",
+    "encodeUtf8-memory-arguments-intercept",
+    "encodeUtf8-memory-arguments-slope",
+    "equalsByteString-cpu-arguments-constant",
+    "equalsByteString-cpu-arguments-intercept",
+    "equalsByteString-cpu-arguments-slope",
+    "equalsByteString-memory-arguments",
+    "equalsData-cpu-arguments-intercept",
+    "equalsData-cpu-arguments-slope",
+    "equalsData-memory-arguments",
+    "equalsInteger-cpu-arguments-intercept",
+    "equalsInteger-cpu-arguments-slope",
+    "equalsInteger-memory-arguments",
+    "equalsString-cpu-arguments-constant",
+    "equalsString-cpu-arguments-intercept",
+    "equalsString-cpu-arguments-slope",
+    "equalsString-memory-arguments",
+    "fstPair-cpu-arguments",
+    "fstPair-memory-arguments",
+    "headList-cpu-arguments",
+    "headList-memory-arguments",
+    "iData-cpu-arguments",
+    "iData-memory-arguments",
+    "ifThenElse-cpu-arguments",
+    "ifThenElse-memory-arguments",
+    "indexByteString-cpu-arguments",
+    "indexByteString-memory-arguments",
+    "lengthOfByteString-cpu-arguments",
+    "lengthOfByteString-memory-arguments",
+    "lessThanByteString-cpu-arguments-intercept",
+    "lessThanByteString-cpu-arguments-slope",
+    "lessThanByteString-memory-arguments",
+    "lessThanEqualsByteString-cpu-arguments-intercept",
+    "lessThanEqualsByteString-cpu-arguments-slope",
+    "lessThanEqualsByteString-memory-arguments",
+    "lessThanEqualsInteger-cpu-arguments-intercept",
+    "lessThanEqualsInteger-cpu-arguments-slope",
+    "lessThanEqualsInteger-memory-arguments",
+    "lessThanInteger-cpu-arguments-intercept",
+    "lessThanInteger-cpu-arguments-slope",
+    "lessThanInteger-memory-arguments",
+    "listData-cpu-arguments",
+    "listData-memory-arguments",
+    "mapData-cpu-arguments",
+    "mapData-memory-arguments",
+    "mkCons-cpu-arguments",
+    "mkCons-memory-arguments",
+    "mkNilData-cpu-arguments",
+    "mkNilData-memory-arguments",
+    "mkNilPairData-cpu-arguments",
+    "mkNilPairData-memory-arguments",
+    "mkPairData-cpu-arguments",
+    "mkPairData-memory-arguments",
+    "modInteger-cpu-arguments-constant",
+    "modInteger-cpu-arguments-model-arguments-c00",
+    "modInteger-cpu-arguments-model-arguments-c01",
+    "modInteger-cpu-arguments-model-arguments-c02",
+    "modInteger-cpu-arguments-model-arguments-c10",
+    "modInteger-cpu-arguments-model-arguments-c11",
+    "modInteger-cpu-arguments-model-arguments-c20",
+    "modInteger-cpu-arguments-model-arguments-minimum",
+    "modInteger-memory-arguments-intercept",
+    "modInteger-memory-arguments-slope",
+    "multiplyInteger-cpu-arguments-intercept",
+    "multiplyInteger-cpu-arguments-slope",
+    "multiplyInteger-memory-arguments-intercept",
+    "multiplyInteger-memory-arguments-slope",
+    "nullList-cpu-arguments",
+    "nullList-memory-arguments",
+    "quotientInteger-cpu-arguments-constant",
+    "quotientInteger-cpu-arguments-model-arguments-c00",
+    "quotientInteger-cpu-arguments-model-arguments-c01",
+    "quotientInteger-cpu-arguments-model-arguments-c02",
+    "quotientInteger-cpu-arguments-model-arguments-c10",
+    "quotientInteger-cpu-arguments-model-arguments-c11",
+    "quotientInteger-cpu-arguments-model-arguments-c20",
+    "quotientInteger-cpu-arguments-model-arguments-minimum",
+    "quotientInteger-memory-arguments-intercept",
+    "quotientInteger-memory-arguments-minimum",
+    "quotientInteger-memory-arguments-slope",
+    "remainderInteger-cpu-arguments-constant",
+    "remainderInteger-cpu-arguments-model-arguments-c00",
+    "remainderInteger-cpu-arguments-model-arguments-c01",
+    "remainderInteger-cpu-arguments-model-arguments-c02",
+    "remainderInteger-cpu-arguments-model-arguments-c10",
+    "remainderInteger-cpu-arguments-model-arguments-c11",
+    "remainderInteger-cpu-arguments-model-arguments-c20",
+    "remainderInteger-cpu-arguments-model-arguments-minimum",
+    "remainderInteger-memory-arguments-intercept",
+    "remainderInteger-memory-arguments-slope",
+    "serialiseData-cpu-arguments-intercept",
+    "serialiseData-cpu-arguments-slope",
+    "serialiseData-memory-arguments-intercept",
+    "serialiseData-memory-arguments-slope",
+    "sha2_256-cpu-arguments-intercept",
+    "sha2_256-cpu-arguments-slope",
+    "sha2_256-memory-arguments",
+    "sha3_256-cpu-arguments-intercept",
+    "sha3_256-cpu-arguments-slope",
+    "sha3_256-memory-arguments",
+    "sliceByteString-cpu-arguments-intercept",
+    "sliceByteString-cpu-arguments-slope",
+    "sliceByteString-memory-arguments-intercept",
+    "sliceByteString-memory-arguments-slope",
+    "sndPair-cpu-arguments",
+    "sndPair-memory-arguments",
+    "subtractInteger-cpu-arguments-intercept",
+    "subtractInteger-cpu-arguments-slope",
+    "subtractInteger-memory-arguments-intercept",
+    "subtractInteger-memory-arguments-slope",
+    "tailList-cpu-arguments",
+    "tailList-memory-arguments",
+    "trace-cpu-arguments",
+    "trace-memory-arguments",
+    "unBData-cpu-arguments",
+    "unBData-memory-arguments",
+    "unConstrData-cpu-arguments",
+    "unConstrData-memory-arguments",
+    "unIData-cpu-arguments",
+    "unIData-memory-arguments",
+    "unListData-cpu-arguments",
+    "unListData-memory-arguments",
+    "unMapData-cpu-arguments",
+    "unMapData-memory-arguments",
+    "verifyEcdsaSecp256k1Signature-cpu-arguments",
+    "verifyEcdsaSecp256k1Signature-memory-arguments",
+    "verifyEd25519Signature-cpu-arguments-intercept",
+    "verifyEd25519Signature-cpu-arguments-slope",
+    "verifyEd25519Signature-memory-arguments",
+    "verifySchnorrSecp256k1Signature-cpu-arguments-intercept",
+    "verifySchnorrSecp256k1Signature-cpu-arguments-slope",
+    "verifySchnorrSecp256k1Signature-memory-arguments",
+    "cekConstrCost-exBudgetCPU",
+    "cekConstrCost-exBudgetMemory",
+    "cekCaseCost-exBudgetCPU",
+    "cekCaseCost-exBudgetMemory",
+    "bls12_381_G1_add-cpu-arguments",
+    "bls12_381_G1_add-memory-arguments",
+    "bls12_381_G1_compress-cpu-arguments",
+    "bls12_381_G1_compress-memory-arguments",
+    "bls12_381_G1_equal-cpu-arguments",
+    "bls12_381_G1_equal-memory-arguments",
+    "bls12_381_G1_hashToGroup-cpu-arguments-intercept",
+    "bls12_381_G1_hashToGroup-cpu-arguments-slope",
+    "bls12_381_G1_hashToGroup-memory-arguments",
+    "bls12_381_G1_neg-cpu-arguments",
+    "bls12_381_G1_neg-memory-arguments",
+    "bls12_381_G1_scalarMul-cpu-arguments-intercept",
+    "bls12_381_G1_scalarMul-cpu-arguments-slope",
+    "bls12_381_G1_scalarMul-memory-arguments",
+    "bls12_381_G1_uncompress-cpu-arguments",
+    "bls12_381_G1_uncompress-memory-arguments",
+    "bls12_381_G2_add-cpu-arguments",
+    "bls12_381_G2_add-memory-arguments",
+    "bls12_381_G2_compress-cpu-arguments",
+    "bls12_381_G2_compress-memory-arguments",
+    "bls12_381_G2_equal-cpu-arguments",
+    "bls12_381_G2_equal-memory-arguments",
+    "bls12_381_G2_hashToGroup-cpu-arguments-intercept",
+    "bls12_381_G2_hashToGroup-cpu-arguments-slope",
+    "bls12_381_G2_hashToGroup-memory-arguments",
+    "bls12_381_G2_neg-cpu-arguments",
+    "bls12_381_G2_neg-memory-arguments",
+    "bls12_381_G2_scalarMul-cpu-arguments-intercept",
+    "bls12_381_G2_scalarMul-cpu-arguments-slope",
+    "bls12_381_G2_scalarMul-memory-arguments",
+    "bls12_381_G2_uncompress-cpu-arguments",
+    "bls12_381_G2_uncompress-memory-arguments",
+    "bls12_381_finalVerify-cpu-arguments",
+    "bls12_381_finalVerify-memory-arguments",
+    "bls12_381_millerLoop-cpu-arguments",
+    "bls12_381_millerLoop-memory-arguments",
+    "bls12_381_mulMlResult-cpu-arguments",
+    "bls12_381_mulMlResult-memory-arguments",
+    "keccak_256-cpu-arguments-intercept",
+    "keccak_256-cpu-arguments-slope",
+    "keccak_256-memory-arguments",
+    "blake2b_224-cpu-arguments-intercept",
+    "blake2b_224-cpu-arguments-slope",
+    "blake2b_224-memory-arguments",
+    "integerToByteString-cpu-arguments-c0",
+    "integerToByteString-cpu-arguments-c1",
+    "integerToByteString-cpu-arguments-c2",
+    "integerToByteString-memory-arguments-intercept",
+    "integerToByteString-memory-arguments-slope",
+    "byteStringToInteger-cpu-arguments-c0",
+    "byteStringToInteger-cpu-arguments-c1",
+    "byteStringToInteger-cpu-arguments-c2",
+    "byteStringToInteger-memory-arguments-intercept",
+    "byteStringToInteger-memory-arguments-slope",
+];
+
+/// Values for V3 parameters that have no V2 counterpart: either a complete V3
+/// cost model array (e.g. the current mainnet one) or values by parameter name
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum CostModelDefaults {
+    Array(Vec<i64>),
+    Named(BTreeMap<String, i64>),
+}
+
+impl CostModelDefaults {
+    fn get(&self, index: usize, name: &str) -> Option<i64> {
+        match self {
+            CostModelDefaults::Array(values) => values.get(index).copied(),
+            CostModelDefaults::Named(values) => values.get(name).copied(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CostModelUpgrade {
+    pub cost_model: Vec<i64>,
+    /// Number of parameters copied over from the V2 model
+    pub copied: usize,
+    /// Parameters without a V2 counterpart, filled from the defaults
+    pub defaulted: Vec<String>,
+}
+
+/// Builtins whose parameters PlutusV2 added to the PlutusV1 layout
+const PLUTUS_V2_ADDED_BUILTINS: [&str; 3] = [
+    "serialiseData",
+    "verifyEcdsaSecp256k1Signature",
+    "verifySchnorrSecp256k1Signature",
+];
+
+/// Parameter names of a cost model array in order. Parameters appended after the
+/// named layout (e.g. the last 46 of a 297 parameter V3 model) have no name.
+pub fn cost_model_parameter_names(language: &Language) -> Vec<&'static str> {
+    match language {
+        // The V1 layout is the pre-Conway V2 one without the builtins V2 added
+        Language::PlutusV1 => PLUTUS_V2_PARAMS[..PLUTUS_V2_LENGTHS[0]]
+            .iter()
+            .copied()
+            .filter(|name| !PLUTUS_V2_ADDED_BUILTINS.iter().any(|builtin| name.starts_with(&format!("{}-", builtin))))
+            .collect(),
+        Language::PlutusV2 => PLUTUS_V2_PARAMS.to_vec(),
+        Language::PlutusV3 => PLUTUS_V3_PARAMS.to_vec(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct CostModelParameter {
+    pub index: usize,
+    pub name: Option<String>,
+    pub value: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+pub struct StepCost {
+    pub cpu: i64,
+    pub mem: i64,
+}
+
+/// Machine step and builtin costs resolved from a cost model
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ResolvedCosts {
+    /// Cost of each step kind: "startup", "var", "constant", "lambda", "delay",
+    /// "force", "apply", "builtin", "constr" and "case"
+    pub machine: BTreeMap<String, StepCost>,
+    /// Costing function arguments of each builtin by resource ("cpu" or "memory"),
+    /// e.g. `builtins.addInteger.cpu.slope`; a constant cost is under "constant"
+    pub builtins: BTreeMap<String, BTreeMap<String, BTreeMap<String, i64>>>,
+}
+
+/// The cost model a session runs with: the protocol parameter array it was built
+/// from, named, and the machine and builtin costs the machine resolved from it
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CostModelReport {
+    pub language: String,
+    pub parameter_count: usize,
+    pub parameters: Vec<CostModelParameter>,
+    pub resolved: ResolvedCosts,
+}
+
+fn resolved_costs(parameters: &[CostModelParameter], resolved: &CostModel) -> ResolvedCosts {
+    let machine = [
+        ("startup", StepKind::StartUp),
+        ("var", StepKind::Var),
+        ("constant", StepKind::Constant),
+        ("lambda", StepKind::Lambda),
+        ("delay", StepKind::Delay),
+        ("force", StepKind::Force),
+        ("apply", StepKind::Apply),
+        ("builtin", StepKind::Builtin),
+        ("constr", StepKind::Constr),
+        ("case", StepKind::Case),
+    ]
+    .into_iter()
+    .map(|(name, kind)| {
+        let cost = resolved.machine_costs.get(kind);
+        (name.to_string(), StepCost { cpu: cost.cpu, mem: cost.mem })
+    })
+    .collect();
+
+    // The machine reads builtin costs straight from the named parameters,
+    // `<builtin>-<cpu|memory>-arguments[-<argument>]`
+    let mut builtins: BTreeMap<String, BTreeMap<String, BTreeMap<String, i64>>> = BTreeMap::new();
+    for parameter in parameters {
+        let Some(name) = parameter.name.as_deref() else { continue };
+        let Some((builtin, resource, argument)) = ["cpu", "memory"].into_iter().find_map(|resource| {
+            let (builtin, rest) = name.split_once(&format!("-{}-arguments", resource))?;
+            let argument = match rest.strip_prefix('-') {
+                Some(argument) => argument,
+                None if rest.is_empty() => "constant",
+                None => return None,
+            };
+            Some((builtin, resource, argument))
+        }) else {
+            continue;
+        };
+        builtins
+            .entry(builtin.to_string())
+            .or_default()
+            .entry(resource.to_string())
+            .or_default()
+            .insert(argument.to_string(), parameter.value);
+    }
+    ResolvedCosts { machine, builtins }
+}
+
+pub fn cost_model_report(language: &Language, values: &[i64], resolved: &CostModel) -> CostModelReport {
+    let names = cost_model_parameter_names(language);
+    let parameters: Vec<CostModelParameter> = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| CostModelParameter {
+            index,
+            name: names.get(index).map(|name| name.to_string()),
+            value: *value,
+        })
+        .collect();
+    CostModelReport {
+        language: format!("{:?}", language),
+        parameter_count: values.len(),
+        resolved: resolved_costs(&parameters, resolved),
+        parameters,
+    }
+}
+
+/// Checks that a cost model array has a parameter count the machine accepts for `language`
+pub fn validate_cost_model(language: &Language, cost_model: &[i64]) -> Result<(), String> {
+    let lengths: &[usize] = match language {
+        Language::PlutusV1 => &PLUTUS_V1_LENGTHS,
+        Language::PlutusV2 => &PLUTUS_V2_LENGTHS,
+        Language::PlutusV3 => &PLUTUS_V3_LENGTHS,
+    };
+    if lengths.contains(&cost_model.len()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{:?} cost model has {} parameters, expected one of {:?}",
+            language,
+            cost_model.len(),
+            lengths
+        ))
+    }
+}
+
+/// Like `validate_cost_model`, but also names the language whose layout a
+/// mismatching array has, e.g. a V2 model supplied for a V1 script
+pub fn check_cost_model(language: &Language, cost_model: &[i64]) -> Result<(), String> {
+    validate_cost_model(language, cost_model).map_err(|error| {
+        [Language::PlutusV1, Language::PlutusV2, Language::PlutusV3]
+            .iter()
+            .find(|other| {
+                std::mem::discriminant(*other) != std::mem::discriminant(language)
+                    && validate_cost_model(other, cost_model).is_ok()
+            })
+            .map_or(error.clone(), |other| format!("{}; it has the layout of a {:?} cost model", error, other))
+    })
+}
+
+/// Whether `fun` exists in `language`, and so has parameters in its cost model
+pub fn builtin_in_language(language: &Language, fun: &DefaultFunction) -> bool {
+    match language {
+        Language::PlutusV1 => (*fun as u8) < PLUTUS_V1_BUILTINS,
+        Language::PlutusV2 => (*fun as u8) < PLUTUS_V2_BUILTINS,
+        Language::PlutusV3 => true,
+    }
+}
+
+fn collect_missing_builtins(language: &Language, term: &Term<NamedDeBruijn>, missing: &mut BTreeSet<String>) {
+    match term {
+        Term::Builtin { fun, .. } => {
+            if !builtin_in_language(language, fun) {
+                missing.insert(fun.to_string());
+            }
+        }
+        Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => {
+            collect_missing_builtins(language, body, missing)
+        }
+        Term::Apply { function, argument, .. } => {
+            collect_missing_builtins(language, function, missing);
+            collect_missing_builtins(language, argument, missing);
+        }
+        Term::Constr { fields, .. } => fields
+            .iter()
+            .for_each(|field| collect_missing_builtins(language, field, missing)),
+        Term::Case { constr, branches, .. } => {
+            collect_missing_builtins(language, constr, missing);
+            branches
+                .iter()
+                .for_each(|branch| collect_missing_builtins(language, branch, missing));
+        }
+        Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } => {}
+    }
+}
+
+/// Fails with the builtins of `term` that `language` lacks. The cost model of an
+/// older language has no parameters for them, so such a script could only fail
+/// on chain; reporting them up front beats an opaque error when they are priced.
+pub fn check_program_builtins(language: &Language, term: &Term<NamedDeBruijn>) -> Result<(), String> {
+    let mut missing = BTreeSet::new();
+    collect_missing_builtins(language, term, &mut missing);
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{:?} script uses builtins missing from the {:?} cost model: {}",
+            language,
+            language,
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        ))
+    }
+}
+
+/// Maps a V2 cost model onto the V3 layout. Parameters with the same name keep
+/// their V2 value; the rest (new builtins, the reworked integer division models,
+/// constr/case machine costs) come from `defaults`
+pub fn upgrade_v2_to_v3(v2: &[i64], defaults: &CostModelDefaults) -> Result<CostModelUpgrade, String> {
+    validate_cost_model(&Language::PlutusV2, v2)?;
+    let v2_values: BTreeMap<&str, i64> = PLUTUS_V2_PARAMS.iter().copied().zip(v2.iter().copied()).collect();
+
+    let mut cost_model = Vec::with_capacity(PLUTUS_V3_PARAMS.len());
+    let mut copied = 0;
+    let mut defaulted = vec![];
+    let mut missing = vec![];
+    for (index, name) in PLUTUS_V3_PARAMS.iter().enumerate() {
+        if let Some(value) = v2_values.get(name) {
+            cost_model.push(*value);
+            copied += 1;
+        } else if let Some(value) = defaults.get(index, name) {
+            cost_model.push(value);
+            defaulted.push(name.to_string());
+        } else {
+            missing.push(*name);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!("No default for V3 parameters: {}", missing.join(", ")));
+    }
+
+    if let CostModelDefaults::Array(values) = defaults {
+        cost_model.extend(values.iter().skip(PLUTUS_V3_PARAMS.len()));
+    }
+    validate_cost_model(&Language::PlutusV3, &cost_model)?;
+
+    Ok(CostModelUpgrade {
+        cost_model,
+        copied,
+        defaulted,
+    })
+}
+
+impl CostModels {
+    /// Fills a missing V3 cost model from the V2 one, see `upgrade_v2_to_v3`
+    pub fn with_v3_from_v2(&self, defaults: &CostModelDefaults) -> Result<CostModels, String> {
+        if self.plutus_v3.is_some() {
+            return Ok(self.clone());
+        }
+        let v2 = self.plutus_v2.as_ref().ok_or("No PlutusV2 cost model to upgrade")?;
+        Ok(CostModels {
+            plutus_v3: Some(upgrade_v2_to_v3(v2, defaults)?.cost_model),
+            ..self.clone()
+        })
+    }
+}
+
+/// Maps a V2 cost model array to the V3 layout, filling parameters V2 lacks
+/// from `defaults_json` (a V3 array or an object keyed by parameter name)
+#[wasm_bindgen]
+pub fn upgrade_cost_model_v2_to_v3(cost_model_json: &str, defaults_json: &str) -> Result<String, JsError> {
+    let v2 = serde_json::from_str::<Vec<i64>>(cost_model_json)
+        .map_err(|e| DebuggerError::TransactionParseError(format!("Invalid cost model: {}", e)))?;
+    let defaults = serde_json::from_str::<CostModelDefaults>(defaults_json)
+        .map_err(|e| DebuggerError::TransactionParseError(format!("Invalid cost model defaults: {}", e)))?;
+    let upgrade = upgrade_v2_to_v3(&v2, &defaults).map_err(DebuggerError::TransactionParseError)?;
+    Ok(enveloped_json(&upgrade).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_v2_to_v3() {
+        let v2: Vec<i64> = (0..175).collect();
+        let defaults = CostModelDefaults::Array(vec![-1; 297]);
+        let upgrade = upgrade_v2_to_v3(&v2, &defaults).unwrap();
+
+        assert_eq!(upgrade.cost_model.len(), 297);
+        assert_eq!(upgrade.copied + upgrade.defaulted.len(), PLUTUS_V3_PARAMS.len());
+        // addInteger keeps its V2 values, divideInteger's reworked model is defaulted
+        assert_eq!(&upgrade.cost_model[..4], &[0, 1, 2, 3]);
+        assert!(upgrade.defaulted.contains(&"divideInteger-cpu-arguments-model-arguments-c00".to_string()));
+        assert!(upgrade.defaulted.contains(&"cekCaseCost-exBudgetCPU".to_string()));
+
+        let named = CostModelDefaults::Named(BTreeMap::new());
+        assert!(upgrade_v2_to_v3(&v2, &named).is_err());
+        assert!(upgrade_v2_to_v3(&v2[..100], &defaults).is_err());
+    }
+
+    #[test]
+    fn test_cost_model_parameter_names() {
+        assert_eq!(cost_model_parameter_names(&Language::PlutusV1).len(), PLUTUS_V1_LENGTHS[0]);
+        let report = cost_model_report(&Language::PlutusV3, &vec![7; 297], &CostModel::default());
+        assert_eq!(report.parameters[0].name.as_deref(), Some("addInteger-cpu-arguments-intercept"));
+        assert_eq!(report.parameters[296].name, None);
+        assert_eq!(report.parameter_count, 297);
+
+        let resolved = &report.resolved;
+        assert_eq!(resolved.builtins["addInteger"]["cpu"]["intercept"], 7);
+        assert_eq!(resolved.builtins["bData"]["memory"]["constant"], 7);
+        // Machine step costs are named parameters too, but not builtin ones
+        assert!(!resolved.builtins.contains_key("cekApplyCost"));
+        assert_eq!(resolved.machine.len(), 10);
+        assert!(resolved.machine["apply"].cpu > 0);
+    }
+
+    #[test]
+    fn test_plutus_v1_quirks() {
+        let error = check_cost_model(&Language::PlutusV1, &vec![0; 175]).unwrap_err();
+        assert!(error.ends_with("layout of a PlutusV2 cost model"));
+        assert!(check_cost_model(&Language::PlutusV1, &vec![0; 166]).is_ok());
+
+        assert!(builtin_in_language(&Language::PlutusV1, &DefaultFunction::MkNilPairData));
+        assert!(!builtin_in_language(&Language::PlutusV1, &DefaultFunction::SerialiseData));
+        assert!(builtin_in_language(&Language::PlutusV2, &DefaultFunction::VerifySchnorrSecp256k1Signature));
+        assert!(!builtin_in_language(&Language::PlutusV2, &DefaultFunction::Bls12_381_G1_Add));
+
+        let term = Term::Apply {
+            function: Term::Builtin { fun: DefaultFunction::SerialiseData, uniq_id: 1 }.into(),
+            argument: Term::Error { uniq_id: 2 }.into(),
+            uniq_id: 0,
+        };
+        let error = check_program_builtins(&Language::PlutusV1, &term).unwrap_err();
+        assert!(error.ends_with("serialiseData"));
+        assert!(check_program_builtins(&Language::PlutusV2, &term).is_ok());
+    }
+}
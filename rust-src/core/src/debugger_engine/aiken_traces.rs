@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Suffix Aiken appends to the trace emitted by the `?` operator when the
+/// checked expression evaluates to `False`.
+const BOOLEAN_TRACE_SUFFIX: &str = " ? False";
+
+/// Prefix used by Aiken for traces produced by failing `expect` patterns.
+const EXPECT_PREFIX: &str = "expect ";
+
+/// Messages emitted by Aiken's generated code for `expect` destructuring
+/// failures that don't carry the source pattern.
+const EXPECT_MESSAGES: [&str; 4] = [
+    "Expected on incorrect constructor",
+    "Expected on incorrect boolean variant",
+    "Expected no items for List",
+    "List/Tuple/Constr contains more items than expected",
+];
+
+/// Prefix recognized as an Aiken test label trace
+const TEST_LABEL_PREFIX: &str = "test ";
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct AikenSourceLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Mapping from Aiken source expressions (as they appear in traces) to their
+/// location in the source code
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct AikenSourceMap {
+    pub expressions: HashMap<String, AikenSourceLocation>,
+    /// Location of the code each term id was compiled from, when the compiler provides it
+    #[serde(default)]
+    pub terms: HashMap<i32, AikenSourceLocation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "trace_type")]
+pub enum AikenTraceKind {
+    #[serde(rename = "BooleanCheck")]
+    BooleanCheck { expression: String },
+    #[serde(rename = "ExpectFailure")]
+    ExpectFailure { pattern: Option<String> },
+    #[serde(rename = "TestLabel")]
+    TestLabel { label: String },
+    #[serde(rename = "Plain")]
+    Plain,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct AikenTraceEntry {
+    pub index: usize,
+    pub raw: String,
+    pub rendered: String,
+    pub kind: AikenTraceKind,
+    pub source: Option<AikenSourceLocation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct AikenTraceGroup {
+    pub label: Option<String>,
+    pub entries: Vec<AikenTraceEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct AikenTraceReport {
+    pub groups: Vec<AikenTraceGroup>,
+    pub boolean_checks: usize,
+    pub expect_failures: usize,
+}
+
+impl AikenTraceKind {
+    /// Classify a single trace message according to Aiken conventions
+    pub fn classify(trace: &str) -> Self {
+        let trimmed = trace.trim();
+        if let Some(expression) = trimmed.strip_suffix(BOOLEAN_TRACE_SUFFIX) {
+            return AikenTraceKind::BooleanCheck {
+                expression: expression.trim().to_string(),
+            };
+        }
+        if let Some(pattern) = trimmed.strip_prefix(EXPECT_PREFIX) {
+            return AikenTraceKind::ExpectFailure {
+                pattern: Some(pattern.trim().to_string()),
+            };
+        }
+        if EXPECT_MESSAGES.iter().any(|message| trimmed == *message) {
+            return AikenTraceKind::ExpectFailure { pattern: None };
+        }
+        if let Some(label) = trimmed.strip_prefix(TEST_LABEL_PREFIX) {
+            return AikenTraceKind::TestLabel {
+                label: label.trim().to_string(),
+            };
+        }
+        AikenTraceKind::Plain
+    }
+}
+
+fn render_trace(raw: &str, kind: &AikenTraceKind) -> String {
+    match kind {
+        AikenTraceKind::BooleanCheck { expression } => format!("✗ {} (evaluated to False)", expression),
+        AikenTraceKind::ExpectFailure { pattern: Some(pattern) } => format!("✗ expect failed: {}", pattern),
+        AikenTraceKind::ExpectFailure { pattern: None } => format!("✗ expect failed: {}", raw.trim()),
+        AikenTraceKind::TestLabel { label } => format!("── {} ──", label),
+        AikenTraceKind::Plain => raw.to_string(),
+    }
+}
+
+/// Classify, render and group traces. A test label opens a new group which
+/// collects every following trace until the next label.
+pub fn build_aiken_trace_report(traces: &[String], source_map: Option<&AikenSourceMap>) -> AikenTraceReport {
+    let mut groups = vec![AikenTraceGroup {
+        label: None,
+        entries: vec![],
+    }];
+    let mut boolean_checks = 0;
+    let mut expect_failures = 0;
+
+    for (index, raw) in traces.iter().enumerate() {
+        let kind = AikenTraceKind::classify(raw);
+        let source = match &kind {
+            AikenTraceKind::BooleanCheck { expression } => {
+                boolean_checks += 1;
+                source_map.and_then(|map| map.expressions.get(expression).cloned())
+            }
+            AikenTraceKind::ExpectFailure { pattern } => {
+                expect_failures += 1;
+                pattern
+                    .as_ref()
+                    .and_then(|pattern| source_map.and_then(|map| map.expressions.get(pattern).cloned()))
+            }
+            _ => None,
+        };
+
+        if let AikenTraceKind::TestLabel { label } = &kind {
+            groups.push(AikenTraceGroup {
+                label: Some(label.clone()),
+                entries: vec![],
+            });
+        }
+
+        let entry = AikenTraceEntry {
+            index,
+            raw: raw.clone(),
+            rendered: render_trace(raw, &kind),
+            kind,
+            source,
+        };
+        if let Some(group) = groups.last_mut() {
+            group.entries.push(entry);
+        }
+    }
+
+    // Drop the implicit leading group when every trace belongs to a label
+    if groups.len() > 1 && groups[0].entries.is_empty() {
+        groups.remove(0);
+    }
+
+    AikenTraceReport {
+        groups,
+        boolean_checks,
+        expect_failures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_aiken_traces() {
+        assert!(matches!(
+            AikenTraceKind::classify("amount > 0 ? False"),
+            AikenTraceKind::BooleanCheck { expression } if expression == "amount > 0"
+        ));
+        assert!(matches!(
+            AikenTraceKind::classify("expect Some(datum) = input.datum"),
+            AikenTraceKind::ExpectFailure { pattern: Some(_) }
+        ));
+        assert!(matches!(
+            AikenTraceKind::classify("Expected on incorrect constructor"),
+            AikenTraceKind::ExpectFailure { pattern: None }
+        ));
+        assert!(matches!(
+            AikenTraceKind::classify("test can_spend"),
+            AikenTraceKind::TestLabel { label } if label == "can_spend"
+        ));
+        assert!(matches!(AikenTraceKind::classify("hello"), AikenTraceKind::Plain));
+    }
+
+    #[test]
+    fn test_group_traces_by_label_with_source_map() {
+        let mut source_map = AikenSourceMap::default();
+        source_map.expressions.insert(
+            "signed".to_string(),
+            AikenSourceLocation { file: "validators/vault.ak".to_string(), line: 12, column: 5 },
+        );
+        let traces = vec![
+            "test first".to_string(),
+            "signed ? False".to_string(),
+            "test second".to_string(),
+            "plain".to_string(),
+        ];
+
+        let report = build_aiken_trace_report(&traces, Some(&source_map));
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.groups[0].label.as_deref(), Some("first"));
+        assert_eq!(report.groups[0].entries.len(), 2);
+        assert_eq!(report.groups[0].entries[1].source.as_ref().unwrap().line, 12);
+        assert_eq!(report.boolean_checks, 1);
+        assert_eq!(report.expect_failures, 0);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Host-provided overlay on a term, e.g. the source name a compiler gave it
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct TermAnnotation {
+    pub label: Option<String>,
+    /// Any CSS color understood by the host
+    pub color: Option<String>,
+    pub note: Option<String>,
+    /// Free-form data for plugins, passed through untouched
+    pub data: Option<serde_json::Value>,
+}
+
+impl TermAnnotation {
+    pub fn is_empty(&self) -> bool {
+        *self == TermAnnotation::default()
+    }
+}
+
+/// Annotations of a session by term id. They are kept apart from the term
+/// serialization and survive resets.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct TermAnnotations {
+    annotations: BTreeMap<i32, TermAnnotation>,
+}
+
+impl TermAnnotations {
+    /// Sets the annotation of a term; an empty annotation removes it
+    pub fn set(&mut self, term_id: i32, annotation: TermAnnotation) {
+        if annotation.is_empty() {
+            self.annotations.remove(&term_id);
+        } else {
+            self.annotations.insert(term_id, annotation);
+        }
+    }
+
+    pub fn remove(&mut self, term_id: i32) -> bool {
+        self.annotations.remove(&term_id).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.annotations.clear();
+    }
+
+    /// Drops annotations of terms the program no longer contains
+    pub fn retain_terms(&mut self, term_ids: &std::collections::HashSet<i32>) {
+        self.annotations.retain(|term_id, _| term_ids.contains(term_id));
+    }
+
+    /// Moves annotations to the new ids of their terms, e.g. after a recompile, and
+    /// drops those whose term has no new id
+    pub fn remap_terms(&mut self, new_id: impl Fn(i32) -> Option<i32>) {
+        self.annotations = std::mem::take(&mut self.annotations)
+            .into_iter()
+            .filter_map(|(term_id, annotation)| Some((new_id(term_id)?, annotation)))
+            .collect();
+    }
+
+    pub fn term_ids(&self) -> impl Iterator<Item = i32> + '_ {
+        self.annotations.keys().copied()
+    }
+
+    pub fn get(&self, term_id: i32) -> Option<&TermAnnotation> {
+        self.annotations.get(&term_id)
+    }
+
+    /// Annotations of the given terms, or all of them when `term_ids` is `None`
+    pub fn select(&self, term_ids: Option<&[i32]>) -> BTreeMap<i32, TermAnnotation> {
+        match term_ids {
+            Some(term_ids) => term_ids
+                .iter()
+                .filter_map(|term_id| Some((*term_id, self.annotations.get(term_id)?.clone())))
+                .collect(),
+            None => self.annotations.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remap_terms_drops_unmapped_annotations() {
+        let mut annotations = TermAnnotations::default();
+        let label = |label: &str| TermAnnotation {
+            label: Some(label.to_string()),
+            ..Default::default()
+        };
+        annotations.set(1, label("kept"));
+        annotations.set(2, label("dropped"));
+        annotations.remap_terms(|term_id| (term_id == 1).then_some(10));
+        assert_eq!(annotations.term_ids().collect::<Vec<_>>(), vec![10]);
+        assert_eq!(annotations.get(10), Some(&label("kept")));
+    }
+}
@@ -0,0 +1,104 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::budget::SerializableBudget;
+use crate::debugger_engine::{DebuggerError, SerializableExecutionStatus, SessionController};
+use crate::wasm_tools::JsError;
+
+/// First observable difference between the two executions of an audit
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct AuditDivergence {
+    pub step: u64,
+    /// Which observation differs: "term_id", "status", "budget" or "traces"
+    pub field: String,
+    pub first: String,
+    pub second: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct DeterminismAudit {
+    pub redeemer: String,
+    pub deterministic: bool,
+    pub steps: u64,
+    pub result: SerializableExecutionStatus,
+    pub budget: SerializableBudget,
+    pub first_divergence: Option<AuditDivergence>,
+}
+
+/// Snapshot of everything compared between the two runs after each step
+struct StepObservation {
+    term_id: String,
+    status: String,
+    budget: String,
+    traces: String,
+}
+
+impl StepObservation {
+    fn observe(session: &SessionController, term_id: i32, status: &SerializableExecutionStatus) -> Result<Self, JsError> {
+        Ok(StepObservation {
+            term_id: term_id.to_string(),
+            status: json(status)?,
+            budget: json(&session.get_budget_inner()?)?,
+            traces: json(&session.get_trace_tail())?,
+        })
+    }
+
+    fn first_difference(&self, other: &StepObservation) -> Option<(&'static str, String, String)> {
+        [
+            ("term_id", &self.term_id, &other.term_id),
+            ("status", &self.status, &other.status),
+            ("budget", &self.budget, &other.budget),
+            ("traces", &self.traces, &other.traces),
+        ]
+        .into_iter()
+        .find(|(_, first, second)| first != second)
+        .map(|(field, first, second)| (field, first.clone(), second.clone()))
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> Result<String, JsError> {
+    Ok(serde_json::to_string(value).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+}
+
+/// Steps two independently built sessions of the same redeemer in lockstep and
+/// reports the first step at which term, status, budget or traces differ. The run
+/// ends when the first session stops, runs out of budget or reaches its step limit.
+pub(crate) fn audit_sessions(
+    redeemer: &str,
+    mut first: SessionController,
+    mut second: SessionController,
+) -> Result<DeterminismAudit, JsError> {
+    let mut steps = 0;
+    loop {
+        let first_result = first.step_inner()?;
+        let second_result = second.step_inner()?;
+        steps += 1;
+
+        let first_observation = StepObservation::observe(&first, first_result.term_id, &first_result.status)?;
+        let second_observation = StepObservation::observe(&second, second_result.term_id, &second_result.status)?;
+        let divergence = first_observation
+            .first_difference(&second_observation)
+            .map(|(field, first, second)| AuditDivergence {
+                step: steps,
+                field: field.to_string(),
+                first,
+                second,
+            });
+
+        let result = if first.step_limit_reached() && matches!(first_result.status, SerializableExecutionStatus::Ready) {
+            SerializableExecutionStatus::StepLimitReached { steps }
+        } else {
+            first_result.status
+        };
+        if divergence.is_some() || !matches!(result, SerializableExecutionStatus::Ready) {
+            return Ok(DeterminismAudit {
+                redeemer: redeemer.to_string(),
+                deterministic: divergence.is_none(),
+                steps,
+                result,
+                budget: first.get_budget_inner()?,
+                first_divergence: divergence,
+            });
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::PlutusData;
+
+use crate::hash_format::normalize_script_hash;
+
+/// Upper bound on consecutive `$ref` hops, guarding against self-referencing definitions
+const MAX_REF_DEPTH: usize = 64;
+
+/// Subset of a CIP-57 Plutus blueprint needed to validate datums and redeemers
+#[derive(Deserialize, Debug, Clone)]
+pub struct Blueprint {
+    #[serde(default)]
+    pub validators: Vec<BlueprintValidator>,
+    #[serde(default)]
+    pub definitions: BTreeMap<String, BlueprintSchema>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BlueprintValidator {
+    pub title: String,
+    pub hash: Option<String>,
+    pub datum: Option<BlueprintArgument>,
+    pub redeemer: Option<BlueprintArgument>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BlueprintArgument {
+    pub title: Option<String>,
+    pub schema: BlueprintSchema,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BlueprintSchema {
+    pub title: Option<String>,
+    #[serde(rename = "$ref")]
+    pub reference: Option<String>,
+    pub data_type: Option<String>,
+    pub any_of: Option<Vec<BlueprintSchema>>,
+    pub index: Option<u64>,
+    pub fields: Option<Vec<BlueprintSchema>>,
+    pub items: Option<BlueprintItems>,
+    pub keys: Option<Box<BlueprintSchema>>,
+    pub values: Option<Box<BlueprintSchema>>,
+}
+
+/// `items` is either a single schema (homogeneous list) or one schema per element (tuple)
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum BlueprintItems {
+    Single(Box<BlueprintSchema>),
+    Tuple(Vec<BlueprintSchema>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SchemaMismatch {
+    /// Location of the mismatch inside the value, e.g. `$.fields[1][0]`
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BlueprintValidationResult {
+    pub redeemer: String,
+    pub script_hash: String,
+    /// Title of the blueprint validator matched by script hash and purpose
+    pub validator: Option<String>,
+    pub datum_mismatches: Vec<SchemaMismatch>,
+    pub redeemer_mismatches: Vec<SchemaMismatch>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BlueprintValidationReport {
+    pub results: Vec<BlueprintValidationResult>,
+    pub valid: bool,
+}
+
+/// Handler name used in blueprint validator titles for a redeemer tag
+fn purpose_suffix(redeemer_tag: &str) -> &'static str {
+    match redeemer_tag {
+        "Spend" => "spend",
+        "Mint" => "mint",
+        "Cert" => "publish",
+        "Reward" => "withdraw",
+        "Vote" => "vote",
+        "Propose" => "propose",
+        _ => "else",
+    }
+}
+
+/// Hex form of a hash given as hex or bech32, left as is when it isn't a script hash
+fn comparable_hash(hash: &str) -> String {
+    normalize_script_hash(hash).unwrap_or_else(|_| hash.to_lowercase())
+}
+
+/// Constructor index of a Constr, decoded from its CBOR tag
+fn constr_index(constr: &pallas_primitives::Constr<PlutusData>) -> Option<u64> {
+    match constr.tag {
+        121..=127 => Some(constr.tag - 121),
+        1280..=1400 => Some(constr.tag - 1280 + 7),
+        102 => constr.any_constructor,
+        _ => None,
+    }
+}
+
+impl Blueprint {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /// Find the validator handling `redeemer_tag` for the script with the given hash
+    /// (hex or `script1…`). Multi-handler validators share a hash, so the title
+    /// suffix selects the handler.
+    pub fn find_validator(&self, script_hash: &str, redeemer_tag: &str) -> Option<&BlueprintValidator> {
+        let script_hash = comparable_hash(script_hash);
+        let suffix = format!(".{}", purpose_suffix(redeemer_tag));
+        let candidates: Vec<&BlueprintValidator> = self
+            .validators
+            .iter()
+            .filter(|validator| validator.hash.as_deref().map(comparable_hash) == Some(script_hash.clone()))
+            .collect();
+        candidates
+            .iter()
+            .find(|validator| validator.title.ends_with(&suffix))
+            .or_else(|| candidates.iter().find(|validator| validator.title.ends_with(".else")))
+            .or_else(|| candidates.first())
+            .copied()
+    }
+
+    /// Validate `data` against `schema`, collecting every mismatch found
+    pub fn validate(&self, schema: &BlueprintSchema, data: &PlutusData) -> Vec<SchemaMismatch> {
+        let mut mismatches = vec![];
+        self.validate_at(schema, data, "$".to_string(), 0, &mut mismatches);
+        mismatches
+    }
+
+    fn resolve<'a>(&'a self, reference: &str) -> Option<&'a BlueprintSchema> {
+        let name = reference.strip_prefix("#/definitions/")?;
+        // JSON pointer escapes: "~1" is "/" and "~0" is "~"
+        let name = name.replace("~1", "/").replace("~0", "~");
+        self.definitions.get(&name)
+    }
+
+    fn validate_at(
+        &self,
+        schema: &BlueprintSchema,
+        data: &PlutusData,
+        path: String,
+        ref_depth: usize,
+        mismatches: &mut Vec<SchemaMismatch>,
+    ) {
+        if let Some(reference) = &schema.reference {
+            if ref_depth >= MAX_REF_DEPTH {
+                push_mismatch(mismatches, &path, format!("Too many nested references while resolving {}", reference));
+                return;
+            }
+            match self.resolve(reference) {
+                Some(resolved) => self.validate_at(resolved, data, path, ref_depth + 1, mismatches),
+                None => push_mismatch(mismatches, &path, format!("Unknown schema reference {}", reference)),
+            }
+            return;
+        }
+
+        if let Some(variants) = &schema.any_of {
+            let PlutusData::Constr(constr) = data else {
+                push_mismatch(mismatches, &path, format!("Expected a constructor, found {}", data_kind(data)));
+                return;
+            };
+            let Some(index) = constr_index(constr) else {
+                push_mismatch(mismatches, &path, format!("Invalid constructor tag {}", constr.tag));
+                return;
+            };
+            match variants.iter().find(|variant| variant.index == Some(index)) {
+                Some(variant) => self.validate_constructor(variant, constr, index, path, mismatches),
+                None => {
+                    let expected: Vec<String> = variants
+                        .iter()
+                        .filter_map(|variant| variant.index)
+                        .map(|index| index.to_string())
+                        .collect();
+                    push_mismatch(mismatches, &path, format!(
+                        "Wrong constructor index {} (expected one of {})",
+                        index,
+                        expected.join(", ")
+                    ));
+                }
+            }
+            return;
+        }
+
+        match schema.data_type.as_deref() {
+            // An empty schema accepts any data
+            None => {}
+            Some("integer") => {
+                if !matches!(data, PlutusData::BigInt(_)) {
+                    push_mismatch(mismatches, &path, format!("Expected an integer, found {}", data_kind(data)));
+                }
+            }
+            Some("bytes") => {
+                if !matches!(data, PlutusData::BoundedBytes(_)) {
+                    push_mismatch(mismatches, &path, format!("Expected bytes, found {}", data_kind(data)));
+                }
+            }
+            Some("list") => {
+                let PlutusData::Array(items) = data else {
+                    push_mismatch(mismatches, &path, format!("Expected a list, found {}", data_kind(data)));
+                    return;
+                };
+                match &schema.items {
+                    Some(BlueprintItems::Single(item_schema)) => {
+                        for (i, item) in items.iter().enumerate() {
+                            self.validate_at(item_schema, item, format!("{}[{}]", path, i), 0, mismatches);
+                        }
+                    }
+                    Some(BlueprintItems::Tuple(item_schemas)) => {
+                        if item_schemas.len() != items.len() {
+                            push_mismatch(mismatches, &path, format!(
+                                "Expected a tuple of {} elements, found {}",
+                                item_schemas.len(),
+                                items.len()
+                            ));
+                        }
+                        for (i, (item_schema, item)) in item_schemas.iter().zip(items.iter()).enumerate() {
+                            self.validate_at(item_schema, item, format!("{}[{}]", path, i), 0, mismatches);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            Some("map") => {
+                let PlutusData::Map(pairs) = data else {
+                    push_mismatch(mismatches, &path, format!("Expected a map, found {}", data_kind(data)));
+                    return;
+                };
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if let Some(key_schema) = &schema.keys {
+                        self.validate_at(key_schema, key, format!("{}.keys[{}]", path, i), 0, mismatches);
+                    }
+                    if let Some(value_schema) = &schema.values {
+                        self.validate_at(value_schema, value, format!("{}.values[{}]", path, i), 0, mismatches);
+                    }
+                }
+            }
+            Some("constructor") => {
+                let PlutusData::Constr(constr) = data else {
+                    push_mismatch(mismatches, &path, format!("Expected a constructor, found {}", data_kind(data)));
+                    return;
+                };
+                match constr_index(constr) {
+                    Some(index) => self.validate_constructor(schema, constr, index, path, mismatches),
+                    None => push_mismatch(mismatches, &path, format!("Invalid constructor tag {}", constr.tag)),
+                }
+            }
+            // Builtin (non-data) types such as "#pair" are not checked
+            Some(_) => {}
+        }
+    }
+
+    fn validate_constructor(
+        &self,
+        schema: &BlueprintSchema,
+        constr: &pallas_primitives::Constr<PlutusData>,
+        index: u64,
+        path: String,
+        mismatches: &mut Vec<SchemaMismatch>,
+    ) {
+        if let Some(expected) = schema.index {
+            if expected != index {
+                mismatches.push(SchemaMismatch {
+                    path,
+                    message: format!("Wrong constructor index {} (expected {})", index, expected),
+                });
+                return;
+            }
+        }
+        let Some(field_schemas) = &schema.fields else {
+            return;
+        };
+        let name = schema.title.as_deref().unwrap_or("constructor");
+        if field_schemas.len() != constr.fields.len() {
+            mismatches.push(SchemaMismatch {
+                path: path.clone(),
+                message: format!(
+                    "{} expects {} fields, found {}",
+                    name,
+                    field_schemas.len(),
+                    constr.fields.len()
+                ),
+            });
+        }
+        for (i, (field_schema, field)) in field_schemas.iter().zip(constr.fields.iter()).enumerate() {
+            let field_path = match &field_schema.title {
+                Some(title) => format!("{}.fields[{}]({})", path, i, title),
+                None => format!("{}.fields[{}]", path, i),
+            };
+            self.validate_at(field_schema, field, field_path, 0, mismatches);
+        }
+    }
+}
+
+fn push_mismatch(mismatches: &mut Vec<SchemaMismatch>, path: &str, message: String) {
+    mismatches.push(SchemaMismatch {
+        path: path.to_string(),
+        message,
+    });
+}
+
+fn data_kind(data: &PlutusData) -> &'static str {
+    match data {
+        PlutusData::Constr(_) => "a constructor",
+        PlutusData::Map(_) => "a map",
+        PlutusData::BigInt(_) => "an integer",
+        PlutusData::BoundedBytes(_) => "bytes",
+        PlutusData::Array(_) => "a list",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pallas_codec::utils::MaybeIndefArray;
+    use pallas_primitives::{BigInt, Constr};
+
+    const BLUEPRINT: &str = r##"{
+        "validators": [{
+            "title": "vault.vault.spend",
+            "hash": "abcd",
+            "redeemer": { "schema": { "$ref": "#/definitions/Action" } }
+        }],
+        "definitions": {
+            "Int": { "dataType": "integer" },
+            "Action": {
+                "anyOf": [
+                    { "title": "Withdraw", "dataType": "constructor", "index": 0,
+                      "fields": [{ "title": "amount", "$ref": "#/definitions/Int" }] },
+                    { "title": "Close", "dataType": "constructor", "index": 1, "fields": [] }
+                ]
+            }
+        }
+    }"##;
+
+    fn constr(tag: u64, fields: Vec<PlutusData>) -> PlutusData {
+        PlutusData::Constr(Constr {
+            tag,
+            any_constructor: None,
+            fields: MaybeIndefArray::Indef(fields),
+        })
+    }
+
+    #[test]
+    fn test_validate_redeemer_against_blueprint() {
+        let blueprint = Blueprint::from_json(BLUEPRINT).unwrap();
+        let validator = blueprint.find_validator("abcd", "Spend").unwrap();
+        let schema = &validator.redeemer.as_ref().unwrap().schema;
+
+        let valid = constr(121, vec![PlutusData::BigInt(BigInt::Int(5.into()))]);
+        assert!(blueprint.validate(schema, &valid).is_empty());
+
+        let wrong_index = constr(123, vec![]);
+        let mismatches = blueprint.validate(schema, &wrong_index);
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].message.contains("Wrong constructor index 2"));
+
+        let wrong_field = constr(121, vec![PlutusData::BoundedBytes(vec![1].into())]);
+        let mismatches = blueprint.validate(schema, &wrong_field);
+        assert_eq!(mismatches[0].path, "$.fields[0](amount)");
+
+        let missing_field = constr(121, vec![]);
+        assert!(blueprint.validate(schema, &missing_field)[0].message.contains("expects 1 fields"));
+    }
+}
@@ -0,0 +1,83 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Named marker attached to a step of a session, with an optional free-text note
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct Bookmark {
+    pub id: u32,
+    pub name: String,
+    pub note: Option<String>,
+    /// Step count at which the bookmark was taken
+    pub step: u64,
+    pub term_id: i32,
+}
+
+/// Bookmarks of a session. They survive resets so findings recorded during one
+/// run can be revisited in the next.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct Bookmarks {
+    next_id: u32,
+    bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    pub fn add(&mut self, name: String, note: Option<String>, step: u64, term_id: i32) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.bookmarks.push(Bookmark { id, name, note, step, term_id });
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        let len = self.bookmarks.len();
+        self.bookmarks.retain(|bookmark| bookmark.id != id);
+        self.bookmarks.len() != len
+    }
+
+    pub fn set_note(&mut self, id: u32, note: Option<String>) -> bool {
+        match self.bookmarks.iter_mut().find(|bookmark| bookmark.id == id) {
+            Some(bookmark) => {
+                bookmark.note = note;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves bookmarks to the new ids of their terms, e.g. after a recompile, and
+    /// drops those whose term has no new id
+    pub fn remap_terms(&mut self, new_id: impl Fn(i32) -> Option<i32>) {
+        self.bookmarks.retain_mut(|bookmark| match new_id(bookmark.term_id) {
+            Some(term_id) => {
+                bookmark.term_id = term_id;
+                true
+            }
+            None => false,
+        });
+    }
+
+    pub fn term_ids(&self) -> impl Iterator<Item = i32> + '_ {
+        self.bookmarks.iter().map(|bookmark| bookmark.term_id)
+    }
+
+    /// Bookmarks ordered by step, then by creation
+    pub fn list(&self) -> Vec<Bookmark> {
+        let mut bookmarks = self.bookmarks.clone();
+        bookmarks.sort_by_key(|bookmark| (bookmark.step, bookmark.id));
+        bookmarks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remap_terms_drops_unmapped_bookmarks() {
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.add("kept".to_string(), None, 3, 1);
+        bookmarks.add("dropped".to_string(), None, 5, 2);
+        bookmarks.remap_terms(|term_id| (term_id == 1).then_some(10));
+        assert_eq!(bookmarks.term_ids().collect::<Vec<_>>(), vec![10]);
+    }
+}
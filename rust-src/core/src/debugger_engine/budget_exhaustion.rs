@@ -0,0 +1,205 @@
+use std::collections::{HashMap, VecDeque};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::machine::cost_model::ExBudget;
+
+use super::aiken_traces::{AikenSourceLocation, AikenSourceMap};
+
+/// Number of most recent steps kept to explain where the budget ran out
+pub const RECENT_STEPS_WINDOW: usize = 512;
+
+/// Longest repeating block of term ids looked for at the end of the window
+const MAX_CYCLE_LENGTH: usize = 64;
+
+/// A step as seen just before it was taken
+#[derive(Debug, Clone)]
+pub struct StepSample {
+    pub step: u64,
+    pub term_id: i32,
+    /// Builtin the step applies to its last argument
+    pub builtin: Option<String>,
+    /// Machine budget remaining before the step
+    pub remaining: ExBudget,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecentSteps {
+    samples: VecDeque<StepSample>,
+}
+
+impl Default for RecentSteps {
+    fn default() -> Self {
+        RecentSteps {
+            samples: VecDeque::with_capacity(RECENT_STEPS_WINDOW),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct HotTerm {
+    pub term_id: i32,
+    pub hits: usize,
+    pub cpu: i64,
+    pub mem: i64,
+    pub source: Option<AikenSourceLocation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct HotBuiltin {
+    pub builtin: String,
+    pub calls: usize,
+    pub cpu: i64,
+    pub mem: i64,
+}
+
+/// Where the last chunk of budget went before the declared budget was exceeded
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BudgetExhaustionReport {
+    /// Step at which the budget was found exceeded
+    pub step: u64,
+    pub term_id: i32,
+    /// Number of steps aggregated, ending at `step`
+    pub window_steps: usize,
+    pub window_cpu: i64,
+    pub window_mem: i64,
+    /// Terms stepped in the window, most expensive first
+    pub terms: Vec<HotTerm>,
+    /// Builtins applied in the window, most expensive first
+    pub builtins: Vec<HotBuiltin>,
+    /// Block of term ids repeating at the end of the window, e.g. a recursive loop body
+    pub cycle: Vec<i32>,
+    pub cycle_repetitions: usize,
+}
+
+impl RecentSteps {
+    pub fn push(&mut self, sample: StepSample) {
+        if self.samples.len() == RECENT_STEPS_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Drops the samples of steps after `step`, when the session steps back
+    pub fn truncate_after(&mut self, step: u64) {
+        self.samples.retain(|sample| sample.step <= step);
+    }
+
+    /// Aggregates the window; `remaining` is the machine budget after the last sample
+    pub fn report(
+        &self,
+        step: u64,
+        term_id: i32,
+        remaining: &ExBudget,
+        source_map: Option<&AikenSourceMap>,
+    ) -> BudgetExhaustionReport {
+        let mut terms: HashMap<i32, HotTerm> = HashMap::new();
+        let mut builtins: HashMap<String, HotBuiltin> = HashMap::new();
+        let mut window_cpu = 0;
+        let mut window_mem = 0;
+
+        let after = self.samples.iter().skip(1).map(|sample| &sample.remaining).chain([remaining]);
+        for (sample, after) in self.samples.iter().zip(after) {
+            let cpu = sample.remaining.cpu - after.cpu;
+            let mem = sample.remaining.mem - after.mem;
+            window_cpu += cpu;
+            window_mem += mem;
+
+            let term = terms.entry(sample.term_id).or_insert_with(|| HotTerm {
+                term_id: sample.term_id,
+                hits: 0,
+                cpu: 0,
+                mem: 0,
+                source: source_map.and_then(|map| map.terms.get(&sample.term_id).cloned()),
+            });
+            term.hits += 1;
+            term.cpu += cpu;
+            term.mem += mem;
+
+            if let Some(name) = &sample.builtin {
+                let builtin = builtins.entry(name.clone()).or_insert_with(|| HotBuiltin {
+                    builtin: name.clone(),
+                    calls: 0,
+                    cpu: 0,
+                    mem: 0,
+                });
+                builtin.calls += 1;
+                builtin.cpu += cpu;
+                builtin.mem += mem;
+            }
+        }
+
+        let mut terms: Vec<HotTerm> = terms.into_values().collect();
+        terms.sort_by_key(|term| (std::cmp::Reverse(term.cpu), term.term_id));
+        let mut builtins: Vec<HotBuiltin> = builtins.into_values().collect();
+        builtins.sort_by(|a, b| b.cpu.cmp(&a.cpu).then_with(|| a.builtin.cmp(&b.builtin)));
+
+        let term_ids: Vec<i32> = self.samples.iter().map(|sample| sample.term_id).collect();
+        let (cycle, cycle_repetitions) = trailing_cycle(&term_ids);
+
+        BudgetExhaustionReport {
+            step,
+            term_id,
+            window_steps: self.samples.len(),
+            window_cpu,
+            window_mem,
+            terms,
+            builtins,
+            cycle,
+            cycle_repetitions,
+        }
+    }
+}
+
+/// The block repeating back to back at the end of `ids` that covers the most
+/// steps, with its repetition count. Empty when nothing repeats.
+fn trailing_cycle(ids: &[i32]) -> (Vec<i32>, usize) {
+    let mut best: (usize, usize) = (0, 0);
+    for length in 1..=MAX_CYCLE_LENGTH.min(ids.len() / 2) {
+        let block = &ids[ids.len() - length..];
+        let repetitions = ids
+            .rchunks_exact(length)
+            .take_while(|chunk| *chunk == block)
+            .count();
+        if repetitions >= 2 && repetitions * length > best.0 * best.1 {
+            best = (length, repetitions);
+        }
+    }
+    match best {
+        (0, _) => (vec![], 0),
+        (length, repetitions) => (ids[ids.len() - length..].to_vec(), repetitions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailing_cycle() {
+        assert_eq!(trailing_cycle(&[9, 1, 2, 3, 1, 2, 3, 1, 2, 3]), (vec![1, 2, 3], 3));
+        assert_eq!(trailing_cycle(&[1, 2, 3, 4]), (vec![], 0));
+    }
+
+    #[test]
+    fn test_report_attributes_budget_to_steps() {
+        let mut steps = RecentSteps::default();
+        for (step, (term_id, cpu)) in [(1, 100), (2, 90), (1, 60)].into_iter().enumerate() {
+            steps.push(StepSample {
+                step: step as u64,
+                term_id,
+                builtin: (term_id == 2).then(|| "AddInteger".to_string()),
+                remaining: ExBudget { cpu, mem: cpu },
+            });
+        }
+        let report = steps.report(3, 1, &ExBudget { cpu: 50, mem: 50 }, None);
+        assert_eq!(report.window_cpu, 50);
+        assert_eq!(report.terms[0].term_id, 2);
+        assert_eq!(report.terms[0].cpu, 30);
+        assert_eq!(report.builtins[0].calls, 1);
+    }
+}
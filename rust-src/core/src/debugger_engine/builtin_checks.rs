@@ -0,0 +1,351 @@
+use num_bigint::{BigInt, Sign};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{Constant, NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+    machine::{value::Value, Context, MachineState},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum BuiltinMisuseKind {
+    /// Argument applied before all type forces were taken
+    MissingForce,
+    /// Force applied to a builtin that takes no more forces, or after arguments
+    ExcessForce,
+    /// Argument applied to a builtin that is already saturated
+    ExcessArgument,
+    /// Division, quotient, remainder or modulo by zero (arithmetic guard)
+    DivisionByZero,
+    /// integerToByteString given a negative integer, or one that doesn't fit the
+    /// requested width or the 8192-byte limit (arithmetic guard)
+    IntegerOverflow,
+}
+
+/// Largest width integerToByteString accepts, in bytes
+const INTEGER_TO_BYTESTRING_MAX_WIDTH: u64 = 8192;
+
+/// Builtin application that will make the machine fail once evaluated
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BuiltinMisuse {
+    pub kind: BuiltinMisuseKind,
+    pub builtin: String,
+    /// Id of the Builtin term
+    pub builtin_term_id: i32,
+    /// Id of the Force or Apply term performing the faulty operation (static checks only)
+    pub term_id: Option<i32>,
+    /// Step at which the misuse was observed (runtime checks only)
+    pub step: Option<u64>,
+    pub message: String,
+    /// Arguments the builtin is about to be applied to (arithmetic guard only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BuiltinWarnings {
+    /// Misuses found in the program text, before execution
+    pub static_warnings: Vec<BuiltinMisuse>,
+    /// Misuses observed while stepping, including builtins reached through variables
+    pub runtime_warnings: Vec<BuiltinMisuse>,
+}
+
+fn misuse_message(kind: &BuiltinMisuseKind, fun: &DefaultFunction, forces: u32, args: usize) -> String {
+    match kind {
+        BuiltinMisuseKind::MissingForce => format!(
+            "{:?} applied to an argument after {} of {} forces",
+            fun,
+            forces,
+            fun.force_count()
+        ),
+        BuiltinMisuseKind::ExcessForce if args > 0 => {
+            format!("{:?} forced after being applied to {} arguments", fun, args)
+        }
+        BuiltinMisuseKind::ExcessForce => format!(
+            "{:?} forced {} times but takes {} forces",
+            fun,
+            forces + 1,
+            fun.force_count()
+        ),
+        BuiltinMisuseKind::ExcessArgument => format!(
+            "{:?} applied to {} arguments but takes {}",
+            fun,
+            args + 1,
+            fun.arity()
+        ),
+    }
+}
+
+/// Classify the next operation on a builtin that has taken `forces` forces and `args` arguments
+fn check_operation(fun: &DefaultFunction, forces: u32, args: usize, is_force: bool) -> Option<BuiltinMisuseKind> {
+    if is_force {
+        (args > 0 || forces >= fun.force_count()).then_some(BuiltinMisuseKind::ExcessForce)
+    } else if forces < fun.force_count() {
+        Some(BuiltinMisuseKind::MissingForce)
+    } else if args >= fun.arity() {
+        Some(BuiltinMisuseKind::ExcessArgument)
+    } else {
+        None
+    }
+}
+
+/// Walk the program and check every Force/Apply spine whose head is a builtin
+pub fn scan_builtin_misuse(term: &Term<NamedDeBruijn>) -> Vec<BuiltinMisuse> {
+    let mut warnings = vec![];
+    scan_term(term, false, &mut warnings);
+    warnings
+}
+
+fn scan_term(term: &Term<NamedDeBruijn>, in_spine: bool, warnings: &mut Vec<BuiltinMisuse>) {
+    if !in_spine && matches!(term, Term::Apply { .. } | Term::Force { .. }) {
+        check_spine(term, warnings);
+    }
+    match term {
+        Term::Apply { function, argument, .. } => {
+            scan_term(function, true, warnings);
+            scan_term(argument, false, warnings);
+        }
+        Term::Force { body, .. } => scan_term(body, true, warnings),
+        Term::Delay { body, .. } | Term::Lambda { body, .. } => scan_term(body, false, warnings),
+        Term::Constr { fields, .. } => fields.iter().for_each(|field| scan_term(field, false, warnings)),
+        Term::Case { constr, branches, .. } => {
+            scan_term(constr, false, warnings);
+            branches.iter().for_each(|branch| scan_term(branch, false, warnings));
+        }
+        _ => {}
+    }
+}
+
+fn check_spine(term: &Term<NamedDeBruijn>, warnings: &mut Vec<BuiltinMisuse>) {
+    // Operations from the outermost inwards: (term id, is_force)
+    let mut operations = vec![];
+    let mut head = term;
+    loop {
+        match head {
+            Term::Apply { function, uniq_id, .. } => {
+                operations.push((*uniq_id as i32, false));
+                head = function;
+            }
+            Term::Force { body, uniq_id } => {
+                operations.push((*uniq_id as i32, true));
+                head = body;
+            }
+            _ => break,
+        }
+    }
+    let Term::Builtin { fun, uniq_id } = head else {
+        return;
+    };
+
+    let mut forces = 0;
+    let mut args = 0;
+    for (term_id, is_force) in operations.into_iter().rev() {
+        if let Some(kind) = check_operation(fun, forces, args, is_force) {
+            warnings.push(BuiltinMisuse {
+                message: misuse_message(&kind, fun, forces, args),
+                kind,
+                builtin: format!("{:?}", fun),
+                builtin_term_id: *uniq_id as i32,
+                term_id: Some(term_id),
+                step: None,
+                arguments: vec![],
+            });
+            return;
+        }
+        if is_force {
+            forces += 1;
+        } else {
+            args += 1;
+        }
+    }
+}
+
+/// Check whether the builtin value being returned is about to be forced or
+/// applied in a way the machine will reject
+pub fn check_machine_state(state: &MachineState, step: u64) -> Option<BuiltinMisuse> {
+    let MachineState::Return(context, Value::Builtin { fun, runtime, term_id }) = state else {
+        return None;
+    };
+    let is_force = match context {
+        Context::FrameForce(..) => true,
+        Context::FrameAwaitFunTerm(..) | Context::FrameAwaitFunValue(..) => false,
+        _ => return None,
+    };
+    let kind = check_operation(fun, runtime.forces, runtime.args.len(), is_force)?;
+    Some(BuiltinMisuse {
+        message: misuse_message(&kind, fun, runtime.forces, runtime.args.len()),
+        kind,
+        builtin: format!("{:?}", fun),
+        builtin_term_id: *term_id as i32,
+        term_id: None,
+        step: Some(step),
+        arguments: vec![],
+    })
+}
+
+/// Resolves a builtin by its Rust name (`VerifyEd25519Signature`) or its UPLC
+/// name (`verifyEd25519Signature`), ignoring case
+pub fn builtin_by_name(name: &str) -> Option<DefaultFunction> {
+    let name = name.trim();
+    (0..=u8::MAX)
+        .filter_map(|tag| DefaultFunction::try_from(tag).ok())
+        .find(|fun| format!("{:?}", fun).eq_ignore_ascii_case(name) || fun.to_string().eq_ignore_ascii_case(name))
+}
+
+fn integer_argument(value: &Value) -> Option<&BigInt> {
+    match value {
+        Value::Con(constant) => match constant.as_ref() {
+            Constant::Integer(integer) => Some(integer),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn describe_argument(value: &Value) -> String {
+    match value {
+        Value::Con(constant) => match constant.as_ref() {
+            Constant::Integer(integer) => integer.to_string(),
+            Constant::Bool(b) => b.to_string(),
+            Constant::ByteString(bytes) => format!("#{}", hex::encode(bytes)),
+            other => format!("{:?}", other),
+        },
+        _ => "<non-constant>".to_string(),
+    }
+}
+
+/// Why an arithmetic builtin fails on `args`, if it does
+fn arithmetic_failure(fun: &DefaultFunction, args: &[&Value]) -> Option<(BuiltinMisuseKind, String)> {
+    match fun {
+        DefaultFunction::DivideInteger
+        | DefaultFunction::QuotientInteger
+        | DefaultFunction::RemainderInteger
+        | DefaultFunction::ModInteger => {
+            let divisor = integer_argument(args.get(1)?)?;
+            (divisor.sign() == Sign::NoSign).then(|| {
+                (
+                    BuiltinMisuseKind::DivisionByZero,
+                    format!("{:?} by zero (dividend {})", fun, describe_argument(args[0])),
+                )
+            })
+        }
+        DefaultFunction::IntegerToByteString => {
+            let width = integer_argument(args.get(1)?)?;
+            let integer = integer_argument(args.get(2)?)?;
+            let needed = integer.magnitude().bits().div_ceil(8);
+            let message = if integer.sign() == Sign::Minus {
+                format!("IntegerToByteString of negative integer {}", integer)
+            } else if *width > BigInt::from(INTEGER_TO_BYTESTRING_MAX_WIDTH) {
+                format!("IntegerToByteString width {} exceeds {} bytes", width, INTEGER_TO_BYTESTRING_MAX_WIDTH)
+            } else if width.sign() == Sign::NoSign && needed > INTEGER_TO_BYTESTRING_MAX_WIDTH {
+                format!("IntegerToByteString needs {} bytes, more than {}", needed, INTEGER_TO_BYTESTRING_MAX_WIDTH)
+            } else if width.sign() == Sign::Plus && BigInt::from(needed) > *width {
+                format!("IntegerToByteString needs {} bytes but width is {}", needed, width)
+            } else {
+                return None;
+            };
+            Some((BuiltinMisuseKind::IntegerOverflow, message))
+        }
+        _ => None,
+    }
+}
+
+/// Check the arithmetic builtin the step from `state` saturates for arguments that
+/// make it fail: division by zero, or integerToByteString overflow. Reported
+/// before the step, since the machine's own error names neither term nor operands.
+pub fn check_arithmetic(state: &MachineState, step: u64) -> Option<BuiltinMisuse> {
+    let MachineState::Return(context, value) = state else {
+        return None;
+    };
+    let (function, last) = match context {
+        Context::FrameAwaitArg(function, ..) => (function, value),
+        Context::FrameAwaitFunValue(argument, ..) => (value, argument),
+        _ => return None,
+    };
+    let Value::Builtin { fun, runtime, term_id } = function else {
+        return None;
+    };
+    if runtime.args.len() + 1 != fun.arity() {
+        return None;
+    }
+
+    let args: Vec<&Value> = runtime.args.iter().chain([last]).collect();
+    let (kind, message) = arithmetic_failure(fun, &args)?;
+    Some(BuiltinMisuse {
+        kind,
+        builtin: format!("{:?}", fun),
+        builtin_term_id: *term_id as i32,
+        term_id: None,
+        step: Some(step),
+        message,
+        arguments: args.iter().map(|arg| describe_argument(arg)).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin(fun: DefaultFunction, id: usize) -> Term<NamedDeBruijn> {
+        Term::Builtin { fun, uniq_id: id as _ }
+    }
+
+    fn apply(function: Term<NamedDeBruijn>, id: usize) -> Term<NamedDeBruijn> {
+        Term::Apply {
+            function: function.into(),
+            argument: Term::Constant { value: uplc::ast::Constant::Unit.into(), uniq_id: (100 + id) as _ }.into(),
+            uniq_id: id as _,
+        }
+    }
+
+    fn force(body: Term<NamedDeBruijn>, id: usize) -> Term<NamedDeBruijn> {
+        Term::Force { body: body.into(), uniq_id: id as _ }
+    }
+
+    #[test]
+    fn test_scan_builtin_spines() {
+        // ifThenElse takes one force and three arguments
+        let ok = apply(apply(apply(force(builtin(DefaultFunction::IfThenElse, 1), 2), 3), 4), 5);
+        assert!(scan_builtin_misuse(&ok).is_empty());
+
+        let missing_force = apply(builtin(DefaultFunction::IfThenElse, 1), 2);
+        let warnings = scan_builtin_misuse(&missing_force);
+        assert_eq!(warnings[0].kind, BuiltinMisuseKind::MissingForce);
+        assert_eq!(warnings[0].term_id, Some(2));
+
+        let excess_arg = apply(apply(apply(builtin(DefaultFunction::AddInteger, 1), 2), 3), 4);
+        let warnings = scan_builtin_misuse(&excess_arg);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, BuiltinMisuseKind::ExcessArgument);
+        assert_eq!(warnings[0].term_id, Some(4));
+
+        let excess_force = force(builtin(DefaultFunction::AddInteger, 1), 2);
+        assert_eq!(scan_builtin_misuse(&excess_force)[0].kind, BuiltinMisuseKind::ExcessForce);
+    }
+
+    #[test]
+    fn test_builtin_by_name() {
+        assert_eq!(builtin_by_name("VerifyEd25519Signature"), Some(DefaultFunction::VerifyEd25519Signature));
+        assert_eq!(builtin_by_name("verifyEd25519Signature"), Some(DefaultFunction::VerifyEd25519Signature));
+        assert_eq!(builtin_by_name("bls12_381_G1_add"), Some(DefaultFunction::Bls12_381_G1_Add));
+        assert_eq!(builtin_by_name("verifySignature"), None);
+    }
+
+    #[test]
+    fn test_arithmetic_failures() {
+        let int = |i: i64| Value::Con(Constant::Integer(i.into()).into());
+        let (seven, zero, big) = (int(7), int(0), int(70_000));
+
+        let (kind, _) = arithmetic_failure(&DefaultFunction::ModInteger, &[&seven, &zero]).unwrap();
+        assert_eq!(kind, BuiltinMisuseKind::DivisionByZero);
+        assert!(arithmetic_failure(&DefaultFunction::DivideInteger, &[&seven, &seven]).is_none());
+
+        let endianness = Value::Con(Constant::Bool(true).into());
+        let one = int(1);
+        let (kind, message) =
+            arithmetic_failure(&DefaultFunction::IntegerToByteString, &[&endianness, &one, &big]).unwrap();
+        assert_eq!(kind, BuiltinMisuseKind::IntegerOverflow);
+        assert_eq!(message, "IntegerToByteString needs 3 bytes but width is 1");
+        assert!(arithmetic_failure(&DefaultFunction::IntegerToByteString, &[&endianness, &zero, &big]).is_none());
+    }
+}
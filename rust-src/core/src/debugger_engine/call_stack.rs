@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::machine::{value::Value, Context};
+
+use super::aiken_traces::{AikenSourceLocation, AikenSourceMap};
+use super::decisions::term_id;
+use super::state_summary::frame_type;
+
+/// A machine context frame as a caller would read it in a stack trace
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CallStackFrame {
+    /// Index of the frame in `get_machine_context`, innermost first
+    pub depth: usize,
+    /// Kind of the frame, e.g. "FrameAwaitArg"
+    pub frame_type: String,
+    /// Term the frame came from or continues with, when known
+    pub term_id: Option<i32>,
+    /// Parameter of the Lambda the frame is about to call, if any
+    pub parameter_name: Option<String>,
+    /// What the frame waits for, e.g. "Calling λdatum with the argument being computed"
+    pub description: String,
+    pub source: Option<AikenSourceLocation>,
+}
+
+fn callee(value: &Value) -> (Option<i32>, Option<String>, String) {
+    match value {
+        Value::Lambda { parameter_name, term_id, .. } => (
+            Some(*term_id as i32),
+            Some(parameter_name.text.clone()),
+            format!("λ{}", parameter_name.text),
+        ),
+        Value::Builtin { fun, term_id, .. } => (Some(*term_id as i32), None, fun.to_string()),
+        Value::Delay { term_id, .. } => (Some(*term_id as i32), None, "a delayed term".to_string()),
+        Value::Constr { tag, term_id, .. } => (Some(*term_id as i32), None, format!("Constr {}", tag)),
+        Value::Con(_) => (None, None, "a constant".to_string()),
+    }
+}
+
+/// Term id, called parameter and description of a frame
+pub(crate) fn describe_frame(
+    context: &Context,
+    case_branches: &HashMap<i32, (i32, usize)>,
+) -> (Option<i32>, Option<String>, String) {
+    match context {
+        Context::FrameAwaitArg(function, ..) => {
+            let (term_id, parameter_name, name) = callee(function);
+            (term_id, parameter_name, format!("Calling {} with the argument being computed", name))
+        }
+        Context::FrameAwaitFunTerm(_, argument, ..) => (
+            Some(term_id(argument)),
+            None,
+            format!("Computing the function to apply to term {}", term_id(argument)),
+        ),
+        Context::FrameAwaitFunValue(field, ..) => {
+            let (term_id, _, name) = callee(field);
+            (term_id, None, format!("Passing {} to the chosen case branch", name))
+        }
+        Context::FrameForce(..) => (None, None, "Forcing the value being computed".to_string()),
+        Context::FrameConstr(_, tag, terms, values, .., constr_id) => (
+            Some(*constr_id as i32),
+            None,
+            format!(
+                "Building Constr {}: field {} of {}",
+                tag,
+                values.len() + 1,
+                values.len() + terms.len() + 1
+            ),
+        ),
+        Context::FrameCases(_, branches, ..) => (
+            branches
+                .first()
+                .and_then(|branch| case_branches.get(&term_id(branch)))
+                .map(|(case_id, _)| *case_id),
+            None,
+            format!("Choosing among {} case branches", branches.len()),
+        ),
+        Context::NoFrame => (None, None, "Program entry".to_string()),
+    }
+}
+
+/// Summarizes the machine context frames, innermost first, into a stack trace
+pub fn call_stack(
+    contexts: &[Context],
+    case_branches: &HashMap<i32, (i32, usize)>,
+    source_map: Option<&AikenSourceMap>,
+) -> Vec<CallStackFrame> {
+    contexts
+        .iter()
+        .enumerate()
+        .map(|(depth, context)| {
+            let (term_id, parameter_name, description) = describe_frame(context, case_branches);
+            CallStackFrame {
+                depth,
+                frame_type: frame_type(context).to_string(),
+                term_id,
+                parameter_name,
+                description,
+                source: term_id.and_then(|id| source_map.and_then(|map| map.terms.get(&id).cloned())),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debugger_engine::sandbox::parse_expression;
+    use crate::debugger_engine::session_controller::DEFAULT_SLIPPAGE;
+    use pallas_primitives::conway::Language;
+    use uplc::{
+        machine::cost_model::{CostModel, ExBudget},
+        manual_machine::ManualMachine,
+    };
+
+    /// Steps `expression` until its innermost frame is of `frame`, returning the frames
+    fn frames_at(expression: &str, frame: &str) -> Vec<Context> {
+        let program = parse_expression(expression, (1, 1, 0)).unwrap();
+        let mut machine =
+            ManualMachine::new(Language::PlutusV3, CostModel::default(), ExBudget::max(), DEFAULT_SLIPPAGE, program.term)
+                .unwrap();
+        for _ in 0..20 {
+            let contexts = machine.collect_nested_contexts();
+            if contexts.first().map(frame_type) == Some(frame) {
+                return contexts;
+            }
+            machine.step();
+        }
+        panic!("{} never reached {}", expression, frame);
+    }
+
+    #[test]
+    fn test_nested_apply_call_stack() {
+        let contexts = frames_at("[[(lam x (lam y x)) (con integer 1)] (con integer 2)]", "FrameAwaitArg");
+        let stack = call_stack(&contexts, &HashMap::new(), None);
+
+        // Innermost: λx waits for its argument; below it the outer Apply still has to compute its own
+        assert_eq!(stack[0].depth, 0);
+        assert_eq!(stack[0].parameter_name.as_deref(), Some("x"));
+        assert_eq!(stack[0].description, "Calling λx with the argument being computed");
+        assert_eq!(stack[1].depth, 1);
+        assert_eq!(stack[1].frame_type, "FrameAwaitFunTerm");
+        assert!(stack[1].parameter_name.is_none());
+        assert!(stack[1].description.starts_with("Computing the function to apply to term"));
+        assert!(stack.iter().skip(2).all(|frame| frame.frame_type == "NoFrame"));
+    }
+
+    #[test]
+    fn test_force_and_builtin_frames() {
+        let contexts = frames_at("(force (delay (con integer 1)))", "FrameForce");
+        let stack = call_stack(&contexts, &HashMap::new(), None);
+        assert_eq!((stack[0].term_id, stack[0].description.as_str()), (None, "Forcing the value being computed"));
+
+        let contexts = frames_at("[(builtin iData) (con integer 1)]", "FrameAwaitArg");
+        let stack = call_stack(&contexts, &HashMap::new(), None);
+        assert_eq!(stack[0].description, "Calling iData with the argument being computed");
+        assert!(stack[0].parameter_name.is_none());
+    }
+}
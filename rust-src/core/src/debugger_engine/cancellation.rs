@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::wasm_tools::wasm_bindgen;
+
+/// Steps between two reads of the wall clock in a timed run; reading it every
+/// step would cost more than the step itself under wasm
+pub const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+/// Stops a session's run loop at the next step it checks. The flag is shared
+/// with the session, so a host thread, a worker sharing memory or the progress
+/// callback can cancel a run in progress. Each run clears the flag when it
+/// starts, so a cancel made between runs is dropped.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct RunCancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[wasm_bindgen]
+impl RunCancelToken {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl RunCancelToken {
+    /// Consumes a pending cancel, so the run after a cancelled one proceeds
+    pub(crate) fn take(&self) -> bool {
+        self.cancelled.swap(false, Ordering::Relaxed)
+    }
+
+    pub(crate) fn clear(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_is_consumed_once() {
+        let token = RunCancelToken::default();
+        let shared = token.clone();
+        shared.cancel();
+        assert!(token.is_cancelled());
+        assert!(token.take());
+        assert!(!token.take());
+    }
+}
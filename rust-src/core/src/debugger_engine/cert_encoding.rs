@@ -0,0 +1,109 @@
+use pallas_codec::minicbor;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    tx::{
+        script_context::{ScriptContext, ScriptInfo, ScriptPurpose},
+        to_plutus_data::ToPlutusData,
+    },
+    PlutusData,
+};
+
+use crate::plutus_data::SerializablePlutusData;
+use crate::script_context::SerializableCertificate;
+
+/// Position of the Certifying constructor in both ScriptPurpose (V1/V2) and ScriptInfo (V3)
+const CERTIFYING_CONSTR_INDEX: u64 = 3;
+
+/// A certificate as decoded from the transaction next to the data the certifying script receives for it
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CertificateEncoding {
+    pub redeemer: String,
+    /// Index of the certificate in the transaction body
+    pub index: usize,
+    /// "V1V2" or "V3", the ledger encoding rules the data follows
+    pub encoding: String,
+    pub certificate: SerializableCertificate,
+    /// Exactly as found in the script context applied to the script
+    pub plutus_data: SerializablePlutusData,
+    pub plutus_data_cbor: String,
+}
+
+/// Constructor index and fields of a Constr, decoding the CBOR tag ranges
+pub(crate) fn constr_fields(data: &PlutusData) -> Option<(u64, &[PlutusData])> {
+    let PlutusData::Constr(constr) = data else {
+        return None;
+    };
+    let index = match constr.tag {
+        121..=127 => constr.tag - 121,
+        1280..=1400 => constr.tag - 1280 + 7,
+        102 => constr.any_constructor?,
+        _ => return None,
+    };
+    Some((index, constr.fields.as_slice()))
+}
+
+/// Certificate data inside an encoded script context: the purpose is the last
+/// field of the context, and the certificate the last field of a Certifying purpose
+fn certificate_data(context_data: &PlutusData) -> Option<&PlutusData> {
+    let (_, context_fields) = constr_fields(context_data)?;
+    let (purpose_index, purpose_fields) = constr_fields(context_fields.last()?)?;
+    if purpose_index != CERTIFYING_CONSTR_INDEX {
+        return None;
+    }
+    purpose_fields.last()
+}
+
+/// Pairs the certificate of a certifying script context with its encoded form.
+/// Returns `None` for any other purpose.
+pub fn certificate_encoding(redeemer: &str, context: &ScriptContext) -> Result<Option<CertificateEncoding>, String> {
+    let (index, certificate, encoding) = match context {
+        ScriptContext::V1V2 { purpose, .. } => match purpose.as_ref() {
+            ScriptPurpose::Certifying(index, certificate) => (*index, certificate, "V1V2"),
+            _ => return Ok(None),
+        },
+        ScriptContext::V3 { purpose, .. } => match purpose.as_ref() {
+            ScriptInfo::Certifying(index, certificate) => (*index, certificate, "V3"),
+            _ => return Ok(None),
+        },
+    };
+
+    let context_data = context.to_plutus_data();
+    let data = certificate_data(&context_data)
+        .ok_or_else(|| format!("Certificate not found in the encoded script context of {}", redeemer))?;
+    let cbor = minicbor::to_vec(data).map_err(|e| e.to_string())?;
+
+    Ok(Some(CertificateEncoding {
+        redeemer: redeemer.to_string(),
+        index,
+        encoding: encoding.to_string(),
+        certificate: certificate.clone().into(),
+        plutus_data: data.into(),
+        plutus_data_cbor: hex::encode(cbor),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pallas_codec::utils::MaybeIndefArray;
+    use pallas_primitives::Constr;
+
+    fn constr(tag: u64, fields: Vec<PlutusData>) -> PlutusData {
+        PlutusData::Constr(Constr {
+            tag,
+            any_constructor: None,
+            fields: MaybeIndefArray::Def(fields),
+        })
+    }
+
+    #[test]
+    fn test_certificate_data() {
+        let certificate = constr(122, vec![]);
+        let context = constr(121, vec![constr(121, vec![]), constr(124, vec![certificate.clone()])]);
+        assert_eq!(certificate_data(&context), Some(&certificate));
+
+        let minting = constr(121, vec![constr(121, vec![]), constr(121, vec![])]);
+        assert_eq!(certificate_data(&minting), None);
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Chunk size used when the caller doesn't pick one
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+/// Exports kept at once; preparing another drops the oldest
+const MAX_PREPARED_EXPORTS: usize = 8;
+
+/// Describes a prepared export, to be fetched with `get_export_chunk`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ExportHandle {
+    pub export_id: u32,
+    /// What was exported, e.g. "trace" or "script"
+    pub kind: String,
+    /// Size of the whole export in UTF-8 bytes
+    pub total_bytes: usize,
+    /// Largest chunk size in bytes; chunks end on character boundaries, so some are shorter
+    pub chunk_size: usize,
+    pub chunk_count: usize,
+}
+
+#[derive(Debug, Clone)]
+struct PreparedExport {
+    handle: ExportHandle,
+    content: String,
+    /// Byte offset of every chunk start, followed by the content length
+    boundaries: Vec<usize>,
+}
+
+fn chunk_boundaries(content: &str, chunk_size: usize) -> Vec<usize> {
+    let mut boundaries = vec![0];
+    let mut start = 0;
+    while start < content.len() {
+        let mut end = (start + chunk_size).min(content.len());
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        // A chunk smaller than one character still has to make progress
+        if end == start {
+            end = start + content[start..].chars().next().map_or(1, char::len_utf8);
+        }
+        boundaries.push(end);
+        start = end;
+    }
+    boundaries
+}
+
+/// Exports held by a session until every chunk has been fetched
+#[derive(Debug, Clone, Default)]
+pub struct ExportStore {
+    next_id: u32,
+    exports: BTreeMap<u32, PreparedExport>,
+}
+
+impl ExportStore {
+    pub fn prepare(&mut self, kind: &str, content: String, chunk_size: usize) -> ExportHandle {
+        let chunk_size = chunk_size.max(1);
+        let boundaries = chunk_boundaries(&content, chunk_size);
+        let handle = ExportHandle {
+            export_id: self.next_id,
+            kind: kind.to_string(),
+            total_bytes: content.len(),
+            chunk_size,
+            chunk_count: boundaries.len() - 1,
+        };
+        self.next_id += 1;
+        if self.exports.len() >= MAX_PREPARED_EXPORTS {
+            self.exports.pop_first();
+        }
+        self.exports.insert(
+            handle.export_id,
+            PreparedExport {
+                handle: handle.clone(),
+                content,
+                boundaries,
+            },
+        );
+        handle
+    }
+
+    pub fn chunk(&self, export_id: u32, index: usize) -> Result<&str, String> {
+        let export = self
+            .exports
+            .get(&export_id)
+            .ok_or_else(|| format!("Unknown export {}", export_id))?;
+        if index >= export.handle.chunk_count {
+            return Err(format!(
+                "Export {} has {} chunks, no chunk {}",
+                export_id, export.handle.chunk_count, index
+            ));
+        }
+        Ok(&export.content[export.boundaries[index]..export.boundaries[index + 1]])
+    }
+
+    /// Up to `max_bytes` of an export's UTF-8 bytes from `offset`; empty at the end
+    pub fn bytes(&self, export_id: u32, offset: usize, max_bytes: usize) -> Result<&[u8], String> {
+        let content = &self
+            .exports
+            .get(&export_id)
+            .ok_or_else(|| format!("Unknown export {}", export_id))?
+            .content;
+        if offset > content.len() {
+            return Err(format!("Offset {} is past the end of the {} byte export", offset, content.len()));
+        }
+        Ok(&content.as_bytes()[offset..offset.saturating_add(max_bytes).min(content.len())])
+    }
+
+    pub fn contains(&self, export_id: u32) -> bool {
+        self.exports.contains_key(&export_id)
+    }
+
+    pub fn release(&mut self, export_id: u32) -> bool {
+        self.exports.remove(&export_id).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_split_on_char_boundaries() {
+        let mut store = ExportStore::default();
+        let handle = store.prepare("script", "ab\u{3bb}cd\u{3bb}".to_string(), 3);
+        assert_eq!((handle.total_bytes, handle.chunk_count), (8, 3));
+
+        let chunks: Vec<&str> = (0..handle.chunk_count)
+            .map(|index| store.chunk(handle.export_id, index).unwrap())
+            .collect();
+        assert_eq!(chunks, vec!["ab", "\u{3bb}c", "d\u{3bb}"]);
+        assert!(store.chunk(handle.export_id, 3).is_err());
+
+        let empty = store.prepare("logs", String::new(), 3);
+        assert_eq!(empty.chunk_count, 0);
+        assert!(store.release(handle.export_id));
+        assert!(store.chunk(handle.export_id, 0).is_err());
+    }
+
+    #[test]
+    fn test_bytes_by_offset() {
+        let mut store = ExportStore::default();
+        let handle = store.prepare("script", "ab\u{3bb}cd".to_string(), 3);
+        assert_eq!(store.bytes(handle.export_id, 0, 3).unwrap(), b"ab\xce");
+        assert_eq!(store.bytes(handle.export_id, 3, 10).unwrap(), b"\xbbcd");
+        assert!(store.bytes(handle.export_id, 6, 10).unwrap().is_empty());
+        assert!(store.bytes(handle.export_id, 7, 10).is_err());
+        assert!(store.bytes(handle.export_id + 1, 0, 10).is_err());
+    }
+}
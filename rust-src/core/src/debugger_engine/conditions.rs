@@ -0,0 +1,466 @@
+use std::rc::Rc;
+
+use uplc::{ast::Constant, machine::value::Value};
+
+/// State a breakpoint condition is evaluated against
+pub struct ConditionContext<'a> {
+    pub cpu: i64,
+    pub mem: i64,
+    pub step: u64,
+    pub term_id: i32,
+    /// Builtin of the current term, or of the value being returned
+    pub current_builtin: Option<String>,
+    /// Values of the current env, in the order they are serialized
+    pub env: Option<&'a [Value]>,
+}
+
+/// Parsed breakpoint condition, e.g. `env[2] is Constr tag 1 && budget.cpu > 5e9`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Compare(Operand, Comparison, Operand),
+    Is(Operand, Pattern),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Cpu,
+    Mem,
+    Step,
+    TermId,
+    CurrentBuiltin,
+    Env(usize),
+    Int(i128),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Constr(Option<usize>),
+    Kind(String),
+}
+
+/// Value an operand evaluates to
+#[derive(Debug, PartialEq)]
+enum Scalar {
+    Int(i128),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Str(String),
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Op(&'static str),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.' || chars[i] == '_'
+                || (chars[i] == '+' && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c == '"' || c == '\'' {
+            let start = i + 1;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err("Unterminated string literal".to_string());
+            }
+            tokens.push(Token::Str(chars[start..i].iter().collect()));
+            i += 1;
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let token = match two.as_str() {
+                "==" => Some(Token::Op("==")),
+                "!=" => Some(Token::Op("!=")),
+                ">=" => Some(Token::Op(">=")),
+                "<=" => Some(Token::Op("<=")),
+                "&&" => Some(Token::Op("&&")),
+                "||" => Some(Token::Op("||")),
+                _ => None,
+            };
+            if let Some(token) = token {
+                tokens.push(token);
+                i += 2;
+                continue;
+            }
+            tokens.push(match c {
+                '[' => Token::LBracket,
+                ']' => Token::RBracket,
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                '>' => Token::Op(">"),
+                '<' => Token::Op("<"),
+                '!' => Token::Op("!"),
+                '=' => Token::Op("=="),
+                _ => return Err(format!("Unexpected character '{}'", c)),
+            });
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_number(text: &str) -> Result<i128, String> {
+    let cleaned = text.replace('_', "");
+    if let Ok(value) = cleaned.parse::<i128>() {
+        return Ok(value);
+    }
+    // Scientific notation such as 5e9
+    match cleaned.parse::<f64>() {
+        Ok(value) if value.is_finite() && value.fract() == 0.0 => Ok(value as i128),
+        _ => Err(format!("Invalid number '{}'", text)),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(format!("Expected {:?}, found {:?}", expected, other)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut left = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Op("||"))) || self.peek_keyword("or") {
+            self.next();
+            let right = self.parse_and()?;
+            left = Condition::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut left = self.parse_not()?;
+        while matches!(self.peek(), Some(Token::Op("&&"))) || self.peek_keyword("and") {
+            self.next();
+            let right = self.parse_not()?;
+            left = Condition::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Condition, String> {
+        if matches!(self.peek(), Some(Token::Op("!"))) || self.peek_keyword("not") {
+            self.next();
+            return Ok(Condition::Not(Box::new(self.parse_not()?)));
+        }
+        if matches!(self.peek(), Some(Token::LParen)) {
+            self.next();
+            let condition = self.parse_or()?;
+            self.expect(Token::RParen)?;
+            return Ok(condition);
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Condition, String> {
+        let left = self.parse_operand()?;
+        if self.peek_keyword("is") {
+            self.next();
+            return Ok(Condition::Is(left, self.parse_pattern()?));
+        }
+        let comparison = match self.next() {
+            Some(Token::Op("==")) => Comparison::Eq,
+            Some(Token::Op("!=")) => Comparison::Ne,
+            Some(Token::Op("<")) => Comparison::Lt,
+            Some(Token::Op("<=")) => Comparison::Le,
+            Some(Token::Op(">")) => Comparison::Gt,
+            Some(Token::Op(">=")) => Comparison::Ge,
+            other => return Err(format!("Expected a comparison or 'is', found {:?}", other)),
+        };
+        let right = self.parse_operand()?;
+        Ok(Condition::Compare(left, comparison, right))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Operand::Int(parse_number(&number)?)),
+            Some(Token::Str(text)) => Ok(Operand::Text(text)),
+            Some(Token::Ident(ident)) => match ident.to_lowercase().as_str() {
+                "budget.cpu" | "cpu" => Ok(Operand::Cpu),
+                "budget.mem" | "mem" => Ok(Operand::Mem),
+                "step" => Ok(Operand::Step),
+                "term_id" | "term" => Ok(Operand::TermId),
+                "builtin" => Ok(Operand::CurrentBuiltin),
+                "current" => {
+                    if self.peek_keyword("builtin") {
+                        self.next();
+                        Ok(Operand::CurrentBuiltin)
+                    } else {
+                        Err("Expected 'builtin' after 'current'".to_string())
+                    }
+                }
+                "env" => {
+                    self.expect(Token::LBracket)?;
+                    let index = match self.next() {
+                        Some(Token::Number(number)) => number
+                            .parse::<usize>()
+                            .map_err(|_| format!("Invalid env index '{}'", number))?,
+                        other => return Err(format!("Expected env index, found {:?}", other)),
+                    };
+                    self.expect(Token::RBracket)?;
+                    Ok(Operand::Env(index))
+                }
+                _ => Ok(Operand::Text(ident)),
+            },
+            other => Err(format!("Expected an operand, found {:?}", other)),
+        }
+    }
+
+    fn parse_pattern(&mut self) -> Result<Pattern, String> {
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name,
+            other => return Err(format!("Expected a pattern, found {:?}", other)),
+        };
+        if name.eq_ignore_ascii_case("constr") {
+            if self.peek_keyword("tag") {
+                self.next();
+                return match self.next() {
+                    Some(Token::Number(number)) => number
+                        .parse::<usize>()
+                        .map(|tag| Pattern::Constr(Some(tag)))
+                        .map_err(|_| format!("Invalid constructor tag '{}'", number)),
+                    other => Err(format!("Expected constructor tag, found {:?}", other)),
+                };
+            }
+            return Ok(Pattern::Constr(None));
+        }
+        Ok(Pattern::Kind(name))
+    }
+}
+
+impl Condition {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        if parser.tokens.is_empty() {
+            return Err("Empty condition".to_string());
+        }
+        let condition = parser.parse_or()?;
+        if parser.position < parser.tokens.len() {
+            return Err(format!("Unexpected {:?}", parser.tokens[parser.position]));
+        }
+        Ok(condition)
+    }
+
+    /// Evaluates the condition; operands that cannot be resolved (e.g. an env
+    /// index out of range) make the enclosing comparison false
+    pub fn evaluate(&self, context: &ConditionContext) -> bool {
+        match self {
+            Condition::And(left, right) => left.evaluate(context) && right.evaluate(context),
+            Condition::Or(left, right) => left.evaluate(context) || right.evaluate(context),
+            Condition::Not(inner) => !inner.evaluate(context),
+            Condition::Compare(left, comparison, right) => {
+                match (left.resolve(context), right.resolve(context)) {
+                    (Some(Scalar::Int(left)), Some(Scalar::Int(right))) => match comparison {
+                        Comparison::Eq => left == right,
+                        Comparison::Ne => left != right,
+                        Comparison::Lt => left < right,
+                        Comparison::Le => left <= right,
+                        Comparison::Gt => left > right,
+                        Comparison::Ge => left >= right,
+                    },
+                    (Some(Scalar::Text(left)), Some(Scalar::Text(right))) => match comparison {
+                        Comparison::Eq => left.eq_ignore_ascii_case(&right),
+                        Comparison::Ne => !left.eq_ignore_ascii_case(&right),
+                        _ => false,
+                    },
+                    (Some(_), Some(_)) => *comparison == Comparison::Ne,
+                    _ => false,
+                }
+            }
+            Condition::Is(operand, pattern) => match operand {
+                Operand::Env(index) => context
+                    .env
+                    .and_then(|env| env.get(*index))
+                    .is_some_and(|value| pattern.matches(value)),
+                Operand::CurrentBuiltin => match pattern {
+                    Pattern::Kind(name) => context
+                        .current_builtin
+                        .as_ref()
+                        .is_some_and(|builtin| builtin.eq_ignore_ascii_case(name)),
+                    Pattern::Constr(_) => false,
+                },
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Operand {
+    fn resolve(&self, context: &ConditionContext) -> Option<Scalar> {
+        match self {
+            Operand::Cpu => Some(Scalar::Int(context.cpu as i128)),
+            Operand::Mem => Some(Scalar::Int(context.mem as i128)),
+            Operand::Step => Some(Scalar::Int(context.step as i128)),
+            Operand::TermId => Some(Scalar::Int(context.term_id as i128)),
+            Operand::CurrentBuiltin => context.current_builtin.clone().map(Scalar::Text),
+            Operand::Env(index) => context.env.and_then(|env| env.get(*index)).map(value_scalar),
+            Operand::Int(value) => Some(Scalar::Int(*value)),
+            Operand::Text(text) => Some(Scalar::Text(text.clone())),
+        }
+    }
+}
+
+fn value_scalar(value: &Value) -> Scalar {
+    match value {
+        Value::Con(constant) => match constant.as_ref() {
+            Constant::Integer(integer) => match i128::try_from(integer) {
+                Ok(integer) => Scalar::Int(integer),
+                Err(_) => Scalar::Text(integer.to_string()),
+            },
+            Constant::ByteString(bytes) => Scalar::Text(hex::encode(bytes)),
+            Constant::String(text) => Scalar::Text(text.clone()),
+            Constant::Bool(b) => Scalar::Text(if *b { "True" } else { "False" }.to_string()),
+            Constant::Unit => Scalar::Text("()".to_string()),
+            _ => Scalar::Text(constant_kind(constant).to_string()),
+        },
+        Value::Builtin { fun, .. } => Scalar::Text(format!("{:?}", fun)),
+        Value::Constr { tag, .. } => Scalar::Int(*tag as i128),
+        Value::Delay { .. } => Scalar::Text("Delay".to_string()),
+        Value::Lambda { .. } => Scalar::Text("Lambda".to_string()),
+    }
+}
+
+fn constant_kind(constant: &Rc<Constant>) -> &'static str {
+    match constant.as_ref() {
+        Constant::Integer(_) => "Integer",
+        Constant::ByteString(_) => "ByteString",
+        Constant::String(_) => "String",
+        Constant::Bool(_) => "Bool",
+        Constant::Unit => "Unit",
+        Constant::ProtoList(_, _) => "List",
+        Constant::ProtoPair(_, _, _, _) => "Pair",
+        Constant::Data(_) => "Data",
+        Constant::Bls12_381G1Element(_) => "Bls12_381G1Element",
+        Constant::Bls12_381G2Element(_) => "Bls12_381G2Element",
+        Constant::Bls12_381MlResult(_) => "Bls12_381MlResult",
+    }
+}
+
+impl Pattern {
+    fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Pattern::Constr(None), Value::Constr { .. }) => true,
+            (Pattern::Constr(Some(expected)), Value::Constr { tag, .. }) => tag == expected,
+            (Pattern::Constr(_), _) => false,
+            (Pattern::Kind(name), value) => {
+                let name = name.to_lowercase();
+                match value {
+                    Value::Con(constant) => {
+                        name == "con"
+                            || name == constant_kind(constant).to_lowercase()
+                            || matches!(constant.as_ref(), Constant::Bool(b) if name == b.to_string())
+                    }
+                    Value::Delay { .. } => name == "delay",
+                    Value::Lambda { .. } => name == "lambda",
+                    Value::Builtin { fun, .. } => name == "builtin" || name == format!("{:?}", fun).to_lowercase(),
+                    Value::Constr { .. } => name == "constr",
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(env: &[Value]) -> ConditionContext<'_> {
+        ConditionContext {
+            cpu: 6_000_000_000,
+            mem: 100,
+            step: 42,
+            term_id: 7,
+            current_builtin: Some("EqualsData".to_string()),
+            env: Some(env),
+        }
+    }
+
+    #[test]
+    fn test_parse_and_evaluate_conditions() {
+        let env = vec![
+            Value::Con(Rc::new(Constant::Integer(3.into()))),
+            Value::Con(Rc::new(Constant::Bool(true))),
+            Value::Constr { tag: 1, fields: vec![], term_id: 0 },
+        ];
+        let context = context(&env);
+
+        let holds = |condition: &str| Condition::parse(condition).unwrap().evaluate(&context);
+        assert!(holds("env[2] is Constr tag 1"));
+        assert!(!holds("env[2] is Constr tag 0"));
+        assert!(holds("budget.cpu > 5e9"));
+        assert!(holds("current builtin == EqualsData"));
+        assert!(holds("env[0] >= 3 && (step == 42 || mem > 1000)"));
+        assert!(holds("not env[1] is False"));
+        assert!(!holds("env[9] is Constr"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Condition::parse("").is_err());
+        assert!(Condition::parse("env[").is_err());
+        assert!(Condition::parse("step >").is_err());
+        assert!(Condition::parse("step == 1 )").is_err());
+    }
+}
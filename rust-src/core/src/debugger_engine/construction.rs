@@ -0,0 +1,109 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::wasm_tools::now_millis;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum StageStatus {
+    Ok,
+    /// Failed for some items; the engine is usable without them
+    Partial,
+    Failed,
+}
+
+/// One phase of building an engine from a transaction
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ConstructionStage {
+    /// "decode_hex", "decode_transaction", "convert_utxos", "find_scripts" or "build_tx_info_v1/v2/v3"
+    pub stage: String,
+    pub status: StageStatus,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// A redeemer left out of the engine because its script could not be resolved
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RedeemerFailure {
+    pub redeemer: String,
+    pub error: String,
+}
+
+/// Phases of engine construction in the order they ran. Construction stops at the
+/// first failed stage, except for TxInfo builds and script lookups, whose failures
+/// only make the affected redeemers unavailable.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct ConstructionReport {
+    pub stages: Vec<ConstructionStage>,
+    pub failed_redeemers: Vec<RedeemerFailure>,
+    /// Whether an engine was built
+    pub completed: bool,
+}
+
+impl ConstructionReport {
+    /// Runs a stage, recording its duration and outcome
+    pub fn run<T, E: ToString>(&mut self, stage: &str, run: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let started = now_millis();
+        let result = run();
+        let status = if result.is_ok() { StageStatus::Ok } else { StageStatus::Failed };
+        self.push(stage, status, started, result.as_ref().err().map(ToString::to_string));
+        result
+    }
+
+    pub fn record_failure(&mut self, stage: &str, started: u64, error: &impl ToString) {
+        self.push(stage, StageStatus::Failed, started, Some(error.to_string()));
+    }
+
+    /// Records a stage that went on after `failures` of its items failed
+    pub fn record_partial(&mut self, stage: &str, started: u64, failures: Vec<RedeemerFailure>) {
+        let (status, error) = match failures.len() {
+            0 => (StageStatus::Ok, None),
+            count => (StageStatus::Partial, Some(format!("{} redeemer(s) unavailable", count))),
+        };
+        self.push(stage, status, started, error);
+        self.failed_redeemers.extend(failures);
+    }
+
+    fn push(&mut self, stage: &str, status: StageStatus, started: u64, error: Option<String>) {
+        self.stages.push(ConstructionStage {
+            stage: stage.to_string(),
+            status,
+            duration_ms: now_millis().saturating_sub(started),
+            error,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages_record_their_outcome() {
+        let mut report = ConstructionReport::default();
+        assert_eq!(report.run("decode_hex", || Ok::<_, String>(1)), Ok(1));
+        assert!(report.run("decode_transaction", || Err::<(), _>("bad cbor".to_string())).is_err());
+        report.record_partial("find_scripts", now_millis(), vec![]);
+        report.record_partial(
+            "build_tx_info_v3",
+            now_millis(),
+            vec![RedeemerFailure {
+                redeemer: "Spend:0".to_string(),
+                error: "missing".to_string(),
+            }],
+        );
+
+        let statuses: Vec<_> = report.stages.iter().map(|stage| (stage.stage.as_str(), stage.status.clone())).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("decode_hex", StageStatus::Ok),
+                ("decode_transaction", StageStatus::Failed),
+                ("find_scripts", StageStatus::Ok),
+                ("build_tx_info_v3", StageStatus::Partial),
+            ]
+        );
+        assert_eq!(report.stages[1].error.as_deref(), Some("bad cbor"));
+        assert_eq!(report.failed_redeemers.len(), 1);
+        assert!(!report.completed);
+    }
+}
@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{tx::script_context::ScriptContext, PlutusData};
+
+/// The script context of a redeemer and the arguments its script is applied to,
+/// shared by every session of that redeemer
+#[derive(Debug, Clone)]
+pub struct PreparedContext {
+    pub script_context: Arc<ScriptContext>,
+    pub arguments: Arc<Vec<PlutusData>>,
+}
+
+/// How often the engine had to copy a TxInfo to build a script context, and how
+/// often sessions reused a context built earlier
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct ContextCacheStats {
+    /// Deep copies of a TxInfo, one per redeemer whose context was built
+    pub tx_info_clones: u64,
+    /// Sessions and exports that reused a cached context
+    pub hits: u64,
+    /// Redeemers with a cached context
+    pub cached_redeemers: usize,
+}
+
+/// Script contexts by redeemer. `TxInfo::into_script_context` consumes the TxInfo,
+/// so each redeemer's context costs one deep copy; caching it keeps later sessions
+/// of the same redeemer from paying it again.
+#[derive(Debug, Default)]
+pub struct ContextCache {
+    contexts: RefCell<HashMap<String, PreparedContext>>,
+    stats: RefCell<ContextCacheStats>,
+}
+
+impl ContextCache {
+    /// Returns the context cached for `redeemer`, building it with `build` on first use
+    pub fn get_or_build<E>(
+        &self,
+        redeemer: &str,
+        build: impl FnOnce() -> Result<PreparedContext, E>,
+    ) -> Result<PreparedContext, E> {
+        if let Some(prepared) = self.contexts.borrow().get(redeemer) {
+            self.stats.borrow_mut().hits += 1;
+            return Ok(prepared.clone());
+        }
+
+        let prepared = build()?;
+        let mut contexts = self.contexts.borrow_mut();
+        contexts.insert(redeemer.to_string(), prepared.clone());
+        let mut stats = self.stats.borrow_mut();
+        stats.tx_info_clones += 1;
+        stats.cached_redeemers = contexts.len();
+        Ok(prepared)
+    }
+
+    pub fn stats(&self) -> ContextCacheStats {
+        self.stats.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.contexts.borrow_mut().clear();
+        self.stats.borrow_mut().cached_redeemers = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_builds_are_not_cached() {
+        let cache = ContextCache::default();
+        let result = cache.get_or_build("Spend:0", || Err::<PreparedContext, _>("no script"));
+        assert_eq!(result.unwrap_err(), "no script");
+        let stats = cache.stats();
+        assert_eq!((stats.tx_info_clones, stats.hits, stats.cached_redeemers), (0, 0, 0));
+
+        // The next call builds again
+        let mut built = false;
+        let _ = cache.get_or_build("Spend:0", || {
+            built = true;
+            Err::<PreparedContext, _>("still no script")
+        });
+        assert!(built);
+    }
+}
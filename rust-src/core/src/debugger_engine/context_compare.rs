@@ -0,0 +1,272 @@
+use pallas_codec::minicbor;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uplc::tx::{script_context::TxInfo, to_plutus_data::ToPlutusData};
+
+use super::cert_encoding::constr_fields;
+use crate::script_context::{SerializableScriptContext, SerializableTxInfo};
+
+/// Upper bound on leaf differences reported, so comparing unrelated contexts stays cheap
+const MAX_DIFFERENCES: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ContextDifference {
+    /// JSON path of the differing leaf, e.g. `tx_info.redeemers[1]`
+    pub path: String,
+    pub a: Option<serde_json::Value>,
+    pub b: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ContextComparison {
+    pub redeemer_a: String,
+    pub redeemer_b: String,
+    /// TxInfo version of each context ("V1", "V2" or "V3")
+    pub tx_info_version_a: Option<String>,
+    pub tx_info_version_b: Option<String>,
+    /// Top-level TxInfo fields identical in both contexts
+    pub shared_fields: Vec<String>,
+    /// Top-level TxInfo fields that differ between the contexts
+    pub differing_fields: Vec<String>,
+    /// Purpose-specific parts outside TxInfo (purpose, redeemer) that differ
+    pub differing_purpose_fields: Vec<String>,
+    pub differences: Vec<ContextDifference>,
+    /// Whether `differences` was cut at the reporting limit
+    pub truncated: bool,
+}
+
+/// Split `{"V3": {...}}` into its version tag and body
+fn untag(value: &Value) -> (Option<String>, &Value) {
+    match value {
+        Value::Object(map) if map.len() == 1 => {
+            let (tag, body) = map.iter().next().unwrap();
+            (Some(tag.clone()), body)
+        }
+        _ => (None, value),
+    }
+}
+
+fn collect_differences(path: &str, a: Option<&Value>, b: Option<&Value>, out: &mut Vec<ContextDifference>) -> bool {
+    if a == b {
+        return true;
+    }
+    if out.len() >= MAX_DIFFERENCES {
+        return false;
+    }
+    match (a, b) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter()
+                .all(|key| collect_differences(&format!("{}.{}", path, key), a.get(key), b.get(key), out))
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => (0..a.len().max(b.len()))
+            .all(|i| collect_differences(&format!("{}[{}]", path, i), a.get(i), b.get(i), out)),
+        _ => {
+            out.push(ContextDifference {
+                path: path.to_string(),
+                a: a.cloned(),
+                b: b.cloned(),
+            });
+            true
+        }
+    }
+}
+
+/// Compare two script contexts of the same transaction, separating the TxInfo
+/// fields both validators see identically from the ones that differ
+pub fn compare_contexts(
+    redeemer_a: &str,
+    context_a: &SerializableScriptContext,
+    redeemer_b: &str,
+    context_b: &SerializableScriptContext,
+) -> Result<ContextComparison, String> {
+    let a = serde_json::to_value(context_a).map_err(|e| e.to_string())?;
+    let b = serde_json::to_value(context_b).map_err(|e| e.to_string())?;
+
+    let (tx_info_version_a, tx_info_a) = untag(&a["tx_info"]);
+    let (tx_info_version_b, tx_info_b) = untag(&b["tx_info"]);
+
+    let mut shared_fields = vec![];
+    let mut differing_fields = vec![];
+    if let (Value::Object(fields_a), Value::Object(fields_b)) = (tx_info_a, tx_info_b) {
+        let mut keys: Vec<&String> = fields_a.keys().chain(fields_b.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            if fields_a.get(key) == fields_b.get(key) {
+                shared_fields.push(key.clone());
+            } else {
+                differing_fields.push(key.clone());
+            }
+        }
+    }
+
+    let differing_purpose_fields = ["purpose", "redeemer"]
+        .into_iter()
+        .filter(|field| a.get(*field) != b.get(*field))
+        .map(str::to_string)
+        .collect();
+
+    let mut differences = vec![];
+    let complete = collect_differences("tx_info", Some(tx_info_a), Some(tx_info_b), &mut differences)
+        && ["purpose", "redeemer"]
+            .into_iter()
+            .all(|field| collect_differences(field, a.get(field), b.get(field), &mut differences));
+
+    Ok(ContextComparison {
+        redeemer_a: redeemer_a.to_string(),
+        redeemer_b: redeemer_b.to_string(),
+        tx_info_version_a,
+        tx_info_version_b,
+        shared_fields,
+        differing_fields,
+        differing_purpose_fields,
+        differences,
+        truncated: !complete,
+    })
+}
+
+/// TxInfo fields in the order the ledger encodes them as Plutus data, per version
+fn encoded_fields(version: &str) -> &'static [&'static str] {
+    match version {
+        "V1" => &[
+            "inputs", "outputs", "fee", "mint", "certificates", "withdrawals", "valid_range", "signatories", "data",
+            "id",
+        ],
+        "V2" => &[
+            "inputs", "reference_inputs", "outputs", "fee", "mint", "certificates", "withdrawals", "valid_range",
+            "signatories", "redeemers", "data", "id",
+        ],
+        _ => &[
+            "inputs", "reference_inputs", "outputs", "fee", "mint", "certificates", "withdrawals", "valid_range",
+            "signatories", "redeemers", "data", "id", "votes", "proposal_procedures", "current_treasury_amount",
+            "treasury_donation",
+        ],
+    }
+}
+
+/// Known encoding changes of a field across Plutus versions
+fn representation_note(field: &str) -> Option<&'static str> {
+    match field {
+        "fee" => Some("A Value up to V2, a plain lovelace integer in V3"),
+        "mint" => Some("Carries a zero-lovelace entry up to V2, only the minted policies in V3"),
+        "certificates" => Some("DCert up to V2, TxCert with the Conway certificates in V3"),
+        "withdrawals" => Some("A list of pairs in V1, a map from then on, keyed by credential in V3"),
+        "data" => Some("A list of pairs in V1, a map of datum hash to datum from V2"),
+        "redeemers" => Some("Not in V1; keyed by ScriptPurpose in V2, by the extended V3 ScriptPurpose in V3"),
+        "id" => Some("Wrapped in a TxId constructor up to V2, a plain bytestring in V3"),
+        _ => None,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TxInfoFieldComparison {
+    pub field: String,
+    pub in_a: bool,
+    pub in_b: bool,
+    /// Whether the decoded views of the field are identical
+    pub view_equal: bool,
+    /// Whether the scripts receive the same Plutus data for the field; absent
+    /// when one version does not encode it
+    pub encoding_equal: Option<bool>,
+    /// CBOR of the field as encoded in each version
+    pub encoding_a: Option<String>,
+    pub encoding_b: Option<String>,
+    pub note: Option<String>,
+}
+
+/// The same transaction seen through two TxInfo versions
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TxInfoVersionComparison {
+    pub version_a: String,
+    pub version_b: String,
+    pub fields: Vec<TxInfoFieldComparison>,
+    /// Fields both versions encode, but differently: what a script migrated
+    /// from one version to the other has to read differently
+    pub representation_changes: Vec<String>,
+    pub differences: Vec<ContextDifference>,
+    /// Whether `differences` was cut at the reporting limit
+    pub truncated: bool,
+}
+
+/// Encoded TxInfo fields by name, as CBOR hex
+fn encode_fields(version: &str, tx_info: &TxInfo) -> Result<Vec<(&'static str, String)>, String> {
+    let data = tx_info.to_plutus_data();
+    let (_, fields) = constr_fields(&data).ok_or("TxInfo is not encoded as a constructor")?;
+    encoded_fields(version)
+        .iter()
+        .zip(fields)
+        .map(|(name, field)| Ok((*name, hex::encode(minicbor::to_vec(field).map_err(|e| e.to_string())?))))
+        .collect()
+}
+
+/// Compares the views two Plutus versions give of the same transaction, field by
+/// field, both as decoded and as the Plutus data the scripts receive
+pub fn compare_tx_info_versions(a: &TxInfo, b: &TxInfo) -> Result<TxInfoVersionComparison, String> {
+    let serializable_a: SerializableTxInfo = a.clone().try_into().map_err(|e| format!("{:?}", e))?;
+    let serializable_b: SerializableTxInfo = b.clone().try_into().map_err(|e| format!("{:?}", e))?;
+    let value_a = serde_json::to_value(&serializable_a).map_err(|e| e.to_string())?;
+    let value_b = serde_json::to_value(&serializable_b).map_err(|e| e.to_string())?;
+    let (version_a, view_a) = untag(&value_a);
+    let (version_b, view_b) = untag(&value_b);
+    let version_a = version_a.unwrap_or_default();
+    let version_b = version_b.unwrap_or_default();
+
+    let encoded_a = encode_fields(&version_a, a)?;
+    let encoded_b = encode_fields(&version_b, b)?;
+    let encoding = |encoded: &[(&str, String)], field: &str| {
+        encoded.iter().find(|(name, _)| *name == field).map(|(_, cbor)| cbor.clone())
+    };
+
+    let mut names: Vec<&String> = [view_a, view_b]
+        .into_iter()
+        .filter_map(Value::as_object)
+        .flat_map(|fields| fields.keys())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let mut representation_changes = vec![];
+    let fields = names
+        .into_iter()
+        .map(|field| {
+            let encoding_a = encoding(&encoded_a, field);
+            let encoding_b = encoding(&encoded_b, field);
+            let encoding_equal = match (&encoding_a, &encoding_b) {
+                (Some(a), Some(b)) => Some(a == b),
+                _ => None,
+            };
+            if encoding_equal == Some(false) {
+                representation_changes.push(field.clone());
+            }
+            TxInfoFieldComparison {
+                field: field.clone(),
+                in_a: view_a.get(field).is_some(),
+                in_b: view_b.get(field).is_some(),
+                view_equal: view_a.get(field) == view_b.get(field),
+                encoding_equal,
+                encoding_a,
+                encoding_b,
+                note: (encoding_equal == Some(false))
+                    .then(|| representation_note(field))
+                    .flatten()
+                    .map(str::to_string),
+            }
+        })
+        .collect();
+
+    let mut differences = vec![];
+    let complete = collect_differences("tx_info", Some(view_a), Some(view_b), &mut differences);
+
+    Ok(TxInfoVersionComparison {
+        version_a,
+        version_b,
+        fields,
+        representation_changes,
+        differences,
+        truncated: !complete,
+    })
+}
@@ -0,0 +1,314 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::ast::{NamedDeBruijn, Term};
+
+use super::decisions::{case_branches, term_id};
+use crate::hash_format::normalize_script_hash;
+
+/// Executed and total terms under one node of the program
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SubtreeCoverage {
+    pub term_id: i32,
+    /// "Program", "Lambda", "Delay" or "Branch" (a Case branch)
+    pub kind: String,
+    pub total_terms: usize,
+    pub executed_terms: usize,
+    pub percent: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CoverageReport {
+    pub total_terms: usize,
+    pub executed_terms: usize,
+    pub percent: f64,
+    /// Coverage under every Lambda, Delay and Case branch, in program order
+    pub subtrees: Vec<SubtreeCoverage>,
+    /// Terms never computed although the term containing them was: the dead
+    /// branches and never called functions. Their subterms are left out.
+    pub unexecuted_roots: Vec<i32>,
+}
+
+fn percent(executed: usize, total: usize) -> f64 {
+    if total > 0 {
+        executed as f64 * 100.0 / total as f64
+    } else {
+        0.0
+    }
+}
+
+struct Walk<'a> {
+    executed: &'a dyn Fn(i32) -> bool,
+    case_branches: &'a HashMap<i32, (i32, usize)>,
+    subtrees: Vec<SubtreeCoverage>,
+    unexecuted_roots: Vec<i32>,
+}
+
+impl Walk<'_> {
+    /// Returns the total and executed terms under `term`, itself included
+    fn term(&mut self, term: &Term<NamedDeBruijn>, parent_executed: bool) -> (usize, usize) {
+        let id = term_id(term);
+        let executed = (self.executed)(id);
+        if !executed && parent_executed {
+            self.unexecuted_roots.push(id);
+        }
+        let kind = match term {
+            Term::Lambda { .. } => Some("Lambda"),
+            Term::Delay { .. } => Some("Delay"),
+            _ if self.case_branches.contains_key(&id) => Some("Branch"),
+            _ => None,
+        };
+        // Reserve the slot so subtrees stay in program order
+        let slot = kind.map(|_| {
+            self.subtrees.push(SubtreeCoverage {
+                term_id: id,
+                kind: String::new(),
+                total_terms: 0,
+                executed_terms: 0,
+                percent: 0.0,
+            });
+            self.subtrees.len() - 1
+        });
+
+        let (mut total, mut covered) = (1, executed as usize);
+        let mut add = |counts: (usize, usize)| {
+            total += counts.0;
+            covered += counts.1;
+        };
+        match term {
+            Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => add(self.term(body, executed)),
+            Term::Apply { function, argument, .. } => {
+                add(self.term(function, executed));
+                add(self.term(argument, executed));
+            }
+            Term::Constr { fields, .. } => {
+                for field in fields {
+                    add(self.term(field, executed));
+                }
+            }
+            Term::Case { constr, branches, .. } => {
+                add(self.term(constr, executed));
+                for branch in branches {
+                    add(self.term(branch, executed));
+                }
+            }
+            Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } | Term::Builtin { .. } => {}
+        }
+
+        if let (Some(slot), Some(kind)) = (slot, kind) {
+            self.subtrees[slot] = SubtreeCoverage {
+                term_id: id,
+                kind: kind.to_string(),
+                total_terms: total,
+                executed_terms: covered,
+                percent: percent(covered, total),
+            };
+        }
+        (total, covered)
+    }
+}
+
+/// Compares the terms of `program` with those `executed` reports as computed
+pub fn coverage_report(
+    program: &Term<NamedDeBruijn>,
+    case_branches: &HashMap<i32, (i32, usize)>,
+    executed: &dyn Fn(i32) -> bool,
+) -> CoverageReport {
+    let mut walk = Walk {
+        executed,
+        case_branches,
+        subtrees: vec![],
+        unexecuted_roots: vec![],
+    };
+    let (total, covered) = walk.term(program, true);
+    let mut subtrees = vec![SubtreeCoverage {
+        term_id: term_id(program),
+        kind: "Program".to_string(),
+        total_terms: total,
+        executed_terms: covered,
+        percent: percent(covered, total),
+    }];
+    subtrees.append(&mut walk.subtrees);
+
+    CoverageReport {
+        total_terms: total,
+        executed_terms: covered,
+        percent: percent(covered, total),
+        subtrees,
+        unexecuted_roots: walk.unexecuted_roots,
+    }
+}
+
+/// The terms one run computed, in a form that can be stored and merged with
+/// the maps of other runs of the same script
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CoverageMap {
+    pub script_hash: String,
+    /// Where the run came from, e.g. a redeemer or test name
+    #[serde(default)]
+    pub label: Option<String>,
+    pub executed_term_ids: Vec<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RunCoverage {
+    pub label: Option<String>,
+    pub executed_terms: usize,
+    pub percent: f64,
+}
+
+/// Coverage of a script by the union of several runs
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct AggregatedCoverage {
+    pub script_hash: String,
+    pub report: CoverageReport,
+    /// Each run on its own, in the order given
+    pub runs: Vec<RunCoverage>,
+}
+
+/// Unites the executed terms of maps of the same script. Hashes are compared in
+/// hex, so maps exported with hex and bech32 hashes merge.
+pub fn merge_coverage_maps(maps: &[CoverageMap]) -> Result<CoverageMap, String> {
+    let first = maps.first().ok_or("No coverage maps to merge")?;
+    let script_hash = normalize_script_hash(&first.script_hash)?;
+    for map in &maps[1..] {
+        if normalize_script_hash(&map.script_hash)? != script_hash {
+            return Err(format!(
+                "Coverage maps of different scripts: {} and {}",
+                first.script_hash, map.script_hash
+            ));
+        }
+    }
+    let executed: BTreeSet<i32> = maps.iter().flat_map(|map| map.executed_term_ids.iter().copied()).collect();
+    Ok(CoverageMap {
+        script_hash,
+        label: Some(format!("{} runs", maps.len())),
+        executed_term_ids: executed.into_iter().collect(),
+    })
+}
+
+/// Reports the coverage of `program` by every map and by their union. Term ids
+/// outside the program, such as those of applied arguments, are ignored.
+pub fn aggregate_coverage(program: &Term<NamedDeBruijn>, maps: &[CoverageMap]) -> Result<AggregatedCoverage, String> {
+    let merged = merge_coverage_maps(maps)?;
+    let branches = case_branches(program);
+    let report_for = |executed: &[i32]| {
+        let executed: HashSet<i32> = executed.iter().copied().collect();
+        coverage_report(program, &branches, &|id| executed.contains(&id))
+    };
+    let runs = maps
+        .iter()
+        .map(|map| {
+            let report = report_for(&map.executed_term_ids);
+            RunCoverage {
+                label: map.label.clone(),
+                executed_terms: report.executed_terms,
+                percent: report.percent,
+            }
+        })
+        .collect();
+
+    Ok(AggregatedCoverage {
+        script_hash: merged.script_hash,
+        report: report_for(&merged.executed_term_ids),
+        runs,
+    })
+}
+
+/// Step at which each term was first computed
+#[derive(Debug, Clone, Default)]
+pub struct CoverageTracker {
+    first_computed: HashMap<i32, u64>,
+}
+
+impl CoverageTracker {
+    pub fn record(&mut self, term_id: i32, step: u64) {
+        if term_id >= 0 {
+            self.first_computed.entry(term_id).or_insert(step);
+        }
+    }
+
+    pub fn contains(&self, term_id: i32) -> bool {
+        self.first_computed.contains_key(&term_id)
+    }
+
+    /// Executed term ids in ascending order
+    pub fn executed(&self) -> Vec<i32> {
+        let mut executed: Vec<i32> = self.first_computed.keys().copied().collect();
+        executed.sort_unstable();
+        executed
+    }
+
+    /// Forgets terms first computed after `step`, for stepping back
+    pub fn truncate_after(&mut self, step: u64) {
+        self.first_computed.retain(|_, first| *first <= step);
+    }
+
+    pub fn clear(&mut self) {
+        self.first_computed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use uplc::ast::Constant;
+
+    #[test]
+    fn test_coverage_report() {
+        let unit = |id: usize| Term::Constant { value: Rc::new(Constant::Unit), uniq_id: id as _ };
+        let delayed = Term::Delay { body: unit(2).into(), uniq_id: 1 };
+        let program: Term<NamedDeBruijn> = Term::Constr { tag: 0, fields: vec![delayed, unit(3)], uniq_id: 0 };
+
+        let executed = [0, 1, 3];
+        let report = coverage_report(&program, &HashMap::new(), &|id| executed.contains(&id));
+        assert_eq!((report.total_terms, report.executed_terms), (4, 3));
+        assert_eq!(report.unexecuted_roots, vec![2]);
+        let delay = &report.subtrees[1];
+        assert_eq!((delay.kind.as_str(), delay.total_terms, delay.executed_terms), ("Delay", 2, 1));
+        assert_eq!(delay.percent, 50.0);
+    }
+
+    #[test]
+    fn test_merge_coverage_maps() {
+        let hash = "ab".repeat(28);
+        let map = |ids: Vec<i32>| CoverageMap {
+            script_hash: hash.clone(),
+            label: None,
+            executed_term_ids: ids,
+        };
+        let merged = merge_coverage_maps(&[map(vec![3, 1]), map(vec![1, 2])]).unwrap();
+        assert_eq!(merged.executed_term_ids, vec![1, 2, 3]);
+
+        let other = CoverageMap {
+            script_hash: "cd".repeat(28),
+            ..map(vec![])
+        };
+        assert!(merge_coverage_maps(&[map(vec![]), other]).is_err());
+        assert!(merge_coverage_maps(&[]).is_err());
+        let invalid = CoverageMap {
+            script_hash: "cd".to_string(),
+            ..map(vec![])
+        };
+        assert!(merge_coverage_maps(&[map(vec![]), invalid]).is_err());
+    }
+
+    #[test]
+    fn test_merge_normalizes_every_hash() {
+        let hash = "ab".repeat(28);
+        let bech32 = crate::hash_format::script_hash_to_bech32(&hash).unwrap();
+        let map = |script_hash: String, ids: Vec<i32>| CoverageMap {
+            script_hash,
+            label: None,
+            executed_term_ids: ids,
+        };
+        let maps = [map(hash.to_uppercase(), vec![1]), map(bech32.clone(), vec![2]), map(format!(" {} ", hash), vec![3])];
+        let merged = merge_coverage_maps(&maps).unwrap();
+        assert_eq!((merged.script_hash, merged.executed_term_ids), (hash.clone(), vec![1, 2, 3]));
+
+        let merged = merge_coverage_maps(&[map(bech32, vec![])]).unwrap();
+        assert_eq!(merged.script_hash, hash);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, HashSet};
+
+use pallas_codec::minicbor;
+use pallas_crypto::hash::{Hash, Hasher};
+use pallas_primitives::conway::{MintedTx, PseudoDatumOption, PseudoTransactionOutput, TransactionInput};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{tx::ResolvedInput, PlutusData};
+
+use crate::plutus_data::SerializablePlutusData;
+
+/// Where a datum (or a reference to it) appears in the transaction
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "location_type")]
+pub enum DatumLocation {
+    #[serde(rename = "Input")]
+    Input {
+        input_index: usize,
+        transaction_id: String,
+        output_index: u64,
+        inline: bool,
+    },
+    #[serde(rename = "ReferenceInput")]
+    ReferenceInput {
+        input_index: usize,
+        transaction_id: String,
+        output_index: u64,
+        inline: bool,
+    },
+    #[serde(rename = "Output")]
+    Output { output_index: usize, inline: bool },
+    #[serde(rename = "Witness")]
+    Witness { witness_index: usize },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct DecodedDatum {
+    pub hash: String,
+    pub cbor_hex: String,
+    pub data: SerializablePlutusData,
+    pub locations: Vec<DatumLocation>,
+}
+
+/// Datum hash referenced by an input or output whose preimage is not in the transaction
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct UnresolvedDatum {
+    pub hash: String,
+    pub locations: Vec<DatumLocation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct DecodedDatums {
+    pub datums: Vec<DecodedDatum>,
+    pub unresolved: Vec<UnresolvedDatum>,
+}
+
+#[derive(Default)]
+struct DatumCollector {
+    preimages: BTreeMap<String, (Vec<u8>, PlutusData)>,
+    locations: BTreeMap<String, Vec<DatumLocation>>,
+}
+
+impl DatumCollector {
+    fn add_data(&mut self, cbor: Vec<u8>, data: &PlutusData, location: DatumLocation) {
+        let hash = hex::encode(Hasher::<256>::hash(&cbor));
+        self.preimages.entry(hash.clone()).or_insert_with(|| (cbor, data.clone()));
+        self.locations.entry(hash).or_default().push(location);
+    }
+
+    fn add_hash(&mut self, hash: &Hash<32>, location: DatumLocation) {
+        self.locations.entry(hex::encode(hash)).or_default().push(location);
+    }
+
+    fn finish(mut self) -> DecodedDatums {
+        let mut result = DecodedDatums::default();
+        for (hash, locations) in std::mem::take(&mut self.locations) {
+            match self.preimages.remove(&hash) {
+                Some((cbor, data)) => result.datums.push(DecodedDatum {
+                    hash,
+                    cbor_hex: hex::encode(cbor),
+                    data: SerializablePlutusData::from_pallas(&data),
+                    locations,
+                }),
+                None => result.unresolved.push(UnresolvedDatum { hash, locations }),
+            }
+        }
+        result
+    }
+}
+
+/// Collect every datum of the transaction: witness datums, inline and hashed datums
+/// of outputs and of the resolved (spent and reference) inputs. Datums are keyed by
+/// their blake2b-256 hash, and each one lists every location it appears at.
+pub fn collect_decoded_datums(tx: &MintedTx, resolved_inputs: &[ResolvedInput]) -> DecodedDatums {
+    let mut collector = DatumCollector::default();
+
+    if let Some(witness_data) = &tx.transaction_witness_set.plutus_data {
+        for (witness_index, data) in witness_data.iter().enumerate() {
+            collector.add_data(
+                data.raw_cbor().to_vec(),
+                data,
+                DatumLocation::Witness { witness_index },
+            );
+        }
+    }
+
+    for (output_index, output) in tx.transaction_body.outputs.iter().enumerate() {
+        match output {
+            PseudoTransactionOutput::Legacy(output) => {
+                if let Some(hash) = &output.datum_hash {
+                    collector.add_hash(hash, DatumLocation::Output { output_index, inline: false });
+                }
+            }
+            PseudoTransactionOutput::PostAlonzo(output) => match &output.datum_option {
+                Some(PseudoDatumOption::Hash(hash)) => {
+                    collector.add_hash(hash, DatumLocation::Output { output_index, inline: false });
+                }
+                Some(PseudoDatumOption::Data(data)) => {
+                    collector.add_data(
+                        data.0.raw_cbor().to_vec(),
+                        &data.0,
+                        DatumLocation::Output { output_index, inline: true },
+                    );
+                }
+                None => {}
+            },
+        }
+    }
+
+    let spent: Vec<&TransactionInput> = tx.transaction_body.inputs.iter().collect();
+    let referenced: Vec<&TransactionInput> = tx
+        .transaction_body
+        .reference_inputs
+        .iter()
+        .flat_map(|inputs| inputs.iter())
+        .collect();
+    let mut seen = HashSet::new();
+
+    for resolved in resolved_inputs {
+        if !seen.insert((resolved.input.transaction_id, resolved.input.index)) {
+            continue;
+        }
+        let location = |inline: bool| {
+            let transaction_id = hex::encode(resolved.input.transaction_id);
+            let output_index = resolved.input.index;
+            if let Some(input_index) = spent.iter().position(|input| **input == resolved.input) {
+                Some(DatumLocation::Input { input_index, transaction_id, output_index, inline })
+            } else {
+                referenced
+                    .iter()
+                    .position(|input| **input == resolved.input)
+                    .map(|input_index| DatumLocation::ReferenceInput {
+                        input_index,
+                        transaction_id,
+                        output_index,
+                        inline,
+                    })
+            }
+        };
+
+        match &resolved.output {
+            PseudoTransactionOutput::Legacy(output) => {
+                if let (Some(hash), Some(location)) = (&output.datum_hash, location(false)) {
+                    collector.add_hash(hash, location);
+                }
+            }
+            PseudoTransactionOutput::PostAlonzo(output) => match &output.datum_option {
+                Some(PseudoDatumOption::Hash(hash)) => {
+                    if let Some(location) = location(false) {
+                        collector.add_hash(hash, location);
+                    }
+                }
+                Some(PseudoDatumOption::Data(data)) => {
+                    if let (Ok(cbor), Some(location)) = (minicbor::to_vec(&data.0), location(true)) {
+                        collector.add_data(cbor, &data.0, location);
+                    }
+                }
+                None => {}
+            },
+        }
+    }
+
+    collector.finish()
+}
@@ -0,0 +1,1297 @@
+use crate::wasm_tools::{wasm_bindgen, JsError};
+use pallas_primitives::{
+    conway::{Language, MintedTx, Redeemer, RedeemerTag},
+    Fragment,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use uplc::{
+    ast::{FakeNamedDeBruijn, NamedDeBruijn, Program},
+    machine::cost_model::{initialize_cost_model, CostModel, ExBudget},
+    tx::{
+        iter_redeemers, redeemer_tag_to_string,
+        script_context::{
+            find_script, PlutusScript, ScriptContext, TxInfo, TxInfoV1, TxInfoV2, TxInfoV3,
+        },
+        to_plutus_data::ToPlutusData,
+        DataLookupTable, ResolvedInput, SlotConfig,
+    },
+    PlutusData,
+};
+use uuid::Uuid;
+
+use super::{SerializableExecutionStatus, SessionController};
+use crate::cost_model_upgrade::{check_cost_model, check_program_builtins};
+use crate::debugger_engine::audit::audit_sessions;
+use crate::debugger_engine::blueprint::{Blueprint, BlueprintValidationReport, BlueprintValidationResult};
+use crate::debugger_engine::cert_encoding::certificate_encoding;
+use crate::debugger_engine::construction::{ConstructionReport, RedeemerFailure};
+use crate::debugger_engine::context_cache::{ContextCache, PreparedContext};
+use crate::debugger_engine::context_compare::{compare_contexts, compare_tx_info_versions};
+use crate::debugger_engine::coverage::{aggregate_coverage, CoverageMap};
+use crate::debugger_engine::datums::{collect_decoded_datums, DecodedDatums};
+use crate::debugger_engine::fixture::{ExportedFixture, ScriptFixture};
+use crate::debugger_engine::observers::{
+    stake_observer, term_embeds_bytes, withdrawal_for_script, ForwardingSpend, ObserverLink, StakeObserver,
+};
+use crate::debugger_engine::ordering::{check_tx_info, OrderingCheck};
+use crate::debugger_engine::quota::{QuotaLimits, QuotaTracker, SharedQuota};
+use crate::debugger_engine::redeemer_graph::{GraphNodeKind, RedeemerGraph};
+use crate::debugger_engine::ref_script_fees::{ref_script_fee_breakdown, RefScriptFeeBreakdown};
+use crate::debugger_engine::run_all::{run_session, RedeemerRun, TransactionRun};
+use crate::debugger_engine::session_persistence::SavedSession;
+use crate::debugger_engine::settings::{BudgetMode, EngineSettings, SessionOptions};
+use crate::debugger_engine::DebuggerError;
+use crate::budget::{with_margin, BudgetPricing};
+use crate::hash_format::{normalize_script_hash, script_hash_to_bech32};
+use crate::debugger_engine::datums::DatumLocation;
+use crate::script_context::utils::reward_account_to_bech32;
+use crate::protocol_params::{CostModels, ProtocolParameters};
+use crate::script_context::{SerializableExUnits, SerializableMintPolicy, SerializableMintValue};
+use crate::serialization_format::{serialize, SerializationFormat};
+use crate::utxo::UtxoOutput;
+use crate::wasm_tools::{envelope_json, enveloped_json, now_millis};
+
+const SLOT_CONFIG_MAINNET: SlotConfig = SlotConfig {
+    zero_time: 1596059091000, // Shelley era start
+    zero_slot: 4492800,       // Shelley era start slot
+    slot_length: 1000,        // 1 second per slot
+};
+
+const SLOT_CONFIG_PREPROD: SlotConfig = SlotConfig {
+    zero_time: 1654041600000 + 1728000000, // Shelley era start
+    zero_slot: 0,                          // Shelley era start slot
+    slot_length: 1000,                     // 1 second per slot
+};
+
+const SLOT_CONFIG_PREVIEW: SlotConfig = SlotConfig {
+    zero_time: 1666656000000, // Shelley era start
+    zero_slot: 0,             // Shelley era start slot
+    slot_length: 1000,        // 1 second per slot
+};
+
+// Conversion from DebuggerError to JsError
+impl From<DebuggerError> for JsError {
+    fn from(error: DebuggerError) -> Self {
+        JsError::from_str(&error.to_string())
+    }
+}
+
+// Conversion from UtxoConversionError to JsError
+impl From<crate::utxo::UtxoConversionError> for JsError {
+    fn from(error: crate::utxo::UtxoConversionError) -> Self {
+        JsError::from_str(&error.to_string())
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct DebuggerEngine {
+    v1_context: Option<Arc<TxInfo>>,
+    v2_context: Option<Arc<TxInfo>>,
+    v3_context: Option<Arc<TxInfo>>,
+    /// Kept to build the TxInfo of versions no redeemer of the transaction uses
+    tx_bytes: Vec<u8>,
+    resolved_inputs: Vec<ResolvedInput>,
+    slot_config: SlotConfig,
+    transaction_id: String,
+    protocol_params: ProtocolParameters,
+    /// Sessions kept by the engine, by session id
+    sessions: HashMap<String, SessionController>,
+
+    redeemers: HashMap<String, Redeemer>,
+    redeemer_scripts: HashMap<String, (PlutusScript, Option<PlutusData>)>,
+    datums: DecodedDatums,
+    mint_policies: Vec<SerializableMintPolicy>,
+    ref_script_fees: RefScriptFeeBreakdown,
+    ordering_checks: Vec<OrderingCheck>,
+    blueprint: Option<Blueprint>,
+    /// Decoded programs by script hash, shared by every session of the same script
+    program_cache: RefCell<HashMap<String, Rc<Program<NamedDeBruijn>>>>,
+    /// Script contexts and applied arguments by redeemer
+    context_cache: ContextCache,
+    construction: ConstructionReport,
+    quota: SharedQuota,
+    settings: EngineSettings,
+}
+
+#[wasm_bindgen]
+impl DebuggerEngine {
+    pub(crate) fn new_internal(
+        tx_hex: &str,
+        utxos: Vec<UtxoOutput>,
+        protocol_params: ProtocolParameters,
+        network: &str,
+    ) -> Result<Self, JsError> {
+        let mut report = ConstructionReport::default();
+        let mut engine = Self::build(tx_hex, utxos, protocol_params, network, &mut report)?;
+        report.completed = true;
+        engine.construction = report;
+        Ok(engine)
+    }
+
+    /// Builds the engine stage by stage, recording each stage in `report`
+    fn build(
+        tx_hex: &str,
+        utxos: Vec<UtxoOutput>,
+        protocol_params: ProtocolParameters,
+        network: &str,
+        report: &mut ConstructionReport,
+    ) -> Result<Self, JsError> {
+        let network = network.to_lowercase().trim().to_string();
+        let slot_config = if network == "mainnet" {
+            SLOT_CONFIG_MAINNET
+        } else if network == "preprod" {
+            SLOT_CONFIG_PREPROD
+        } else if network == "preview" {
+            SLOT_CONFIG_PREVIEW
+        } else {
+            return Err(JsError::from_str("Invalid network"));
+        };
+
+        // Parse transaction from hex
+        let tx_bytes = report.run("decode_hex", || {
+            hex::decode(tx_hex).map_err(|e| DebuggerError::TransactionParseError(e.to_string()))
+        })?;
+
+        let (tx, tx_id) = report.run("decode_transaction", || {
+            let tx = MintedTx::decode_fragment(&tx_bytes)
+                .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+
+            // Calculate transaction ID using hash_transaction
+            let tx_body_bytes = tx
+                .transaction_body
+                .encode_fragment()
+                .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+            let tx_hash = pallas_crypto::hash::Hasher::<256>::hash(&tx_body_bytes);
+            Ok::<_, DebuggerError>((tx, hex::encode(tx_hash)))
+        })?;
+
+        let ref_script_fees =
+            ref_script_fee_breakdown(&tx, &utxos, protocol_params.min_fee_ref_script_cost_per_byte);
+
+        // Convert UtxoOutput to ResolvedInput
+        let resolved_inputs: Vec<ResolvedInput> = report.run("convert_utxos", || {
+            utxos
+                .into_iter()
+                .map(|utxo| utxo.try_into())
+                .collect::<Result<Vec<_>, crate::utxo::UtxoConversionError>>()
+        })?;
+
+        let datums = collect_decoded_datums(&tx, &resolved_inputs);
+
+        // Create data lookup table
+        let lookup_table = DataLookupTable::from_transaction(&tx, &resolved_inputs);
+
+        let started = now_millis();
+        let redeemers = tx.transaction_witness_set.redeemer.as_ref().ok_or_else(|| {
+            let error = DebuggerError::TransactionParseError("No redeemers in transaction".to_string());
+            report.record_failure("find_scripts", started, &error);
+            error
+        })?;
+        let mut redeemers_map = HashMap::new();
+        let mut redeemer_scripts = HashMap::new();
+        let mut failures = vec![];
+
+        for (key, data, ex_units) in iter_redeemers(redeemers) {
+            let redeemer_key = format!("{}:{}", redeemer_tag_to_string(&key.tag), key.index);
+            redeemers_map.insert(
+                redeemer_key.clone(),
+                Redeemer {
+                    tag: key.tag,
+                    index: key.index,
+                    data: data.clone(),
+                    ex_units,
+                },
+            );
+
+            // A redeemer whose script is missing stays listed; sessions for it fail with ScriptNotFound
+            match find_script(&redeemers_map[&redeemer_key], &tx, &resolved_inputs, &lookup_table) {
+                Ok((script, datum)) => {
+                    redeemer_scripts.insert(redeemer_key, (script, datum));
+                }
+                Err(e) => failures.push(RedeemerFailure {
+                    error: DebuggerError::ScriptNotFound(format!("{}: {:?}", redeemer_key, e)).to_string(),
+                    redeemer: redeemer_key,
+                }),
+            }
+        }
+        report.record_partial("find_scripts", started, failures);
+
+        let mint: SerializableMintValue = tx
+            .transaction_body
+            .mint
+            .clone()
+            .map(Into::into)
+            .unwrap_or_else(SerializableMintValue::empty);
+        let mint_policies = mint.policies(|policy_id| {
+            redeemers_map
+                .iter()
+                .filter(|(_, redeemer)| matches!(redeemer.tag, RedeemerTag::Mint))
+                .find_map(|(key, _)| {
+                    let (script, _) = redeemer_scripts.get(key)?;
+                    (compute_script_hash(script) == policy_id)
+                        .then(|| (key.clone(), format!("{:?}", script_language(script))))
+                })
+        });
+
+        let has_v1_script_redeemer = redeemer_scripts.values().any(|(script, _)| {
+            matches!(script, PlutusScript::V1(_))
+        });
+
+        let has_v2_script_redeemer = redeemer_scripts.values().any(|(script, _)| {
+            matches!(script, PlutusScript::V2(_))
+        });
+
+        let has_v3_script_redeemer = redeemer_scripts.values().any(|(script, _)| {
+            matches!(script, PlutusScript::V3(_))
+        });
+
+        // A TxInfo that fails to build only makes the redeemers of its Plutus version
+        // unavailable, so a failed build is recorded and construction goes on
+        let v1_context = if has_v1_script_redeemer {
+            report
+                .run("build_tx_info_v1", || {
+                    TxInfoV1::from_transaction(&tx, &resolved_inputs, &slot_config)
+                        .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))
+                })
+                .ok()
+                .map(Arc::new)
+        } else {
+            None
+        };
+
+        let v2_context = if has_v2_script_redeemer {
+            report
+                .run("build_tx_info_v2", || {
+                    TxInfoV2::from_transaction(&tx, &resolved_inputs, &slot_config)
+                        .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))
+                })
+                .ok()
+                .map(Arc::new)
+        } else {
+            None
+        };
+
+        let v3_context = if has_v3_script_redeemer {
+            report
+                .run("build_tx_info_v3", || {
+                    TxInfoV3::from_transaction(&tx, &resolved_inputs, &slot_config)
+                        .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))
+                })
+                .ok()
+                .map(Arc::new)
+        } else {
+            None
+        };
+
+        let ordering_checks = [&v1_context, &v2_context, &v3_context]
+            .into_iter()
+            .flatten()
+            .map(|tx_info| check_tx_info(&tx, tx_info))
+            .collect();
+
+        Ok(DebuggerEngine {
+            v1_context,
+            v2_context,
+            v3_context,
+            tx_bytes: tx_bytes.clone(),
+            resolved_inputs,
+            slot_config,
+            transaction_id: tx_id,
+            protocol_params,
+            sessions: HashMap::new(),
+            redeemers: redeemers_map,
+            redeemer_scripts,
+            datums,
+            mint_policies,
+            ref_script_fees,
+            ordering_checks,
+            blueprint: None,
+            program_cache: RefCell::new(HashMap::new()),
+            context_cache: ContextCache::default(),
+            construction: ConstructionReport::default(),
+            quota: QuotaTracker::new_shared(),
+            settings: EngineSettings::default(),
+        })
+    }
+
+    pub fn new(
+        tx_hex: &str,
+        utxos_json: &str,
+        protocol_params_json: &str,
+        network: &str,
+    ) -> Result<Self, JsError> {
+        let utxos = serde_json::from_str::<Vec<UtxoOutput>>(utxos_json)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+
+        let protocol_params = serde_json::from_str::<ProtocolParameters>(protocol_params_json)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+
+        Self::new_internal(tx_hex, utxos, protocol_params, network)
+    }
+
+    /// Runs engine construction and reports each stage (decode, UTxO conversion,
+    /// script lookup, TxInfo builds) with its duration, including the stage that
+    /// failed when no engine could be built
+    pub fn diagnose_construction(
+        tx_hex: &str,
+        utxos_json: &str,
+        protocol_params_json: &str,
+        network: &str,
+    ) -> Result<String, JsError> {
+        let mut report = ConstructionReport::default();
+        let inputs = report.run("parse_inputs", || {
+            let utxos = serde_json::from_str::<Vec<UtxoOutput>>(utxos_json)
+                .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+            let protocol_params = serde_json::from_str::<ProtocolParameters>(protocol_params_json)
+                .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+            Ok::<_, DebuggerError>((utxos, protocol_params))
+        });
+        if let Ok((utxos, protocol_params)) = inputs {
+            report.completed = Self::build(tx_hex, utxos, protocol_params, network, &mut report).is_ok();
+        }
+        Ok(enveloped_json(&report).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+    }
+
+    /// Gets the stages this engine was built in, with the redeemers and TxInfo
+    /// versions left unavailable by partial failures
+    pub fn get_construction_report(&self) -> Result<String, JsError> {
+        self.to_json(&self.construction)
+    }
+
+    /// Creates an engine whose sessions all share the given `EngineSettings` JSON
+    pub fn new_with_settings(
+        tx_hex: &str,
+        utxos_json: &str,
+        protocol_params_json: &str,
+        network: &str,
+        settings_json: &str,
+    ) -> Result<Self, JsError> {
+        let settings = EngineSettings::from_json(settings_json)
+            .map_err(|e| DebuggerError::MachineError(format!("Invalid settings: {}", e)))?;
+        let mut engine = Self::new(tx_hex, utxos_json, protocol_params_json, network)?;
+        engine.quota.borrow_mut().set_limits(settings.quota.clone());
+        engine.settings = settings;
+        Ok(engine)
+    }
+
+    /// Gets the settings applied to every session of this engine
+    pub fn get_settings(&self) -> Result<String, JsError> {
+        self.to_json(&self.settings)
+    }
+
+    /// Lists the protocol parameters that differ between the ones this engine was
+    /// created with and `protocol_params_json`, cost models compared per index
+    pub fn diff_protocol_params(&self, protocol_params_json: &str) -> Result<String, JsError> {
+        let other = serde_json::from_str::<ProtocolParameters>(protocol_params_json)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+        self.to_json(&self.protocol_params.diff(&other))
+    }
+
+    /// Gets list of available redeemers in the transaction
+    pub fn get_redeemers(&self) -> Result<Vec<String>, JsError> {
+        Ok(self.redeemers.keys().cloned().collect())
+    }
+
+    /// Gets the redeemers whose script has the given hash or policy id, given as
+    /// hex or as CIP-5 `script1…`
+    pub fn find_redeemers_by_script(&self, script_hash: &str) -> Result<Vec<String>, JsError> {
+        let script_hash = normalize_script_hash(script_hash).map_err(DebuggerError::ScriptNotFound)?;
+        let mut redeemers: Vec<String> = self
+            .redeemer_scripts
+            .iter()
+            .filter(|(_, (script, _))| compute_script_hash(script) == script_hash)
+            .map(|(redeemer, _)| redeemer.clone())
+            .collect();
+        redeemers.sort();
+        Ok(redeemers)
+    }
+
+    /// Gets the transaction ID
+    pub fn get_transaction_id(&self) -> Result<String, JsError> {
+        Ok(self.transaction_id.clone())
+    }
+
+    /// Gets every datum of the transaction (witness set, outputs and resolved inputs),
+    /// grouped by hash with all the locations it appears at
+    pub fn get_decoded_datums(&self) -> Result<String, JsError> {
+        self.to_json(&self.datums)
+    }
+
+    /// Gets the tokens minted or burnt by the transaction grouped by policy, each
+    /// with the redeemer key and Plutus version of its script, or "native"
+    pub fn get_mint_policies(&self) -> Result<String, JsError> {
+        self.to_json(&self.mint_policies)
+    }
+
+    /// Gets the size of every reference script the transaction pays for and its
+    /// share of the Conway tiered reference script fee
+    pub fn get_ref_script_fees(&self) -> Result<String, JsError> {
+        self.to_json(&self.ref_script_fees)
+    }
+
+    /// Checks, for every TxInfo version built for the transaction, that inputs and
+    /// reference inputs are in ledger order (sorted by tx hash, then index) and
+    /// outputs in body order, mapping each body index to its context index
+    pub fn verify_context_ordering(&self) -> Result<String, JsError> {
+        self.to_json(&self.ordering_checks)
+    }
+
+    /// Loads a CIP-57 blueprint used to validate datums and redeemers before execution
+    pub fn load_blueprint(&mut self, blueprint_json: &str) -> Result<(), JsError> {
+        let blueprint = Blueprint::from_json(blueprint_json).map_err(DebuggerError::BlueprintError)?;
+        self.blueprint = Some(blueprint);
+        Ok(())
+    }
+
+    /// Validates the datum and redeemer of every script redeemer against the schemas
+    /// of the matching blueprint validator, reporting mismatches without executing anything
+    pub fn validate_against_blueprint(&self) -> Result<String, JsError> {
+        let blueprint = self
+            .blueprint
+            .as_ref()
+            .ok_or(DebuggerError::BlueprintError("No blueprint loaded".to_string()))?;
+
+        let mut keys: Vec<&String> = self.redeemers.keys().collect();
+        keys.sort();
+
+        let mut results = vec![];
+        for key in keys {
+            let redeemer = &self.redeemers[key];
+            let Some((script, datum)) = self.redeemer_scripts.get(key) else {
+                continue;
+            };
+            let script_hash = compute_script_hash(script);
+            let tag = key.split(':').next().unwrap_or_default();
+            let validator = blueprint.find_validator(&script_hash, tag);
+
+            let (datum_mismatches, redeemer_mismatches) = match validator {
+                Some(validator) => (
+                    match (&validator.datum, datum) {
+                        (Some(argument), Some(datum)) => blueprint.validate(&argument.schema, datum),
+                        _ => vec![],
+                    },
+                    match &validator.redeemer {
+                        Some(argument) => blueprint.validate(&argument.schema, &redeemer.data),
+                        None => vec![],
+                    },
+                ),
+                None => (vec![], vec![]),
+            };
+
+            results.push(BlueprintValidationResult {
+                redeemer: key.clone(),
+                script_hash,
+                validator: validator.map(|validator| validator.title.clone()),
+                datum_mismatches,
+                redeemer_mismatches,
+            });
+        }
+
+        let valid = results
+            .iter()
+            .all(|result| result.datum_mismatches.is_empty() && result.redeemer_mismatches.is_empty());
+        let report = BlueprintValidationReport { results, valid };
+        self.to_json(&report)
+    }
+
+    /// Evaluates the program of every redeemer to completion, without breakpoints,
+    /// stopping a redeemer once it spends more than its declared ex units, and
+    /// reports each one's outcome, spent and declared ex units and traces
+    pub fn run_all(&self) -> Result<String, JsError> {
+        let run = self.run_all_inner()?;
+        self.to_json(&run)
+    }
+
+    pub(crate) fn run_all_inner(&self) -> Result<TransactionRun, JsError> {
+        let mut keys: Vec<&String> = self.redeemers.keys().collect();
+        keys.sort();
+
+        let mut runs = vec![];
+        for key in keys {
+            let redeemer = &self.redeemers[key];
+            let run = match self.create_session(key) {
+                Ok(mut session) => run_session(&mut session)?,
+                Err(e) => RedeemerRun::not_run(
+                    key,
+                    redeemer.ex_units.steps as i64,
+                    redeemer.ex_units.mem as i64,
+                    e.as_string().unwrap_or_default(),
+                ),
+            };
+            runs.push(run);
+        }
+        Ok(TransactionRun::new(runs))
+    }
+
+    /// Runs a redeemer to completion without its declared ex units as a limit and
+    /// gives the ex units it needs, to declare them in a transaction. The run stops
+    /// at the protocol's per-transaction ex units, as no larger declaration is valid.
+    ///
+    /// # Arguments
+    /// * `redeemer_str` - Redeemer to run
+    /// * `margin_percent` - Percentage added on top of what the run spent, rounded up; 0 by default
+    ///
+    /// # Returns
+    /// JSON `SerializableExUnits`
+    pub fn estimate_ex_units(&self, redeemer_str: &str, margin_percent: Option<u32>) -> Result<String, JsError> {
+        let ex_units = self.estimate_ex_units_inner(redeemer_str, margin_percent.unwrap_or(0))?;
+        self.to_json(&ex_units)
+    }
+
+    pub(crate) fn estimate_ex_units_inner(
+        &self,
+        redeemer_str: &str,
+        margin_percent: u32,
+    ) -> Result<SerializableExUnits, JsError> {
+        let options = SessionOptions {
+            budget_mode: BudgetMode::Max,
+            ..SessionOptions::default()
+        };
+        let mut session = self.create_session_with(redeemer_str, &options)?;
+        let tx_limit = self.tx_ex_unit_limit();
+        match session.evaluate_inner(tx_limit)? {
+            SerializableExecutionStatus::Done { .. } => {}
+            SerializableExecutionStatus::Error { message } => {
+                return Err(DebuggerError::MachineError(format!(
+                    "Script failed, its ex units can't be estimated: {}",
+                    message
+                ))
+                .into())
+            }
+            SerializableExecutionStatus::BudgetExceeded { .. } => {
+                return Err(DebuggerError::MachineError(format!(
+                    "Script spent more than the per-transaction limit of {} cpu and {} mem",
+                    tx_limit.cpu, tx_limit.mem
+                ))
+                .into())
+            }
+            other => {
+                return Err(DebuggerError::MachineError(format!(
+                    "Script did not finish, its ex units can't be estimated: {:?}",
+                    other
+                ))
+                .into())
+            }
+        }
+        let budget = session.get_budget_inner()?;
+        Ok(SerializableExUnits::new(
+            with_margin(budget.memory_units_spent, margin_percent),
+            with_margin(budget.ex_units_spent, margin_percent),
+        ))
+    }
+
+    /// Most ex units a transaction may spend under the protocol parameters, the
+    /// machine's default budget when they don't say
+    fn tx_ex_unit_limit(&self) -> ExBudget {
+        let pricing = BudgetPricing::from_protocol_params(&self.protocol_params);
+        ExBudget {
+            cpu: pricing.max_tx_ex_steps.unwrap_or(ExBudget::default().cpu),
+            mem: pricing.max_tx_ex_mem.unwrap_or(ExBudget::default().mem),
+        }
+    }
+
+    /// Prices the latest registered session of a redeemer again under other cost
+    /// models, from its recorded trace and builtin calls, without running the
+    /// machine again. Trace recording must have been on from the session's first step.
+    ///
+    /// # Arguments
+    /// * `redeemer_str` - Redeemer the session was opened for
+    /// * `cost_models_json` - `CostModels` JSON; the session's language must have one
+    ///
+    /// # Returns
+    /// JSON `RepriceReport` with the new totals next to the original ones
+    pub fn reprice(&self, redeemer_str: &str, cost_models_json: &str) -> Result<String, JsError> {
+        let cost_models = serde_json::from_str::<CostModels>(cost_models_json)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+        let mut latest: Option<(u64, &SessionController)> = None;
+        for session in self.sessions.values() {
+            if session.get_redeemer()? != redeemer_str {
+                continue;
+            }
+            let created_at = session.get_session_info_inner()?.created_at;
+            if latest.is_none_or(|(latest_at, _)| created_at >= latest_at) {
+                latest = Some((created_at, session));
+            }
+        }
+        let (_, session) = latest.ok_or_else(|| DebuggerError::SessionNotFound(redeemer_str.to_string()))?;
+        let report = session.reprice_inner(&cost_models)?;
+        self.to_json(&report)
+    }
+
+    /// Executes a redeemer twice from independently built sessions and checks that
+    /// both runs take the same steps, spend the same budget and end with the same
+    /// result, reporting the first step at which they diverge. Both runs get the
+    /// per-transaction ex units as their budget and stop at the engine's step limit.
+    pub fn audit_determinism(&self, redeemer_str: &str) -> Result<String, JsError> {
+        let tx_limit = self.tx_ex_unit_limit();
+        let options = SessionOptions {
+            budget_mode: BudgetMode::Custom {
+                cpu: tx_limit.cpu,
+                mem: tx_limit.mem,
+            },
+            ..SessionOptions::default()
+        };
+        let first = self.create_session_with(redeemer_str, &options)?;
+        let second = self.create_session_with(redeemer_str, &options)?;
+        let audit = audit_sessions(redeemer_str, first, second)?;
+        self.to_json(&audit)
+    }
+
+    /// Compares the script contexts of two sessions of this transaction (e.g. a
+    /// spend and a mint validator that must agree), listing the TxInfo fields they
+    /// share and the ones that differ per purpose
+    pub fn compare_contexts(&self, session_a: &SessionController, session_b: &SessionController) -> Result<String, JsError> {
+        let context_a = session_a.get_tx_script_context_inner()?;
+        let context_b = session_b.get_tx_script_context_inner()?;
+        let comparison = compare_contexts(
+            &session_a.get_redeemer()?,
+            &context_a,
+            &session_b.get_redeemer()?,
+            &context_b,
+        )
+        .map_err(DebuggerError::MachineError)?;
+
+        // `differing_fields` compares every TxInfo field; `differences` may be cut short
+        if comparison.differing_fields.iter().any(|field| field == "id") {
+            return Err(DebuggerError::MachineError("Sessions belong to different transactions".to_string()).into());
+        }
+
+        self.to_json(&comparison)
+    }
+
+    /// The TxInfo a script of the given Plutus version ("V1", "V2" or "V3") sees for
+    /// this transaction, built on demand when no redeemer uses that version
+    fn tx_info_for_version(&self, version: &str) -> Result<Arc<TxInfo>, DebuggerError> {
+        let existing = match version {
+            "V1" => &self.v1_context,
+            "V2" => &self.v2_context,
+            "V3" => &self.v3_context,
+            _ => return Err(DebuggerError::MachineError(format!("Unknown Plutus version: {}", version))),
+        };
+        if let Some(tx_info) = existing {
+            return Ok(tx_info.clone());
+        }
+
+        let tx = MintedTx::decode_fragment(&self.tx_bytes)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+        let tx_info = match version {
+            "V1" => TxInfoV1::from_transaction(&tx, &self.resolved_inputs, &self.slot_config),
+            "V2" => TxInfoV2::from_transaction(&tx, &self.resolved_inputs, &self.slot_config),
+            _ => TxInfoV3::from_transaction(&tx, &self.resolved_inputs, &self.slot_config),
+        };
+        tx_info
+            .map(Arc::new)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))
+    }
+
+    /// Builds the TxInfo of two Plutus versions (e.g. "V2" and "V3") for this
+    /// transaction and diffs them field by field, both decoded and as the data the
+    /// scripts receive, to check what a script upgraded between them reads differently
+    pub fn compare_tx_info_versions(&self, version_a: &str, version_b: &str) -> Result<String, JsError> {
+        let tx_info_a = self.tx_info_for_version(&version_a.to_uppercase())?;
+        let tx_info_b = self.tx_info_for_version(&version_b.to_uppercase())?;
+        let comparison = compare_tx_info_versions(&tx_info_a, &tx_info_b).map_err(DebuggerError::MachineError)?;
+        self.to_json(&comparison)
+    }
+
+    /// Exports a self-contained fixture (flat program, applied arguments, cost model,
+    /// expected budget and result) for a redeemer, with a Rust test replaying it
+    pub fn export_fixture(&self, redeemer_str: &str) -> Result<String, JsError> {
+        let redeemer = self
+            .redeemers
+            .get(redeemer_str)
+            .ok_or(DebuggerError::RedeemerNotFound(redeemer_str.to_string()))?;
+        let (script, datum) = self
+            .redeemer_scripts
+            .get(redeemer_str)
+            .ok_or(DebuggerError::ScriptNotFound(redeemer_str.to_string()))?;
+
+        let language = script_language(script);
+        let (program, arguments, _) = self.build_program(redeemer, script, datum.as_ref())?;
+        let fixture = ScriptFixture::capture(
+            &self.transaction_id,
+            redeemer_str,
+            &compute_script_hash(script),
+            &language,
+            &program,
+            &arguments,
+            cost_model_values(&self.protocol_params, &language)?,
+            ExBudget {
+                mem: redeemer.ex_units.mem as i64,
+                cpu: redeemer.ex_units.steps as i64,
+            },
+        )?;
+
+        let export = ExportedFixture {
+            rust_test: fixture.rust_test(),
+            fixture,
+        };
+        self.to_json(&export)
+    }
+
+    /// Lists every certificate handled by a certifying script next to the exact
+    /// PlutusData its script receives, encoded per the script's Plutus version
+    pub fn get_certificate_encodings(&self) -> Result<String, JsError> {
+        let mut redeemer_keys: Vec<&String> = self.redeemers.keys().collect();
+        redeemer_keys.sort();
+
+        let mut encodings = vec![];
+        for redeemer_str in redeemer_keys {
+            let redeemer = &self.redeemers[redeemer_str];
+            if !matches!(redeemer.tag, RedeemerTag::Cert) {
+                continue;
+            }
+            let Some((script, datum)) = self.redeemer_scripts.get(redeemer_str) else {
+                continue;
+            };
+            let (_, _, script_context) = self.build_program(redeemer, script, datum.as_ref())?;
+            if let Some(encoding) = certificate_encoding(redeemer_str, &script_context)
+                .map_err(DebuggerError::ScriptContextBuildError)?
+            {
+                encodings.push(encoding);
+            }
+        }
+
+        self.to_json(&encodings)
+    }
+
+    /// Gets the Reward-purpose scripts of the transaction, flagging the 0-lovelace
+    /// withdrawals of the "stake validator as observer" pattern, each with the
+    /// spend redeemers whose scripts forward to it
+    pub fn get_stake_observers(&self) -> Result<String, JsError> {
+        let observers = self.stake_observers()?;
+        self.to_json(&observers)
+    }
+
+    fn stake_observers(&self) -> Result<Vec<StakeObserver>, DebuggerError> {
+        let tx = MintedTx::decode_fragment(&self.tx_bytes)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+
+        let mut redeemer_keys: Vec<&String> = self.redeemer_scripts.keys().collect();
+        redeemer_keys.sort();
+        let spends: Vec<(&String, String, &PlutusScript)> = redeemer_keys
+            .iter()
+            .filter(|key| matches!(self.redeemers[**key].tag, RedeemerTag::Spend))
+            .map(|key| {
+                let (script, _) = &self.redeemer_scripts[*key];
+                (*key, compute_script_hash(script), script)
+            })
+            .collect();
+
+        let mut observers = vec![];
+        for key in redeemer_keys {
+            if !matches!(self.redeemers[key].tag, RedeemerTag::Reward) {
+                continue;
+            }
+            let (script, _) = &self.redeemer_scripts[key];
+            let script_hash = compute_script_hash(script);
+            let Some((reward_account, amount)) = withdrawal_for_script(&tx, &script_hash) else {
+                continue;
+            };
+            let mut observer = stake_observer(key, &script_hash, &reward_account, amount);
+
+            let hash_bytes = hex::decode(&script_hash).map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+            for (spend_key, spend_hash, spend_script) in &spends {
+                let link = if *spend_hash == script_hash {
+                    ObserverLink::SameScript
+                } else if term_embeds_bytes(&self.decoded_program(spend_script)?.term, &hash_bytes) {
+                    ObserverLink::EmbeddedHash
+                } else {
+                    continue;
+                };
+                observer.forwarding_spends.push(ForwardingSpend {
+                    redeemer: spend_key.to_string(),
+                    script_hash: spend_hash.clone(),
+                    link,
+                });
+            }
+            observers.push(observer);
+        }
+        Ok(observers)
+    }
+
+    /// Merges coverage maps (JSON array of `CoverageMap`, see `export_coverage_map`)
+    /// of one of this transaction's scripts, possibly exported from sessions of
+    /// other transactions, and reports the script's coverage by each and by all
+    pub fn aggregate_coverage(&self, maps_json: &str) -> Result<String, JsError> {
+        let maps = serde_json::from_str::<Vec<CoverageMap>>(maps_json)
+            .map_err(|e| DebuggerError::MachineError(format!("Invalid coverage maps: {}", e)))?;
+        let script_hash = maps
+            .first()
+            .map(|map| normalize_script_hash(&map.script_hash))
+            .transpose()
+            .map_err(DebuggerError::ScriptNotFound)?
+            .ok_or_else(|| DebuggerError::MachineError("No coverage maps to merge".to_string()))?;
+        let script = self
+            .redeemer_scripts
+            .values()
+            .map(|(script, _)| script)
+            .find(|script| compute_script_hash(script) == script_hash)
+            .ok_or_else(|| DebuggerError::ScriptNotFound(script_hash.clone()))?;
+        let program = self.decoded_program(script)?;
+        let aggregated = aggregate_coverage(&program.term, &maps).map_err(DebuggerError::MachineError)?;
+        self.to_json(&aggregated)
+    }
+
+    /// Gets the redeemers of the transaction as a graph: the script each runs, the
+    /// input, policy, certificate, withdrawal, vote or proposal it governs, the
+    /// datums of spent inputs and the spend scripts forwarding to stake observers
+    pub fn get_redeemer_graph(&self) -> Result<String, JsError> {
+        let graph = self.redeemer_graph()?;
+        self.to_json(&graph)
+    }
+
+    fn redeemer_graph(&self) -> Result<RedeemerGraph, DebuggerError> {
+        let tx = MintedTx::decode_fragment(&self.tx_bytes)
+            .map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?;
+        let mut inputs: Vec<_> = tx.transaction_body.inputs.iter().collect();
+        inputs.sort_by(|a, b| (a.transaction_id.as_ref(), a.index).cmp(&(b.transaction_id.as_ref(), b.index)));
+
+        let mut redeemer_keys: Vec<&String> = self.redeemers.keys().collect();
+        redeemer_keys.sort();
+
+        let mut graph = RedeemerGraph::default();
+        for key in redeemer_keys {
+            let redeemer = &self.redeemers[key];
+            let redeemer_node = graph.add_node(
+                format!("redeemer:{}", key),
+                GraphNodeKind::Redeemer,
+                key.clone(),
+                Some(format!("cpu {}, mem {}", redeemer.ex_units.steps, redeemer.ex_units.mem)),
+            );
+
+            let script_hash = self.redeemer_scripts.get(key).map(|(script, _)| {
+                let script_hash = compute_script_hash(script);
+                let label = script_hash_to_bech32(&script_hash).unwrap_or_else(|_| script_hash.clone());
+                let script_node = graph.add_node(
+                    format!("script:{}", script_hash),
+                    GraphNodeKind::Script,
+                    label,
+                    Some(format!("{:?}", script_language(script))),
+                );
+                graph.add_edge(&redeemer_node, &script_node, "runs");
+                script_hash
+            });
+
+            let index = redeemer.index as usize;
+            match redeemer.tag {
+                RedeemerTag::Spend => {
+                    let Some(input) = inputs.get(index) else {
+                        continue;
+                    };
+                    let transaction_id = hex::encode(input.transaction_id);
+                    let outref = format!("{}#{}", transaction_id, input.index);
+                    let input_node = graph.add_node(format!("input:{}", outref), GraphNodeKind::Input, outref, None);
+                    graph.add_edge(&redeemer_node, &input_node, "spends");
+
+                    let datum = self.datums.datums.iter().find(|datum| {
+                        datum.locations.iter().any(|location| {
+                            matches!(location, DatumLocation::Input { transaction_id: id, output_index, .. }
+                                if *id == transaction_id && *output_index == input.index)
+                        })
+                    });
+                    if let Some(datum) = datum {
+                        let datum_node = graph.add_node(
+                            format!("datum:{}", datum.hash),
+                            GraphNodeKind::Datum,
+                            datum.hash.clone(),
+                            None,
+                        );
+                        graph.add_edge(&input_node, &datum_node, "has_datum");
+                    }
+                }
+                RedeemerTag::Mint => {
+                    if let Some(policy) = self.mint_policies.iter().find(|policy| policy.redeemer.as_ref() == Some(key)) {
+                        let policy_node = graph.add_node(
+                            format!("policy:{}", policy.policy_id),
+                            GraphNodeKind::Policy,
+                            policy.policy_id.clone(),
+                            Some(format!("{} token(s)", policy.tokens.len())),
+                        );
+                        graph.add_edge(&redeemer_node, &policy_node, "mints");
+                    }
+                }
+                RedeemerTag::Cert => {
+                    let node = graph.add_node(
+                        format!("certificate:{}", index),
+                        GraphNodeKind::Certificate,
+                        format!("Certificate {}", index),
+                        None,
+                    );
+                    graph.add_edge(&redeemer_node, &node, "certifies");
+                }
+                RedeemerTag::Reward => {
+                    let withdrawal = script_hash.as_deref().and_then(|hash| withdrawal_for_script(&tx, hash));
+                    if let Some((reward_account, amount)) = withdrawal {
+                        let node = graph.add_node(
+                            format!("withdrawal:{}", hex::encode(&reward_account)),
+                            GraphNodeKind::Withdrawal,
+                            reward_account_to_bech32(&reward_account).unwrap_or_else(|_| hex::encode(&reward_account)),
+                            Some(format!("{} lovelace", amount)),
+                        );
+                        graph.add_edge(&redeemer_node, &node, "withdraws");
+                    }
+                }
+                RedeemerTag::Vote => {
+                    let node = graph.add_node(format!("voter:{}", index), GraphNodeKind::Voter, format!("Voter {}", index), None);
+                    graph.add_edge(&redeemer_node, &node, "votes");
+                }
+                RedeemerTag::Propose => {
+                    let node = graph.add_node(
+                        format!("proposal:{}", index),
+                        GraphNodeKind::Proposal,
+                        format!("Proposal {}", index),
+                        None,
+                    );
+                    graph.add_edge(&redeemer_node, &node, "proposes");
+                }
+            }
+        }
+
+        for observer in self.stake_observers()? {
+            for spend in &observer.forwarding_spends {
+                graph.add_edge(
+                    &format!("redeemer:{}", spend.redeemer),
+                    &format!("redeemer:{}", observer.redeemer),
+                    "forwards_to",
+                );
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Opens a session on the stake observer a spend redeemer forwards to, or on
+    /// the given redeemer itself when it is already a Reward redeemer
+    pub fn init_observer_session(&mut self, redeemer_str: &str) -> Result<SessionController, JsError> {
+        let observer = self
+            .stake_observers()?
+            .into_iter()
+            .find(|observer| {
+                observer.redeemer == redeemer_str
+                    || observer.forwarding_spends.iter().any(|spend| spend.redeemer == redeemer_str)
+            })
+            .ok_or_else(|| DebuggerError::RedeemerNotFound(format!("No stake observer for {}", redeemer_str)))?;
+        self.init_debug_session(&observer.redeemer, None)
+    }
+
+    /// Initializes a new debug session for a specific redeemer. The session is
+    /// handed to the caller; `open_session` keeps it in the engine instead.
+    ///
+    /// # Arguments
+    /// * `options_json` - `SessionOptions` as JSON: slippage and the budget the machine starts with
+    pub fn init_debug_session(&mut self, redeemer_str: &str, options_json: Option<String>) -> Result<SessionController, JsError> {
+        let options = session_options(options_json.as_deref())?;
+        self.create_session_with(redeemer_str, &options)
+    }
+
+    /// Creates a session for a redeemer and keeps it in the engine's registry, so
+    /// several redeemers of the transaction can be debugged side by side
+    ///
+    /// # Returns
+    /// The id of the new session
+    pub fn open_session(&mut self, redeemer_str: &str, options_json: Option<String>) -> Result<String, JsError> {
+        let options = session_options(options_json.as_deref())?;
+        let session = self.create_session_with(redeemer_str, &options)?;
+        Ok(self.store_session(session))
+    }
+
+    /// Adds a session to the registry, e.g. one taken out with `take_session`,
+    /// replacing any registered session with the same id
+    pub fn store_session(&mut self, session: SessionController) -> String {
+        let session_id = session.get_session_id().to_string();
+        self.sessions.insert(session_id.clone(), session);
+        session_id
+    }
+
+    /// Removes a session from the registry and hands it to the caller, which
+    /// can drive it directly and give it back with `store_session`
+    pub fn take_session(&mut self, session_id: &str) -> Result<SessionController, JsError> {
+        Ok(self
+            .sessions
+            .remove(session_id)
+            .ok_or_else(|| DebuggerError::SessionNotFound(session_id.to_string()))?)
+    }
+
+    /// Gets the `SessionInfo` of a registered session. The session stays in the
+    /// registry; `take_session` hands it out to be driven.
+    pub fn get_session(&self, session_id: &str) -> Result<String, JsError> {
+        let info = self
+            .session(session_id)
+            .ok_or_else(|| DebuggerError::SessionNotFound(session_id.to_string()))?
+            .get_session_info_inner()?;
+        self.to_json(&info)
+    }
+
+    /// Gets the `SessionInfo` of every registered session, oldest first
+    pub fn list_sessions(&self) -> Result<String, JsError> {
+        let mut sessions = self
+            .sessions
+            .values()
+            .map(|session| session.get_session_info_inner())
+            .collect::<Result<Vec<_>, _>>()?;
+        sessions.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.session_id.cmp(&b.session_id)));
+        self.to_json(&sessions)
+    }
+
+    /// Drops a registered session, returning whether it existed
+    pub fn close_session(&mut self, session_id: &str) -> bool {
+        self.sessions.remove(session_id).is_some()
+    }
+
+    /// Rebuilds a session saved with `SessionController::save_session` for a
+    /// redeemer of this transaction and replays it to the saved step. Like
+    /// `init_debug_session`, the session is handed to the caller.
+    pub fn load_session(&self, bytes: &[u8]) -> Result<SessionController, JsError> {
+        let saved = SavedSession::from_bytes(bytes).map_err(DebuggerError::MachineError)?;
+        let mut session = self.create_session(&saved.redeemer)?;
+        session.load_saved(&saved)?;
+        Ok(session)
+    }
+
+    fn create_session(&self, redeemer_str: &str) -> Result<SessionController, JsError> {
+        self.create_session_with(redeemer_str, &SessionOptions::default())
+    }
+
+    fn create_session_with(&self, redeemer_str: &str, options: &SessionOptions) -> Result<SessionController, JsError> {
+        // Parse redeemer string to find the specific redeemer
+        let parts: Vec<&str> = redeemer_str.split(":").collect();
+        if parts.len() != 2 {
+            return Err(JsError::from_str(&format!(
+                "Redeemer not found: {}",
+                redeemer_str
+            )));
+        }
+
+        let redeemer = self
+            .redeemers
+            .get(redeemer_str)
+            .ok_or(DebuggerError::RedeemerNotFound(redeemer_str.to_string()))?;
+
+        let (script, datum) = self
+            .redeemer_scripts
+            .get(redeemer_str)
+            .ok_or(DebuggerError::ScriptNotFound(redeemer_str.to_string()))?;
+
+        let script_hash = compute_script_hash(script);
+
+        let session_id = Uuid::new_v4().to_string();
+        let language = script_language(script);
+        let cost_model = self.get_const_model(&language)?;
+        let cost_model_values = cost_model_values(&self.protocol_params, &language)?.clone();
+        let (program, arguments, script_context) = self.build_program(redeemer, script, datum.as_ref())?;
+        check_program_builtins(&language, &program.term).map_err(DebuggerError::ProgramBuildError)?;
+        let real_budget = ExBudget {
+            mem: redeemer.ex_units.mem as i64,
+            cpu: redeemer.ex_units.steps as i64,
+        };
+        let upper_bound_budget = match options.budget_mode {
+            BudgetMode::Declared => real_budget,
+            BudgetMode::Max => ExBudget::max(),
+            BudgetMode::Custom { cpu, mem } => ExBudget { cpu, mem },
+        };
+
+        let mut session = SessionController::new(
+            script_hash,
+            session_id,
+            language,
+            program,
+            arguments,
+            script_context,
+            cost_model,
+            cost_model_values,
+            upper_bound_budget,
+            options.slippage,
+            real_budget,
+            redeemer_str.to_string(),
+            self.quota.clone(),
+            BudgetPricing::from_protocol_params(&self.protocol_params),
+        )?;
+        session.apply_settings(&self.settings)?;
+        Ok(session)
+    }
+
+    /// Sets soft limits on steps executed and bytes serialized across all sessions
+    pub fn set_quota_limits(&mut self, limits_json: &str) -> Result<(), JsError> {
+        let limits = serde_json::from_str::<QuotaLimits>(limits_json)
+            .map_err(|e| DebuggerError::MachineError(format!("Invalid quota limits: {}", e)))?;
+        self.quota.borrow_mut().set_limits(limits);
+        Ok(())
+    }
+
+    /// Gets the steps executed and bytes serialized across all sessions, with any soft limit warnings
+    pub fn get_quota_usage(&self) -> Result<String, JsError> {
+        let usage = self.quota.borrow().usage().clone();
+        self.to_json(&usage)
+    }
+
+    fn build_program(
+        &self,
+        redeemer: &Redeemer,
+        script: &PlutusScript,
+        datum: Option<&PlutusData>,
+    ) -> Result<(Box<Program<NamedDeBruijn>>, Arc<Vec<PlutusData>>, Arc<ScriptContext>), JsError> {
+        let initial_program = self.decoded_program(script)?;
+        let redeemer_key = format!("{}:{}", redeemer_tag_to_string(&redeemer.tag), redeemer.index);
+        let prepared = self
+            .context_cache
+            .get_or_build(&redeemer_key, || self.prepare_context(redeemer, script, datum))?;
+
+        Ok((Box::new(Program::clone(&initial_program)), prepared.arguments, prepared.script_context))
+    }
+
+    /// Builds the script context of a redeemer from the shared TxInfo of its
+    /// script's version. `into_script_context` consumes the TxInfo, so this is the
+    /// one deep copy per redeemer; the context cache keeps it from being repeated.
+    fn prepare_context(
+        &self,
+        redeemer: &Redeemer,
+        script: &PlutusScript,
+        datum: Option<&PlutusData>,
+    ) -> Result<PreparedContext, DebuggerError> {
+        let tx_info = match script {
+            PlutusScript::V1(_) => &self.v1_context,
+            PlutusScript::V2(_) => &self.v2_context,
+            PlutusScript::V3(_) => &self.v3_context,
+        }
+        .as_ref()
+        .ok_or(DebuggerError::ScriptContextBuildError(format!(
+            "Failed to get script context for script: {:?}",
+            script
+        )))?;
+
+        let script_context = TxInfo::clone(tx_info)
+            .into_script_context(redeemer, datum)
+            .ok_or(DebuggerError::ScriptContextBuildError(format!(
+                "Failed to build script context for script: {:?}",
+                script
+            )))?;
+
+        let arguments = match script_context {
+            ScriptContext::V1V2 { .. } => datum
+                .cloned()
+                .into_iter()
+                .chain([redeemer.data.clone(), script_context.to_plutus_data()])
+                .collect(),
+
+            ScriptContext::V3 { .. } => vec![script_context.to_plutus_data()],
+        };
+
+        Ok(PreparedContext {
+            script_context: Arc::new(script_context),
+            arguments: Arc::new(arguments),
+        })
+    }
+
+    /// Decodes a script into a program once per script hash; later sessions and
+    /// rebuilds of the same script skip the CBOR decode and de Bruijn conversion
+    fn decoded_program(&self, script: &PlutusScript) -> Result<Rc<Program<NamedDeBruijn>>, DebuggerError> {
+        let script_hash = compute_script_hash(script);
+        if let Some(program) = self.program_cache.borrow().get(&script_hash) {
+            return Ok(program.clone());
+        }
+
+        let mut buffer = Vec::new();
+        let program: Program<NamedDeBruijn> = Program::<FakeNamedDeBruijn>::from_cbor(&script, &mut buffer)
+            .map(Into::into)
+            .map_err(|e| DebuggerError::ProgramBuildError(e.to_string()))?;
+        let program = Rc::new(program);
+        self.program_cache.borrow_mut().insert(script_hash, program.clone());
+        Ok(program)
+    }
+
+    /// Drops the decoded programs and script contexts kept for creating sessions
+    pub fn clear_program_cache(&self) {
+        self.program_cache.borrow_mut().clear();
+        self.context_cache.clear();
+    }
+
+    /// Gets how many TxInfo copies building script contexts took, and how many
+    /// sessions reused a context instead
+    pub fn get_context_cache_stats(&self) -> Result<String, JsError> {
+        self.to_json(&self.context_cache.stats())
+    }
+
+    fn get_const_model(&self, language: &Language) -> Result<CostModel, JsError> {
+        Ok(cost_model_for(&self.protocol_params, language)?)
+    }
+}
+
+impl DebuggerEngine {
+    /// Serializes a response, compact and in the schema version envelope when the settings ask for it
+    fn to_json<T: serde::Serialize>(&self, value: &T) -> Result<String, JsError> {
+        let format = if self.settings.compact_json { SerializationFormat::CompactJson } else { SerializationFormat::Json };
+        let json = serialize(value, format).map_err(DebuggerError::MachineError)?;
+        let json = String::from_utf8(json).map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+        Ok(if self.settings.json_envelope { envelope_json(&json) } else { json })
+    }
+
+    /// A registered session, for native callers that drive it in place. Hosts
+    /// across the wasm boundary use `take_session` and `store_session` instead.
+    pub fn session(&self, session_id: &str) -> Option<&SessionController> {
+        self.sessions.get(session_id)
+    }
+
+    pub fn session_mut(&mut self, session_id: &str) -> Option<&mut SessionController> {
+        self.sessions.get_mut(session_id)
+    }
+}
+
+/// Raw cost model parameters for `language` from the protocol parameters
+pub(crate) fn cost_model_values<'a>(
+    protocol_params: &'a ProtocolParameters,
+    language: &Language,
+) -> Result<&'a Vec<i64>, DebuggerError> {
+    protocol_params
+        .cost_models
+        .as_ref()
+        .map(|cost_models| match language {
+            Language::PlutusV1 => &cost_models.plutus_v1,
+            Language::PlutusV2 => &cost_models.plutus_v2,
+            Language::PlutusV3 => &cost_models.plutus_v3,
+        })
+        .into_iter()
+        .flatten()
+        .next()
+        .ok_or(DebuggerError::TransactionParseError(format!(
+            "No cost models in protocol parameters for language {:?}",
+            language,
+        )))
+}
+
+/// Builds the machine cost model for `language` from the protocol parameters
+pub(crate) fn cost_model_for(protocol_params: &ProtocolParameters, language: &Language) -> Result<CostModel, DebuggerError> {
+    let cost_models = cost_model_values(protocol_params, language)?;
+    // initialize_cost_model indexes the array by position and panics on a short one
+    check_cost_model(language, cost_models).map_err(DebuggerError::TransactionParseError)?;
+    Ok(initialize_cost_model(language, cost_models))
+}
+
+fn session_options(options_json: Option<&str>) -> Result<SessionOptions, DebuggerError> {
+    options_json
+        .map(SessionOptions::from_json)
+        .transpose()
+        .map_err(|e| DebuggerError::MachineError(format!("Invalid session options: {}", e)))
+        .map(Option::unwrap_or_default)
+}
+
+fn script_language(script: &PlutusScript) -> Language {
+    match script {
+        PlutusScript::V1(_) => Language::PlutusV1,
+        PlutusScript::V2(_) => Language::PlutusV2,
+        PlutusScript::V3(_) => Language::PlutusV3,
+    }
+}
+
+fn compute_script_hash(script: &PlutusScript) -> String {
+    use pallas_crypto::hash::Hasher;
+    let script_hash = match script {
+        PlutusScript::V1(script) => Hasher::<224>::hash_tagged(&script.0, 1),
+        PlutusScript::V2(script) => Hasher::<224>::hash_tagged(&script.0, 2),
+        PlutusScript::V3(script) => Hasher::<224>::hash_tagged(&script.0, 3),
+    };
+    hex::encode(script_hash)
+}
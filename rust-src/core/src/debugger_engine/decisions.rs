@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{Constant, NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+    machine::{value::Value, Context, MachineState},
+    PlutusData,
+};
+
+/// A control-flow choice made during execution
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct DecisionPoint {
+    pub step: u64,
+    /// Id of the Case term, or of the Builtin term for builtin choices
+    pub term_id: i32,
+    /// "Case", "IfThenElse", "ChooseList" or "ChooseData"
+    pub kind: String,
+    /// Index of the branch taken (for IfThenElse: 0 = then, 1 = else)
+    pub branch_index: usize,
+    /// The scrutinee that decided the branch, e.g. `Constr 1 (2 fields)` or `False`
+    pub reason: String,
+}
+
+/// Upper bound on recorded decisions; later ones only set `truncated`
+pub const MAX_DECISIONS: usize = 100_000;
+
+/// The branch choices of a run, in execution order
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct DecisionTrace {
+    pub decisions: Vec<DecisionPoint>,
+    /// Whether decisions past `MAX_DECISIONS` were left out
+    pub truncated: bool,
+}
+
+impl DecisionTrace {
+    pub fn push(&mut self, decision: DecisionPoint) {
+        if self.decisions.len() < MAX_DECISIONS {
+            self.decisions.push(decision);
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    /// Drops the decisions made after `step`
+    pub fn truncate_after(&mut self, step: u64) {
+        self.decisions.retain(|decision| decision.step <= step);
+        if self.decisions.len() < MAX_DECISIONS {
+            self.truncated = false;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.decisions.clear();
+        self.truncated = false;
+    }
+}
+
+/// Maps the term id of every Case branch to its Case term id and branch index,
+/// so a branch chosen at runtime can be attributed to its Case
+pub fn case_branches(term: &Term<NamedDeBruijn>) -> HashMap<i32, (i32, usize)> {
+    let mut branches = HashMap::new();
+    collect_case_branches(term, &mut branches);
+    branches
+}
+
+fn collect_case_branches(term: &Term<NamedDeBruijn>, out: &mut HashMap<i32, (i32, usize)>) {
+    match term {
+        Term::Case { constr, branches, uniq_id } => {
+            for (index, branch) in branches.iter().enumerate() {
+                out.insert(term_id(branch), (*uniq_id as i32, index));
+                collect_case_branches(branch, out);
+            }
+            collect_case_branches(constr, out);
+        }
+        Term::Apply { function, argument, .. } => {
+            collect_case_branches(function, out);
+            collect_case_branches(argument, out);
+        }
+        Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => {
+            collect_case_branches(body, out)
+        }
+        Term::Constr { fields, .. } => fields.iter().for_each(|field| collect_case_branches(field, out)),
+        _ => {}
+    }
+}
+
+pub(crate) fn term_id(term: &Term<NamedDeBruijn>) -> i32 {
+    match term {
+        Term::Var { uniq_id, .. }
+        | Term::Delay { uniq_id, .. }
+        | Term::Lambda { uniq_id, .. }
+        | Term::Apply { uniq_id, .. }
+        | Term::Constant { uniq_id, .. }
+        | Term::Force { uniq_id, .. }
+        | Term::Error { uniq_id, .. }
+        | Term::Builtin { uniq_id, .. }
+        | Term::Constr { uniq_id, .. }
+        | Term::Case { uniq_id, .. } => *uniq_id as i32,
+    }
+}
+
+/// Branch selected by a Case scrutinee, with a short description of it
+pub(crate) fn case_choice(value: &Value) -> Option<(usize, String)> {
+    match value {
+        Value::Constr { tag, fields, .. } => Some((*tag, format!("Constr {} ({} fields)", tag, fields.len()))),
+        Value::Con(constant) => match constant.as_ref() {
+            Constant::Bool(b) => Some((*b as usize, if *b { "True" } else { "False" }.to_string())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Branch selected by a choice builtin from its first argument
+fn builtin_choice(fun: &DefaultFunction, scrutinee: &Value) -> Option<(usize, String)> {
+    let Value::Con(constant) = scrutinee else {
+        return None;
+    };
+    match (fun, constant.as_ref()) {
+        (DefaultFunction::IfThenElse, Constant::Bool(b)) => {
+            Some((if *b { 0 } else { 1 }, if *b { "True" } else { "False" }.to_string()))
+        }
+        (DefaultFunction::ChooseList, Constant::ProtoList(_, items)) if items.is_empty() => {
+            Some((0, "[]".to_string()))
+        }
+        (DefaultFunction::ChooseList, Constant::ProtoList(_, items)) => {
+            Some((1, format!("list of {} items", items.len())))
+        }
+        (DefaultFunction::ChooseData, Constant::Data(data)) => Some(match data {
+            PlutusData::Constr(constr) => (0, format!("Constr (tag {})", constr.tag)),
+            PlutusData::Map(_) => (1, "Map".to_string()),
+            PlutusData::Array(_) => (2, "List".to_string()),
+            PlutusData::BigInt(_) => (3, "I".to_string()),
+            PlutusData::BoundedBytes(_) => (4, "B".to_string()),
+        }),
+        _ => None,
+    }
+}
+
+/// Inspects the state about to be stepped and reports the decision it makes, if any
+pub fn observe_decision(
+    state: &MachineState,
+    step: u64,
+    case_branches: &HashMap<i32, (i32, usize)>,
+) -> Option<DecisionPoint> {
+    let MachineState::Return(context, value) = state else {
+        return None;
+    };
+    match context {
+        Context::FrameCases(_, branches, ..) => {
+            let (branch_index, reason) = case_choice(value)?;
+            let (case_id, _) = case_branches.get(&term_id(branches.get(branch_index)?))?;
+            Some(DecisionPoint {
+                step,
+                term_id: *case_id,
+                kind: "Case".to_string(),
+                branch_index,
+                reason,
+            })
+        }
+        // The last argument is about to saturate a choice builtin
+        _ => saturating_choice(saturating_builtin(state)?, step),
+    }
+}
+
+/// The builtin that the step from `state` applies to its last argument, if any
+pub(crate) fn saturating_builtin(state: &MachineState) -> Option<&Value> {
+    let MachineState::Return(context, value) = state else {
+        return None;
+    };
+    let function = match context {
+        Context::FrameAwaitArg(function, ..) => function,
+        Context::FrameAwaitFunValue(..) => value,
+        _ => return None,
+    };
+    match function {
+        Value::Builtin { fun, runtime, .. } if runtime.args.len() + 1 == fun.arity() => Some(function),
+        _ => None,
+    }
+}
+
+fn saturating_choice(function: &Value, step: u64) -> Option<DecisionPoint> {
+    let Value::Builtin { fun, runtime, term_id } = function else {
+        return None;
+    };
+    if !matches!(fun, DefaultFunction::IfThenElse | DefaultFunction::ChooseList | DefaultFunction::ChooseData) {
+        return None;
+    }
+    let (branch_index, reason) = builtin_choice(fun, runtime.args.first()?)?;
+    Some(DecisionPoint {
+        step,
+        term_id: *term_id as i32,
+        kind: format!("{:?}", fun),
+        branch_index,
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(step: u64) -> DecisionPoint {
+        DecisionPoint {
+            step,
+            term_id: 1,
+            kind: "IfThenElse".to_string(),
+            branch_index: 0,
+            reason: "True".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_decision_trace_is_capped() {
+        let mut trace = DecisionTrace::default();
+        for step in 1..=MAX_DECISIONS as u64 + 10 {
+            trace.push(decision(step));
+        }
+        assert_eq!(trace.decisions.len(), MAX_DECISIONS);
+        assert!(trace.truncated);
+        assert_eq!(trace.decisions.last().unwrap().step, MAX_DECISIONS as u64);
+
+        trace.truncate_after(5);
+        assert_eq!(trace.decisions.len(), 5);
+        assert!(!trace.truncated);
+        trace.clear();
+        assert!(trace.decisions.is_empty());
+    }
+
+    #[test]
+    fn test_builtin_choices() {
+        let condition = Value::Con(Constant::Bool(false).into());
+        assert_eq!(builtin_choice(&DefaultFunction::IfThenElse, &condition), Some((1, "False".to_string())));
+        let empty = Value::Con(Constant::ProtoList(uplc::ast::Type::Integer, vec![]).into());
+        assert_eq!(builtin_choice(&DefaultFunction::ChooseList, &empty), Some((0, "[]".to_string())));
+        let data = Value::Con(Constant::Data(PlutusData::BoundedBytes(vec![1].into())).into());
+        assert_eq!(builtin_choice(&DefaultFunction::ChooseData, &data), Some((4, "B".to_string())));
+        assert_eq!(builtin_choice(&DefaultFunction::AddInteger, &condition), None);
+    }
+}
@@ -0,0 +1,94 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::SerializableEnv;
+
+/// Serialized copy of an env taken at a given point of execution
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct EnvPin {
+    pub label: String,
+    pub step: u64,
+    pub term_id: i32,
+    /// Env values outermost binding first, as in `SerializableEnv`
+    pub values: Vec<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum EnvChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct EnvChange {
+    /// Position in the env, outermost binding first
+    pub index: usize,
+    pub kind: EnvChangeKind,
+    pub pinned: Option<Value>,
+    pub current: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct EnvPinComparison {
+    pub label: String,
+    pub pinned_step: u64,
+    pub current_step: u64,
+    pub pinned_size: usize,
+    pub current_size: usize,
+    /// Number of positions holding the same value in both envs
+    pub unchanged: usize,
+    pub changes: Vec<EnvChange>,
+}
+
+impl EnvPin {
+    pub fn new(label: String, step: u64, term_id: i32, env: &SerializableEnv) -> Result<Self, String> {
+        let values = env
+            .values
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok(EnvPin {
+            label,
+            step,
+            term_id,
+            values,
+        })
+    }
+
+    /// Diffs `current` against the pinned env position by position
+    pub fn compare(&self, current: &EnvPin) -> EnvPinComparison {
+        let mut unchanged = 0;
+        let mut changes = vec![];
+        for index in 0..self.values.len().max(current.values.len()) {
+            let (pinned, now) = (self.values.get(index), current.values.get(index));
+            let kind = match (pinned, now) {
+                (Some(pinned), Some(now)) if pinned == now => {
+                    unchanged += 1;
+                    continue;
+                }
+                (Some(_), Some(_)) => EnvChangeKind::Changed,
+                (None, _) => EnvChangeKind::Added,
+                (_, None) => EnvChangeKind::Removed,
+            };
+            changes.push(EnvChange {
+                index,
+                kind,
+                pinned: pinned.cloned(),
+                current: now.cloned(),
+            });
+        }
+
+        EnvPinComparison {
+            label: self.label.clone(),
+            pinned_step: self.step,
+            current_step: current.step,
+            pinned_size: self.values.len(),
+            current_size: current.values.len(),
+            unchanged,
+            changes,
+        }
+    }
+}
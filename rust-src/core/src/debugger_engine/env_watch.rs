@@ -0,0 +1,78 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::machine::{value::Value, Context, MachineState};
+
+/// What an env watchpoint pauses on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(tag = "by")]
+pub enum EnvWatchTarget {
+    /// Any Lambda binding a value at this env position, counted from the outermost
+    /// binding as `get_current_env` lists values
+    Slot { index: usize },
+    /// The Lambda with this term id binding its parameter
+    Binder { term_id: i32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct EnvWatch {
+    pub id: u32,
+    pub target: EnvWatchTarget,
+}
+
+/// A value about to be bound: the Lambda applied and the env position its parameter takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnvBinding {
+    pub lambda_id: i32,
+    pub index: usize,
+}
+
+impl EnvWatchTarget {
+    pub fn matches(&self, binding: &EnvBinding) -> bool {
+        match self {
+            EnvWatchTarget::Slot { index } => *index == binding.index,
+            EnvWatchTarget::Binder { term_id } => *term_id == binding.lambda_id,
+        }
+    }
+}
+
+/// Env position a variable with de Bruijn index `index` refers to under an env of `env_len` values
+pub fn slot_of_index(env_len: usize, index: usize) -> Option<usize> {
+    (index >= 1 && index <= env_len).then(|| env_len - index)
+}
+
+/// The binding the step from `state` makes, if it applies a Lambda to its argument
+pub fn binding_at(state: &MachineState) -> Option<EnvBinding> {
+    let MachineState::Return(context, value) = state else {
+        return None;
+    };
+    let function = match context {
+        Context::FrameAwaitArg(function, ..) => function,
+        Context::FrameAwaitFunValue(..) => value,
+        _ => return None,
+    };
+    match function {
+        Value::Lambda { env, term_id, .. } => Some(EnvBinding {
+            lambda_id: *term_id as i32,
+            index: env.len(),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_watch_targets() {
+        let binding = EnvBinding { lambda_id: 12, index: 3 };
+        assert!(EnvWatchTarget::Slot { index: 3 }.matches(&binding));
+        assert!(!EnvWatchTarget::Slot { index: 2 }.matches(&binding));
+        assert!(EnvWatchTarget::Binder { term_id: 12 }.matches(&binding));
+
+        assert_eq!(slot_of_index(4, 1), Some(3));
+        assert_eq!(slot_of_index(4, 4), Some(0));
+        assert_eq!(slot_of_index(4, 0), None);
+        assert_eq!(slot_of_index(4, 5), None);
+    }
+}
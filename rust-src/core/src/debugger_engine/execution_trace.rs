@@ -0,0 +1,223 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::machine::{cost_model::ExBudget, MachineState};
+
+/// Upper bound on recorded steps; later steps only set `truncated`
+pub const MAX_TRACE_ENTRIES: usize = 5_000_000;
+
+/// Leading bytes of the binary trace format, followed by a format version
+const BINARY_MAGIC: &[u8; 4] = b"UPTR";
+const BINARY_VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+pub enum TraceStateKind {
+    Compute,
+    Return,
+    Done,
+}
+
+impl TraceStateKind {
+    pub fn of(state: &MachineState) -> Self {
+        match state {
+            MachineState::Compute(..) => TraceStateKind::Compute,
+            MachineState::Return(..) => TraceStateKind::Return,
+            MachineState::Done(_) => TraceStateKind::Done,
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            TraceStateKind::Compute => 0,
+            TraceStateKind::Return => 1,
+            TraceStateKind::Done => 2,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(TraceStateKind::Compute),
+            1 => Some(TraceStateKind::Return),
+            2 => Some(TraceStateKind::Done),
+            _ => None,
+        }
+    }
+}
+
+/// One step: the state it started from and what it cost
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct TraceEntry {
+    pub kind: TraceStateKind,
+    /// Term computed by the step, -1 for Return states
+    pub term_id: i32,
+    pub cpu: i64,
+    pub mem: i64,
+}
+
+/// Every step of a run as recorded by `set_trace_recording`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ExecutionTrace {
+    /// Step count before the first recorded step
+    pub first_step: u64,
+    pub entries: Vec<TraceEntry>,
+    /// Whether steps beyond `MAX_TRACE_ENTRIES` went unrecorded
+    pub truncated: bool,
+}
+
+/// Records steps while enabled
+#[derive(Debug, Clone, Default)]
+pub struct TraceRecorder {
+    trace: Option<ExecutionTrace>,
+}
+
+impl TraceRecorder {
+    pub fn is_enabled(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Starts recording from step `step` on, or stops and drops the recording
+    pub fn set_enabled(&mut self, enabled: bool, step: u64) {
+        self.trace = enabled.then(|| ExecutionTrace {
+            first_step: step,
+            entries: vec![],
+            truncated: false,
+        });
+    }
+
+    pub fn record(&mut self, kind: TraceStateKind, term_id: i32, before: &ExBudget, after: &ExBudget) {
+        let Some(trace) = self.trace.as_mut() else {
+            return;
+        };
+        if trace.entries.len() >= MAX_TRACE_ENTRIES {
+            trace.truncated = true;
+            return;
+        }
+        // The machine budget counts down, so the cost of a step is what it removed
+        trace.entries.push(TraceEntry {
+            kind,
+            term_id,
+            cpu: before.cpu - after.cpu,
+            mem: before.mem - after.mem,
+        });
+    }
+
+    /// Drops the entries of steps after `step`, for stepping back
+    pub fn truncate_after(&mut self, step: u64) {
+        if let Some(trace) = self.trace.as_mut() {
+            if step < trace.first_step {
+                trace.first_step = step;
+                trace.entries.clear();
+            } else {
+                trace.entries.truncate((step - trace.first_step) as usize);
+            }
+            trace.truncated = false;
+        }
+    }
+
+    pub fn trace(&self) -> Option<&ExecutionTrace> {
+        self.trace.as_ref()
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], at: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*at).ok_or("Trace ends inside a number")?;
+        *at += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("Number too long in trace".to_string())
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+impl ExecutionTrace {
+    /// Compact binary form: `UPTR`, a version byte, the first step, a truncated
+    /// flag and the entry count, then per entry the state kind byte and the term
+    /// id and cpu/mem costs as zigzag LEB128 varints
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(16 + self.entries.len() * 6);
+        out.extend_from_slice(BINARY_MAGIC);
+        out.push(BINARY_VERSION);
+        write_varint(&mut out, self.first_step);
+        out.push(self.truncated as u8);
+        write_varint(&mut out, self.entries.len() as u64);
+        for entry in &self.entries {
+            out.push(entry.kind.code());
+            write_varint(&mut out, zigzag(entry.term_id as i64));
+            write_varint(&mut out, zigzag(entry.cpu));
+            write_varint(&mut out, zigzag(entry.mem));
+        }
+        out
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 5 || &bytes[..4] != BINARY_MAGIC {
+            return Err("Not a binary execution trace".to_string());
+        }
+        if bytes[4] != BINARY_VERSION {
+            return Err(format!("Unsupported trace format version {}", bytes[4]));
+        }
+        let mut at = 5;
+        let first_step = read_varint(bytes, &mut at)?;
+        let truncated = *bytes.get(at).ok_or("Trace ends before its header")? != 0;
+        at += 1;
+        let count = read_varint(bytes, &mut at)?;
+        let mut entries = Vec::with_capacity((count as usize).min(MAX_TRACE_ENTRIES));
+        for _ in 0..count {
+            let code = *bytes.get(at).ok_or("Trace ends inside an entry")?;
+            at += 1;
+            let kind = TraceStateKind::from_code(code).ok_or_else(|| format!("Unknown state kind {}", code))?;
+            entries.push(TraceEntry {
+                kind,
+                term_id: unzigzag(read_varint(bytes, &mut at)?) as i32,
+                cpu: unzigzag(read_varint(bytes, &mut at)?),
+                mem: unzigzag(read_varint(bytes, &mut at)?),
+            });
+        }
+        Ok(ExecutionTrace {
+            first_step,
+            entries,
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut recorder = TraceRecorder::default();
+        recorder.set_enabled(true, 0);
+        let budget = |cpu, mem| ExBudget { cpu, mem };
+        recorder.record(TraceStateKind::Compute, 12, &budget(1_000, 100), &budget(984, 99));
+        recorder.record(TraceStateKind::Return, -1, &budget(984, 99), &budget(984, 99));
+        recorder.record(TraceStateKind::Compute, 300_000, &budget(984, 99), &budget(0, 0));
+
+        let trace = recorder.trace().unwrap().clone();
+        assert_eq!(trace.entries[0].cpu, 16);
+        assert_eq!(ExecutionTrace::from_binary(&trace.to_binary()).unwrap(), trace);
+        assert!(ExecutionTrace::from_binary(b"UPTR").is_err());
+
+        recorder.truncate_after(1);
+        assert_eq!(recorder.trace().unwrap().entries.len(), 1);
+    }
+}
@@ -0,0 +1,233 @@
+use pallas_codec::minicbor;
+use pallas_primitives::conway::Language;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{FakeNamedDeBruijn, NamedDeBruijn, Program},
+    machine::cost_model::{initialize_cost_model, ExBudget},
+    manual_machine::{ExecutionStatus, ManualMachine},
+    PlutusData,
+};
+
+use super::session_controller::{apply_arguments, DEFAULT_SLIPPAGE};
+use super::DebuggerError;
+use crate::cost_model_upgrade::check_cost_model;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct FixtureBudget {
+    pub cpu: i64,
+    pub mem: i64,
+}
+
+/// Observable result of evaluating a fixture, compared by the generated test
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct FixtureOutcome {
+    pub success: bool,
+    pub error: Option<String>,
+    pub budget: FixtureBudget,
+}
+
+/// Everything needed to re-evaluate one redeemer without the transaction it came from
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ScriptFixture {
+    pub name: String,
+    pub transaction_id: String,
+    pub redeemer: String,
+    pub script_hash: String,
+    /// "PlutusV1", "PlutusV2" or "PlutusV3"
+    pub plutus_version: String,
+    /// Unapplied program, flat encoded (hex)
+    pub program_flat: String,
+    /// Arguments applied to the program in order, CBOR encoded (hex)
+    pub arguments: Vec<String>,
+    /// Cost model parameters of the script's language
+    pub cost_model: Vec<i64>,
+    /// Ex units declared by the redeemer, used as the evaluation limit
+    pub budget_limit: FixtureBudget,
+    pub expected: FixtureOutcome,
+}
+
+/// Fixture together with a Rust test that replays it
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ExportedFixture {
+    pub fixture: ScriptFixture,
+    /// `#[test]` expecting the fixture JSON next to it as `<name>.json`
+    pub rust_test: String,
+}
+
+fn parse_language(plutus_version: &str) -> Result<Language, DebuggerError> {
+    match plutus_version {
+        "PlutusV1" => Ok(Language::PlutusV1),
+        "PlutusV2" => Ok(Language::PlutusV2),
+        "PlutusV3" => Ok(Language::PlutusV3),
+        other => Err(DebuggerError::ProgramBuildError(format!("Unknown Plutus version: {}", other))),
+    }
+}
+
+/// Lowercase identifier usable both as a test function name and a file name
+fn fixture_name(transaction_id: &str, redeemer: &str) -> String {
+    let redeemer: String = redeemer
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{}_{}", redeemer, &transaction_id[..transaction_id.len().min(8)])
+}
+
+/// Flat encoding (hex) of an unapplied program
+pub(crate) fn program_flat(program: &Program<NamedDeBruijn>) -> Result<Vec<u8>, DebuggerError> {
+    Program::<FakeNamedDeBruijn>::from(program.clone())
+        .to_flat()
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Failed to flat encode program: {}", e)))
+}
+
+pub(crate) fn encode_program_flat(program: &Program<NamedDeBruijn>) -> Result<String, DebuggerError> {
+    program_flat(program).map(hex::encode)
+}
+
+/// Wraps flat program bytes in a CBOR bytestring, the form scripts take in transactions
+pub(crate) fn cbor_wrap(flat: &[u8]) -> Result<Vec<u8>, DebuggerError> {
+    let mut encoder = minicbor::Encoder::new(Vec::new());
+    encoder
+        .bytes(flat)
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Failed to CBOR wrap program: {}", e)))?;
+    Ok(encoder.into_writer())
+}
+
+pub(crate) fn decode_program_flat(program_flat: &str) -> Result<Program<NamedDeBruijn>, DebuggerError> {
+    let program_flat = hex::decode(program_flat)
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid program hex: {}", e)))?;
+    Program::<FakeNamedDeBruijn>::from_flat(&program_flat)
+        .map(Into::<Program<NamedDeBruijn>>::into)
+        .map_err(|e| DebuggerError::ProgramBuildError(e.to_string()))
+}
+
+/// CBOR encoding (hex) of each argument
+pub(crate) fn encode_arguments(arguments: &[PlutusData]) -> Result<Vec<String>, DebuggerError> {
+    arguments
+        .iter()
+        .map(|argument| {
+            minicbor::to_vec(argument)
+                .map(hex::encode)
+                .map_err(|e| DebuggerError::ProgramBuildError(format!("Failed to encode argument: {}", e)))
+        })
+        .collect()
+}
+
+pub(crate) fn decode_arguments(arguments: &[String]) -> Result<Vec<PlutusData>, DebuggerError> {
+    arguments
+        .iter()
+        .map(|argument| {
+            let bytes = hex::decode(argument)
+                .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid argument hex: {}", e)))?;
+            minicbor::decode::<PlutusData>(&bytes)
+                .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid argument: {}", e)))
+        })
+        .collect()
+}
+
+impl ScriptFixture {
+    /// Captures the program and arguments of a redeemer and records the outcome
+    /// of evaluating them as the expectation
+    pub(crate) fn capture(
+        transaction_id: &str,
+        redeemer: &str,
+        script_hash: &str,
+        language: &Language,
+        program: &Program<NamedDeBruijn>,
+        arguments: &[PlutusData],
+        cost_model: &[i64],
+        budget_limit: ExBudget,
+    ) -> Result<Self, DebuggerError> {
+        let program_flat = encode_program_flat(program)?;
+        let arguments = encode_arguments(arguments)?;
+
+        let mut fixture = ScriptFixture {
+            name: fixture_name(transaction_id, redeemer),
+            transaction_id: transaction_id.to_string(),
+            redeemer: redeemer.to_string(),
+            script_hash: script_hash.to_string(),
+            plutus_version: format!("{:?}", language),
+            program_flat,
+            arguments,
+            cost_model: cost_model.to_vec(),
+            budget_limit: FixtureBudget {
+                cpu: budget_limit.cpu,
+                mem: budget_limit.mem,
+            },
+            expected: FixtureOutcome {
+                success: false,
+                error: None,
+                budget: FixtureBudget { cpu: 0, mem: 0 },
+            },
+        };
+        fixture.expected = fixture.replay()?;
+        Ok(fixture)
+    }
+
+    /// Decodes the program and arguments and evaluates them from scratch
+    pub fn replay(&self) -> Result<FixtureOutcome, DebuggerError> {
+        let language = parse_language(&self.plutus_version)?;
+        let program = decode_program_flat(&self.program_flat)?;
+        let arguments = decode_arguments(&self.arguments)?;
+
+        check_cost_model(&language, &self.cost_model).map_err(DebuggerError::ProgramBuildError)?;
+        let limit = ExBudget {
+            cpu: self.budget_limit.cpu,
+            mem: self.budget_limit.mem,
+        };
+        let mut machine = ManualMachine::new(
+            language.clone(),
+            initialize_cost_model(&language, &self.cost_model),
+            limit,
+            DEFAULT_SLIPPAGE,
+            apply_arguments(&program, &arguments).term,
+        )
+        .map_err(|e| DebuggerError::MachineError(format!("Failed to create manual machine: {:?}", e)))?;
+
+        let error = loop {
+            match machine.step() {
+                ExecutionStatus::Ready => continue,
+                ExecutionStatus::Done(_) => break None,
+                ExecutionStatus::Error(error) => break Some(error.to_string()),
+            }
+        };
+
+        Ok(FixtureOutcome {
+            success: error.is_none(),
+            error,
+            budget: FixtureBudget {
+                cpu: limit.cpu - machine.ex_budget.cpu,
+                mem: limit.mem - machine.ex_budget.mem,
+            },
+        })
+    }
+
+    /// Rust test replaying this fixture through the crate's machine integration
+    pub fn rust_test(&self) -> String {
+        format!(
+            r#"#[test]
+fn fixture_{name}() {{
+    // {redeemer} of transaction {transaction_id}
+    let fixture: de_uplc::debugger_engine::fixture::ScriptFixture =
+        serde_json::from_str(include_str!("{name}.json")).unwrap();
+    let outcome = fixture.replay().unwrap();
+    assert_eq!(outcome, fixture.expected);
+}}
+"#,
+            name = self.name,
+            redeemer = self.redeemer,
+            transaction_id = self.transaction_id,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cbor_wrap() {
+        assert_eq!(cbor_wrap(&[1, 2, 3]).unwrap(), vec![0x43, 1, 2, 3]);
+        assert_eq!(cbor_wrap(&[0; 24]).unwrap()[..2], [0x58, 24]);
+    }
+}
@@ -0,0 +1,120 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::ast::{NamedDeBruijn, Term};
+
+use super::builtin_checks::{scan_builtin_misuse, BuiltinMisuse};
+use super::decisions::term_id;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum ForceDelayIssueKind {
+    /// Force applied directly to a lambda, constant or constructor
+    ForceNonDelay,
+    /// Delay, constant or constructor applied to an argument
+    ApplyNonFunction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ForceDelayIssue {
+    pub kind: ForceDelayIssueKind,
+    pub term_id: i32,
+    pub message: String,
+}
+
+/// Forces a polymorphic builtin needs at one occurrence, against those written around it
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BuiltinForceSite {
+    pub builtin: String,
+    pub term_id: i32,
+    pub required_forces: u32,
+    pub applied_forces: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ForceDelayReport {
+    pub delays: usize,
+    pub forces: usize,
+    pub builtin_sites: Vec<BuiltinForceSite>,
+    /// Nodes that will fail if evaluated
+    pub issues: Vec<ForceDelayIssue>,
+    /// Builtin spines with missing or excess forces or arguments
+    pub builtin_misuses: Vec<BuiltinMisuse>,
+}
+
+/// Name of a term that is a value of the wrong kind for Force or Apply
+fn non_delay_kind(term: &Term<NamedDeBruijn>) -> Option<&'static str> {
+    match term {
+        Term::Lambda { .. } => Some("lambda"),
+        Term::Constant { .. } => Some("constant"),
+        Term::Constr { .. } => Some("constructor"),
+        _ => None,
+    }
+}
+
+fn non_function_kind(term: &Term<NamedDeBruijn>) -> Option<&'static str> {
+    match term {
+        Term::Delay { .. } => Some("delay"),
+        Term::Constant { .. } => Some("constant"),
+        Term::Constr { .. } => Some("constructor"),
+        _ => None,
+    }
+}
+
+/// Walk the program counting Force/Delay nodes and flagging statically visible mismatches
+pub fn analyze_force_balance(term: &Term<NamedDeBruijn>) -> ForceDelayReport {
+    let mut report = ForceDelayReport {
+        delays: 0,
+        forces: 0,
+        builtin_sites: vec![],
+        issues: vec![],
+        builtin_misuses: scan_builtin_misuse(term),
+    };
+    visit(term, 0, &mut report);
+    report
+}
+
+/// `forces` is the number of Force nodes directly wrapping `term`
+fn visit(term: &Term<NamedDeBruijn>, forces: u32, report: &mut ForceDelayReport) {
+    match term {
+        Term::Force { body, uniq_id } => {
+            report.forces += 1;
+            if let Some(kind) = non_delay_kind(body) {
+                report.issues.push(ForceDelayIssue {
+                    kind: ForceDelayIssueKind::ForceNonDelay,
+                    term_id: *uniq_id as i32,
+                    message: format!("Force applied to a {}", kind),
+                });
+            }
+            visit(body, forces + 1, report);
+        }
+        Term::Builtin { fun, uniq_id } if fun.force_count() > 0 || forces > 0 => {
+            report.builtin_sites.push(BuiltinForceSite {
+                builtin: format!("{:?}", fun),
+                term_id: *uniq_id as i32,
+                required_forces: fun.force_count(),
+                applied_forces: forces,
+            });
+        }
+        Term::Apply { function, argument, uniq_id } => {
+            if let Some(kind) = non_function_kind(function) {
+                report.issues.push(ForceDelayIssue {
+                    kind: ForceDelayIssueKind::ApplyNonFunction,
+                    term_id: *uniq_id as i32,
+                    message: format!("Applied a {} to an argument (term {})", kind, term_id(argument)),
+                });
+            }
+            visit(function, 0, report);
+            visit(argument, 0, report);
+        }
+        Term::Delay { body, .. } => {
+            report.delays += 1;
+            visit(body, 0, report);
+        }
+        Term::Lambda { body, .. } => visit(body, 0, report),
+        Term::Constr { fields, .. } => fields.iter().for_each(|field| visit(field, 0, report)),
+        Term::Case { constr, branches, .. } => {
+            visit(constr, 0, report);
+            branches.iter().for_each(|branch| visit(branch, 0, report));
+        }
+        _ => {}
+    }
+}
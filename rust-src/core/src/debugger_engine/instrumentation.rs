@@ -0,0 +1,162 @@
+use std::rc::Rc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{Constant, NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+};
+
+use super::decisions::term_id;
+
+/// A trace inserted around a term of the session's program
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TraceInjection {
+    /// Term the trace is emitted before
+    pub term_id: i32,
+    pub message: String,
+    /// Ids given to the inserted terms; the outermost one replaces `term_id` in its parent
+    pub inserted_term_ids: Vec<i32>,
+}
+
+/// Largest term id of `term`, so inserted terms can be numbered after it
+pub fn max_term_id(term: &Term<NamedDeBruijn>) -> i32 {
+    let children = match term {
+        Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => max_term_id(body),
+        Term::Apply { function, argument, .. } => max_term_id(function).max(max_term_id(argument)),
+        Term::Constr { fields, .. } => fields.iter().map(max_term_id).max().unwrap_or(-1),
+        Term::Case { constr, branches, .. } => branches.iter().map(max_term_id).fold(max_term_id(constr), i32::max),
+        Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } | Term::Builtin { .. } => -1,
+    };
+    term_id(term).max(children)
+}
+
+/// `(force [(force (builtin trace)) (con string message) (delay target)])`: emits
+/// `message` right before `target` is computed, in the same env and with the same
+/// result. Ids are taken from `next_id` upwards.
+fn traced(target: Term<NamedDeBruijn>, message: &str, next_id: i32) -> (Term<NamedDeBruijn>, Vec<i32>) {
+    let ids: Vec<i32> = (next_id..next_id + 6).collect();
+    let builtin = Term::Builtin { fun: DefaultFunction::Trace, uniq_id: ids[0] as _ };
+    let instantiated = Term::Force { body: builtin.into(), uniq_id: ids[1] as _ };
+    let text = Term::Constant { value: Rc::new(Constant::String(message.to_string())), uniq_id: ids[2] as _ };
+    let partial = Term::Apply { function: instantiated.into(), argument: text.into(), uniq_id: ids[3] as _ };
+    let delayed = Term::Delay { body: target.into(), uniq_id: ids[4] as _ };
+    let applied = Term::Apply { function: partial.into(), argument: delayed.into(), uniq_id: ids[5] as _ };
+    let outer_id = next_id + 6;
+    (
+        Term::Force { body: applied.into(), uniq_id: outer_id as _ },
+        std::iter::once(outer_id).chain(ids).collect(),
+    )
+}
+
+fn rewrite(
+    term: &Term<NamedDeBruijn>,
+    target: i32,
+    wrap: &mut dyn FnMut(Term<NamedDeBruijn>) -> Term<NamedDeBruijn>,
+) -> Option<Term<NamedDeBruijn>> {
+    if term_id(term) == target {
+        return Some(wrap(term.clone()));
+    }
+    match term {
+        Term::Delay { body, uniq_id } => rewrite(body, target, wrap).map(|body| Term::Delay {
+            body: body.into(),
+            uniq_id: *uniq_id,
+        }),
+        Term::Force { body, uniq_id } => rewrite(body, target, wrap).map(|body| Term::Force {
+            body: body.into(),
+            uniq_id: *uniq_id,
+        }),
+        Term::Lambda { parameter_name, body, uniq_id } => rewrite(body, target, wrap).map(|body| Term::Lambda {
+            parameter_name: parameter_name.clone(),
+            body: body.into(),
+            uniq_id: *uniq_id,
+        }),
+        Term::Apply { function, argument, uniq_id } => match rewrite(function, target, wrap) {
+            Some(function) => Some(Term::Apply {
+                function: function.into(),
+                argument: argument.clone(),
+                uniq_id: *uniq_id,
+            }),
+            None => rewrite(argument, target, wrap).map(|argument| Term::Apply {
+                function: function.clone(),
+                argument: argument.into(),
+                uniq_id: *uniq_id,
+            }),
+        },
+        Term::Constr { tag, fields, uniq_id } => {
+            let (index, field) = fields
+                .iter()
+                .enumerate()
+                .find_map(|(index, field)| rewrite(field, target, wrap).map(|field| (index, field)))?;
+            let mut fields = fields.clone();
+            fields[index] = field;
+            Some(Term::Constr {
+                tag: *tag,
+                fields,
+                uniq_id: *uniq_id,
+            })
+        }
+        Term::Case { constr, branches, uniq_id } => match rewrite(constr, target, wrap) {
+            Some(constr) => Some(Term::Case {
+                constr: constr.into(),
+                branches: branches.clone(),
+                uniq_id: *uniq_id,
+            }),
+            None => {
+                let (index, branch) = branches
+                    .iter()
+                    .enumerate()
+                    .find_map(|(index, branch)| rewrite(branch, target, wrap).map(|branch| (index, branch)))?;
+                let mut branches = branches.clone();
+                branches[index] = branch;
+                Some(Term::Case {
+                    constr: constr.clone(),
+                    branches,
+                    uniq_id: *uniq_id,
+                })
+            }
+        },
+        Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } | Term::Builtin { .. } => None,
+    }
+}
+
+/// Copy of `term` in which the term `target` emits `message` each time it is
+/// about to be computed, or None when `term` has no such term
+pub fn inject_trace(term: &Term<NamedDeBruijn>, target: i32, message: &str) -> Option<(Term<NamedDeBruijn>, TraceInjection)> {
+    let next_id = max_term_id(term) + 1;
+    let mut inserted_term_ids = vec![];
+    let rewritten = rewrite(term, target, &mut |found| {
+        let (wrapped, ids) = traced(found, message, next_id);
+        inserted_term_ids = ids;
+        wrapped
+    })?;
+    Some((
+        rewritten,
+        TraceInjection {
+            term_id: target,
+            message: message.to_string(),
+            inserted_term_ids,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_trace() {
+        let unit = |id: usize| Term::Constant { value: Rc::new(Constant::Unit), uniq_id: id as _ };
+        let term: Term<NamedDeBruijn> = Term::Constr { tag: 0, fields: vec![unit(1), unit(2)], uniq_id: 0 };
+
+        let (rewritten, injection) = inject_trace(&term, 2, "here").unwrap();
+        assert_eq!(injection.inserted_term_ids[0], 9);
+        assert_eq!(max_term_id(&rewritten), 9);
+        let Term::Constr { fields, .. } = &rewritten else {
+            panic!("expected the Constr to be kept");
+        };
+        assert_eq!(term_id(&fields[0]), 1);
+        assert!(matches!(&fields[1], Term::Force { .. }));
+        assert!(inject_trace(&term, 42, "missing").is_none());
+    }
+}
@@ -1,13 +1,14 @@
 use crate::{
-    lazy_loading::{LazyLoadConfig, NavigationResult, PathSegment},
-    value::{navigate_to_value, navigate_to_env_lazy, SerializableEnv, navigate_to_value_from_env},
-    context::navigate_context_to_any,
+    lazy_loading::{self, embed_stub_paths, LazyLoadConfig, NavigationResult, PathRoot, PathSegment},
+    value::{navigate_env_to_any, navigate_value_to_any, SerializableEnv},
+    context::{navigate_context_to_any, serializable_frames},
     serializer::navigate_to_term_lazy,
     SerializableMachineState,
     debugger_engine::DebuggerError,
-    wasm_tools::JsError,
+    wasm_tools::{enveloped_json, wasm_bindgen, JsError},
 };
-use std::collections::HashSet;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use uplc::machine::MachineState;
 use uplc::manual_machine::ManualMachine;
 
@@ -28,6 +29,7 @@ impl LazySessionApi {
         path: Vec<PathSegment>,
         return_full_object: bool,
     ) -> Result<String, JsError> {
+        let path = lazy_loading::canonicalize_path(PathRoot::MachineState, &path);
         let state = machine.current_state();
         
         // If no path specified, return the top-level state
@@ -37,54 +39,36 @@ impl LazySessionApi {
                 return_full_object,
             };
             let lazy_state = SerializableMachineState::from_uplc_machine_state_lazy(state, term_ids, &config);
-            return Ok(serde_json::to_string(&lazy_state)
-                .map_err(|e| DebuggerError::MachineError(e.to_string()))?);
+            return Self::to_json_with_paths(&lazy_state, &path);
         }
         
         // Navigate to the specific element
         let result = match (state, path.first().unwrap()) {
             (MachineState::Return(_context, value), PathSegment::Field(field)) if field == "value" => {
-                navigate_to_value(value, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v))
+                navigate_value_to_any(value, &path[1..], term_ids, return_full_object)
             }
             (MachineState::Return(context, _), PathSegment::Field(field)) if field == "context" => {
                 navigate_context_to_any(context, &path[1..], term_ids, return_full_object)
-                    .map(|v| Ok(v))
             }
             (MachineState::Compute(_context, env, _term), PathSegment::Field(field)) if field == "env" => {
-                // If path continues after 'env', navigate to values inside env
-                if path.len() > 1 {
-                    navigate_to_value_from_env(env, &path[1..], term_ids, return_full_object)
-                        .map(|v| serde_json::to_value(v))
-                } else {
-                    // Just return the env itself
-                match navigate_to_env_lazy(env, &path[1..], term_ids, return_full_object) {
-                    NavigationResult::Found(env_lazy) => NavigationResult::Found(serde_json::to_value(env_lazy)),
-                    NavigationResult::InvalidPath(msg) => NavigationResult::InvalidPath(msg),
-                    NavigationResult::Incomplete => NavigationResult::Incomplete,
-                }
-            }
+                navigate_env_to_any(env, &path[1..], term_ids, return_full_object)
             }
             (MachineState::Compute(context, _, _), PathSegment::Field(field)) if field == "context" => {
                 navigate_context_to_any(context, &path[1..], term_ids, return_full_object)
-                    .map(|v| Ok(v))
             }
             (MachineState::Compute(_, _, term), PathSegment::Field(field)) if field == "term" => {
                 navigate_to_term_lazy(term, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v))
+                    .map(|v| serde_json::to_value(v).unwrap())
             }
             (MachineState::Done(term), PathSegment::Field(field)) if field == "term" => {
                 navigate_to_term_lazy(term, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v))
+                    .map(|v| serde_json::to_value(v).unwrap())
             }
             _ => NavigationResult::InvalidPath(format!("Invalid path for machine state: {:?}", path))
         };
         
         match result {
-            NavigationResult::Found(value) => {
-                Ok(value.map_err(|e| DebuggerError::MachineError(format!("Serialization error: {}", e)))
-                    .and_then(|v| serde_json::to_string(&v).map_err(|e| DebuggerError::MachineError(e.to_string())))?)
-            }
+            NavigationResult::Found(value) => Self::to_json_with_paths(&value, &path),
             NavigationResult::InvalidPath(msg) => Err(DebuggerError::MachineError(msg))?,
             NavigationResult::Incomplete => Err(DebuggerError::MachineError("Path incomplete".to_string()))?,
         }
@@ -103,6 +87,7 @@ impl LazySessionApi {
         path: Vec<PathSegment>,
         return_full_object: bool,
     ) -> Result<String, JsError> {
+        let path = lazy_loading::canonicalize_path(PathRoot::Env, &path);
         match machine.current_state() {
             MachineState::Compute(_, env, _) => {
                 // If path is empty, return the whole env
@@ -112,24 +97,11 @@ impl LazySessionApi {
                         return_full_object,
                     };
                     let lazy_env = SerializableEnv::from_uplc_env_lazy(env, term_ids, &config);
-                    Ok(serde_json::to_string(&lazy_env)
-                        .map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+                    Self::to_json_with_paths(&lazy_env, &path)
                 } else {
                     // Navigate to specific element
-                    let result = if path.first() == Some(&PathSegment::Field("values".to_string())) || 
-                                    matches!(path.first(), Some(PathSegment::Index(_))) {
-                        // Navigating to a value
-                        navigate_to_value_from_env(env, &path, term_ids, return_full_object)
-                            .map(|v| serde_json::to_value(v))
-                    } else {
-                        NavigationResult::InvalidPath(format!("Invalid path for env: {:?}", path))
-                    };
-                    
-                    match result {
-                        NavigationResult::Found(value) => {
-                            Ok(value.map_err(|e| DebuggerError::MachineError(format!("Serialization error: {}", e)))
-                                .and_then(|v| serde_json::to_string(&v).map_err(|e| DebuggerError::MachineError(e.to_string())))?)
-                        }
+                    match navigate_env_to_any(env, &path, term_ids, return_full_object) {
+                        NavigationResult::Found(value) => Self::to_json_with_paths(&value, &path),
                         NavigationResult::InvalidPath(msg) => Err(DebuggerError::MachineError(msg))?,
                         NavigationResult::Incomplete => Err(DebuggerError::MachineError("Path incomplete".to_string()))?,
                     }
@@ -150,16 +122,22 @@ impl LazySessionApi {
     /// # Arguments
     /// * `machine` - The machine to get contexts from
     /// * `term_ids` - Set of term IDs for optimization
+    /// * `case_branches` - Case branches of the program, to summarize frames
     /// * `path` - Path segments to navigate to specific element (e.g., ["0", "env", "values", "2"])
     /// * `return_full_object` - If true, returns full object at path; if false, returns object with children 1 level deep only
+    ///
+    /// Without a path, returns the frames as `SerializableFrame`s; paths index a
+    /// frame and then its payload, without a `payload` segment.
     pub fn get_machine_context_lazy(
         machine: &ManualMachine,
         term_ids: &HashSet<i32>,
+        case_branches: &HashMap<i32, (i32, usize)>,
         path: Vec<PathSegment>,
         return_full_object: bool,
     ) -> Result<String, JsError> {
         use crate::SerializableMachineContext;
         
+        let path = lazy_loading::canonicalize_path(PathRoot::MachineContext, &path);
         let contexts = machine.collect_nested_contexts();
         
         // If path is empty, return all contexts
@@ -168,13 +146,17 @@ impl LazySessionApi {
                 path: vec![],
                 return_full_object,
             };
-            let lazy_contexts: Vec<_> = contexts
-                .into_iter()
-                .map(|ctx| SerializableMachineContext::from_uplc_context_lazy(&ctx, term_ids, &config))
-                .collect();
-            
-            return Ok(serde_json::to_string(&lazy_contexts)
-                .map_err(|e| DebuggerError::MachineError(e.to_string()))?);
+            let frames = serializable_frames(&contexts, 0..contexts.len(), case_branches, |ctx| {
+                SerializableMachineContext::from_uplc_context_lazy(ctx, term_ids, &config)
+            });
+            let mut frames = serde_json::to_value(&frames).map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+            // Stub paths go through the frame index straight into its payload
+            for (depth, frame) in frames.as_array_mut().into_iter().flatten().enumerate() {
+                if let Some(payload) = frame.get_mut("payload") {
+                    embed_stub_paths(payload, &[PathSegment::Index(depth)]);
+                }
+            }
+            return Ok(serde_json::to_string(&frames).map_err(|e| DebuggerError::MachineError(e.to_string()))?);
         }
         
         // Navigate to specific element
@@ -184,10 +166,7 @@ impl LazySessionApi {
                 let result = navigate_context_to_any(context, &path[1..], term_ids, return_full_object);
                 
                 match result {
-                    NavigationResult::Found(value) => {
-                        Ok(serde_json::to_string(&value)
-                            .map_err(|e| DebuggerError::MachineError(e.to_string()))?)
-                    }
+                    NavigationResult::Found(value) => Self::to_json_with_paths(&value, &path),
                     NavigationResult::InvalidPath(msg) => Err(DebuggerError::MachineError(msg))?,
                     NavigationResult::Incomplete => Err(DebuggerError::MachineError("Path incomplete".to_string()))?,
                 }
@@ -205,20 +184,42 @@ impl LazySessionApi {
             return Ok(vec![]);
         }
         
-        let path_array: Vec<String> = serde_json::from_str(path_json)
+        // Segments are strings; plain numbers are accepted as indices too
+        let path_array: Vec<serde_json::Value> = serde_json::from_str(path_json)
             .map_err(|e| DebuggerError::MachineError(format!("Invalid path format: {}", e)))?;
         
         let segments: Vec<PathSegment> = path_array
             .into_iter()
-            .map(|s| {
-                if let Ok(index) = s.parse::<usize>() {
-                    PathSegment::Index(index)
-                } else {
-                    PathSegment::Field(s)
-                }
+            .map(|segment| match segment {
+                serde_json::Value::String(s) => Ok(match s.parse::<usize>() {
+                    Ok(index) => PathSegment::Index(index),
+                    Err(_) => PathSegment::Field(s),
+                }),
+                serde_json::Value::Number(n) => n
+                    .as_u64()
+                    .map(|index| PathSegment::Index(index as usize))
+                    .ok_or_else(|| DebuggerError::MachineError(format!("Invalid path index: {}", n))),
+                other => Err(DebuggerError::MachineError(format!("Invalid path segment: {}", other))),
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
         
         Ok(segments)
     }
+
+    /// Serializes a lazy payload found at `path`, embedding the canonical path of every stub in it
+    fn to_json_with_paths<T: Serialize>(payload: &T, path: &[PathSegment]) -> Result<String, JsError> {
+        let mut value = serde_json::to_value(payload).map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+        embed_stub_paths(&mut value, path);
+        Ok(serde_json::to_string(&value).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+    }
+}
+
+/// Normalizes a lazy path (JSON array) relative to `root` ("state", "env" or
+/// "context") into the canonical form lazy payloads report in `_path`
+#[wasm_bindgen]
+pub fn canonicalize_path(root: &str, path_json: &str) -> Result<String, JsError> {
+    let root = PathRoot::parse(root).map_err(DebuggerError::MachineError)?;
+    let path = LazySessionApi::parse_path(path_json)?;
+    Ok(enveloped_json(&lazy_loading::path_to_strings(&lazy_loading::canonicalize_path(root, &path)))
+        .map_err(|e| DebuggerError::MachineError(e.to_string()))?)
 }
@@ -0,0 +1,87 @@
+use regex_lite::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Traces shown around each match
+pub const SEARCH_CONTEXT_LINES: usize = 2;
+/// Matches returned by one search; continue from `next_index` for more
+pub const SEARCH_MAX_MATCHES: usize = 100;
+
+/// A trace matching the search pattern, with the traces surrounding it
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct LogMatch {
+    /// Index of the trace in `get_logs`
+    pub index: usize,
+    pub trace: String,
+    /// Up to `SEARCH_CONTEXT_LINES` traces before and after the match
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct LogSearchResult {
+    pub pattern: String,
+    /// Number of traces collected by the session
+    pub total_traces: usize,
+    pub matches: Vec<LogMatch>,
+    /// Index to resume the search from when the match limit was reached
+    pub next_index: Option<usize>,
+}
+
+/// Searches `traces` for `pattern` starting at `from_index`. `trace_at` renders a
+/// trace on demand, so only matches and their context are converted to strings.
+pub fn search_traces(
+    total_traces: usize,
+    trace_at: impl Fn(usize) -> String,
+    pattern: &str,
+    from_index: usize,
+) -> Result<LogSearchResult, String> {
+    let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+    let mut matches = vec![];
+    let mut next_index = None;
+
+    for index in from_index..total_traces {
+        let trace = trace_at(index);
+        if !regex.is_match(&trace) {
+            continue;
+        }
+        if matches.len() == SEARCH_MAX_MATCHES {
+            next_index = Some(index);
+            break;
+        }
+        let context_start = index.saturating_sub(SEARCH_CONTEXT_LINES);
+        let context_end = (index + 1 + SEARCH_CONTEXT_LINES).min(total_traces);
+        matches.push(LogMatch {
+            index,
+            trace,
+            before: (context_start..index).map(&trace_at).collect(),
+            after: (index + 1..context_end).map(&trace_at).collect(),
+        });
+    }
+
+    Ok(LogSearchResult {
+        pattern: pattern.to_string(),
+        total_traces,
+        matches,
+        next_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_with_context() {
+        let traces: Vec<String> = (0..10).map(|i| format!("trace {}", i)).collect();
+        let result = search_traces(traces.len(), |i| traces[i].clone(), r"trace [05]$", 1).unwrap();
+        assert_eq!(result.matches.len(), 1);
+        let found = &result.matches[0];
+        assert_eq!(found.index, 5);
+        assert_eq!(found.before, vec!["trace 3", "trace 4"]);
+        assert_eq!(found.after, vec!["trace 6", "trace 7"]);
+        assert!(result.next_index.is_none());
+
+        assert!(search_traces(traces.len(), |i| traces[i].clone(), "(", 0).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+pub mod debugger_engine;
+pub mod session_controller;
+pub mod session_persistence;
+pub mod lazy_session_api;
+pub mod aiken_traces;
+pub mod annotations;
+pub mod quick_run;
+pub mod sandbox;
+pub mod settings;
+pub mod shrink;
+pub mod state_summary;
+pub mod step_history;
+pub mod term_costs;
+pub mod term_dot;
+pub mod term_ids;
+pub mod uplc_text;
+pub mod blueprint;
+pub mod audit;
+pub mod bookmarks;
+pub mod budget_exhaustion;
+pub mod conditions;
+pub mod builtin_checks;
+pub mod call_stack;
+pub mod cancellation;
+pub mod cert_encoding;
+pub mod chunked_export;
+pub mod construction;
+pub mod context_cache;
+pub mod context_compare;
+pub mod coverage;
+pub mod datums;
+pub mod decisions;
+pub mod env_pins;
+pub mod env_watch;
+pub mod execution_trace;
+pub mod force_balance;
+pub mod fixture;
+pub mod instrumentation;
+pub mod log_search;
+pub mod quota;
+pub mod reprice;
+pub mod redeemer_graph;
+pub mod observers;
+pub mod optimizations;
+pub mod ordering;
+pub mod prelude;
+pub mod profile;
+pub mod progress;
+pub mod overshoot;
+pub mod ref_script_fees;
+pub mod run_all;
+pub mod value_search;
+pub mod value_pins;
+
+pub use debugger_engine::{DebuggerEngine};
+pub use session_controller::SessionController;
+pub use quick_run::{debug_minting_policy, debug_withdrawal_validator};
+pub use shrink::shrink_failure;
+pub use lazy_session_api::canonicalize_path;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status_type")]
+pub enum SerializableExecutionStatus {
+    #[serde(rename = "Ready")]
+    Ready,
+    #[serde(rename = "Done")]
+    Done {
+        result: crate::serializer::SerializableTerm,
+    },
+    #[serde(rename = "Error")]
+    Error { message: String },
+    #[serde(rename = "Paused")]
+    Paused { reason: PauseReason, term_id: i32 },
+    #[serde(rename = "BudgetExceeded")]
+    BudgetExceeded {
+        budget: crate::budget::SerializableBudget,
+    },
+    #[serde(rename = "StepLimitReached")]
+    StepLimitReached { steps: u64 },
+    #[serde(rename = "Running")]
+    Running { steps: u64 },
+}
+
+/// What paused a run before the machine finished
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind")]
+pub enum PauseReason {
+    #[serde(rename = "Breakpoint")]
+    Breakpoint,
+    #[serde(rename = "Condition")]
+    Condition,
+    #[serde(rename = "Watch")]
+    Watch,
+    #[serde(rename = "Cancelled")]
+    Cancelled,
+    /// Reached the term of `run_until_term`
+    #[serde(rename = "Target")]
+    Target,
+    /// Reached the end of the prelude passed over by `skip_prelude`
+    #[serde(rename = "Prelude")]
+    Prelude,
+    /// A builtin breakpoint matched a builtin about to be applied
+    #[serde(rename = "Builtin")]
+    Builtin { name: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StepResult {
+    pub term_id: i32,
+    pub status: SerializableExecutionStatus,
+    /// CPU spent by the last step taken; `null` when the session charges
+    /// several steps together (slippage above 1), so no single step's cost is known
+    pub cpu_delta: Option<i64>,
+    /// Memory spent by the last step taken; `null` when slippage is above 1
+    pub mem_delta: Option<i64>,
+}
+
+/// One step of a `step_n` batch
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StepSummary {
+    pub term_id: i32,
+    /// State the step started from
+    pub kind: execution_trace::TraceStateKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StepBatchResult {
+    pub term_id: i32,
+    /// Status after the last step of the batch
+    pub status: SerializableExecutionStatus,
+    pub steps: Vec<StepSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum StopReasonKind {
+    #[serde(rename = "Step")]
+    Step,
+    #[serde(rename = "Breakpoint")]
+    Breakpoint,
+    #[serde(rename = "Watch")]
+    Watch,
+    #[serde(rename = "Condition")]
+    Condition,
+    #[serde(rename = "Cancelled")]
+    Cancelled,
+    #[serde(rename = "BuiltinBreakpoint")]
+    BuiltinBreakpoint,
+    /// Reached the term of `run_until_term` or the end of the prelude passed over by `skip_prelude`
+    #[serde(rename = "Target")]
+    Target,
+    #[serde(rename = "BudgetExceeded")]
+    BudgetExceeded,
+    #[serde(rename = "StepLimit")]
+    StepLimit,
+    #[serde(rename = "RunLimit")]
+    RunLimit,
+    #[serde(rename = "Done")]
+    Done,
+    #[serde(rename = "Error")]
+    Error,
+}
+
+/// Why execution last halted, mirroring DAP stopped events
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StopReason {
+    pub kind: StopReasonKind,
+    pub term_id: i32,
+    pub step: u64,
+    /// Term id of the breakpoint (or watched/conditional location) that matched
+    pub breakpoint_id: Option<i32>,
+    /// Trace emitted by the step that halted execution, if any
+    pub matched_trace: Option<String>,
+    pub description: Option<String>,
+    pub budget: crate::budget::SerializableBudget,
+    /// Host annotation of the term execution halted on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<annotations::TermAnnotation>,
+}
+
+/// Identity and liveness of a session, for hosts that manage many of them
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub redeemer: String,
+    /// As hex or `script1…` depending on the engine settings
+    pub script_hash: String,
+    /// "V1", "V2" or "V3"
+    pub language: String,
+    /// Milliseconds since the Unix epoch
+    pub created_at: u64,
+    /// Milliseconds since the Unix epoch of the last call that stepped, reset or read the session
+    pub last_activity: u64,
+    pub step_count: u64,
+    pub version: u64,
+}
+
+impl std::fmt::Display for PauseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PauseReason::Breakpoint => write!(f, "breakpoint"),
+            PauseReason::Condition => write!(f, "condition"),
+            PauseReason::Watch => write!(f, "watch"),
+            PauseReason::Cancelled => write!(f, "cancelled"),
+            PauseReason::Target => write!(f, "target"),
+            PauseReason::Prelude => write!(f, "prelude"),
+            PauseReason::Builtin { name } => write!(f, "builtin {}", name),
+        }
+    }
+}
+
+impl StopReasonKind {
+    pub fn from_status(status: &SerializableExecutionStatus) -> Self {
+        match status {
+            SerializableExecutionStatus::Ready => StopReasonKind::Step,
+            SerializableExecutionStatus::Done { .. } => StopReasonKind::Done,
+            SerializableExecutionStatus::Error { .. } => StopReasonKind::Error,
+            SerializableExecutionStatus::Paused { reason, .. } => match reason {
+                PauseReason::Breakpoint => StopReasonKind::Breakpoint,
+                PauseReason::Condition => StopReasonKind::Condition,
+                PauseReason::Watch => StopReasonKind::Watch,
+                PauseReason::Cancelled => StopReasonKind::Cancelled,
+                PauseReason::Target | PauseReason::Prelude => StopReasonKind::Target,
+                PauseReason::Builtin { .. } => StopReasonKind::BuiltinBreakpoint,
+            },
+            SerializableExecutionStatus::BudgetExceeded { .. } => StopReasonKind::BudgetExceeded,
+            SerializableExecutionStatus::StepLimitReached { .. } => StopReasonKind::StepLimit,
+            SerializableExecutionStatus::Running { .. } => StopReasonKind::RunLimit,
+        }
+    }
+}
+
+impl From<uplc::manual_machine::ExecutionStatus> for SerializableExecutionStatus {
+    fn from(status: uplc::manual_machine::ExecutionStatus) -> Self {
+        match status {
+            uplc::manual_machine::ExecutionStatus::Ready => SerializableExecutionStatus::Ready,
+            uplc::manual_machine::ExecutionStatus::Done(term) => {
+                SerializableExecutionStatus::Done {
+                    result: crate::serializer::SerializableTerm::from_uplc_term(&term),
+                }
+            }
+            uplc::manual_machine::ExecutionStatus::Error(error) => {
+                SerializableExecutionStatus::Error {
+                    message: error.to_string(),
+                }
+            }
+        }
+    }
+}
+
+impl From<&uplc::manual_machine::ExecutionStatus> for SerializableExecutionStatus {
+    fn from(status: &uplc::manual_machine::ExecutionStatus) -> Self {
+        match status {
+            uplc::manual_machine::ExecutionStatus::Ready => SerializableExecutionStatus::Ready,
+            uplc::manual_machine::ExecutionStatus::Done(term) => {
+                SerializableExecutionStatus::Done {
+                    result: crate::serializer::SerializableTerm::from_uplc_term(term),
+                }
+            }
+            uplc::manual_machine::ExecutionStatus::Error(error) => {
+                SerializableExecutionStatus::Error {
+                    message: error.to_string(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Budget {
+    pub mem: i64,
+    pub cpu: i64,
+}
+
+impl From<uplc::machine::cost_model::ExBudget> for Budget {
+    fn from(ex_budget: uplc::machine::cost_model::ExBudget) -> Self {
+        Budget {
+            mem: ex_budget.mem,
+            cpu: ex_budget.cpu,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DebuggerError {
+    #[error("Failed to parse transaction: {0}")]
+    TransactionParseError(String),
+
+    #[error("Failed to find redeemer: {0}")]
+    RedeemerNotFound(String),
+
+    #[error("Failed to find script: {0}")]
+    ScriptNotFound(String),
+
+    #[error("Failed to build script context: {0}")]
+    ScriptContextBuildError(String),
+
+    #[error("Failed to build program: {0}")]
+    ProgramBuildError(String),
+
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+
+    #[error("Machine error: {0}")]
+    MachineError(String),
+
+    #[error("Invalid condition: {0}")]
+    InvalidCondition(String),
+
+    #[error("Invalid blueprint: {0}")]
+    BlueprintError(String),
+
+    #[error("Invalid script context")]
+    InvalidScriptContext,
+
+    #[error("UTXO conversion error: {0}")]
+    UtxoConversionError(#[from] crate::utxo::UtxoConversionError),
+
+    #[error("IO Error: {0}")]
+    IoError(#[from] std::io::Error),
+}
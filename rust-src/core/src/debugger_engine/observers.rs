@@ -0,0 +1,165 @@
+use pallas_primitives::conway::MintedTx;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{Constant, NamedDeBruijn, Term},
+    PlutusData,
+};
+
+use super::cert_encoding::constr_fields;
+use crate::hash_format::script_hash_to_bech32;
+use crate::script_context::utils::{reward_account_to_bech32, reward_account_to_stake_credential};
+use crate::script_context::SerializableStakeCredential;
+
+/// How a spend script was tied to the observer it forwards to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum ObserverLink {
+    /// The spend and withdraw handlers are the same multi-purpose script
+    SameScript,
+    /// The spend script carries the observer's script hash as a constant,
+    /// typically as a parameter applied at compile time
+    EmbeddedHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ForwardingSpend {
+    /// Redeemer key, e.g. "Spend:2"
+    pub redeemer: String,
+    pub script_hash: String,
+    pub link: ObserverLink,
+}
+
+/// A Reward-purpose script run through a withdrawal, usually of 0 lovelace, so
+/// that the spend scripts of the transaction can delegate their checks to it once
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct StakeObserver {
+    /// Redeemer key, e.g. "Reward:0"
+    pub redeemer: String,
+    pub script_hash: String,
+    /// Reward account withdrawn from, as hex and `stake1…`
+    pub reward_account: String,
+    pub reward_account_bech32: Option<String>,
+    pub withdrawal_amount: u64,
+    /// Whether the withdrawal is the 0-lovelace trigger of the observer pattern
+    pub zero_withdrawal: bool,
+    /// Display name, e.g. "Observer script1… (withdraw 0)"
+    pub label: String,
+    /// Purpose as shown next to the redeemer, e.g. "Rewarding (withdraw-zero observer)"
+    pub purpose: String,
+    /// Spend redeemers of the transaction whose scripts forward to this observer
+    pub forwarding_spends: Vec<ForwardingSpend>,
+}
+
+/// Reward account and amount of the withdrawal from the script stake credential `script_hash`
+pub fn withdrawal_for_script(tx: &MintedTx, script_hash: &str) -> Option<(Vec<u8>, u64)> {
+    tx.transaction_body
+        .withdrawals
+        .iter()
+        .flat_map(|withdrawals| withdrawals.iter())
+        .find(|(reward_account, _)| {
+            matches!(
+                reward_account_to_stake_credential(reward_account),
+                Ok((_, SerializableStakeCredential::ScriptHash { hash })) if hash == script_hash
+            )
+        })
+        .map(|(reward_account, amount)| (reward_account.to_vec(), *amount))
+}
+
+/// Builds the observer entry of a Reward redeemer, without its forwarding spends
+pub fn stake_observer(redeemer: &str, script_hash: &str, reward_account: &[u8], amount: u64) -> StakeObserver {
+    let name = script_hash_to_bech32(script_hash).unwrap_or_else(|_| script_hash.to_string());
+    let (label, purpose) = if amount == 0 {
+        (
+            format!("Observer {} (withdraw 0)", name),
+            "Rewarding (withdraw-zero observer)".to_string(),
+        )
+    } else {
+        (
+            format!("Stake validator {} (withdraw {})", name, amount),
+            "Rewarding".to_string(),
+        )
+    };
+
+    StakeObserver {
+        redeemer: redeemer.to_string(),
+        script_hash: script_hash.to_string(),
+        reward_account: hex::encode(reward_account),
+        reward_account_bech32: reward_account_to_bech32(reward_account).ok(),
+        withdrawal_amount: amount,
+        zero_withdrawal: amount == 0,
+        label,
+        purpose,
+        forwarding_spends: vec![],
+    }
+}
+
+fn data_contains(data: &PlutusData, bytes: &[u8]) -> bool {
+    match data {
+        PlutusData::BoundedBytes(found) => found.as_slice() == bytes,
+        PlutusData::Constr(_) => {
+            constr_fields(data).is_some_and(|(_, fields)| fields.iter().any(|field| data_contains(field, bytes)))
+        }
+        PlutusData::Map(pairs) => pairs
+            .iter()
+            .any(|(key, value)| data_contains(key, bytes) || data_contains(value, bytes)),
+        PlutusData::Array(values) => values.iter().any(|value| data_contains(value, bytes)),
+        _ => false,
+    }
+}
+
+fn constant_contains(constant: &Constant, bytes: &[u8]) -> bool {
+    match constant {
+        Constant::ByteString(found) => found.as_slice() == bytes,
+        Constant::Data(data) => data_contains(data, bytes),
+        Constant::ProtoList(_, values) => values.iter().any(|value| constant_contains(value, bytes)),
+        Constant::ProtoPair(_, _, first, second) => constant_contains(first, bytes) || constant_contains(second, bytes),
+        _ => false,
+    }
+}
+
+/// Whether any constant of `term`, including bytes nested in Data, equals `bytes`
+pub fn term_embeds_bytes(term: &Term<NamedDeBruijn>, bytes: &[u8]) -> bool {
+    match term {
+        Term::Constant { value, .. } => constant_contains(value, bytes),
+        Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => {
+            term_embeds_bytes(body, bytes)
+        }
+        Term::Apply { function, argument, .. } => term_embeds_bytes(function, bytes) || term_embeds_bytes(argument, bytes),
+        Term::Constr { fields, .. } => fields.iter().any(|field| term_embeds_bytes(field, bytes)),
+        Term::Case { constr, branches, .. } => {
+            term_embeds_bytes(constr, bytes) || branches.iter().any(|branch| term_embeds_bytes(branch, bytes))
+        }
+        Term::Var { .. } | Term::Error { .. } | Term::Builtin { .. } => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stake_observer_labels() {
+        let hash = "00".repeat(28);
+        let mut reward_account = vec![0xf1];
+        reward_account.extend([0u8; 28]);
+
+        let observer = stake_observer("Reward:0", &hash, &reward_account, 0);
+        assert!(observer.zero_withdrawal);
+        assert!(observer.label.starts_with("Observer script1"));
+        assert!(observer.reward_account_bech32.as_deref().is_some_and(|bech32| bech32.starts_with("stake1")));
+
+        let validator = stake_observer("Reward:0", &hash, &reward_account, 5);
+        assert_eq!(validator.purpose, "Rewarding");
+    }
+
+    #[test]
+    fn test_embedded_hash_in_data() {
+        let hash = vec![9u8; 28];
+        let data = PlutusData::Array(pallas_codec::utils::MaybeIndefArray::Def(vec![
+            PlutusData::BoundedBytes(vec![1u8; 28].into()),
+            PlutusData::BoundedBytes(hash.clone().into()),
+        ]));
+        assert!(constant_contains(&Constant::Data(data), &hash));
+        assert!(!constant_contains(&Constant::ByteString(vec![1u8; 28]), &hash));
+    }
+}
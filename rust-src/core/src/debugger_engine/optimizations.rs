@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+    machine::cost_model::ExBudget,
+};
+
+use super::decisions::term_id;
+
+/// Cost of one CEK machine step (the same for every step kind in all Plutus versions so far)
+pub const MACHINE_STEP_COST: ExBudget = ExBudget { cpu: 16_000, mem: 100 };
+/// Cost charged once when a machine starts, included in every sandboxed measurement
+const MACHINE_STARTUP_COST: ExBudget = ExBudget { cpu: 100, mem: 100 };
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum OptimizationKind {
+    /// Force directly applied to a Delay; both can be dropped
+    ForceDelay,
+    /// Saturated builtin whose arguments are all constants, foldable to its result
+    ConstantFold,
+    /// The same constant builtin application written in several places
+    RepeatedApplication,
+    /// equalsByteString of a constant with itself, always True
+    IdenticalEquality,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct OptimizationHint {
+    pub kind: OptimizationKind,
+    /// Terms the hint applies to, one per occurrence
+    pub term_ids: Vec<i32>,
+    pub message: String,
+    /// Budget saved per evaluation of every occurrence; terms run repeatedly
+    /// (e.g. inside recursion) save this each time
+    pub estimated_cpu_savings: i64,
+    pub estimated_mem_savings: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct OptimizationReport {
+    pub hints: Vec<OptimizationHint>,
+    pub total_cpu_savings: i64,
+    pub total_mem_savings: i64,
+}
+
+/// Builtin application whose arguments are all constants
+struct ConstantApplication<'a> {
+    term: &'a Term<NamedDeBruijn>,
+    fun: DefaultFunction,
+    /// Identifies equal applications regardless of term ids
+    fingerprint: String,
+    identical_arguments: bool,
+}
+
+/// Splits a saturated builtin application over constants into its parts
+fn constant_application(term: &Term<NamedDeBruijn>) -> Option<ConstantApplication<'_>> {
+    let mut arguments = vec![];
+    let mut head = term;
+    while let Term::Apply { function, argument, .. } = head {
+        arguments.push(argument.as_ref());
+        head = function;
+    }
+    let mut forces = 0;
+    while let Term::Force { body, .. } = head {
+        forces += 1;
+        head = body;
+    }
+    let Term::Builtin { fun, .. } = head else {
+        return None;
+    };
+    if arguments.len() != fun.arity() || forces != fun.force_count() {
+        return None;
+    }
+    arguments.reverse();
+    let constants = arguments
+        .iter()
+        .map(|argument| match argument {
+            Term::Constant { value, .. } => Some(value.as_ref()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(ConstantApplication {
+        term,
+        fun: *fun,
+        fingerprint: format!("{:?} {:?}", fun, constants),
+        identical_arguments: constants.len() == 2 && constants[0] == constants[1],
+    })
+}
+
+fn collect<'a>(term: &'a Term<NamedDeBruijn>, force_delays: &mut Vec<(i32, bool)>, applications: &mut Vec<ConstantApplication<'a>>) {
+    if let Some(application) = constant_application(term) {
+        applications.push(application);
+        return;
+    }
+    match term {
+        Term::Force { body, uniq_id } => {
+            if let Term::Delay { body: delayed, .. } = body.as_ref() {
+                force_delays.push((*uniq_id as i32, matches!(delayed.as_ref(), Term::Constant { .. })));
+            }
+            collect(body, force_delays, applications);
+        }
+        Term::Delay { body, .. } | Term::Lambda { body, .. } => collect(body, force_delays, applications),
+        Term::Apply { function, argument, .. } => {
+            collect(function, force_delays, applications);
+            collect(argument, force_delays, applications);
+        }
+        Term::Constr { fields, .. } => fields.iter().for_each(|field| collect(field, force_delays, applications)),
+        Term::Case { constr, branches, .. } => {
+            collect(constr, force_delays, applications);
+            branches.iter().for_each(|branch| collect(branch, force_delays, applications));
+        }
+        _ => {}
+    }
+}
+
+/// Statically finds wasteful patterns in `term`. `measure` evaluates a closed
+/// term and returns its cost, or None when it fails; failing applications are
+/// not reported, as folding them would not preserve the failure.
+pub fn analyze_optimizations(
+    term: &Term<NamedDeBruijn>,
+    measure: impl Fn(&Term<NamedDeBruijn>) -> Option<ExBudget>,
+) -> OptimizationReport {
+    let mut force_delays = vec![];
+    let mut applications = vec![];
+    collect(term, &mut force_delays, &mut applications);
+
+    let mut hints: Vec<OptimizationHint> = force_delays
+        .into_iter()
+        .map(|(id, constant)| OptimizationHint {
+            kind: OptimizationKind::ForceDelay,
+            term_ids: vec![id],
+            message: if constant {
+                "Force of a delayed constant: use the constant directly".to_string()
+            } else {
+                "Force applied directly to a Delay: both can be removed".to_string()
+            },
+            estimated_cpu_savings: 2 * MACHINE_STEP_COST.cpu,
+            estimated_mem_savings: 2 * MACHINE_STEP_COST.mem,
+        })
+        .collect();
+
+    let mut groups: BTreeMap<&str, Vec<&ConstantApplication>> = BTreeMap::new();
+    for application in &applications {
+        groups.entry(application.fingerprint.as_str()).or_default().push(application);
+    }
+    for group in groups.values() {
+        let first = group[0];
+        let Some(cost) = measure(first.term) else {
+            continue;
+        };
+        // Folded, the application is a single constant step
+        let cpu = (cost.cpu - MACHINE_STARTUP_COST.cpu - MACHINE_STEP_COST.cpu).max(0);
+        let mem = (cost.mem - MACHINE_STARTUP_COST.mem - MACHINE_STEP_COST.mem).max(0);
+        let occurrences = group.len();
+        let (kind, message) = if first.fun == DefaultFunction::EqualsByteString && first.identical_arguments {
+            (
+                OptimizationKind::IdenticalEquality,
+                format!("equalsByteString of a constant with itself is always True ({} occurrence(s))", occurrences),
+            )
+        } else if occurrences > 1 {
+            (
+                OptimizationKind::RepeatedApplication,
+                format!("{:?} on the same constants is computed in {} places; fold it once", first.fun, occurrences),
+            )
+        } else {
+            (
+                OptimizationKind::ConstantFold,
+                format!("{:?} on constants can be folded to its result", first.fun),
+            )
+        };
+        hints.push(OptimizationHint {
+            kind,
+            term_ids: group.iter().map(|application| term_id(application.term)).collect(),
+            message,
+            estimated_cpu_savings: cpu * occurrences as i64,
+            estimated_mem_savings: mem * occurrences as i64,
+        });
+    }
+
+    hints.sort_by_key(|hint| std::cmp::Reverse(hint.estimated_cpu_savings));
+    OptimizationReport {
+        total_cpu_savings: hints.iter().map(|hint| hint.estimated_cpu_savings).sum(),
+        total_mem_savings: hints.iter().map(|hint| hint.estimated_mem_savings).sum(),
+        hints,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use uplc::ast::Constant;
+
+    fn constant(bytes: &[u8], id: usize) -> Term<NamedDeBruijn> {
+        Term::Constant {
+            value: Rc::new(Constant::ByteString(bytes.to_vec())),
+            uniq_id: id as _,
+        }
+    }
+
+    fn equals(left: Term<NamedDeBruijn>, right: Term<NamedDeBruijn>, id: usize) -> Term<NamedDeBruijn> {
+        let builtin = Term::Builtin { fun: DefaultFunction::EqualsByteString, uniq_id: id as _ };
+        let partial = Term::Apply { function: builtin.into(), argument: left.into(), uniq_id: (id + 1) as _ };
+        Term::Apply { function: partial.into(), argument: right.into(), uniq_id: (id + 2) as _ }
+    }
+
+    #[test]
+    fn test_constant_patterns() {
+        let identical = equals(constant(b"a", 1), constant(b"a", 2), 10);
+        let forced = Term::Force {
+            body: Term::Delay { body: constant(b"b", 3).into(), uniq_id: 4 }.into(),
+            uniq_id: 5,
+        };
+        let term = Term::Constr { tag: 0, fields: vec![identical, forced], uniq_id: 0 };
+
+        let report = analyze_optimizations(&term, |_| Some(ExBudget { cpu: 200_000, mem: 300 }));
+        let kinds: Vec<_> = report.hints.iter().map(|hint| hint.kind.clone()).collect();
+        assert_eq!(kinds, vec![OptimizationKind::IdenticalEquality, OptimizationKind::ForceDelay]);
+        assert_eq!(report.hints[0].term_ids, vec![12]);
+        assert_eq!(report.hints[0].estimated_cpu_savings, 200_000 - 100 - 16_000);
+    }
+}
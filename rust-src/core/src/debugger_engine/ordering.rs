@@ -0,0 +1,201 @@
+use pallas_primitives::{
+    alonzo,
+    conway::{self, MintedTransactionOutput, MintedTx, PseudoTransactionOutput, TransactionInput, TransactionOutput},
+};
+use uplc::tx::script_context::TxInfo;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Where an input or output of the transaction body appears in a TxInfo
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct IndexMapping {
+    pub body_index: usize,
+    /// None when the entry is missing from the context
+    pub context_index: Option<usize>,
+    /// `<tx hash>#<index>` for inputs, the output address for outputs
+    pub entry: String,
+}
+
+/// Ordering of the inputs and outputs of one TxInfo version against the ledger rules:
+/// inputs sorted lexicographically by (tx hash, index), outputs in body order
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct OrderingCheck {
+    /// "V1", "V2" or "V3"
+    pub version: String,
+    pub inputs: Vec<IndexMapping>,
+    pub reference_inputs: Vec<IndexMapping>,
+    pub outputs: Vec<IndexMapping>,
+    pub issues: Vec<String>,
+}
+
+impl OrderingCheck {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+fn input_entry(input: &TransactionInput) -> String {
+    format!("{}#{}", hex::encode(input.transaction_id), input.index)
+}
+
+fn map_inputs(
+    kind: &str,
+    body: &[&TransactionInput],
+    context: &[&TransactionInput],
+    issues: &mut Vec<String>,
+) -> Vec<IndexMapping> {
+    let mut expected = body.to_vec();
+    expected.sort_by(|a, b| (a.transaction_id.as_ref(), a.index).cmp(&(b.transaction_id.as_ref(), b.index)));
+    expected.dedup();
+    if expected != context {
+        issues.push(format!("{} are not in ledger order (sorted by tx hash, then index)", kind));
+    }
+
+    body.iter()
+        .enumerate()
+        .map(|(body_index, input)| IndexMapping {
+            body_index,
+            context_index: context.iter().position(|candidate| candidate == input),
+            entry: input_entry(input),
+        })
+        .collect()
+}
+
+/// Address and lovelace of an output, enough to tell outputs apart positionally
+type OutputKey = (Vec<u8>, u64);
+
+fn body_output_key(output: &MintedTransactionOutput) -> OutputKey {
+    match output {
+        PseudoTransactionOutput::Legacy(output) => (output.address.to_vec(), alonzo_coin(&output.amount)),
+        PseudoTransactionOutput::PostAlonzo(output) => (output.address.to_vec(), conway_coin(&output.value)),
+    }
+}
+
+fn context_output_key(output: &TransactionOutput) -> OutputKey {
+    match output {
+        PseudoTransactionOutput::Legacy(output) => (output.address.to_vec(), alonzo_coin(&output.amount)),
+        PseudoTransactionOutput::PostAlonzo(output) => (output.address.to_vec(), conway_coin(&output.value)),
+    }
+}
+
+fn alonzo_coin(value: &alonzo::Value) -> u64 {
+    match value {
+        alonzo::Value::Coin(coin) | alonzo::Value::Multiasset(coin, _) => *coin,
+    }
+}
+
+fn conway_coin(value: &conway::Value) -> u64 {
+    match value {
+        conway::Value::Coin(coin) | conway::Value::Multiasset(coin, _) => *coin,
+    }
+}
+
+/// Compares the body's inputs, reference inputs and outputs with those a TxInfo
+/// presents, mapping every body index to its index in the context
+pub fn check_ordering(
+    version: &str,
+    body_inputs: &[&TransactionInput],
+    body_reference_inputs: &[&TransactionInput],
+    body_outputs: &[MintedTransactionOutput],
+    context_inputs: &[&TransactionInput],
+    context_reference_inputs: &[&TransactionInput],
+    context_outputs: &[TransactionOutput],
+) -> OrderingCheck {
+    let mut issues = vec![];
+    let inputs = map_inputs("Inputs", body_inputs, context_inputs, &mut issues);
+    let reference_inputs = map_inputs("Reference inputs", body_reference_inputs, context_reference_inputs, &mut issues);
+
+    let context_keys: Vec<OutputKey> = context_outputs.iter().map(context_output_key).collect();
+    let outputs = body_outputs
+        .iter()
+        .enumerate()
+        .map(|(body_index, output)| {
+            let key = body_output_key(output);
+            let context_index = if context_keys.get(body_index) == Some(&key) {
+                Some(body_index)
+            } else {
+                issues.push(format!("Output {} is not at the same position in the context", body_index));
+                context_keys.iter().position(|candidate| *candidate == key)
+            };
+            IndexMapping {
+                body_index,
+                context_index,
+                entry: hex::encode(&key.0),
+            }
+        })
+        .collect();
+    if context_outputs.len() != body_outputs.len() {
+        issues.push(format!(
+            "The context has {} outputs, the body {}",
+            context_outputs.len(),
+            body_outputs.len()
+        ));
+    }
+
+    OrderingCheck {
+        version: version.to_string(),
+        inputs,
+        reference_inputs,
+        outputs,
+        issues,
+    }
+}
+
+/// Checks the ordering of a TxInfo built from `tx`
+pub fn check_tx_info(tx: &MintedTx, tx_info: &TxInfo) -> OrderingCheck {
+    let body = &tx.transaction_body;
+    let body_inputs: Vec<&TransactionInput> = body.inputs.iter().collect();
+    let body_reference_inputs: Vec<&TransactionInput> =
+        body.reference_inputs.iter().flat_map(|inputs| inputs.iter()).collect();
+
+    let (version, inputs, reference_inputs, outputs) = match tx_info {
+        TxInfo::V1(info) => ("V1", &info.inputs, None, &info.outputs),
+        TxInfo::V2(info) => ("V2", &info.inputs, Some(&info.reference_inputs), &info.outputs),
+        TxInfo::V3(info) => ("V3", &info.inputs, Some(&info.reference_inputs), &info.outputs),
+    };
+    let context_inputs: Vec<&TransactionInput> = inputs.iter().map(|input| &input.out_ref).collect();
+    // V1 has no reference inputs, so there is nothing to check them against
+    let (body_reference_inputs, context_reference_inputs): (Vec<_>, Vec<_>) = match reference_inputs {
+        Some(reference_inputs) => (
+            body_reference_inputs,
+            reference_inputs.iter().map(|input| &input.out_ref).collect(),
+        ),
+        None => (vec![], vec![]),
+    };
+
+    check_ordering(
+        version,
+        &body_inputs,
+        &body_reference_inputs,
+        &body.outputs,
+        &context_inputs,
+        &context_reference_inputs,
+        outputs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pallas_crypto::hash::Hash;
+
+    fn input(byte: u8, index: u64) -> TransactionInput {
+        TransactionInput {
+            transaction_id: Hash::from([byte; 32]),
+            index,
+        }
+    }
+
+    #[test]
+    fn test_inputs_map_to_sorted_positions() {
+        let (a, b, c) = (input(2, 0), input(1, 1), input(1, 0));
+        let mut issues = vec![];
+        let mapping = map_inputs("Inputs", &[&a, &b, &c], &[&c, &b, &a], &mut issues);
+        assert!(issues.is_empty());
+        let context_indices: Vec<_> = mapping.iter().map(|m| m.context_index).collect();
+        assert_eq!(context_indices, vec![Some(2), Some(1), Some(0)]);
+
+        map_inputs("Inputs", &[&a, &b, &c], &[&a, &b, &c], &mut issues);
+        assert_eq!(issues.len(), 1);
+    }
+}
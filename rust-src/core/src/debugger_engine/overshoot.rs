@@ -0,0 +1,118 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::machine::cost_model::ExBudget;
+
+use super::state_summary::StateSummary;
+
+/// The step whose cost first took the spent budget past the declared ex-units
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct BudgetCrossing {
+    /// Step count of the crossing step; the ledger fails the script instead of completing it
+    pub step: u64,
+    pub term_id: i32,
+    /// "cpu", "mem" or "cpu and mem"
+    pub exhausted: String,
+    pub spent_cpu: i64,
+    pub spent_mem: i64,
+    /// The state the crossing step started from, i.e. where the ledger cuts execution;
+    /// it has no Case preview
+    pub state: StateSummary,
+}
+
+impl BudgetCrossing {
+    /// Returns the crossing when `spent` exceeds `declared`; `state` is only called then
+    pub fn detect(
+        step: u64,
+        term_id: i32,
+        spent: &ExBudget,
+        declared: &ExBudget,
+        state: impl FnOnce() -> StateSummary,
+    ) -> Option<Self> {
+        let exhausted = match (spent.cpu > declared.cpu, spent.mem > declared.mem) {
+            (true, true) => "cpu and mem",
+            (true, false) => "cpu",
+            (false, true) => "mem",
+            (false, false) => return None,
+        };
+        Some(BudgetCrossing {
+            step,
+            term_id,
+            exhausted: exhausted.to_string(),
+            spent_cpu: spent.cpu,
+            spent_mem: spent.mem,
+            state: state(),
+        })
+    }
+}
+
+/// How far measured usage exceeds the ex-units declared by the redeemer
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct OvershootAnalysis {
+    pub declared_cpu: i64,
+    pub declared_mem: i64,
+    pub spent_cpu: i64,
+    pub spent_mem: i64,
+    /// Usage above the declared ex-units, 0 when within them
+    pub overshoot_cpu: i64,
+    pub overshoot_mem: i64,
+    /// Overshoot as a percentage of the declared ex-units
+    pub overshoot_cpu_percent: f64,
+    pub overshoot_mem_percent: f64,
+    /// Whether the script has finished, so the spent figures are final
+    pub finished: bool,
+    pub first_exhausted: BudgetCrossing,
+}
+
+fn percent(overshoot: i64, declared: i64) -> f64 {
+    if declared > 0 {
+        overshoot as f64 * 100.0 / declared as f64
+    } else {
+        0.0
+    }
+}
+
+impl OvershootAnalysis {
+    pub fn new(declared: &ExBudget, spent: &ExBudget, finished: bool, first_exhausted: BudgetCrossing) -> Self {
+        let overshoot_cpu = (spent.cpu - declared.cpu).max(0);
+        let overshoot_mem = (spent.mem - declared.mem).max(0);
+        OvershootAnalysis {
+            declared_cpu: declared.cpu,
+            declared_mem: declared.mem,
+            spent_cpu: spent.cpu,
+            spent_mem: spent.mem,
+            overshoot_cpu,
+            overshoot_mem,
+            overshoot_cpu_percent: percent(overshoot_cpu, declared.cpu),
+            overshoot_mem_percent: percent(overshoot_mem, declared.mem),
+            finished,
+            first_exhausted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> StateSummary {
+        StateSummary {
+            state_type: "Compute".to_string(),
+            term_id: 3,
+            frame_type: None,
+            case_preview: None,
+        }
+    }
+
+    #[test]
+    fn test_overshoot_amounts() {
+        let declared = ExBudget { cpu: 1_000, mem: 100 };
+        let spent = ExBudget { cpu: 1_050, mem: 90 };
+        assert!(BudgetCrossing::detect(1, 3, &declared, &declared, summary).is_none());
+
+        let crossing = BudgetCrossing::detect(7, 3, &spent, &declared, summary).unwrap();
+        assert_eq!(crossing.exhausted, "cpu");
+        let analysis = OvershootAnalysis::new(&declared, &spent, true, crossing);
+        assert_eq!((analysis.overshoot_cpu, analysis.overshoot_mem), (50, 0));
+        assert_eq!(analysis.overshoot_cpu_percent, 5.0);
+    }
+}
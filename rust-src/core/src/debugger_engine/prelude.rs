@@ -0,0 +1,108 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{Constant, Term},
+    machine::{value::Value, Context, MachineState},
+    PlutusData,
+};
+
+/// Where `skip_prelude` stopped and what the steps before it cost
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct PreludeReport {
+    /// "Case" (a Case term is computed next), "ContextBuiltin" (a builtin is about
+    /// to be applied to the script context), "Finished", "StepLimit" or "Cancelled"
+    pub stopped_at: String,
+    pub term_id: i32,
+    /// Builtin applied to the script context, for "ContextBuiltin"
+    pub builtin: Option<String>,
+    pub steps: u64,
+    pub cpu: i64,
+    pub mem: i64,
+    /// Share of the redeemer's declared ex units the prelude used
+    pub cpu_percent: f64,
+    pub mem_percent: f64,
+}
+
+/// Why the prelude ends at `state`, with the builtin involved, or None while it goes on
+pub fn prelude_end(state: &MachineState, script_context: Option<&PlutusData>) -> Option<(&'static str, Option<String>)> {
+    match state {
+        MachineState::Compute(_, _, Term::Case { .. }) => Some(("Case", None)),
+        MachineState::Return(context, value) => {
+            let (function, last) = match context {
+                Context::FrameAwaitArg(function, ..) => (function, value),
+                Context::FrameAwaitFunValue(argument, ..) => (value, argument),
+                _ => return None,
+            };
+            let Value::Builtin { fun, runtime, .. } = function else {
+                return None;
+            };
+            if runtime.args.len() + 1 != fun.arity() {
+                return None;
+            }
+            let script_context = script_context?;
+            let touches_context = runtime.args.iter().chain([last]).any(|argument| {
+                matches!(argument, Value::Con(constant) if matches!(constant.as_ref(), Constant::Data(data) if data == script_context))
+            });
+            touches_context.then(|| ("ContextBuiltin", Some(format!("{:?}", fun))))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas_primitives::conway::Language;
+    use uplc::{
+        ast::Data,
+        machine::cost_model::{CostModel, ExBudget},
+        manual_machine::ManualMachine,
+    };
+
+    use super::*;
+    use crate::debugger_engine::sandbox::parse_expression;
+    use crate::debugger_engine::session_controller::DEFAULT_SLIPPAGE;
+
+    /// Steps `expression` to the end, returning the first prelude end met and the step it was met at
+    fn first_end(expression: &str, script_context: Option<&PlutusData>) -> Option<(usize, (&'static str, Option<String>))> {
+        let program = parse_expression(expression, (1, 1, 0)).unwrap();
+        let mut machine =
+            ManualMachine::new(Language::PlutusV3, CostModel::default(), ExBudget::max(), DEFAULT_SLIPPAGE, program.term)
+                .unwrap();
+        for step in 0..50 {
+            if let Some(end) = prelude_end(machine.current_state(), script_context) {
+                return Some((step, end));
+            }
+            if matches!(machine.current_state(), MachineState::Done(_)) {
+                return None;
+            }
+            machine.step();
+        }
+        None
+    }
+
+    #[test]
+    fn test_prelude_ends_at_case_terms() {
+        let (step, end) = first_end("(case (constr 0) (con integer 1))", None).unwrap();
+        assert_eq!((step, end), (0, ("Case", None)));
+        assert_eq!(first_end("[(lam x x) (con integer 1)]", None), None);
+    }
+
+    #[test]
+    fn test_prelude_ends_at_builtins_applied_to_the_context() {
+        let script_context = Data::integer(7.into());
+
+        // The script context as the only argument of unConstrData
+        let expression = "[(builtin unConstrData) (con data (I 7))]";
+        let (_, end) = first_end(expression, Some(&script_context)).unwrap();
+        assert_eq!(end, ("ContextBuiltin", Some("UnConstrData".to_string())));
+        assert_eq!(first_end(expression, None), None);
+
+        // Another value goes on
+        assert_eq!(first_end("[(builtin unConstrData) (con data (I 8))]", Some(&script_context)), None);
+
+        // The context given first, once another argument saturates the builtin
+        let (step, end) = first_end("[[(builtin equalsData) (con data (I 7))] (con data (I 8))]", Some(&script_context)).unwrap();
+        assert_eq!(end, ("ContextBuiltin", Some("EqualsData".to_string())));
+        assert!(step > 3, "stopped at step {} before the builtin was saturated", step);
+    }
+}
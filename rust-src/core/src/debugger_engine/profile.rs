@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+use uplc::{
+    ast::{NamedDeBruijn, Term},
+    machine::{value::Value, Context, MachineState},
+};
+
+use super::aiken_traces::AikenSourceMap;
+use super::decisions::{saturating_builtin, term_id};
+
+/// A machine context frame, or the step itself at the top of a stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProfileFrame {
+    /// Waiting for the argument of the Lambda or Builtin with this term id
+    Call(i32),
+    /// Computing the function to apply to the argument term with this id
+    Apply(i32),
+    CaseField,
+    Force,
+    Constr(i32),
+    Case(i32),
+    /// The term computed by the step, or the Builtin it applies to its last argument
+    Step(i32),
+}
+
+/// What a profile is weighted by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileWeight {
+    Cpu,
+    Mem,
+}
+
+impl ProfileWeight {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "cpu" => Ok(ProfileWeight::Cpu),
+            "mem" => Ok(ProfileWeight::Mem),
+            other => Err(format!("Unknown profile weight {}, expected cpu or mem", other)),
+        }
+    }
+}
+
+fn frame_of(context: &Context, case_branches: &HashMap<i32, (i32, usize)>) -> Option<ProfileFrame> {
+    Some(match context {
+        Context::FrameAwaitArg(function, ..) => ProfileFrame::Call(match function {
+            Value::Lambda { term_id, .. } | Value::Builtin { term_id, .. } => *term_id as i32,
+            _ => -1,
+        }),
+        Context::FrameAwaitFunTerm(_, argument, ..) => ProfileFrame::Apply(term_id(argument)),
+        Context::FrameAwaitFunValue(..) => ProfileFrame::CaseField,
+        Context::FrameForce(..) => ProfileFrame::Force,
+        Context::FrameConstr(.., constr_id) => ProfileFrame::Constr(*constr_id as i32),
+        Context::FrameCases(_, branches, ..) => ProfileFrame::Case(
+            branches
+                .first()
+                .and_then(|branch| case_branches.get(&term_id(branch)))
+                .map_or(-1, |(case_id, _)| *case_id),
+        ),
+        Context::NoFrame => return None,
+    })
+}
+
+/// Stack of the step about to be taken from `state`, outermost frame first.
+/// `contexts` are the machine context frames, innermost first.
+pub fn profile_stack(
+    contexts: &[Context],
+    state: &MachineState,
+    case_branches: &HashMap<i32, (i32, usize)>,
+) -> Vec<ProfileFrame> {
+    let mut stack: Vec<ProfileFrame> = contexts
+        .iter()
+        .rev()
+        .filter_map(|context| frame_of(context, case_branches))
+        .collect();
+    match state {
+        MachineState::Compute(_, _, term) => stack.push(ProfileFrame::Step(term_id(term))),
+        MachineState::Return(..) => {
+            if let Some(Value::Builtin { term_id, .. }) = saturating_builtin(state) {
+                stack.push(ProfileFrame::Step(*term_id as i32));
+            }
+        }
+        MachineState::Done(_) => {}
+    }
+    stack
+}
+
+/// Budget spent per distinct stack while profiling is on
+#[derive(Debug, Clone, Default)]
+pub struct ProfileRecorder {
+    enabled: bool,
+    stacks: HashMap<Vec<ProfileFrame>, (i64, i64)>,
+}
+
+impl ProfileRecorder {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.stacks.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn record(&mut self, stack: Vec<ProfileFrame>, cpu: i64, mem: i64) {
+        if cpu == 0 && mem == 0 {
+            return;
+        }
+        let spent = self.stacks.entry(stack).or_default();
+        spent.0 += cpu;
+        spent.1 += mem;
+    }
+
+    /// Drops what was recorded, keeping profiling on
+    pub fn restart(&mut self) {
+        self.stacks.clear();
+    }
+
+    /// Stacks with their weight, in a stable order, leaving out those that weigh nothing
+    fn weighted(&self, weight: ProfileWeight) -> Vec<(&Vec<ProfileFrame>, i64)> {
+        let mut stacks: Vec<(&Vec<ProfileFrame>, i64)> = self
+            .stacks
+            .iter()
+            .map(|(stack, (cpu, mem))| {
+                (
+                    stack,
+                    match weight {
+                        ProfileWeight::Cpu => *cpu,
+                        ProfileWeight::Mem => *mem,
+                    },
+                )
+            })
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        stacks.sort();
+        stacks
+    }
+
+    /// One `frame;frame;frame weight` line per stack, as read by flamegraph.pl and inferno
+    pub fn folded(&self, weight: ProfileWeight, labels: &FrameLabels) -> String {
+        self.weighted(weight)
+            .into_iter()
+            .map(|(stack, weight)| {
+                let frames: Vec<String> = stack.iter().map(|frame| labels.label(frame).replace(';', ",")).collect();
+                format!("{} {}\n", frames.join(";"), weight)
+            })
+            .collect()
+    }
+
+    /// A sampled profile in speedscope's file format, one sample per stack
+    pub fn speedscope(&self, weight: ProfileWeight, labels: &FrameLabels, name: &str) -> serde_json::Value {
+        let mut frame_index: HashMap<ProfileFrame, usize> = HashMap::new();
+        let mut frames = vec![];
+        let mut samples = vec![];
+        let mut weights = vec![];
+        for (stack, stack_weight) in self.weighted(weight) {
+            let sample: Vec<usize> = stack
+                .iter()
+                .map(|frame| {
+                    *frame_index.entry(*frame).or_insert_with(|| {
+                        frames.push(json!({ "name": labels.label(frame) }));
+                        frames.len() - 1
+                    })
+                })
+                .collect();
+            samples.push(sample);
+            weights.push(stack_weight);
+        }
+        let total: i64 = weights.iter().sum();
+        json!({
+            "$schema": "https://www.speedscope.app/file-format-schema.json",
+            "name": name,
+            "exporter": "de-uplc",
+            "shared": { "frames": frames },
+            "profiles": [{
+                "type": "sampled",
+                "name": name,
+                "unit": "none",
+                "startValue": 0,
+                "endValue": total,
+                "samples": samples,
+                "weights": weights,
+            }],
+        })
+    }
+}
+
+/// Readable names of a program's terms, for profile frames
+pub struct FrameLabels<'a> {
+    names: HashMap<i32, String>,
+    source_map: Option<&'a AikenSourceMap>,
+}
+
+impl<'a> FrameLabels<'a> {
+    pub fn new(term: &Term<NamedDeBruijn>, source_map: Option<&'a AikenSourceMap>) -> Self {
+        let mut names = HashMap::new();
+        collect_names(term, &mut names);
+        FrameLabels { names, source_map }
+    }
+
+    fn name(&self, term_id: i32) -> String {
+        let name = self.names.get(&term_id).map_or("Term", String::as_str);
+        let location = self
+            .source_map
+            .and_then(|map| map.terms.get(&term_id))
+            .map(|location| format!(" {}:{}", location.file, location.line))
+            .unwrap_or_default();
+        format!("{} #{}{}", name, term_id, location)
+    }
+
+    pub fn label(&self, frame: &ProfileFrame) -> String {
+        match frame {
+            ProfileFrame::Call(term_id) | ProfileFrame::Step(term_id) => self.name(*term_id),
+            ProfileFrame::Apply(term_id) => format!("Apply to #{}", term_id),
+            ProfileFrame::CaseField => "Case field".to_string(),
+            ProfileFrame::Force => "Force".to_string(),
+            ProfileFrame::Constr(term_id) | ProfileFrame::Case(term_id) => self.name(*term_id),
+        }
+    }
+}
+
+fn collect_names(term: &Term<NamedDeBruijn>, names: &mut HashMap<i32, String>) {
+    let name = match term {
+        Term::Var { .. } => "Var".to_string(),
+        Term::Delay { .. } => "Delay".to_string(),
+        Term::Lambda { parameter_name, .. } => format!("\u{3bb}{}", parameter_name.text),
+        Term::Apply { .. } => "Apply".to_string(),
+        Term::Constant { .. } => "Constant".to_string(),
+        Term::Force { .. } => "Force".to_string(),
+        Term::Error { .. } => "Error".to_string(),
+        Term::Builtin { fun, .. } => format!("{:?}", fun),
+        Term::Constr { tag, .. } => format!("Constr {}", tag),
+        Term::Case { .. } => "Case".to_string(),
+    };
+    names.insert(term_id(term), name);
+    match term {
+        Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => collect_names(body, names),
+        Term::Apply { function, argument, .. } => {
+            collect_names(function, names);
+            collect_names(argument, names);
+        }
+        Term::Constr { fields, .. } => fields.iter().for_each(|field| collect_names(field, names)),
+        Term::Case { constr, branches, .. } => {
+            collect_names(constr, names);
+            branches.iter().for_each(|branch| collect_names(branch, names));
+        }
+        Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } | Term::Builtin { .. } => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uplc::builtins::DefaultFunction;
+
+    #[test]
+    fn test_folded_profile() {
+        let term: Term<NamedDeBruijn> = Term::Apply {
+            function: Term::Builtin { fun: DefaultFunction::Sha2_256, uniq_id: 1 }.into(),
+            argument: Term::Error { uniq_id: 2 }.into(),
+            uniq_id: 0,
+        };
+        let labels = FrameLabels::new(&term, None);
+
+        let mut recorder = ProfileRecorder::default();
+        recorder.set_enabled(true);
+        recorder.record(vec![ProfileFrame::Step(0)], 100, 10);
+        recorder.record(vec![ProfileFrame::Call(1), ProfileFrame::Step(2)], 100, 10);
+        recorder.record(vec![ProfileFrame::Call(1), ProfileFrame::Step(2)], 50, 5);
+        recorder.record(vec![ProfileFrame::Force], 0, 0);
+
+        assert_eq!(
+            recorder.folded(ProfileWeight::Cpu, &labels),
+            "Sha2_256 #1;Error #2 150\nApply #0 100\n"
+        );
+        let speedscope = recorder.speedscope(ProfileWeight::Mem, &labels, "spend:0");
+        assert_eq!(speedscope["profiles"][0]["endValue"], 25);
+        assert_eq!(speedscope["shared"]["frames"].as_array().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+use std::rc::Rc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How far a run has advanced, passed to the progress hook during long runs
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RunProgress {
+    /// Steps since the session was created or last reset
+    pub step_count: u64,
+    /// Steps executed by the current run call
+    pub run_steps: u64,
+    pub term_id: i32,
+    pub cpu_spent: i64,
+    pub mem_spent: i64,
+    /// Spent budget as a percentage of the ex-units declared by the redeemer
+    pub cpu_percent: f64,
+    pub mem_percent: f64,
+}
+
+/// `spent` as a percentage of the `declared` ex units, 0 when nothing was declared
+pub fn declared_percent(spent: i64, declared: i64) -> f64 {
+    if declared > 0 {
+        spent as f64 * 100.0 / declared as f64
+    } else {
+        0.0
+    }
+}
+
+/// Callback invoked every `interval` steps of a run
+#[derive(Clone, Default)]
+pub struct ProgressHook {
+    callback: Option<Rc<dyn Fn(&RunProgress)>>,
+    interval: u64,
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressHook")
+            .field("set", &self.callback.is_some())
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl ProgressHook {
+    pub fn set(&mut self, callback: Rc<dyn Fn(&RunProgress)>, interval: u64) {
+        self.callback = Some(callback);
+        self.interval = interval.max(1);
+    }
+
+    pub fn clear(&mut self) {
+        self.callback = None;
+    }
+
+    /// Whether the hook should be called after `run_steps` steps of the current run
+    pub fn due(&self, run_steps: u64) -> bool {
+        self.callback.is_some() && run_steps % self.interval == 0
+    }
+
+    pub fn report(&self, progress: &RunProgress) {
+        if let Some(callback) = &self.callback {
+            callback(progress);
+        }
+    }
+}
+
+/// Wraps a JS function so it receives each `RunProgress` as a JSON string
+#[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+pub fn js_progress_callback(callback: js_sys::Function) -> Rc<dyn Fn(&RunProgress)> {
+    use crate::wasm_tools::JsValue;
+    Rc::new(move |progress: &RunProgress| {
+        if let Ok(json) = serde_json::to_string(progress) {
+            // A throwing callback must not abort the run it observes
+            let _ = callback.call1(&JsValue::NULL, &JsValue::from_str(&json));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    fn progress(run_steps: u64) -> RunProgress {
+        RunProgress {
+            step_count: run_steps,
+            run_steps,
+            term_id: 0,
+            cpu_spent: 0,
+            mem_spent: 0,
+            cpu_percent: 0.0,
+            mem_percent: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_hook_is_due_every_interval() {
+        let reported = Rc::new(RefCell::new(vec![]));
+        let mut hook = ProgressHook::default();
+        assert!(!hook.due(1));
+
+        let sink = reported.clone();
+        hook.set(Rc::new(move |progress: &RunProgress| sink.borrow_mut().push(progress.run_steps)), 3);
+        for run_steps in 1..=9 {
+            if hook.due(run_steps) {
+                hook.report(&progress(run_steps));
+            }
+        }
+        assert_eq!(*reported.borrow(), vec![3, 6, 9]);
+
+        hook.clear();
+        assert!(!hook.due(12));
+        hook.report(&progress(12));
+        assert_eq!(reported.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_zero_interval_reports_every_step() {
+        let mut hook = ProgressHook::default();
+        hook.set(Rc::new(|_: &RunProgress| {}), 0);
+        assert!(hook.due(1) && hook.due(2));
+    }
+
+    #[test]
+    fn test_declared_percent() {
+        assert_eq!(declared_percent(50, 200), 25.0);
+        assert_eq!(declared_percent(300, 200), 150.0);
+        assert_eq!(declared_percent(10, 0), 0.0);
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+
+use pallas_codec::minicbor::{self, Encoder};
+use pallas_primitives::conway::Language;
+use serde::Deserialize;
+
+use super::{DebuggerEngine, DebuggerError, SessionController};
+use crate::plutus_data::SerializablePlutusData;
+use crate::protocol_params::ProtocolParameters;
+use crate::utxo::{ScriptType, UtxoOutput, UtxoValue};
+use crate::wasm_tools::{wasm_bindgen, JsError};
+
+/// Transaction id used for the synthetic input of quick-run transactions
+const SYNTHETIC_TX_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Lovelace locked in the synthetic input and paid to the synthetic output
+const SYNTHETIC_LOVELACE: u64 = 10_000_000;
+
+/// Fee declared by the synthetic transaction
+const SYNTHETIC_FEE: u64 = 200_000;
+
+/// Ex units declared for the redeemer when neither the spec nor the protocol
+/// parameters provide a limit
+const DEFAULT_EX_UNITS_MEM: u64 = 14_000_000;
+const DEFAULT_EX_UNITS_STEPS: u64 = 10_000_000_000;
+
+// Redeemer tags as encoded in the witness set
+const REDEEMER_TAG_MINT: u8 = 1;
+const REDEEMER_TAG_REWARD: u8 = 3;
+
+/// Reward account header for a script stake credential (network id in the low nibble)
+const SCRIPT_REWARD_ACCOUNT_HEADER: u8 = 0xf0;
+
+/// Settings shared by every quick-run helper
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickRunEnvironment {
+    pub protocol_params: ProtocolParameters,
+    #[serde(default)]
+    pub network: Option<String>,
+    #[serde(default)]
+    pub plutus_version: Option<ScriptType>,
+    #[serde(default)]
+    pub ex_units: Option<QuickRunExUnits>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct QuickRunExUnits {
+    pub mem: u64,
+    pub steps: u64,
+}
+
+/// Description of the mint performed by the synthetic transaction
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MintSpec {
+    /// Asset name (hex) to quantity; negative quantities burn
+    pub assets: BTreeMap<String, i64>,
+    #[serde(flatten)]
+    pub environment: QuickRunEnvironment,
+}
+
+/// Description of the withdrawal performed by the synthetic transaction.
+/// `amount` defaults to zero, matching the "0-ADA withdrawal trick" used to
+/// trigger staking validators.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalSpec {
+    #[serde(default)]
+    pub amount: u64,
+    #[serde(flatten)]
+    pub environment: QuickRunEnvironment,
+}
+
+impl QuickRunEnvironment {
+    pub(crate) fn network(&self) -> &str {
+        self.network.as_deref().unwrap_or("mainnet")
+    }
+
+    pub(crate) fn network_tag(&self) -> u8 {
+        if self.network().trim().eq_ignore_ascii_case("mainnet") { 1 } else { 0 }
+    }
+
+    /// Witness set key and hash tag for the requested Plutus version
+    pub(crate) fn script_slot(&self) -> Result<(u8, u8), DebuggerError> {
+        match self.plutus_version.as_ref().unwrap_or(&ScriptType::PlutusV3) {
+            ScriptType::PlutusV1 => Ok((3, 1)),
+            ScriptType::PlutusV2 => Ok((6, 2)),
+            ScriptType::PlutusV3 => Ok((7, 3)),
+            ScriptType::NativeScript => Err(DebuggerError::ProgramBuildError(
+                "Native scripts can't be debugged".to_string(),
+            )),
+        }
+    }
+
+    pub(crate) fn language(&self) -> Result<Language, DebuggerError> {
+        match self.plutus_version.as_ref().unwrap_or(&ScriptType::PlutusV3) {
+            ScriptType::PlutusV1 => Ok(Language::PlutusV1),
+            ScriptType::PlutusV2 => Ok(Language::PlutusV2),
+            ScriptType::PlutusV3 => Ok(Language::PlutusV3),
+            ScriptType::NativeScript => Err(DebuggerError::ProgramBuildError(
+                "Native scripts can't be debugged".to_string(),
+            )),
+        }
+    }
+
+    pub(crate) fn ex_units(&self) -> QuickRunExUnits {
+        self.ex_units.unwrap_or_else(|| QuickRunExUnits {
+            mem: self
+                .protocol_params
+                .max_tx_ex_mem
+                .as_ref()
+                .and_then(|mem| mem.parse().ok())
+                .unwrap_or(DEFAULT_EX_UNITS_MEM),
+            steps: self
+                .protocol_params
+                .max_tx_ex_steps
+                .as_ref()
+                .and_then(|steps| steps.parse().ok())
+                .unwrap_or(DEFAULT_EX_UNITS_STEPS),
+        })
+    }
+}
+
+/// Redeemer attached to a synthetic transaction
+pub(crate) struct SyntheticRedeemer {
+    pub tag: u8,
+    pub index: u32,
+    pub data: pallas_primitives::PlutusData,
+    pub ex_units: QuickRunExUnits,
+}
+
+/// Minimal Conway transaction with a single input and output, built directly
+/// as CBOR so it can be fed through the regular `DebuggerEngine` pipeline
+pub(crate) struct SyntheticTx {
+    pub network_tag: u8,
+    pub script: Vec<u8>,
+    pub script_witness_key: u8,
+    pub mint: Option<(Vec<u8>, BTreeMap<Vec<u8>, i64>)>,
+    pub withdrawals: Vec<(Vec<u8>, u64)>,
+    pub redeemers: Vec<SyntheticRedeemer>,
+}
+
+impl SyntheticTx {
+    fn owner_address(&self) -> Vec<u8> {
+        // Enterprise address with an all-zero key hash
+        let mut address = vec![0x60 | self.network_tag];
+        address.extend_from_slice(&[0u8; 28]);
+        address
+    }
+
+    /// Encodes the transaction and the UTxO its input spends
+    pub(crate) fn build(&self) -> Result<(String, Vec<UtxoOutput>), DebuggerError> {
+        let encode_error = |e: minicbor::encode::Error<std::convert::Infallible>| {
+            DebuggerError::TransactionParseError(format!("Failed to encode synthetic transaction: {}", e))
+        };
+        let tx_hash = hex::decode(SYNTHETIC_TX_HASH).expect("valid synthetic tx hash");
+        let address = self.owner_address();
+
+        let mut body_fields = 3;
+        if self.mint.is_some() {
+            body_fields += 1;
+        }
+        if !self.withdrawals.is_empty() {
+            body_fields += 1;
+        }
+
+        let mut e = Encoder::new(Vec::new());
+        e.array(4).map_err(encode_error)?;
+
+        // Transaction body
+        e.map(body_fields).map_err(encode_error)?;
+        e.u8(0).map_err(encode_error)?;
+        e.array(1).map_err(encode_error)?;
+        e.array(2).map_err(encode_error)?.bytes(&tx_hash).map_err(encode_error)?.u64(0).map_err(encode_error)?;
+        e.u8(1).map_err(encode_error)?;
+        e.array(1).map_err(encode_error)?;
+        e.array(2).map_err(encode_error)?;
+        e.bytes(&address).map_err(encode_error)?;
+        e.u64(SYNTHETIC_LOVELACE - SYNTHETIC_FEE).map_err(encode_error)?;
+        e.u8(2).map_err(encode_error)?.u64(SYNTHETIC_FEE).map_err(encode_error)?;
+        if !self.withdrawals.is_empty() {
+            e.u8(5).map_err(encode_error)?;
+            e.map(self.withdrawals.len() as u64).map_err(encode_error)?;
+            for (reward_account, amount) in &self.withdrawals {
+                e.bytes(reward_account).map_err(encode_error)?.u64(*amount).map_err(encode_error)?;
+            }
+        }
+        if let Some((policy_id, assets)) = &self.mint {
+            e.u8(9).map_err(encode_error)?;
+            e.map(1).map_err(encode_error)?;
+            e.bytes(policy_id).map_err(encode_error)?;
+            e.map(assets.len() as u64).map_err(encode_error)?;
+            for (asset_name, quantity) in assets {
+                e.bytes(asset_name).map_err(encode_error)?.i64(*quantity).map_err(encode_error)?;
+            }
+        }
+
+        // Witness set: the script and its redeemers
+        e.map(2).map_err(encode_error)?;
+        e.u8(self.script_witness_key).map_err(encode_error)?;
+        e.array(1).map_err(encode_error)?.bytes(&self.script).map_err(encode_error)?;
+        e.u8(5).map_err(encode_error)?;
+        e.array(self.redeemers.len() as u64).map_err(encode_error)?;
+        for redeemer in &self.redeemers {
+            e.array(4).map_err(encode_error)?;
+            e.u8(redeemer.tag).map_err(encode_error)?;
+            e.u32(redeemer.index).map_err(encode_error)?;
+            e.encode(&redeemer.data).map_err(encode_error)?;
+            e.array(2).map_err(encode_error)?;
+            e.u64(redeemer.ex_units.mem).map_err(encode_error)?;
+            e.u64(redeemer.ex_units.steps).map_err(encode_error)?;
+        }
+
+        e.bool(true).map_err(encode_error)?;
+        e.null().map_err(encode_error)?;
+
+        let utxo = UtxoOutput {
+            tx_hash: SYNTHETIC_TX_HASH.to_string(),
+            output_index: 0,
+            address: hex::encode(&address),
+            value: UtxoValue::new_lovelace_only(SYNTHETIC_LOVELACE.to_string()),
+            datum_hash: None,
+            inline_datum: None,
+            reference_script: None,
+        };
+
+        Ok((hex::encode(e.into_writer()), vec![utxo]))
+    }
+}
+
+pub(crate) fn parse_redeemer(redeemer_json: &str) -> Result<pallas_primitives::PlutusData, DebuggerError> {
+    SerializablePlutusData::from_json_string(redeemer_json)
+        .map_err(|e| DebuggerError::TransactionParseError(format!("Invalid redeemer: {}", e)))?
+        .to_pallas()
+        .map_err(|e| DebuggerError::TransactionParseError(format!("Invalid redeemer: {}", e)))
+}
+
+pub(crate) fn script_hash(script: &[u8], hash_tag: u8) -> Vec<u8> {
+    use pallas_crypto::hash::Hasher;
+    Hasher::<224>::hash_tagged(script, hash_tag).to_vec()
+}
+
+/// Synthesizes a transaction minting `mint_spec.assets` under the given policy
+/// and opens a debug session for its `Mint:0` redeemer
+#[wasm_bindgen]
+pub fn debug_minting_policy(
+    script_cbor: &str,
+    redeemer_json: &str,
+    mint_spec_json: &str,
+) -> Result<SessionController, JsError> {
+    let mint_spec = serde_json::from_str::<MintSpec>(mint_spec_json)
+        .map_err(|e| DebuggerError::TransactionParseError(format!("Invalid mint spec: {}", e)))?;
+    let environment = &mint_spec.environment;
+
+    let script = hex::decode(script_cbor)
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid script hex: {}", e)))?;
+    let (script_witness_key, hash_tag) = environment.script_slot()?;
+    let policy_id = script_hash(&script, hash_tag);
+
+    let assets = mint_spec
+        .assets
+        .iter()
+        .map(|(asset_name, quantity)| {
+            if *quantity == 0 {
+                return Err(DebuggerError::TransactionParseError(format!(
+                    "Mint quantity for asset {} must not be zero",
+                    asset_name
+                )));
+            }
+            let asset_name = hex::decode(asset_name).map_err(|e| {
+                DebuggerError::TransactionParseError(format!("Invalid asset name {}: {}", asset_name, e))
+            })?;
+            Ok((asset_name, *quantity))
+        })
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+    if assets.is_empty() {
+        return Err(DebuggerError::TransactionParseError("Mint spec has no assets".to_string()).into());
+    }
+
+    let tx = SyntheticTx {
+        network_tag: environment.network_tag(),
+        script,
+        script_witness_key,
+        mint: Some((policy_id, assets)),
+        withdrawals: vec![],
+        redeemers: vec![SyntheticRedeemer {
+            tag: REDEEMER_TAG_MINT,
+            index: 0,
+            data: parse_redeemer(redeemer_json)?,
+            ex_units: environment.ex_units(),
+        }],
+    };
+    let (tx_hex, utxos) = tx.build()?;
+
+    let mut engine = DebuggerEngine::new_internal(
+        &tx_hex,
+        utxos,
+        environment.protocol_params.clone(),
+        environment.network(),
+    )?;
+    engine.init_debug_session("Mint:0", None)
+}
+
+/// Builds the reward account of a script stake credential
+pub(crate) fn script_reward_account(script_hash: &[u8], network_tag: u8) -> Vec<u8> {
+    let mut reward_account = vec![SCRIPT_REWARD_ACCOUNT_HEADER | network_tag];
+    reward_account.extend_from_slice(script_hash);
+    reward_account
+}
+
+/// Synthesizes a transaction withdrawing `amount` from the reward account of
+/// the given staking validator and opens a debug session for its `Reward:0` redeemer
+#[wasm_bindgen]
+pub fn debug_withdrawal_validator(
+    script_cbor: &str,
+    redeemer_json: &str,
+    withdrawal_spec_json: &str,
+) -> Result<SessionController, JsError> {
+    let withdrawal_spec = serde_json::from_str::<WithdrawalSpec>(withdrawal_spec_json)
+        .map_err(|e| DebuggerError::TransactionParseError(format!("Invalid withdrawal spec: {}", e)))?;
+    let environment = &withdrawal_spec.environment;
+
+    let script = hex::decode(script_cbor)
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid script hex: {}", e)))?;
+    let (script_witness_key, hash_tag) = environment.script_slot()?;
+    let reward_account = script_reward_account(&script_hash(&script, hash_tag), environment.network_tag());
+
+    let tx = SyntheticTx {
+        network_tag: environment.network_tag(),
+        script,
+        script_witness_key,
+        mint: None,
+        withdrawals: vec![(reward_account, withdrawal_spec.amount)],
+        redeemers: vec![SyntheticRedeemer {
+            tag: REDEEMER_TAG_REWARD,
+            index: 0,
+            data: parse_redeemer(redeemer_json)?,
+            ex_units: environment.ex_units(),
+        }],
+    };
+    let (tx_hex, utxos) = tx.build()?;
+
+    let mut engine = DebuggerEngine::new_internal(
+        &tx_hex,
+        utxos,
+        environment.protocol_params.clone(),
+        environment.network(),
+    )?;
+    engine.init_debug_session("Reward:0", None)
+}
@@ -0,0 +1,98 @@
+use std::{cell::RefCell, rc::Rc};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Soft limits shared by every session of an engine. Crossing a limit never
+/// aborts execution, it only records a warning the host can act upon.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct QuotaLimits {
+    pub max_steps: Option<u64>,
+    pub max_serialized_bytes: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct QuotaUsage {
+    pub steps: u64,
+    pub serialized_bytes: u64,
+    pub limits: QuotaLimits,
+    pub steps_limit_exceeded: bool,
+    pub serialized_bytes_limit_exceeded: bool,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct QuotaTracker {
+    usage: QuotaUsage,
+}
+
+/// Quota tracker shared between an engine and the sessions it created
+pub type SharedQuota = Rc<RefCell<QuotaTracker>>;
+
+impl QuotaTracker {
+    pub fn new_shared() -> SharedQuota {
+        Rc::new(RefCell::new(QuotaTracker::default()))
+    }
+
+    pub fn set_limits(&mut self, limits: QuotaLimits) {
+        self.usage.limits = limits;
+        self.usage.steps_limit_exceeded = false;
+        self.usage.serialized_bytes_limit_exceeded = false;
+        self.check_limits();
+    }
+
+    pub fn record_steps(&mut self, steps: u64) {
+        self.usage.steps = self.usage.steps.saturating_add(steps);
+        self.check_limits();
+    }
+
+    pub fn record_serialized_bytes(&mut self, bytes: usize) {
+        self.usage.serialized_bytes = self.usage.serialized_bytes.saturating_add(bytes as u64);
+        self.check_limits();
+    }
+
+    pub fn usage(&self) -> &QuotaUsage {
+        &self.usage
+    }
+
+    /// Records a warning the first time each limit is crossed
+    fn check_limits(&mut self) {
+        let usage = &mut self.usage;
+        if let Some(max_steps) = usage.limits.max_steps {
+            if usage.steps > max_steps && !usage.steps_limit_exceeded {
+                usage.steps_limit_exceeded = true;
+                usage.warnings.push(format!(
+                    "Step soft limit exceeded: {} steps executed (limit {})",
+                    usage.steps, max_steps
+                ));
+            }
+        }
+        if let Some(max_bytes) = usage.limits.max_serialized_bytes {
+            if usage.serialized_bytes > max_bytes && !usage.serialized_bytes_limit_exceeded {
+                usage.serialized_bytes_limit_exceeded = true;
+                usage.warnings.push(format!(
+                    "Serialization soft limit exceeded: {} bytes serialized (limit {})",
+                    usage.serialized_bytes, max_bytes
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soft_limit_warns_once() {
+        let mut tracker = QuotaTracker::default();
+        tracker.set_limits(QuotaLimits { max_steps: Some(2), max_serialized_bytes: None });
+        tracker.record_steps(2);
+        assert!(tracker.usage().warnings.is_empty());
+        tracker.record_steps(1);
+        tracker.record_steps(1);
+        assert!(tracker.usage().steps_limit_exceeded);
+        assert_eq!(tracker.usage().warnings.len(), 1);
+        assert_eq!(tracker.usage().steps, 4);
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum GraphNodeKind {
+    Redeemer,
+    Script,
+    /// Spent input, `txid#index`
+    Input,
+    Policy,
+    Certificate,
+    /// Reward account withdrawn from
+    Withdrawal,
+    Voter,
+    Proposal,
+    Datum,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct GraphNode {
+    /// Unique id, prefixed by the node kind, e.g. `redeemer:Spend:0` or `script:<hash>`
+    pub id: String,
+    pub kind: GraphNodeKind,
+    pub label: String,
+    /// Short extra text, e.g. the declared ex-units of a redeemer
+    pub details: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// "runs", "spends", "mints", "certifies", "withdraws", "votes", "proposes",
+    /// "has_datum" or "forwards_to"
+    pub relation: String,
+}
+
+/// Redeemers of a transaction with the scripts they run, what each governs and
+/// the datums involved, as nodes and edges for an overview diagram
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct RedeemerGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    #[serde(skip)]
+    node_ids: HashSet<String>,
+}
+
+impl RedeemerGraph {
+    /// Adds a node unless one with the same id exists, returning its id
+    pub fn add_node(&mut self, id: String, kind: GraphNodeKind, label: String, details: Option<String>) -> String {
+        if self.node_ids.insert(id.clone()) {
+            self.nodes.push(GraphNode {
+                id: id.clone(),
+                kind,
+                label,
+                details,
+            });
+        }
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, relation: &str) {
+        self.edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nodes_are_deduplicated() {
+        let mut graph = RedeemerGraph::default();
+        let script = graph.add_node("script:ab".to_string(), GraphNodeKind::Script, "ab".to_string(), None);
+        for key in ["Spend:0", "Spend:1"] {
+            let redeemer = graph.add_node(format!("redeemer:{}", key), GraphNodeKind::Redeemer, key.to_string(), None);
+            graph.add_node(script.clone(), GraphNodeKind::Script, "ab".to_string(), None);
+            graph.add_edge(&redeemer, &script, "runs");
+        }
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+    }
+}
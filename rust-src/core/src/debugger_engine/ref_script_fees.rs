@@ -0,0 +1,125 @@
+use pallas_primitives::conway::{MintedTx, TransactionInput};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::quick_run::script_hash;
+use crate::utxo::{ScriptType, UtxoOutput};
+
+/// Size of each price tier of the Conway reference script fee, in bytes
+const REF_SCRIPT_TIER_SIZE: u64 = 25_600;
+
+/// Price increase from one tier to the next
+const REF_SCRIPT_TIER_MULTIPLIER: f64 = 1.2;
+
+/// A reference script the transaction pays for, through a spent or a reference input
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RefScriptFee {
+    /// `<tx hash>#<output index>` of the UTxO holding the script
+    pub utxo: String,
+    /// "ReferenceInput" or "Input"
+    pub source: String,
+    pub script_hash: String,
+    pub script_type: String,
+    pub size: u64,
+    /// Fee added by this script on top of the scripts listed before it
+    pub fee_contribution: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RefScriptFeeBreakdown {
+    /// `minFeeRefScriptCostPerByte`; fees are absent when the parameter is unknown
+    pub cost_per_byte: Option<f64>,
+    pub total_size: u64,
+    pub total_fee: Option<u64>,
+    pub scripts: Vec<RefScriptFee>,
+}
+
+/// Conway reference script fee for `total_size` bytes: every 25 KiB tier costs
+/// 1.2 times the previous one per byte
+pub fn tiered_ref_script_fee(total_size: u64, cost_per_byte: f64) -> u64 {
+    let mut fee = 0.0;
+    let mut tier_price = cost_per_byte;
+    let mut remaining = total_size;
+    while remaining >= REF_SCRIPT_TIER_SIZE {
+        fee += REF_SCRIPT_TIER_SIZE as f64 * tier_price;
+        tier_price *= REF_SCRIPT_TIER_MULTIPLIER;
+        remaining -= REF_SCRIPT_TIER_SIZE;
+    }
+    (fee + remaining as f64 * tier_price).floor() as u64
+}
+
+fn hash_tag(script_type: &ScriptType) -> u8 {
+    match script_type {
+        ScriptType::NativeScript => 0,
+        ScriptType::PlutusV1 => 1,
+        ScriptType::PlutusV2 => 2,
+        ScriptType::PlutusV3 => 3,
+    }
+}
+
+/// Lists the reference scripts of reference inputs, then spent inputs (the order
+/// the ledger sums them in), attributing the tiered fee to each one marginally.
+/// Inputs are counted every time they appear, as the ledger does.
+pub fn ref_script_fee_breakdown(tx: &MintedTx, utxos: &[UtxoOutput], cost_per_byte: Option<f64>) -> RefScriptFeeBreakdown {
+    let referenced = tx
+        .transaction_body
+        .reference_inputs
+        .iter()
+        .flat_map(|inputs| inputs.iter())
+        .map(|input| ("ReferenceInput", input));
+    let spent = tx.transaction_body.inputs.iter().map(|input| ("Input", input));
+
+    let mut scripts = vec![];
+    let mut total_size = 0;
+    for (source, input) in referenced.chain(spent) {
+        let Some((utxo, reference_script)) = find_utxo(utxos, input)
+            .and_then(|utxo| Some((utxo, utxo.reference_script.as_ref()?)))
+        else {
+            continue;
+        };
+        let Ok(bytes) = hex::decode(&reference_script.script) else {
+            continue;
+        };
+
+        let size = bytes.len() as u64;
+        let fee_contribution = cost_per_byte.map(|price| {
+            tiered_ref_script_fee(total_size + size, price) - tiered_ref_script_fee(total_size, price)
+        });
+        total_size += size;
+        scripts.push(RefScriptFee {
+            utxo: format!("{}#{}", utxo.tx_hash, utxo.output_index),
+            source: source.to_string(),
+            script_hash: hex::encode(script_hash(&bytes, hash_tag(&reference_script.r#type))),
+            script_type: format!("{:?}", reference_script.r#type),
+            size,
+            fee_contribution,
+        });
+    }
+
+    RefScriptFeeBreakdown {
+        cost_per_byte,
+        total_size,
+        total_fee: cost_per_byte.map(|price| tiered_ref_script_fee(total_size, price)),
+        scripts,
+    }
+}
+
+fn find_utxo<'a>(utxos: &'a [UtxoOutput], input: &TransactionInput) -> Option<&'a UtxoOutput> {
+    let transaction_id = hex::encode(input.transaction_id);
+    utxos
+        .iter()
+        .find(|utxo| utxo.output_index as u64 == input.index && utxo.tx_hash.eq_ignore_ascii_case(&transaction_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiered_ref_script_fee() {
+        assert_eq!(tiered_ref_script_fee(0, 15.0), 0);
+        assert_eq!(tiered_ref_script_fee(1_000, 15.0), 15_000);
+        // A full first tier at 15/byte, then 1000 bytes at 18/byte
+        assert_eq!(tiered_ref_script_fee(REF_SCRIPT_TIER_SIZE + 1_000, 15.0), 384_000 + 18_000);
+    }
+}
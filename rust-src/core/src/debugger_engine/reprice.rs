@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use pallas_primitives::conway::Language;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+    machine::{
+        cost_model::{initialize_cost_model, CostModel, ExBudget, StepKind},
+        value::Value,
+        Context, MachineState,
+    },
+};
+
+use super::decisions::term_id;
+use super::execution_trace::{ExecutionTrace, TraceStateKind, MAX_TRACE_ENTRIES};
+use crate::cost_model_upgrade::check_cost_model;
+use crate::protocol_params::CostModels;
+
+/// Totals of a recorded execution priced under another cost model
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RepriceReport {
+    pub redeemer: String,
+    pub session_id: String,
+    /// Steps of the recorded execution
+    pub steps: u64,
+    /// What the execution spent under the session's own cost model
+    pub original_cpu: i64,
+    pub original_mem: i64,
+    pub cpu: i64,
+    pub mem: i64,
+    /// Machine startup and steps, under the new cost model
+    pub machine_cpu: i64,
+    pub machine_mem: i64,
+    /// Builtin calls, under the new cost model
+    pub builtin_cpu: i64,
+    pub builtin_mem: i64,
+    pub declared_cpu: i64,
+    pub declared_mem: i64,
+    pub within_declared: bool,
+}
+
+/// A builtin applied to its last argument, with every argument it was given
+#[derive(Debug, Clone)]
+pub struct BuiltinCall {
+    pub step: u64,
+    pub fun: DefaultFunction,
+    pub args: Vec<Value>,
+}
+
+/// Builtin calls with their arguments, kept while trace recording is on so the
+/// execution can be priced again. Like the trace, at most `MAX_TRACE_ENTRIES`
+/// calls are kept.
+#[derive(Debug, Clone, Default)]
+pub struct BuiltinCallRecorder {
+    calls: Vec<BuiltinCall>,
+    truncated: bool,
+}
+
+impl BuiltinCallRecorder {
+    pub fn record(&mut self, call: BuiltinCall) {
+        if self.calls.len() >= MAX_TRACE_ENTRIES {
+            self.truncated = true;
+            return;
+        }
+        self.calls.push(call);
+    }
+
+    pub fn calls(&self) -> &[BuiltinCall] {
+        &self.calls
+    }
+
+    /// Whether calls beyond `MAX_TRACE_ENTRIES` went unrecorded
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Drops the calls of steps after `step`. Going back before a recorded call
+    /// also goes back before every call that was left out.
+    pub fn truncate_after(&mut self, step: u64) {
+        while self.calls.last().is_some_and(|call| call.step > step) {
+            self.calls.pop();
+            self.truncated = false;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.calls.clear();
+        self.truncated = false;
+    }
+}
+
+/// The builtin the step from `state` applies to its last argument, with all its arguments
+pub fn saturating_call(state: &MachineState) -> Option<(DefaultFunction, Vec<Value>)> {
+    let MachineState::Return(context, value) = state else {
+        return None;
+    };
+    let (function, last) = match context {
+        Context::FrameAwaitArg(function, ..) => (function, value),
+        Context::FrameAwaitFunValue(argument, ..) => (value, argument),
+        _ => return None,
+    };
+    match function {
+        Value::Builtin { fun, runtime, .. } if runtime.args.len() + 1 == fun.arity() => {
+            Some((*fun, runtime.args.iter().chain([last]).cloned().collect()))
+        }
+        _ => None,
+    }
+}
+
+fn step_kind(term: &Term<NamedDeBruijn>) -> Option<StepKind> {
+    match term {
+        Term::Var { .. } => Some(StepKind::Var),
+        Term::Delay { .. } => Some(StepKind::Delay),
+        Term::Lambda { .. } => Some(StepKind::Lambda),
+        Term::Apply { .. } => Some(StepKind::Apply),
+        Term::Constant { .. } => Some(StepKind::Constant),
+        Term::Force { .. } => Some(StepKind::Force),
+        Term::Builtin { .. } => Some(StepKind::Builtin),
+        Term::Constr { .. } => Some(StepKind::Constr),
+        Term::Case { .. } => Some(StepKind::Case),
+        Term::Error { .. } => None,
+    }
+}
+
+fn collect_step_kinds(term: &Term<NamedDeBruijn>, kinds: &mut HashMap<i32, StepKind>) {
+    if let Some(kind) = step_kind(term) {
+        kinds.insert(term_id(term), kind);
+    }
+    match term {
+        Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => collect_step_kinds(body, kinds),
+        Term::Apply { function, argument, .. } => {
+            collect_step_kinds(function, kinds);
+            collect_step_kinds(argument, kinds);
+        }
+        Term::Constr { fields, .. } => fields.iter().for_each(|field| collect_step_kinds(field, kinds)),
+        Term::Case { constr, branches, .. } => {
+            collect_step_kinds(constr, kinds);
+            branches.iter().for_each(|branch| collect_step_kinds(branch, kinds));
+        }
+        Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } | Term::Builtin { .. } => {}
+    }
+}
+
+/// Builds the machine cost model `language` gets from `cost_models`
+pub fn cost_model_from(cost_models: &CostModels, language: &Language) -> Result<CostModel, String> {
+    let values = match language {
+        Language::PlutusV1 => &cost_models.plutus_v1,
+        Language::PlutusV2 => &cost_models.plutus_v2,
+        Language::PlutusV3 => &cost_models.plutus_v3,
+    }
+    .as_ref()
+    .ok_or_else(|| format!("No cost model given for {:?}", language))?;
+    check_cost_model(language, values)?;
+    Ok(initialize_cost_model(language, values))
+}
+
+/// Prices the steps of `trace` and the builtin `calls` made along it under
+/// `cost_model`, returning the machine and the builtin costs
+pub fn reprice_trace(
+    program: &Term<NamedDeBruijn>,
+    trace: &ExecutionTrace,
+    calls: &[BuiltinCall],
+    cost_model: &CostModel,
+) -> Result<(ExBudget, ExBudget), String> {
+    let mut kinds = HashMap::new();
+    collect_step_kinds(program, &mut kinds);
+
+    let mut machine = cost_model.machine_costs.get(StepKind::StartUp);
+    for entry in trace.entries.iter().filter(|entry| entry.kind == TraceStateKind::Compute) {
+        if let Some(kind) = kinds.get(&entry.term_id) {
+            let cost = cost_model.machine_costs.get(*kind);
+            machine.cpu += cost.cpu;
+            machine.mem += cost.mem;
+        }
+    }
+
+    let mut builtin = ExBudget { cpu: 0, mem: 0 };
+    for call in calls {
+        let cost = cost_model
+            .builtin_costs
+            .to_ex_budget(call.fun, &call.args)
+            .map_err(|e| format!("Failed to price {:?} at step {}: {:?}", call.fun, call.step, e))?;
+        builtin.cpu += cost.cpu;
+        builtin.mem += cost.mem;
+    }
+    Ok((machine, builtin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_kinds_and_recorded_calls() {
+        let term: Term<NamedDeBruijn> = Term::Apply {
+            function: Term::Builtin { fun: DefaultFunction::Sha2_256, uniq_id: 1 }.into(),
+            argument: Term::Error { uniq_id: 2 }.into(),
+            uniq_id: 0,
+        };
+        let mut kinds = HashMap::new();
+        collect_step_kinds(&term, &mut kinds);
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(kinds[&1], StepKind::Builtin));
+
+        let mut recorder = BuiltinCallRecorder::default();
+        for step in [3, 8] {
+            recorder.record(BuiltinCall {
+                step,
+                fun: DefaultFunction::Sha2_256,
+                args: vec![],
+            });
+        }
+        recorder.truncate_after(5);
+        assert_eq!(recorder.calls().len(), 1);
+        assert!(cost_model_from(&CostModels::new(None, None, None), &Language::PlutusV3).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::debugger_engine::{SerializableExecutionStatus, SessionController};
+use crate::wasm_tools::JsError;
+
+/// Outcome of evaluating one redeemer's program to completion
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RedeemerRun {
+    pub redeemer: String,
+    /// Hex script hash, whatever the engine's `script_hash_format`
+    pub script_hash: Option<String>,
+    /// "Success", "Failure" (the script errored or spent more than declared) or
+    /// "NotRun" (no session could be built, see `error`)
+    pub outcome: String,
+    /// Final status; None when the redeemer was not run
+    pub status: Option<SerializableExecutionStatus>,
+    pub error: Option<String>,
+    pub steps: u64,
+    pub spent_cpu: i64,
+    pub spent_mem: i64,
+    pub declared_cpu: i64,
+    pub declared_mem: i64,
+    pub within_budget: bool,
+    pub traces: Vec<String>,
+}
+
+/// Outcome of `run_all`: every redeemer of the transaction, in key order
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TransactionRun {
+    /// Whether every redeemer succeeded within its declared ex units
+    pub valid: bool,
+    pub total_spent_cpu: i64,
+    pub total_spent_mem: i64,
+    pub total_declared_cpu: i64,
+    pub total_declared_mem: i64,
+    pub redeemers: Vec<RedeemerRun>,
+}
+
+impl RedeemerRun {
+    pub fn not_run(redeemer: &str, declared_cpu: i64, declared_mem: i64, error: String) -> Self {
+        RedeemerRun {
+            redeemer: redeemer.to_string(),
+            script_hash: None,
+            outcome: "NotRun".to_string(),
+            status: None,
+            error: Some(error),
+            steps: 0,
+            spent_cpu: 0,
+            spent_mem: 0,
+            declared_cpu,
+            declared_mem,
+            within_budget: false,
+            traces: vec![],
+        }
+    }
+}
+
+impl TransactionRun {
+    pub fn new(redeemers: Vec<RedeemerRun>) -> Self {
+        TransactionRun {
+            valid: redeemers.iter().all(|run| run.outcome == "Success"),
+            total_spent_cpu: redeemers.iter().map(|run| run.spent_cpu).sum(),
+            total_spent_mem: redeemers.iter().map(|run| run.spent_mem).sum(),
+            total_declared_cpu: redeemers.iter().map(|run| run.declared_cpu).sum(),
+            total_declared_mem: redeemers.iter().map(|run| run.declared_mem).sum(),
+            redeemers,
+        }
+    }
+}
+
+/// Evaluates a fresh session until its program finishes, fails or spends more
+/// than the redeemer declares, as the ledger would. Breakpoints and step limits
+/// are not consulted.
+pub(crate) fn run_session(session: &mut SessionController) -> Result<RedeemerRun, JsError> {
+    let status = session.evaluate_inner(session.declared_budget())?;
+    let budget = session.get_budget_inner()?;
+
+    let within_budget = !matches!(status, SerializableExecutionStatus::BudgetExceeded { .. });
+    let (outcome, error) = match &status {
+        SerializableExecutionStatus::Done { .. } => ("Success", None),
+        SerializableExecutionStatus::Error { message } => ("Failure", Some(message.clone())),
+        SerializableExecutionStatus::BudgetExceeded { .. } => {
+            ("Failure", Some("Declared ex units exceeded".to_string()))
+        }
+        _ => ("Failure", None),
+    };
+    Ok(RedeemerRun {
+        redeemer: session.get_redeemer()?,
+        script_hash: Some(session.script_hash_hex().to_string()),
+        outcome: outcome.to_string(),
+        status: Some(status),
+        error,
+        steps: session.get_step_count(),
+        spent_cpu: budget.ex_units_spent,
+        spent_mem: budget.memory_units_spent,
+        declared_cpu: budget.ex_units_available,
+        declared_mem: budget.memory_units_available,
+        within_budget,
+        traces: session.get_logs_inner()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_run_totals() {
+        let failed = RedeemerRun::not_run("spend:1", 300, 30, "Script not found".to_string());
+        let mut succeeded = RedeemerRun::not_run("spend:0", 1_000, 100, String::new());
+        succeeded.outcome = "Success".to_string();
+        succeeded.error = None;
+        succeeded.spent_cpu = 400;
+        succeeded.spent_mem = 40;
+        succeeded.within_budget = true;
+
+        let run = TransactionRun::new(vec![succeeded.clone()]);
+        assert!(run.valid);
+        let run = TransactionRun::new(vec![succeeded, failed]);
+        assert!(!run.valid);
+        assert_eq!((run.total_spent_cpu, run.total_spent_mem), (400, 40));
+        assert_eq!((run.total_declared_cpu, run.total_declared_mem), (1_300, 130));
+    }
+}
@@ -0,0 +1,241 @@
+use pallas_primitives::{conway::Language, BigInt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{Constant, Name, NamedDeBruijn, Program, Term},
+    machine::{
+        cost_model::{CostModel, ExBudget},
+        value::Value,
+        Error as MachineFailure, MachineState,
+    },
+    manual_machine::{ExecutionStatus, ManualMachine},
+    PlutusData,
+};
+
+use super::decisions::saturating_builtin;
+use super::session_controller::DEFAULT_SLIPPAGE;
+use crate::budget::SerializableBudget;
+use crate::SerializableTerm;
+
+/// Limits for expressions evaluated outside the session being debugged
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(default)]
+pub struct SandboxLimits {
+    pub cpu: i64,
+    pub mem: i64,
+    pub max_steps: u64,
+    /// Most bytes the constants built by builtins may add up to over the run
+    pub max_memory_bytes: usize,
+    /// Largest serialized result returned, in bytes
+    pub max_result_bytes: usize,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        SandboxLimits {
+            cpu: 10_000_000_000,
+            mem: 14_000_000,
+            max_steps: 1_000_000,
+            max_memory_bytes: 64 * 1024 * 1024,
+            max_result_bytes: 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "outcome_type")]
+pub enum SandboxOutcome {
+    #[serde(rename = "Done")]
+    Done { result: SerializableTerm },
+    /// The expression itself failed (error term, builtin failure...)
+    #[serde(rename = "Error")]
+    Error { message: String },
+    #[serde(rename = "ParseError")]
+    ParseError { message: String },
+    #[serde(rename = "BudgetExceeded")]
+    BudgetExceeded,
+    #[serde(rename = "StepLimitExceeded")]
+    StepLimitExceeded,
+    #[serde(rename = "MemoryLimitExceeded")]
+    MemoryLimitExceeded { bytes: usize },
+    #[serde(rename = "ResultTooLarge")]
+    ResultTooLarge { bytes: usize },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SandboxResult {
+    pub outcome: SandboxOutcome,
+    pub steps: u64,
+    /// Bytes of the constants built by builtins during the run
+    pub allocated_bytes: usize,
+    pub budget: SerializableBudget,
+}
+
+/// Parses a closed UPLC term in textual syntax, e.g. `[(builtin addInteger) (con integer 1) (con integer 2)]`
+pub fn parse_expression(expression: &str, version: (usize, usize, usize)) -> Result<Program<NamedDeBruijn>, String> {
+    let source = format!("(program {}.{}.{} {})", version.0, version.1, version.2, expression);
+    let program = uplc::parser::program(&source).map_err(|e| e.to_string())?;
+    Program::<NamedDeBruijn>::try_from(program).map_err(|e| e.to_string())
+}
+
+/// Textual application of a builtin to the given argument terms, with the
+/// forces its type arguments require
+pub fn builtin_application(builtin: &str, arguments: &[String]) -> Result<String, String> {
+    let builtin_term = format!("(builtin {})", builtin.trim());
+    let forces = match uplc::parser::term(&builtin_term).map_err(|e| e.to_string())? {
+        Term::<Name>::Builtin { fun, .. } => fun.force_count(),
+        _ => return Err(format!("Unknown builtin {}", builtin)),
+    };
+    let head = (0..forces).fold(builtin_term, |term, _| format!("(force {})", term));
+    if arguments.is_empty() {
+        Ok(head)
+    } else {
+        Ok(format!("[{} {}]", head, arguments.join(" ")))
+    }
+}
+
+fn data_bytes(data: &PlutusData) -> usize {
+    match data {
+        PlutusData::Constr(constr) => 8 + constr.fields.iter().map(data_bytes).sum::<usize>(),
+        PlutusData::Map(pairs) => pairs.iter().map(|(key, value)| data_bytes(key) + data_bytes(value)).sum(),
+        PlutusData::Array(items) => items.iter().map(data_bytes).sum(),
+        PlutusData::BigInt(BigInt::Int(_)) => 8,
+        PlutusData::BigInt(BigInt::BigUInt(bytes) | BigInt::BigNInt(bytes)) => bytes.len(),
+        PlutusData::BoundedBytes(bytes) => bytes.len(),
+    }
+}
+
+fn constant_bytes(constant: &Constant) -> usize {
+    match constant {
+        Constant::Integer(integer) => integer.bits().div_ceil(8) as usize,
+        Constant::ByteString(bytes) => bytes.len(),
+        Constant::String(string) => string.len(),
+        Constant::Unit | Constant::Bool(_) => 1,
+        Constant::ProtoList(_, items) => items.iter().map(constant_bytes).sum(),
+        Constant::ProtoPair(_, _, first, second) => constant_bytes(first) + constant_bytes(second),
+        Constant::Data(data) => data_bytes(data),
+        Constant::Bls12_381G1Element(_) => 144,
+        Constant::Bls12_381G2Element(_) => 288,
+        Constant::Bls12_381MlResult(_) => 576,
+    }
+}
+
+/// Bytes of a value a builtin returned. Builtins only build constants; other
+/// values share what the program already holds.
+fn allocated_bytes(value: &Value) -> usize {
+    match value {
+        Value::Con(constant) => constant_bytes(constant),
+        _ => 0,
+    }
+}
+
+/// Runs a program on a fresh machine with its own budget, step cap and memory
+/// cap. Nothing is shared with the caller's machine, so a runaway expression
+/// can't affect it. Memory is counted as the bytes of every constant a builtin
+/// builds, the only way a program's data grows beyond its own text.
+pub fn run_sandboxed(
+    language: &Language,
+    cost_model: &CostModel,
+    program: Program<NamedDeBruijn>,
+    limits: &SandboxLimits,
+) -> Result<SandboxResult, String> {
+    let budget = ExBudget {
+        cpu: limits.cpu,
+        mem: limits.mem,
+    };
+    let mut machine = ManualMachine::new(language.clone(), cost_model.clone(), budget, DEFAULT_SLIPPAGE, program.term)
+        .map_err(|e| format!("Failed to create manual machine: {:?}", e))?;
+
+    let mut steps = 0;
+    let mut allocated = 0;
+    let outcome = loop {
+        if steps >= limits.max_steps {
+            break SandboxOutcome::StepLimitExceeded;
+        }
+        steps += 1;
+        let applies_builtin = saturating_builtin(machine.current_state()).is_some();
+        match machine.step() {
+            ExecutionStatus::Ready => {
+                if let (true, MachineState::Return(_, value)) = (applies_builtin, machine.current_state()) {
+                    allocated += allocated_bytes(value);
+                    if allocated > limits.max_memory_bytes {
+                        break SandboxOutcome::MemoryLimitExceeded { bytes: allocated };
+                    }
+                }
+            }
+            ExecutionStatus::Done(term) => {
+                let result = SerializableTerm::from_uplc_term(term);
+                let bytes = serde_json::to_string(&result).map_err(|e| e.to_string())?.len();
+                break if bytes > limits.max_result_bytes {
+                    SandboxOutcome::ResultTooLarge { bytes }
+                } else {
+                    SandboxOutcome::Done { result }
+                };
+            }
+            ExecutionStatus::Error(MachineFailure::OutOfExError(_)) => break SandboxOutcome::BudgetExceeded,
+            ExecutionStatus::Error(error) => break SandboxOutcome::Error { message: error.to_string() },
+        }
+    };
+
+    Ok(SandboxResult {
+        outcome,
+        steps,
+        allocated_bytes: allocated,
+        budget: SerializableBudget::new(
+            limits.cpu - machine.ex_budget.cpu,
+            limits.cpu,
+            limits.mem - machine.ex_budget.mem,
+            limits.mem,
+            &Default::default(),
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_application() {
+        let arguments = vec!["(con bool True)".to_string(), "(con integer 1)".to_string(), "(con integer 2)".to_string()];
+        assert_eq!(
+            builtin_application("ifThenElse", &arguments).unwrap(),
+            "[(force (builtin ifThenElse)) (con bool True) (con integer 1) (con integer 2)]"
+        );
+        assert_eq!(builtin_application("addInteger", &[]).unwrap(), "(builtin addInteger)");
+        assert!(builtin_application("notABuiltin", &[]).is_err());
+    }
+
+    fn run(expression: &str, limits: &SandboxLimits) -> SandboxResult {
+        let program = parse_expression(expression, (1, 1, 0)).unwrap();
+        run_sandboxed(&Language::PlutusV3, &CostModel::default(), program, limits).unwrap()
+    }
+
+    #[test]
+    fn test_memory_cap_counts_builtin_results() {
+        let expression = "[(builtin appendByteString) (con bytestring #0102) (con bytestring #0304)]";
+        let result = run(expression, &SandboxLimits::default());
+        assert!(matches!(result.outcome, SandboxOutcome::Done { .. }));
+        assert_eq!(result.allocated_bytes, 4);
+
+        let limits = SandboxLimits {
+            max_memory_bytes: 3,
+            ..SandboxLimits::default()
+        };
+        let result = run(expression, &limits);
+        assert!(matches!(result.outcome, SandboxOutcome::MemoryLimitExceeded { bytes: 4 }));
+    }
+
+    #[test]
+    fn test_constant_bytes() {
+        assert_eq!(constant_bytes(&Constant::Integer(256.into())), 2);
+        assert_eq!(constant_bytes(&Constant::Data(PlutusData::BoundedBytes(vec![0; 28].into()))), 28);
+        let pair = Constant::ProtoPair(
+            uplc::ast::Type::Integer,
+            uplc::ast::Type::String,
+            Constant::Integer(1.into()).into(),
+            Constant::String("abc".to_string()).into(),
+        );
+        assert_eq!(constant_bytes(&pair), 4);
+    }
+}
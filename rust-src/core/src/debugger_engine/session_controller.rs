@@ -0,0 +1,2743 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::cost_model_upgrade::{cost_model_report, CostModelReport};
+use crate::budget::{BudgetBreakdown, BudgetPricing, BudgetProjection, BuiltinSpend, SerializableBudget};
+use crate::debugger_engine::{
+    aiken_traces::{build_aiken_trace_report, AikenSourceMap, AikenTraceReport},
+    annotations::{TermAnnotation, TermAnnotations},
+    bookmarks::{Bookmark, Bookmarks},
+    budget_exhaustion::{BudgetExhaustionReport, RecentSteps, StepSample},
+    cancellation::{RunCancelToken, CLOCK_CHECK_INTERVAL},
+    builtin_checks::{builtin_by_name, check_arithmetic, check_machine_state, scan_builtin_misuse, BuiltinMisuse, BuiltinWarnings},
+    conditions::{Condition, ConditionContext},
+    decisions::{case_branches, observe_decision, saturating_builtin, DecisionTrace},
+    call_stack::{call_stack, CallStackFrame},
+    chunked_export::{ExportHandle, ExportStore, DEFAULT_CHUNK_SIZE},
+    coverage::{coverage_report, CoverageMap, CoverageReport, CoverageTracker},
+    env_pins::{EnvPin, EnvPinComparison},
+    env_watch::{binding_at, slot_of_index, EnvBinding, EnvWatch, EnvWatchTarget},
+    instrumentation::{inject_trace, TraceInjection},
+    execution_trace::{ExecutionTrace, TraceRecorder, TraceStateKind},
+    fixture::{cbor_wrap, decode_arguments, decode_program_flat, encode_arguments, encode_program_flat, program_flat, FixtureBudget},
+    force_balance::analyze_force_balance,
+    sandbox::{builtin_application, parse_expression, run_sandboxed, SandboxLimits, SandboxOutcome, SandboxResult},
+    session_persistence::{SavedSession, SavedTraceInjection},
+    settings::{EngineSettings, ScriptHashFormat},
+    state_summary::{summarize_state, StateSketch, StateSummary},
+    term_ids::{program_hash, remap_by_source, term_id_info, TermIdInfo, TermIdRemapping},
+    term_costs::{TermCostMap, TermCosts},
+    term_dot::term_dot,
+    uplc_text::program_text,
+    step_history::{Checkpoint, CheckpointInfo, StepBackResult, StepHistory, StepRecords, StepSnapshot},
+    lazy_session_api::LazySessionApi,
+    log_search::{search_traces, LogSearchResult},
+    optimizations::{analyze_optimizations, OptimizationReport},
+    overshoot::{BudgetCrossing, OvershootAnalysis},
+    prelude::{prelude_end, PreludeReport},
+    profile::{profile_stack, FrameLabels, ProfileRecorder, ProfileWeight},
+    progress::{declared_percent, ProgressHook, RunProgress},
+    quota::SharedQuota,
+    reprice::{cost_model_from, reprice_trace, saturating_call, BuiltinCall, BuiltinCallRecorder, RepriceReport},
+    value_pins::{resolve_pin_target, ValuePinReport, ValuePinTarget, ValuePins},
+    value_search::{find_values, ValuePredicate, ValueSearchResult},
+    DebuggerError,
+};
+use crate::context::{context_page_bounds, embed_frame_paths, serializable_frames, SerializableFrame, SerializableMachineContextPage};
+use crate::env_sharing::{frame_env, EnvIds, SerializableEnvIdentity, SerializableSharedContext};
+use crate::hash_format::{normalize_script_hash, script_hash_to_bech32};
+use crate::lazy_loading::{
+    embed_truncated_paths, use_serializer_options, PathRoot, SerializerOptions, SerializerOptionsGuard,
+};
+use crate::plutus_data::SerializablePlutusData;
+use crate::protocol_params::CostModels;
+use crate::script_context::CipScriptContext;
+use crate::serialization_format::{serialize, SerializationFormat};
+use crate::wasm_tools::{envelope_json, now_millis, JsError};
+use crate::{SerializableEnv, SerializableExecutionStatus, SerializableMachineContext, SerializableMachineState, SerializableScriptContext, SerializableTerm};
+use pallas_primitives::conway::Language;
+use uplc::{
+    ast::{FakeNamedDeBruijn, NamedDeBruijn, Program, Term},
+    machine::{
+        cost_model::{CostModel, ExBudget},
+        value::Value,
+        MachineState,
+    },
+    manual_machine::{ExecutionStatus, ManualMachine},
+    tx::script_context::ScriptContext,
+    PlutusData,
+};
+
+use crate::wasm_tools::wasm_bindgen;
+
+pub(crate) const DEFAULT_SLIPPAGE: u32 = 1;
+
+/// Most step summaries `step_n` reserves room for up front; larger batches grow
+/// as they run, since most stop early when the machine finishes
+const STEP_BATCH_RESERVE: u32 = 1024;
+
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct SessionController {
+    redeemer: String,
+    session_id: String,
+    machine: Box<ManualMachine>,
+    language: Language,
+    real_budget: ExBudget,
+    image_budget: ExBudget,
+    /// Machine steps whose cost is charged together
+    slippage: u32,
+    script_hash: String,
+    last_error: Option<String>,
+    program_version: (usize, usize, usize),
+    entry_term: Box<Term<NamedDeBruijn>>,
+    base_program: Box<Program<NamedDeBruijn>>,
+    arguments: Arc<Vec<PlutusData>>,
+    /// Shared with the engine and every other session of the same redeemer
+    context: Arc<ScriptContext>,
+    cost_model: CostModel,
+    /// Protocol parameter array `cost_model` was built from
+    cost_model_values: Vec<i64>,
+    term_ids: HashSet<i32>,
+    version: u64,
+    source_map: Option<AikenSourceMap>,
+    quota: SharedQuota,
+    pricing: BudgetPricing,
+    generation: u64,
+    /// Ids of the envs reported by `get_env_identity` and the shared context
+    env_ids: EnvIds,
+    previous_env_id: Option<String>,
+    breakpoints: BTreeSet<i32>,
+    /// Term `run_until_term` is running to, only set during that call
+    run_target: Option<i32>,
+    builtin_breakpoints: BTreeSet<String>,
+    breakpoint_conditions: BTreeMap<i32, Condition>,
+    conditions: BTreeMap<u32, Condition>,
+    next_condition_id: u32,
+    env_watches: BTreeMap<u32, EnvWatchTarget>,
+    next_env_watch_id: u32,
+    /// Binding made by the last step, while env watches are set
+    last_binding: Option<EnvBinding>,
+    step_count: u64,
+    step_limit: Option<u64>,
+    cancel_token: RunCancelToken,
+    progress: ProgressHook,
+    budget_exceeded_reported: bool,
+    last_stop_reason: Option<super::StopReason>,
+    bookmarks: Bookmarks,
+    builtin_warnings: Vec<BuiltinMisuse>,
+    arithmetic_guard: bool,
+    case_branches: HashMap<i32, (i32, usize)>,
+    decision_trace: DecisionTrace,
+    env_pins: BTreeMap<String, EnvPin>,
+    value_pins: ValuePins,
+    sandbox_limits: SandboxLimits,
+    script_hash_format: ScriptHashFormat,
+    serializer_options: SerializerOptions,
+    json_envelope: bool,
+    /// JSON form of the getters' responses, `Json` or `CompactJson`
+    json_format: SerializationFormat,
+    annotations: TermAnnotations,
+    created_at: u64,
+    last_activity: Cell<u64>,
+    recent_steps: RecentSteps,
+    builtin_spend: BuiltinSpend,
+    /// CPU and memory spent by the last step
+    last_step_cost: (i64, i64),
+    term_costs: TermCosts,
+    budget_exhaustion: Option<BudgetExhaustionReport>,
+    budget_crossing: Option<BudgetCrossing>,
+    step_history: StepHistory,
+    checkpoints: BTreeMap<String, Checkpoint>,
+    /// Source maps set for each program of the session, by program hash
+    program_source_maps: BTreeMap<String, AikenSourceMap>,
+    execution_trace: TraceRecorder,
+    builtin_calls: BuiltinCallRecorder,
+    profile: ProfileRecorder,
+    trace_injections: Vec<TraceInjection>,
+    coverage: CoverageTracker,
+    exports: ExportStore,
+    /// Export of `get_script`'s JSON that `get_script_chunk` reads from
+    script_export: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl SessionController {
+    pub(crate) fn new(
+        script_hash: String,
+        session_id: String,
+        language: Language,
+        base_program: Box<Program<NamedDeBruijn>>,
+        arguments: Arc<Vec<PlutusData>>,
+        script_context: Arc<ScriptContext>,
+        cost_model: CostModel,
+        cost_model_values: Vec<i64>,
+        upper_bound_budget: ExBudget,
+        slippage: u32,
+        real_budget: ExBudget,
+        redeemer: String,
+        quota: SharedQuota,
+        pricing: BudgetPricing,
+    ) -> Result<Self, JsError> {
+        let program = apply_arguments(&base_program, &arguments);
+        let program_version = program.version;
+        let entry_term = Box::new(program.term);
+        let machine = Box::new(ManualMachine::new(
+            language.clone(),
+            cost_model.clone(),
+            upper_bound_budget.clone(),
+            slippage,
+            (*entry_term).clone(),
+        )
+        .map_err(|e| {
+            DebuggerError::MachineError(format!("Failed to create manual machine: {:?}", e))
+        })?);
+
+        let mut term_ids = HashSet::new();
+        collect_term_ids(&entry_term, &mut term_ids);
+        let case_branches = case_branches(&entry_term);
+
+        Ok(SessionController {
+            script_hash,
+            session_id,
+            machine,
+            language,
+            real_budget,
+            image_budget: upper_bound_budget,
+            slippage,
+            last_error: None,
+            program_version,
+            redeemer,
+            entry_term,
+            base_program,
+            arguments,
+            context: script_context,
+            cost_model,
+            cost_model_values,
+            term_ids,
+            version: 0,
+            source_map: None,
+            quota,
+            pricing,
+            generation: 0,
+            env_ids: EnvIds::default(),
+            previous_env_id: None,
+            breakpoints: BTreeSet::new(),
+            run_target: None,
+            builtin_breakpoints: BTreeSet::new(),
+            breakpoint_conditions: BTreeMap::new(),
+            conditions: BTreeMap::new(),
+            next_condition_id: 0,
+            env_watches: BTreeMap::new(),
+            next_env_watch_id: 0,
+            last_binding: None,
+            step_count: 0,
+            step_limit: None,
+            cancel_token: RunCancelToken::default(),
+            progress: ProgressHook::default(),
+            budget_exceeded_reported: false,
+            last_stop_reason: None,
+            bookmarks: Bookmarks::default(),
+            builtin_warnings: vec![],
+            arithmetic_guard: false,
+            case_branches,
+            decision_trace: DecisionTrace::default(),
+            env_pins: BTreeMap::new(),
+            value_pins: ValuePins::default(),
+            sandbox_limits: SandboxLimits::default(),
+            script_hash_format: ScriptHashFormat::default(),
+            serializer_options: SerializerOptions::default(),
+            json_envelope: true,
+            json_format: SerializationFormat::Json,
+            annotations: TermAnnotations::default(),
+            created_at: now_millis(),
+            last_activity: Cell::new(now_millis()),
+            recent_steps: RecentSteps::default(),
+            builtin_spend: BuiltinSpend::default(),
+            last_step_cost: (0, 0),
+            term_costs: TermCosts::default(),
+            budget_exhaustion: None,
+            budget_crossing: None,
+            step_history: StepHistory::default(),
+            checkpoints: BTreeMap::new(),
+            program_source_maps: BTreeMap::new(),
+            execution_trace: TraceRecorder::default(),
+            profile: ProfileRecorder::default(),
+            builtin_calls: BuiltinCallRecorder::default(),
+            trace_injections: vec![],
+            coverage: CoverageTracker::default(),
+            exports: ExportStore::default(),
+            script_export: None,
+        })
+    }
+
+    /// Applies the engine-wide settings to a newly created session
+    pub(crate) fn apply_settings(&mut self, settings: &EngineSettings) -> Result<(), JsError> {
+        self.serializer_options.bytestring_preview_threshold = settings.bytestring_preview_threshold;
+        self.serializer_options.bytestring_rendering = settings.bytestring_rendering;
+        self.serializer_options.constant_truncation_threshold = settings.constant_truncation_threshold;
+        self.script_hash_format = settings.script_hash_format;
+        self.json_envelope = settings.json_envelope;
+        if settings.compact_json {
+            self.json_format = SerializationFormat::CompactJson;
+        }
+        self.step_limit = settings.step_limit;
+        self.sandbox_limits = settings.sandbox.clone();
+        self.arithmetic_guard = settings.arithmetic_guard;
+        self.step_history.set_capacity(settings.step_history);
+        self.term_costs.set_capacity(settings.step_history);
+        self.execution_trace.set_enabled(settings.record_execution_trace, self.step_count);
+        for condition in &settings.condition_breakpoints {
+            self.add_condition_breakpoint(condition.clone())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Puts this session's serializer options in effect until the guard is dropped.
+    /// Constants stay whole: only getters whose constants can be fetched again
+    /// through a lazy getter truncate them (see `truncating_serializer_scope`).
+    fn serializer_scope(&self) -> SerializerOptionsGuard {
+        use_serializer_options(SerializerOptions {
+            constant_truncation_threshold: None,
+            ..self.serializer_options
+        })
+    }
+
+    /// Like `serializer_scope`, also truncating large constants; the getter then
+    /// gives each truncated constant its lazy path with `embed_truncated_paths`
+    fn truncating_serializer_scope(&self) -> SerializerOptionsGuard {
+        use_serializer_options(self.serializer_options)
+    }
+
+    /// Serializes a response of a truncating getter, after `embed` has given the
+    /// truncated constants in it their lazy paths
+    fn to_json_with_truncated_paths<T: serde::Serialize>(
+        &self,
+        value: &T,
+        embed: impl FnOnce(&mut serde_json::Value),
+    ) -> Result<String, JsError> {
+        if self.serializer_options.constant_truncation_threshold.is_none() {
+            return self.to_json(value);
+        }
+        let mut value = serde_json::to_value(value).map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+        embed(&mut value);
+        self.to_json(&value)
+    }
+
+    /// Serializes a response and accounts its size against the engine quota
+    fn to_json<T: serde::Serialize>(&self, value: &T) -> Result<String, JsError> {
+        let json = self.encode_json(value)?;
+        self.quota.borrow_mut().record_serialized_bytes(json.len());
+        self.touch();
+        Ok(json)
+    }
+
+    /// Serializes a response in the session's JSON format, in the schema version
+    /// envelope when the settings ask for it
+    fn encode_json<T: serde::Serialize>(&self, value: &T) -> Result<String, JsError> {
+        self.encode_json_as(value, self.json_format)
+    }
+
+    fn encode_json_as<T: serde::Serialize>(&self, value: &T, format: SerializationFormat) -> Result<String, JsError> {
+        let json = serialize(value, format).map_err(DebuggerError::MachineError)?;
+        let json = String::from_utf8(json).map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+        Ok(self.enveloped(json))
+    }
+
+    fn enveloped(&self, json: String) -> String {
+        if self.json_envelope {
+            envelope_json(&json)
+        } else {
+            json
+        }
+    }
+
+    fn to_bytes<T: serde::Serialize>(&self, value: &T, format: SerializationFormat) -> Result<Vec<u8>, JsError> {
+        let bytes = match format {
+            SerializationFormat::Json | SerializationFormat::CompactJson => self.encode_json_as(value, format)?.into_bytes(),
+            SerializationFormat::Cbor => serialize(value, format).map_err(DebuggerError::MachineError)?,
+        };
+        self.quota.borrow_mut().record_serialized_bytes(bytes.len());
+        self.touch();
+        Ok(bytes)
+    }
+
+    fn touch(&self) {
+        self.last_activity.set(now_millis());
+    }
+
+    /// Gets the session id, redeemer, script, creation time, step count and last
+    /// activity, so hosts can list sessions and drop idle ones
+    pub fn get_session_info(&self) -> Result<String, JsError> {
+        let info = self.get_session_info_inner()?;
+        // Not routed through to_json: polling the info must not count as activity
+        self.encode_json(&info)
+    }
+
+    pub(crate) fn get_session_info_inner(&self) -> Result<super::SessionInfo, JsError> {
+        Ok(super::SessionInfo {
+            session_id: self.session_id.clone(),
+            redeemer: self.redeemer.clone(),
+            script_hash: self.get_script_hash()?,
+            language: self.get_plutus_language_version()?.unwrap_or_default(),
+            created_at: self.created_at,
+            last_activity: self.last_activity.get(),
+            step_count: self.step_count,
+            version: self.version,
+        })
+    }
+
+    /// Gets the quota usage shared by all sessions of the engine
+    pub fn get_quota_usage(&self) -> Result<String, JsError> {
+        let usage = self.quota.borrow().usage().clone();
+        self.encode_json(&usage)
+    }
+
+    pub fn get_tx_script_context(&self) -> Result<String, JsError> {
+        let context: &ScriptContext = &self.context;
+        let serializable_context: SerializableScriptContext = context
+            .try_into()
+            .map_err(|e| DebuggerError::MachineError(format!("Failed to convert script context to serializable: {:?}", e)))?;
+        self.to_json(&serializable_context)
+    }
+
+    pub(crate) fn get_tx_script_context_inner(&self) -> Result<SerializableScriptContext, JsError> {
+        let context: &ScriptContext = &self.context;
+        let serializable_context: SerializableScriptContext = context
+            .try_into()
+            .map_err(|e| DebuggerError::MachineError(format!("Failed to convert script context to serializable: {:?}", e)))?;
+        Ok(serializable_context)
+    }
+
+    /// Gets the script context with the field names of the ledger specification and
+    /// CIPs (`txInfo`, `scriptInfo`, `txCerts`, ...) instead of the internal ones
+    pub fn get_tx_script_context_cip(&self) -> Result<String, JsError> {
+        let context: CipScriptContext = self.get_tx_script_context_inner()?.into();
+        self.to_json(&context)
+    }
+
+    /// Gets the Plutus Core version
+    pub fn get_plutus_core_version(&self) -> Result<String, JsError> {
+        Ok(format!(
+            "{}.{}.{}",
+            self.program_version.0, self.program_version.1, self.program_version.2
+        ))
+    }
+
+    /// Gets the Plutus language version
+    pub fn get_plutus_language_version(&self) -> Result<Option<String>, JsError> {
+        let version = match self.language {
+            Language::PlutusV1 => "V1",
+            Language::PlutusV2 => "V2",
+            Language::PlutusV3 => "V3",
+        };
+        Ok(Some(version.to_string()))
+    }
+
+    /// Gets the cost model the session runs with: the protocol parameter array by
+    /// name and the machine and builtin costs resolved from it
+    pub fn get_cost_model_json(&self) -> Result<String, JsError> {
+        let report = self.get_cost_model_inner();
+        self.to_json(&report)
+    }
+
+    pub(crate) fn get_cost_model_inner(&self) -> CostModelReport {
+        cost_model_report(&self.language, &self.cost_model_values, &self.cost_model)
+    }
+
+    /// Gets the script hash, as hex or `script1…` depending on the engine settings
+    pub fn get_script_hash(&self) -> Result<String, JsError> {
+        match self.script_hash_format {
+            ScriptHashFormat::Hex => Ok(self.script_hash.clone()),
+            ScriptHashFormat::Bech32 => self.get_script_hash_bech32(),
+        }
+    }
+
+    /// Gets the script hash as hex, whatever the engine settings
+    pub(crate) fn script_hash_hex(&self) -> &str {
+        &self.script_hash
+    }
+
+    /// Declared ex units of the redeemer
+    pub(crate) fn declared_budget(&self) -> ExBudget {
+        self.real_budget
+    }
+
+    /// Gets the script hash in its CIP-5 `script1…` form
+    pub fn get_script_hash_bech32(&self) -> Result<String, JsError> {
+        Ok(script_hash_to_bech32(&self.script_hash).map_err(DebuggerError::MachineError)?)
+    }
+
+    pub fn get_machine_context(&self) -> Result<String, JsError> {
+        let _options = self.truncating_serializer_scope();
+        let contexts = self.get_machine_context_inner()?;
+        self.to_json_with_truncated_paths(&contexts, embed_frame_paths)
+    }
+
+    pub(crate) fn get_machine_context_inner(&self) -> Result<Vec<SerializableFrame<SerializableMachineContext>>, JsError> {
+        let contexts = self.machine.collect_nested_contexts();
+        Ok(serializable_frames(&contexts, 0..contexts.len(), &self.case_branches, |ctx| {
+            SerializableMachineContext::from_uplc_context_with_ids(ctx, &self.term_ids)
+        }))
+    }
+
+    /// Gets the machine context as a stack trace, innermost frame first: the frame
+    /// type, the term it came from, the parameter of the Lambda being called and a
+    /// short description, with source locations when a source map is set
+    pub fn get_call_stack(&self) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let frames = self.get_call_stack_inner();
+        self.to_json(&frames)
+    }
+
+    pub(crate) fn get_call_stack_inner(&self) -> Vec<CallStackFrame> {
+        let contexts = self.machine.collect_nested_contexts();
+        call_stack(&contexts, &self.case_branches, self.source_map.as_ref())
+    }
+
+    /// Gets a window of the machine context, so deep recursion doesn't have to be
+    /// serialized whole. The page reports the total depth of the stack.
+    ///
+    /// # Arguments
+    /// * `offset` - Number of frames to skip from the chosen end
+    /// * `limit` - Maximum number of frames to return
+    /// * `from_bottom` - Count from the outermost frame instead of the innermost one
+    pub fn get_machine_context_page(&self, offset: usize, limit: usize, from_bottom: bool) -> Result<String, JsError> {
+        let _options = self.truncating_serializer_scope();
+        let page = self.get_machine_context_page_inner(offset, limit, from_bottom)?;
+        self.to_json_with_truncated_paths(&page, |page| {
+            if let Some(frames) = page.get_mut("frames") {
+                embed_frame_paths(frames);
+            }
+        })
+    }
+
+    pub(crate) fn get_machine_context_page_inner(
+        &self,
+        offset: usize,
+        limit: usize,
+        from_bottom: bool,
+    ) -> Result<SerializableMachineContextPage, JsError> {
+        let contexts = self.machine.collect_nested_contexts();
+        let bounds = context_page_bounds(contexts.len(), offset, limit, from_bottom);
+        Ok(SerializableMachineContextPage {
+            total_depth: contexts.len(),
+            start_index: bounds.start,
+            truncated: bounds.start > 0 || bounds.end < contexts.len(),
+            frames: serializable_frames(&contexts, bounds, &self.case_branches, |ctx| {
+                SerializableMachineContext::from_uplc_context_with_ids(ctx, &self.term_ids)
+            }),
+        })
+    }
+
+    /// Gets the machine context with every distinct env serialized once and
+    /// referenced by id from frames and closures
+    pub fn get_machine_context_shared(&self) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let context = self.get_machine_context_shared_inner()?;
+        self.to_json(&context)
+    }
+
+    pub(crate) fn get_machine_context_shared_inner(&self) -> Result<SerializableSharedContext, JsError> {
+        let contexts = self.machine.collect_nested_contexts();
+        Ok(SerializableSharedContext::from_uplc_contexts(&contexts, &self.term_ids, &self.env_ids))
+    }
+
+    /// Gets the id of the env the current term executes under
+    pub fn get_current_env_id(&self) -> Option<String> {
+        match self.machine.current_state() {
+            MachineState::Compute(_, env, _) => Some(self.env_ids.id(env)),
+            _ => None,
+        }
+    }
+
+    /// Gets the ids of the current env and of every env captured by the context chain
+    pub fn get_env_identity(&self) -> Result<String, JsError> {
+        let identity = self.get_env_identity_inner()?;
+        self.to_json(&identity)
+    }
+
+    pub(crate) fn get_env_identity_inner(&self) -> Result<SerializableEnvIdentity, JsError> {
+        let current_env_id = self.get_current_env_id();
+        let frame_env_ids = self
+            .machine
+            .collect_nested_contexts()
+            .iter()
+            .map(|context| frame_env(context).map(|env| self.env_ids.id(env)))
+            .collect();
+        Ok(SerializableEnvIdentity {
+            changed_since_last_step: current_env_id != self.previous_env_id,
+            current_env_id,
+            frame_env_ids,
+            generation: self.generation,
+        })
+    }
+
+    pub fn get_logs(&self) -> Result<String, JsError> {
+        let traces = self.get_logs_inner()?;
+        self.to_json(&traces)
+    }
+
+    pub(crate) fn get_logs_inner(&self) -> Result<Vec<String>, JsError> {
+        let traces = self.machine
+            .traces
+            .iter()
+            .map(|trace| trace.to_string())
+            .collect::<Vec<String>>();
+        Ok(traces)
+    }
+
+    /// Searches the collected traces with a regular expression, starting at
+    /// `from_index`, and returns matching indices with the surrounding traces
+    ///
+    /// # Arguments
+    /// * `pattern` - Regular expression matched against each trace
+    /// * `from_index` - First trace index to search; use `next_index` of the previous result to page
+    pub fn search_logs(&self, pattern: &str, from_index: usize) -> Result<String, JsError> {
+        let result = self.search_logs_inner(pattern, from_index)?;
+        self.to_json(&result)
+    }
+
+    pub(crate) fn search_logs_inner(&self, pattern: &str, from_index: usize) -> Result<LogSearchResult, JsError> {
+        let traces = &self.machine.traces;
+        Ok(search_traces(traces.len(), |index| traces[index].to_string(), pattern, from_index)
+            .map_err(|e| DebuggerError::MachineError(format!("Invalid pattern: {}", e)))?)
+    }
+
+    /// Number of traces emitted so far and the latest one
+    pub(crate) fn get_trace_tail(&self) -> (usize, Option<String>) {
+        (
+            self.machine.traces.len(),
+            self.machine.traces.last().map(|trace| trace.to_string()),
+        )
+    }
+
+    /// Sets the Aiken source map used to resolve `?` and `expect` traces and,
+    /// when it maps term ids, the source of budget hot spots
+    pub fn set_source_map(&mut self, source_map_json: &str) -> Result<(), JsError> {
+        let source_map = serde_json::from_str::<AikenSourceMap>(source_map_json)
+            .map_err(|e| DebuggerError::MachineError(format!("Invalid source map: {}", e)))?;
+        // Kept by program so term ids can be remapped after the program is replaced
+        let program_hash = program_hash(&self.base_program)?;
+        self.program_source_maps.insert(program_hash, source_map.clone());
+        self.source_map = Some(source_map);
+        Ok(())
+    }
+
+    /// Gets how the program's terms are numbered: program and layout hashes to
+    /// check that two sessions number a script the same way, the id range, and
+    /// the programs `remap_term_ids` can remap from
+    pub fn get_term_id_info(&self) -> Result<String, JsError> {
+        let info = self.get_term_id_info_inner()?;
+        self.to_json(&info)
+    }
+
+    pub(crate) fn get_term_id_info_inner(&self) -> Result<TermIdInfo, JsError> {
+        let known_programs = self.program_source_maps.keys().cloned().collect();
+        Ok(term_id_info(&self.base_program, &self.entry_term, known_programs)?)
+    }
+
+    /// Moves breakpoints, bookmarks, annotations and binder watches from the term
+    /// ids of an earlier program of this session to those of the current one,
+    /// relating terms through the source maps set for both, e.g. after
+    /// `reset_with_program` with a minor recompile. Whatever refers to a term the
+    /// source maps don't relate is dropped and its id listed as `unmapped`.
+    ///
+    /// # Arguments
+    /// * `old_program_hash` - `program_hash` of the earlier program, as in `get_term_id_info`
+    pub fn remap_term_ids(&mut self, old_program_hash: &str) -> Result<String, JsError> {
+        let remapping = self.remap_term_ids_inner(old_program_hash)?;
+        self.to_json(&remapping)
+    }
+
+    pub(crate) fn remap_term_ids_inner(&mut self, old_program_hash: &str) -> Result<TermIdRemapping, JsError> {
+        let old_map = self.program_source_maps.get(old_program_hash).ok_or_else(|| {
+            DebuggerError::MachineError(format!("No source map was set for program {}", old_program_hash))
+        })?;
+        let new_program_hash = program_hash(&self.base_program)?;
+        let new_map = self.program_source_maps.get(&new_program_hash).ok_or_else(|| {
+            DebuggerError::MachineError("Set the source map of the current program first".to_string())
+        })?;
+        let mapping = remap_by_source(old_map, new_map);
+        let new_id = |term_id: i32| mapping.get(&term_id).copied();
+
+        let referenced: BTreeSet<i32> = self
+            .breakpoints
+            .iter()
+            .copied()
+            .chain(self.bookmarks.term_ids())
+            .chain(self.annotations.term_ids())
+            .chain(self.env_watches.values().filter_map(|target| match target {
+                EnvWatchTarget::Binder { term_id } => Some(*term_id),
+                EnvWatchTarget::Slot { .. } => None,
+            }))
+            .collect();
+        let (remapped, unmapped): (Vec<i32>, Vec<i32>) = referenced.into_iter().partition(|term_id| mapping.contains_key(term_id));
+
+        // Ids the source maps don't relate would point at unrelated terms of the new
+        // program, so whatever refers to them is dropped
+        self.breakpoints = self.breakpoints.iter().filter_map(|term_id| new_id(*term_id)).collect();
+        self.breakpoint_conditions = std::mem::take(&mut self.breakpoint_conditions)
+            .into_iter()
+            .filter_map(|(term_id, condition)| Some((new_id(term_id)?, condition)))
+            .collect();
+        self.bookmarks.remap_terms(new_id);
+        self.annotations.remap_terms(new_id);
+        self.env_watches.retain(|_, target| match target {
+            EnvWatchTarget::Binder { term_id } => match new_id(*term_id) {
+                Some(new_term_id) => {
+                    *term_id = new_term_id;
+                    true
+                }
+                None => false,
+            },
+            EnvWatchTarget::Slot { .. } => true,
+        });
+
+        Ok(TermIdRemapping {
+            from_program_hash: old_program_hash.to_string(),
+            to_program_hash: new_program_hash,
+            remapped: remapped.into_iter().map(|term_id| (term_id, mapping[&term_id])).collect(),
+            unmapped,
+        })
+    }
+
+    /// Gets the traces classified and grouped according to Aiken conventions
+    pub fn get_logs_aiken(&self) -> Result<String, JsError> {
+        let report = self.get_logs_aiken_inner()?;
+        self.to_json(&report)
+    }
+
+    pub(crate) fn get_logs_aiken_inner(&self) -> Result<AikenTraceReport, JsError> {
+        let traces = self.get_logs_inner()?;
+        Ok(build_aiken_trace_report(&traces, self.source_map.as_ref()))
+    }
+
+    pub fn get_machine_state(&self) -> Result<String, JsError> {
+        let _options = self.truncating_serializer_scope();
+        let state = self.get_machine_state_inner()?;
+        self.to_json_with_truncated_paths(&state, |state| {
+            // The frames of the chain are reached through the context getter
+            let chain = state.as_object_mut().and_then(|state| state.remove("context_chain"));
+            embed_truncated_paths(state, PathRoot::MachineState, &[]);
+            if let Some(mut chain) = chain {
+                embed_frame_paths(&mut chain);
+                state["context_chain"] = chain;
+            }
+        })
+    }
+
+    /// Gets the machine state as `get_machine_state` does, encoded as CBOR
+    pub fn get_machine_state_cbor(&self) -> Result<Vec<u8>, JsError> {
+        let _options = self.serializer_scope();
+        let state = self.get_machine_state_inner()?;
+        self.to_bytes(&state, SerializationFormat::Cbor)
+    }
+
+    pub(crate) fn get_machine_state_inner(&self) -> Result<SerializableMachineState, JsError> {
+        let state = self.machine.current_state();
+        let contexts = match state {
+            MachineState::Return(..) => self.machine.collect_nested_contexts(),
+            _ => vec![],
+        };
+        let serializable_state = SerializableMachineState::from_uplc_machine_state_structured(
+            state,
+            &contexts,
+            self.get_budget_inner()?,
+            &self.term_ids,
+            &self.case_branches,
+        );
+        Ok(serializable_state)
+    }
+
+    pub fn get_budget(&self) -> Result<String, JsError> {
+        let budget = self.get_budget_inner()?;
+        self.to_json(&budget)
+    }
+    
+    pub(crate) fn get_budget_inner(&self) -> Result<SerializableBudget, JsError> {
+        let spent_budget = self.machine.ex_budget;
+        let real_budget = self.real_budget;
+        let image_budget = self.image_budget.clone();
+        let cpu_diff = image_budget.cpu - spent_budget.cpu;
+        let mem_diff = image_budget.mem - spent_budget.mem;
+        let budget = SerializableBudget::new(cpu_diff, real_budget.cpu, mem_diff, real_budget.mem, &self.pricing);
+        Ok(budget)
+    }
+
+    /// Measures the budget spent so far against the redeemer's declared ex units and
+    /// the protocol's per-transaction and per-block limits, listing those exceeded
+    pub fn get_budget_projection(&self) -> Result<String, JsError> {
+        let projection = self.get_budget_projection_inner();
+        self.to_json(&projection)
+    }
+
+    pub(crate) fn get_budget_projection_inner(&self) -> BudgetProjection {
+        let spent = self.spent_budget();
+        self.pricing.project(spent.cpu, spent.mem, self.real_budget.cpu, self.real_budget.mem)
+    }
+
+    /// Gets the budget spent since the last reset per builtin function, with
+    /// what machine steps spent outside builtins
+    pub fn get_budget_breakdown(&self) -> Result<String, JsError> {
+        let breakdown = self.get_budget_breakdown_inner()?;
+        self.to_json(&breakdown)
+    }
+
+    pub(crate) fn get_budget_breakdown_inner(&self) -> Result<BudgetBreakdown, DebuggerError> {
+        self.require_exact_step_costs("The budget breakdown")?;
+        let spent = self.spent_budget();
+        Ok(self.builtin_spend.breakdown(spent.cpu, spent.mem))
+    }
+
+    /// Gets the budget spent since the last reset per term id, with how many steps
+    /// each term took, to be laid over the tree of `get_script`
+    pub fn get_cost_by_term(&self) -> Result<String, JsError> {
+        let costs = self.get_cost_by_term_inner()?;
+        self.to_json(&costs)
+    }
+
+    pub(crate) fn get_cost_by_term_inner(&self) -> Result<TermCostMap, DebuggerError> {
+        self.require_exact_step_costs("Cost by term")?;
+        Ok(self.term_costs.map())
+    }
+
+    /// Whether every step is charged its own cost. With slippage above 1 the
+    /// machine charges several steps at once, so per-step costs are not known.
+    fn exact_step_costs(&self) -> bool {
+        self.slippage == 1
+    }
+
+    /// Fails for features built on per-step costs when the session batches them
+    fn require_exact_step_costs(&self, feature: &str) -> Result<(), DebuggerError> {
+        if self.exact_step_costs() {
+            Ok(())
+        } else {
+            Err(DebuggerError::MachineError(format!(
+                "{} needs per-step costs, which a session with slippage {} does not have; open it with slippage 1",
+                feature, self.slippage
+            )))
+        }
+    }
+
+    /// Budget spent since the session started
+    fn spent_budget(&self) -> ExBudget {
+        ExBudget {
+            cpu: self.image_budget.cpu - self.machine.ex_budget.cpu,
+            mem: self.image_budget.mem - self.machine.ex_budget.mem,
+        }
+    }
+
+    pub fn get_script(&self) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let script = self.get_script_inner()?;
+        self.to_json(&script)
+    }
+
+    /// Exports the program being evaluated, arguments applied, in flat encoding,
+    /// to run the exact same program in other evaluators. Traces added with
+    /// `inject_trace_at` are debugging aids and are left out.
+    ///
+    /// # Arguments
+    /// * `cbor_wrapped` - Whether the flat bytes are wrapped in a CBOR bytestring, as
+    ///   scripts are in transactions; false by default
+    pub fn export_program_flat(&self, cbor_wrapped: Option<bool>) -> Result<Vec<u8>, JsError> {
+        let program = apply_arguments(&self.base_program, &self.arguments);
+        let flat = program_flat(&program)?;
+        let bytes = if cbor_wrapped.unwrap_or(false) { cbor_wrap(&flat)? } else { flat };
+        self.quota.borrow_mut().record_serialized_bytes(bytes.len());
+        self.touch();
+        Ok(bytes)
+    }
+
+    /// Renders the program in the UPLC textual syntax, one term per line, to be
+    /// diffed against other tools' output
+    ///
+    /// # Arguments
+    /// * `term_ids` - Whether every term is followed by its id as a `-- #id` comment; true by default
+    pub fn get_script_text(&self, term_ids: Option<bool>) -> Result<String, JsError> {
+        let text = program_text(&self.entry_term, self.program_version, term_ids.unwrap_or(true));
+        self.quota.borrow_mut().record_serialized_bytes(text.len());
+        self.touch();
+        Ok(text)
+    }
+
+    /// Renders the program's term graph in Graphviz DOT, each term labelled with
+    /// its id. The term about to be computed is highlighted, or while a value is
+    /// returned, the term that produced it; constants returned carry no term, so
+    /// nothing is highlighted for them or once the machine is done.
+    pub fn export_term_dot(&self) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let dot = term_dot(&self.get_script_inner()?, self.position_term_id());
+        self.quota.borrow_mut().record_serialized_bytes(dot.len());
+        self.touch();
+        Ok(dot)
+    }
+
+    /// Gets up to `max_bytes` of the JSON `get_script` returns, starting at byte
+    /// `offset`, so large scripts can be fetched and parsed progressively. The
+    /// JSON is prepared once as a "script" export (see `prepare_export`) and kept
+    /// until the session resets or the ByteString rendering changes.
+    ///
+    /// # Returns
+    /// The UTF-8 bytes of the slice, which may end inside a character; fetching
+    /// from `offset + length` until an empty slice comes back gives the whole script
+    pub fn get_script_chunk(&mut self, offset: u32, max_bytes: u32) -> Result<Vec<u8>, JsError> {
+        let export_id = match self.script_export.filter(|export_id| self.exports.contains(*export_id)) {
+            Some(export_id) => export_id,
+            None => {
+                let _options = self.serializer_scope();
+                let json = self.encode_json(&self.get_script_inner()?)?;
+                let export_id = self.exports.prepare("script", json, DEFAULT_CHUNK_SIZE).export_id;
+                self.script_export = Some(export_id);
+                export_id
+            }
+        };
+        let bytes = self
+            .exports
+            .bytes(export_id, offset as usize, max_bytes as usize)
+            .map_err(DebuggerError::MachineError)?
+            .to_vec();
+        self.quota.borrow_mut().record_serialized_bytes(bytes.len());
+        self.touch();
+        Ok(bytes)
+    }
+
+    /// Drops the JSON `get_script_chunk` reads from, once it no longer matches `get_script`
+    fn release_script_export(&mut self) {
+        if let Some(export_id) = self.script_export.take() {
+            self.exports.release(export_id);
+        }
+    }
+
+    pub(crate) fn get_script_inner(&self) -> Result<SerializableTerm, JsError> {
+        let term = self.entry_term.as_ref();
+        let serializable_term = SerializableTerm::from_uplc_term(term);
+        Ok(serializable_term)
+    }
+
+    pub fn get_current_term_id(&self) -> Result<i32, JsError> {
+        match self.machine.current_state() {
+            MachineState::Compute(_, _, term) => match term {
+                Term::Var { uniq_id, .. }
+                | Term::Delay { uniq_id, .. }
+                | Term::Lambda { uniq_id, .. }
+                | Term::Apply { uniq_id, .. }
+                | Term::Constant { uniq_id, .. }
+                | Term::Force { uniq_id, .. }
+                | Term::Error { uniq_id, .. }
+                | Term::Builtin { uniq_id, .. }
+                | Term::Constr { uniq_id, .. }
+                | Term::Case { uniq_id, .. } => Ok(*uniq_id as i32),
+            },
+            _ => Ok(-1),
+        }
+    }
+
+    /// Term of the current position: the one being computed, or the one that
+    /// produced the value being returned
+    fn position_term_id(&self) -> Option<i32> {
+        let term_id = match self.machine.current_state() {
+            MachineState::Compute(..) => self.get_current_term_id().ok()?,
+            MachineState::Return(_, value) => match value {
+                Value::Delay { term_id, .. }
+                | Value::Lambda { term_id, .. }
+                | Value::Builtin { term_id, .. }
+                | Value::Constr { term_id, .. } => *term_id as i32,
+                Value::Con(_) => return None,
+            },
+            MachineState::Done(_) => return None,
+        };
+        Some(term_id).filter(|term_id| self.term_ids.contains(term_id))
+    }
+
+    pub(crate) fn get_current_env_inner(&self) -> Result<SerializableEnv, JsError> {
+        match self.machine.current_state() {
+            MachineState::Compute(_, env, _) => {
+                let serializable_env: SerializableEnv = SerializableEnv::from_uplc_env_with_ids(env, &self.term_ids);
+                Ok(serializable_env)
+            }
+            _ => Ok(SerializableEnv {
+                values: vec![],
+            }),
+        }
+    }
+
+    pub fn get_redeemer(&self) -> Result<String, JsError> {
+        Ok(self.redeemer.clone())
+    }
+
+    pub fn get_current_env(&self) -> Result<String, JsError> {
+        let _options = self.truncating_serializer_scope();
+        let env = self.get_current_env_inner()?;
+        self.to_json_with_truncated_paths(&env, |env| embed_truncated_paths(env, PathRoot::Env, &[]))
+    }
+
+    pub(crate) fn step_inner(&mut self) -> Result<super::StepResult, JsError> {
+        let term_id = self.get_current_term_id()?;
+        self.touch();
+        self.version += 1;
+        self.quota.borrow_mut().record_steps(1);
+        if self.step_history.capacity() > 0 {
+            let snapshot = self.history_snapshot();
+            self.step_history.push(snapshot);
+        }
+        self.step_count += 1;
+        self.coverage.record(term_id, self.step_count);
+        self.previous_env_id = self.get_current_env_id();
+        if let Some(decision) = observe_decision(self.machine.current_state(), self.step_count, &self.case_branches) {
+            self.decision_trace.push(decision);
+        }
+        if self.arithmetic_guard {
+            if let Some(failure) = check_arithmetic(self.machine.current_state(), self.step_count) {
+                self.builtin_warnings.push(failure);
+            }
+        }
+        let applied_builtin = match saturating_builtin(self.machine.current_state()) {
+            Some(Value::Builtin { fun, term_id, .. }) => Some((*fun, *term_id as i32)),
+            _ => None,
+        };
+        let budget_before = self.machine.ex_budget;
+        self.recent_steps.push(StepSample {
+            step: self.step_count,
+            term_id,
+            builtin: applied_builtin.map(|(fun, _)| format!("{:?}", fun)),
+            remaining: budget_before,
+        });
+        // Sketched before the step, as the ledger fails before completing the step that crosses
+        let state_before = self.budget_crossing.is_none().then(|| StateSketch::of(self.machine.current_state()));
+        let traced = self
+            .execution_trace
+            .is_enabled()
+            .then(|| (TraceStateKind::of(self.machine.current_state()), self.machine.ex_budget));
+        let traced_call = self
+            .execution_trace
+            .is_enabled()
+            .then(|| saturating_call(self.machine.current_state()))
+            .flatten();
+        let profiled = self.profile.is_enabled().then(|| {
+            let contexts = self.machine.collect_nested_contexts();
+            profile_stack(&contexts, self.machine.current_state(), &self.case_branches)
+        });
+        self.last_binding = if self.env_watches.is_empty() {
+            None
+        } else {
+            binding_at(self.machine.current_state())
+        };
+        let status: &uplc::manual_machine::ExecutionStatus = self.machine.step();
+        let serializable_status: SerializableExecutionStatus = status.into();
+        if let Some((kind, budget_before)) = traced {
+            self.execution_trace.record(kind, term_id, &budget_before, &self.machine.ex_budget);
+        }
+        if let Some((fun, args)) = traced_call {
+            self.builtin_calls.record(BuiltinCall { step: self.step_count, fun, args });
+        }
+        let step_cpu = budget_before.cpu - self.machine.ex_budget.cpu;
+        let step_mem = budget_before.mem - self.machine.ex_budget.mem;
+        if let Some((fun, _)) = applied_builtin {
+            self.builtin_spend.record(self.step_count, fun, step_cpu, step_mem);
+        }
+        let costed_term = match applied_builtin {
+            Some((_, builtin_term_id)) => Some(builtin_term_id),
+            None => (term_id >= 0).then_some(term_id),
+        };
+        self.term_costs.record(self.step_count, costed_term, step_cpu, step_mem);
+        if let Some(stack) = profiled {
+            self.profile.record(stack, step_cpu, step_mem);
+        }
+        if let Some(state) = state_before {
+            let spent = self.spent_budget();
+            self.budget_crossing =
+                BudgetCrossing::detect(self.step_count, term_id, &spent, &self.real_budget, || state.summary());
+        }
+        if let Some(misuse) = check_machine_state(self.machine.current_state(), self.step_count) {
+            self.builtin_warnings.push(misuse);
+        }
+        self.value_pins.observe(self.machine.current_state(), self.step_count, &self.env_ids);
+        
+        self.last_step_cost = (step_cpu, step_mem);
+
+        Ok(self.step_result(term_id, serializable_status))
+    }
+
+    /// Result of a call that stopped at `term_id` with `status`, with the cost of the last step
+    fn step_result(&self, term_id: i32, status: SerializableExecutionStatus) -> super::StepResult {
+        super::StepResult {
+            term_id,
+            status,
+            cpu_delta: self.exact_step_costs().then_some(self.last_step_cost.0),
+            mem_delta: self.exact_step_costs().then_some(self.last_step_cost.1),
+        }
+    }
+
+    pub fn step(&mut self) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let traces_before = self.machine.traces.len();
+        let result = self.step_inner()?;
+        self.record_stop(&result, traces_before)?;
+        self.to_json(&result)
+    }
+
+    /// Executes up to `count` steps in one call, stopping early once the machine
+    /// is no longer ready; breakpoints are not consulted
+    pub fn step_n(&mut self, count: u32) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let traces_before = self.machine.traces.len();
+        let batch = self.step_n_inner(count)?;
+        let result = self.step_result(batch.term_id, batch.status.clone());
+        self.record_stop(&result, traces_before)?;
+        self.to_json(&batch)
+    }
+
+    pub(crate) fn step_n_inner(&mut self, count: u32) -> Result<super::StepBatchResult, JsError> {
+        let mut steps = Vec::with_capacity(count.min(STEP_BATCH_RESERVE) as usize);
+        let mut result = self.step_result(self.get_current_term_id()?, SerializableExecutionStatus::Ready);
+        for _ in 0..count {
+            let kind = TraceStateKind::of(self.machine.current_state());
+            result = self.step_inner()?;
+            steps.push(super::StepSummary {
+                term_id: result.term_id,
+                kind,
+            });
+            if !matches!(result.status, SerializableExecutionStatus::Ready) {
+                break;
+            }
+        }
+        Ok(super::StepBatchResult {
+            term_id: result.term_id,
+            status: result.status,
+            steps,
+        })
+    }
+
+    /// Replaces the set of term ids execution pauses on
+    pub fn set_breakpoints(&mut self, term_ids: Vec<i32>) {
+        self.breakpoints = term_ids.into_iter().collect();
+        let breakpoints = &self.breakpoints;
+        self.breakpoint_conditions.retain(|term_id, _| breakpoints.contains(term_id));
+    }
+
+    pub fn add_breakpoint(&mut self, term_id: i32) {
+        self.breakpoints.insert(term_id);
+    }
+
+    pub fn remove_breakpoint(&mut self, term_id: i32) {
+        self.breakpoints.remove(&term_id);
+        self.breakpoint_conditions.remove(&term_id);
+    }
+
+    /// Pauses `run_until_breakpoint` right before the named builtin receives its
+    /// last argument and is evaluated, wherever it is applied
+    ///
+    /// # Arguments
+    /// * `builtin` - Builtin name, e.g. `VerifyEd25519Signature` or `verifyEd25519Signature`
+    pub fn break_on_builtin(&mut self, builtin: String) -> Result<(), JsError> {
+        let fun = builtin_by_name(&builtin)
+            .ok_or_else(|| DebuggerError::MachineError(format!("Unknown builtin: {}", builtin)))?;
+        self.builtin_breakpoints.insert(format!("{:?}", fun));
+        Ok(())
+    }
+
+    pub fn remove_builtin_breakpoint(&mut self, builtin: String) -> bool {
+        builtin_by_name(&builtin).is_some_and(|fun| self.builtin_breakpoints.remove(&format!("{:?}", fun)))
+    }
+
+    pub fn get_builtin_breakpoints(&self) -> Vec<String> {
+        self.builtin_breakpoints.iter().cloned().collect()
+    }
+
+    pub fn get_breakpoints(&self) -> Vec<i32> {
+        self.breakpoints.iter().copied().collect()
+    }
+
+    /// Attaches a condition to the breakpoint on `term_id` (adding the breakpoint if
+    /// needed); the breakpoint only pauses when the condition holds. Passing no
+    /// condition makes the breakpoint unconditional again.
+    ///
+    /// Conditions combine comparisons and patterns with `&&`, `||` and `not`, e.g.
+    /// `env[2] is Constr tag 1`, `budget.cpu > 5e9`, `current builtin == EqualsData`.
+    pub fn set_breakpoint_condition(&mut self, term_id: i32, condition: Option<String>) -> Result<(), JsError> {
+        match condition {
+            Some(condition) => {
+                let condition = Condition::parse(&condition).map_err(DebuggerError::InvalidCondition)?;
+                self.breakpoints.insert(term_id);
+                self.breakpoint_conditions.insert(term_id, condition);
+            }
+            None => {
+                self.breakpoint_conditions.remove(&term_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a condition checked after every step regardless of the current term,
+    /// pausing with reason "condition" when it holds. Returns the condition id.
+    pub fn add_condition_breakpoint(&mut self, condition: String) -> Result<u32, JsError> {
+        let condition = Condition::parse(&condition).map_err(DebuggerError::InvalidCondition)?;
+        let id = self.next_condition_id;
+        self.next_condition_id += 1;
+        self.conditions.insert(id, condition);
+        Ok(id)
+    }
+
+    pub fn remove_condition_breakpoint(&mut self, id: u32) -> bool {
+        self.conditions.remove(&id).is_some()
+    }
+
+    /// Pauses runs with reason "watch" whenever a Lambda binds a value at env
+    /// position `index` (counted from the outermost binding, as `get_current_env`
+    /// lists values). Returns the watch id.
+    pub fn watch_env_slot(&mut self, index: usize) -> u32 {
+        self.add_env_watch(EnvWatchTarget::Slot { index })
+    }
+
+    /// Watches the env position the variable with de Bruijn index `index` (1 for
+    /// the innermost binding) refers to in the env of the term being computed
+    pub fn watch_env_index(&mut self, index: usize) -> Result<u32, JsError> {
+        let MachineState::Compute(_, env, _) = self.machine.current_state() else {
+            return Err(DebuggerError::MachineError("De Bruijn indices can only be resolved while a term is being computed".to_string()).into());
+        };
+        let slot = slot_of_index(env.len(), index).ok_or_else(|| {
+            DebuggerError::MachineError(format!("Env has {} values, no de Bruijn index {}", env.len(), index))
+        })?;
+        Ok(self.watch_env_slot(slot))
+    }
+
+    /// Pauses runs with reason "watch" whenever the Lambda `term_id` binds its parameter
+    pub fn watch_binder(&mut self, term_id: i32) -> Result<u32, JsError> {
+        if !self.term_ids.contains(&term_id) {
+            return Err(DebuggerError::MachineError(format!("Unknown term id {}", term_id)).into());
+        }
+        Ok(self.add_env_watch(EnvWatchTarget::Binder { term_id }))
+    }
+
+    pub fn remove_env_watch(&mut self, id: u32) -> bool {
+        self.env_watches.remove(&id).is_some()
+    }
+
+    pub fn get_env_watches(&self) -> Result<String, JsError> {
+        let watches: Vec<EnvWatch> = self
+            .env_watches
+            .iter()
+            .map(|(id, target)| EnvWatch {
+                id: *id,
+                target: target.clone(),
+            })
+            .collect();
+        self.to_json(&watches)
+    }
+
+    fn add_env_watch(&mut self, target: EnvWatchTarget) -> u32 {
+        let id = self.next_env_watch_id;
+        self.next_env_watch_id += 1;
+        self.env_watches.insert(id, target);
+        id
+    }
+
+    /// Sets the maximum number of steps the session may execute before run loops stop
+    pub fn set_step_limit(&mut self, step_limit: Option<u64>) {
+        self.step_limit = step_limit;
+    }
+
+    /// Whether the session executed as many steps as its step limit allows
+    pub(crate) fn step_limit_reached(&self) -> bool {
+        self.step_limit.is_some_and(|step_limit| self.step_count >= step_limit)
+    }
+
+    /// Enables checking arithmetic builtins before they are applied. Division by zero
+    /// and integerToByteString overflow are then reported in `get_builtin_warnings`
+    /// with the builtin's term id and operands, ahead of the generic machine error
+    pub fn set_arithmetic_guard(&mut self, enabled: bool) {
+        self.arithmetic_guard = enabled;
+    }
+
+    /// Sets how many prior machine states are kept for `step_back`; 0 disables the history
+    pub fn set_step_history_size(&mut self, size: usize) {
+        self.step_history.set_capacity(size);
+        self.term_costs.set_capacity(size);
+    }
+
+    /// Returns the session to the state it had `steps` steps ago, or to the oldest
+    /// state kept when the history is shorter
+    pub fn step_back(&mut self, steps: u32) -> Result<String, JsError> {
+        let result = self.step_back_inner(steps)?;
+        self.to_json(&result)
+    }
+
+    pub(crate) fn step_back_inner(&mut self, steps: u32) -> Result<StepBackResult, JsError> {
+        let available = self.step_history.len();
+        if steps == 0 {
+            return Ok(StepBackResult {
+                steps_back: 0,
+                step: self.step_count,
+                term_id: self.get_current_term_id()?,
+                remaining_history: available,
+            });
+        }
+        if self.step_history.capacity() == 0 {
+            return Err(DebuggerError::MachineError(
+                "Step history is off; enable it with set_step_history_size".to_string(),
+            )
+            .into());
+        }
+        let snapshot = self.step_history.rewind(steps as usize).ok_or_else(|| {
+            DebuggerError::MachineError("No earlier state is kept for this session".to_string())
+        })?;
+        self.touch();
+        self.version += 1;
+        self.restore_snapshot(snapshot);
+        Ok(StepBackResult {
+            steps_back: (available - self.step_history.len()) as u64,
+            step: self.step_count,
+            term_id: self.get_current_term_id()?,
+            remaining_history: self.step_history.len(),
+        })
+    }
+
+    /// Copies the machine without its traces, which `restore_snapshot` truncates instead
+    fn history_snapshot(&mut self) -> StepSnapshot {
+        let traces = std::mem::take(&mut self.machine.traces);
+        let machine = (*self.machine).clone();
+        let trace_count = traces.len();
+        self.machine.traces = traces;
+        StepSnapshot {
+            machine,
+            step_count: self.step_count,
+            trace_count,
+        }
+    }
+
+    /// Puts back a machine state taken before a step, dropping what later steps recorded
+    fn restore_snapshot(&mut self, snapshot: StepSnapshot) {
+        let mut traces = std::mem::take(&mut self.machine.traces);
+        traces.truncate(snapshot.trace_count);
+        let mut machine = snapshot.machine;
+        machine.traces = traces;
+        self.restore_machine(machine, snapshot.step_count);
+    }
+
+    /// Installs `machine` as the state reached after `step` steps, dropping what later steps recorded
+    fn restore_machine(&mut self, machine: ManualMachine, step: u64) {
+        *self.machine = machine;
+        self.step_count = step;
+        self.previous_env_id = None;
+        self.last_binding = None;
+        self.last_error = None;
+        self.last_stop_reason = None;
+        self.decision_trace.truncate_after(step);
+        self.builtin_warnings.retain(|warning| warning.step.is_none_or(|at| at <= step));
+        self.recent_steps.truncate_after(step);
+        self.builtin_spend.truncate_after(step);
+        self.last_step_cost = (0, 0);
+        self.term_costs.restore_to(step);
+        self.profile.restart();
+        self.value_pins.truncate_after(step);
+        self.execution_trace.truncate_after(step);
+        self.builtin_calls.truncate_after(step);
+        self.coverage.truncate_after(step);
+        if self.budget_crossing.as_ref().is_some_and(|crossing| crossing.step > step) {
+            self.budget_crossing = None;
+        }
+        if self.budget_exhaustion.as_ref().is_some_and(|report| report.step > step) {
+            self.budget_exhaustion = None;
+            self.budget_exceeded_reported = false;
+        }
+    }
+
+    /// Starts recording the state kind, term id and cost of every following step
+    /// for `export_trace`, or stops and drops the recording
+    pub fn set_trace_recording(&mut self, enabled: bool) {
+        self.execution_trace.set_enabled(enabled, self.step_count);
+        self.builtin_calls.clear();
+    }
+
+    /// Starts attributing the cost of every following step to the machine context
+    /// stack it was taken under, for `export_profile`, or stops and drops the
+    /// profile. Stepping back or resetting starts the profile over.
+    pub fn set_profile_recording(&mut self, enabled: bool) {
+        self.profile.set_enabled(enabled);
+    }
+
+    /// Exports the recorded profile for standard profiler UIs
+    ///
+    /// # Arguments
+    /// * `format` - "folded" (default), the folded stacks read by flamegraph tools, or "speedscope" JSON
+    /// * `weight` - "cpu" (default) or "mem"
+    pub fn export_profile(&self, format: Option<String>, weight: Option<String>) -> Result<String, JsError> {
+        if !self.profile.is_enabled() {
+            return Err(DebuggerError::MachineError("Profile recording is off".to_string()).into());
+        }
+        self.require_exact_step_costs("The profile")?;
+        let weight = ProfileWeight::from_name(weight.as_deref().unwrap_or("cpu")).map_err(DebuggerError::MachineError)?;
+        let labels = FrameLabels::new(&self.entry_term, self.source_map.as_ref());
+        let profile = match format.as_deref().unwrap_or("folded") {
+            "folded" => self.profile.folded(weight, &labels),
+            "speedscope" => serde_json::to_string(&self.profile.speedscope(weight, &labels, &self.redeemer))
+                .map_err(|e| DebuggerError::MachineError(e.to_string()))?,
+            other => {
+                return Err(DebuggerError::MachineError(format!(
+                    "Unknown profile format {}, expected folded or speedscope",
+                    other
+                ))
+                .into())
+            }
+        };
+        self.quota.borrow_mut().record_serialized_bytes(profile.len());
+        self.touch();
+        Ok(profile)
+    }
+
+    /// Exports the recorded steps as JSON, to compare executions across
+    /// protocol parameter sets
+    pub fn export_trace(&self) -> Result<String, JsError> {
+        let trace = self.export_trace_inner()?;
+        self.to_json(trace)
+    }
+
+    /// Exports the recorded steps in the compact binary form of `ExecutionTrace::to_binary`
+    pub fn export_trace_binary(&self) -> Result<Vec<u8>, JsError> {
+        let bytes = self.export_trace_inner()?.to_binary();
+        self.quota.borrow_mut().record_serialized_bytes(bytes.len());
+        Ok(bytes)
+    }
+
+    pub(crate) fn export_trace_inner(&self) -> Result<&ExecutionTrace, JsError> {
+        self.require_exact_step_costs("The execution trace")?;
+        Ok(self.execution_trace.trace().ok_or_else(|| {
+            DebuggerError::MachineError("Trace recording is off; enable it with set_trace_recording".to_string())
+        })?)
+    }
+
+    /// Prices the recorded execution under `cost_models` without running it again.
+    /// Needs a trace recorded from the start of the execution.
+    pub(crate) fn reprice_inner(&self, cost_models: &CostModels) -> Result<RepriceReport, JsError> {
+        let cost_model = cost_model_from(cost_models, &self.language).map_err(DebuggerError::MachineError)?;
+        let trace = self.export_trace_inner()?;
+        if trace.first_step != 0 || trace.truncated || self.builtin_calls.is_truncated() {
+            return Err(DebuggerError::MachineError(
+                "Repricing needs a complete trace; enable trace recording before the first step".to_string(),
+            )
+            .into());
+        }
+        let (machine, builtin) = reprice_trace(&self.entry_term, trace, self.builtin_calls.calls(), &cost_model)
+            .map_err(DebuggerError::MachineError)?;
+        let original = self.spent_budget();
+        let (cpu, mem) = (machine.cpu + builtin.cpu, machine.mem + builtin.mem);
+        Ok(RepriceReport {
+            redeemer: self.redeemer.clone(),
+            session_id: self.session_id.clone(),
+            steps: self.step_count,
+            original_cpu: original.cpu,
+            original_mem: original.mem,
+            cpu,
+            mem,
+            machine_cpu: machine.cpu,
+            machine_mem: machine.mem,
+            builtin_cpu: builtin.cpu,
+            builtin_mem: builtin.mem,
+            declared_cpu: self.real_budget.cpu,
+            declared_mem: self.real_budget.mem,
+            within_declared: cpu <= self.real_budget.cpu && mem <= self.real_budget.mem,
+        })
+    }
+
+    /// Serializes a large export once and keeps it so it can be fetched in chunks
+    /// with `get_export_chunk`, for hosts that can't take multi-megabyte strings
+    /// in one call. Up to 8 exports are kept; `release_export` frees one earlier.
+    ///
+    /// # Arguments
+    /// * `kind` - A kind `export_as` takes, exported as JSON
+    /// * `chunk_size` - Largest chunk in bytes, 1 MiB by default
+    ///
+    /// # Returns
+    /// JSON `ExportHandle` with the export id, total size and chunk count
+    pub fn prepare_export(&mut self, kind: &str, chunk_size: Option<u32>) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let handle = self.prepare_export_inner(kind, chunk_size)?;
+        self.to_json(&handle)
+    }
+
+    pub(crate) fn prepare_export_inner(&mut self, kind: &str, chunk_size: Option<u32>) -> Result<ExportHandle, JsError> {
+        let content = String::from_utf8(self.export_as_inner(kind, SerializationFormat::Json)?)
+            .map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+        let chunk_size = chunk_size.map_or(DEFAULT_CHUNK_SIZE, |size| size as usize);
+        Ok(self.exports.prepare(kind, content, chunk_size))
+    }
+
+    /// Gets what a getter returns, encoded as JSON, compact JSON or CBOR bytes
+    /// whatever the session's JSON format
+    ///
+    /// # Arguments
+    /// * `kind` - "trace", "coverage", "coverage_map", "script", "machine_state",
+    ///   "logs", "current_env", "machine_context", "script_context", "budget",
+    ///   "call_stack", "state_summary" or "decision_trace"
+    /// * `format` - "json" (default), "compact" (JSON with the short keys of
+    ///   `get_compact_json_keys`) or "cbor"
+    pub fn export_as(&self, kind: &str, format: Option<String>) -> Result<Vec<u8>, JsError> {
+        let _options = self.serializer_scope();
+        let format = SerializationFormat::from_name(format.as_deref().unwrap_or("json")).map_err(DebuggerError::MachineError)?;
+        self.export_as_inner(kind, format)
+    }
+
+    pub(crate) fn export_as_inner(&self, kind: &str, format: SerializationFormat) -> Result<Vec<u8>, JsError> {
+        match kind {
+            "trace" => self.to_bytes(self.export_trace_inner()?, format),
+            "coverage" => self.to_bytes(&self.get_coverage_inner(), format),
+            "coverage_map" => self.to_bytes(&self.export_coverage_map_inner(), format),
+            "script" => self.to_bytes(&self.get_script_inner()?, format),
+            "machine_state" => self.to_bytes(&self.get_machine_state_inner()?, format),
+            "logs" => self.to_bytes(&self.get_logs_inner()?, format),
+            "current_env" => self.to_bytes(&self.get_current_env_inner()?, format),
+            "machine_context" => self.to_bytes(&self.get_machine_context_inner()?, format),
+            "script_context" => self.to_bytes(&self.get_tx_script_context_inner()?, format),
+            "budget" => self.to_bytes(&self.get_budget_inner()?, format),
+            "call_stack" => self.to_bytes(&self.get_call_stack_inner(), format),
+            "state_summary" => self.to_bytes(&self.get_state_summary_inner(), format),
+            "decision_trace" => self.to_bytes(&self.get_decision_trace_inner(), format),
+            other => Err(DebuggerError::MachineError(format!("Unknown export kind: {}", other)).into()),
+        }
+    }
+
+    /// Gets chunk `index` of a prepared export; concatenating chunks 0 to
+    /// `chunk_count - 1` gives the whole export
+    pub fn get_export_chunk(&self, export_id: u32, index: u32) -> Result<String, JsError> {
+        let chunk = self
+            .exports
+            .chunk(export_id, index as usize)
+            .map_err(DebuggerError::MachineError)?;
+        self.touch();
+        Ok(chunk.to_string())
+    }
+
+    pub fn release_export(&mut self, export_id: u32) -> bool {
+        self.exports.release(export_id)
+    }
+
+    /// Saves the full machine state (term, env, context stack, budget and traces)
+    /// under `name`, replacing any checkpoint with the same name, so `restore` can
+    /// return to this exact point after trying a branch
+    pub fn snapshot(&mut self, name: String) -> Result<String, JsError> {
+        let info = self.snapshot_inner(name)?;
+        self.to_json(&info)
+    }
+
+    pub(crate) fn snapshot_inner(&mut self, name: String) -> Result<CheckpointInfo, JsError> {
+        self.touch();
+        let spent = self.spent_budget();
+        let info = CheckpointInfo {
+            name: name.clone(),
+            step: self.step_count,
+            term_id: self.get_current_term_id()?,
+            trace_count: self.machine.traces.len(),
+            spent_cpu: spent.cpu,
+            spent_mem: spent.mem,
+            created_at: now_millis(),
+        };
+        self.checkpoints.insert(
+            name,
+            Checkpoint {
+                machine: (*self.machine).clone(),
+                info: info.clone(),
+                records: self.step_records(),
+            },
+        );
+        Ok(info)
+    }
+
+    /// Returns the session to the checkpoint saved as `name`. The checkpoint is
+    /// kept, so it can be restored again; the step history starts over from it.
+    pub fn restore(&mut self, name: &str) -> Result<String, JsError> {
+        let info = self.restore_inner(name)?;
+        self.to_json(&info)
+    }
+
+    pub(crate) fn restore_inner(&mut self, name: &str) -> Result<CheckpointInfo, JsError> {
+        let checkpoint = self
+            .checkpoints
+            .get(name)
+            .cloned()
+            .ok_or_else(|| DebuggerError::MachineError(format!("No checkpoint named {}", name)))?;
+        self.touch();
+        self.version += 1;
+        self.step_history.clear();
+        self.restore_machine(checkpoint.machine, checkpoint.info.step);
+        self.restore_step_records(checkpoint.records);
+        Ok(checkpoint.info)
+    }
+
+    fn step_records(&self) -> StepRecords {
+        StepRecords {
+            decision_trace: self.decision_trace.clone(),
+            builtin_warnings: self.builtin_warnings.clone(),
+            recent_steps: self.recent_steps.clone(),
+            builtin_spend: self.builtin_spend.clone(),
+            term_costs: self.term_costs.clone(),
+            value_pins: self.value_pins.clone(),
+            execution_trace: self.execution_trace.clone(),
+            builtin_calls: self.builtin_calls.clone(),
+            coverage: self.coverage.clone(),
+            budget_crossing: self.budget_crossing.clone(),
+            budget_exhaustion: self.budget_exhaustion.clone(),
+            budget_exceeded_reported: self.budget_exceeded_reported,
+        }
+    }
+
+    /// Puts back the records of a checkpoint, keeping the options set since:
+    /// the pins, the trace recording and the number of steps whose cost can be
+    /// taken off again
+    fn restore_step_records(&mut self, records: StepRecords) {
+        let step = self.step_count;
+        self.decision_trace = records.decision_trace;
+        self.builtin_warnings = records.builtin_warnings;
+        self.recent_steps = records.recent_steps;
+        self.builtin_spend = records.builtin_spend;
+        self.term_costs = records.term_costs;
+        self.term_costs.set_capacity(self.step_history.capacity());
+        self.value_pins.restore_occurrences(&records.value_pins, step);
+        match (self.execution_trace.is_enabled(), records.execution_trace.is_enabled()) {
+            (true, true) => {
+                self.execution_trace = records.execution_trace;
+                self.builtin_calls = records.builtin_calls;
+            }
+            (true, false) => {
+                self.execution_trace.set_enabled(true, step);
+                self.builtin_calls.clear();
+            }
+            (false, _) => {}
+        }
+        self.coverage = records.coverage;
+        self.budget_crossing = records.budget_crossing;
+        self.budget_exhaustion = records.budget_exhaustion;
+        self.budget_exceeded_reported = records.budget_exceeded_reported;
+    }
+
+    /// Gets the saved checkpoints, ordered by name
+    pub fn get_snapshots(&self) -> Result<String, JsError> {
+        let checkpoints: Vec<&CheckpointInfo> = self.checkpoints.values().map(|checkpoint| &checkpoint.info).collect();
+        self.to_json(&checkpoints)
+    }
+
+    pub fn remove_snapshot(&mut self, name: &str) -> bool {
+        self.checkpoints.remove(name).is_some()
+    }
+
+    /// Runs the machine natively until it finishes, fails, reaches a breakpoint,
+    /// exceeds the declared budget or hits a step limit
+    ///
+    /// # Arguments
+    /// * `max_steps` - Maximum number of steps executed by this call; the returned status is `Running` when exhausted
+    pub fn run_until_breakpoint(&mut self, max_steps: Option<u32>) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let result = self.run_until_breakpoint_inner(max_steps)?;
+        self.to_json(&result)
+    }
+
+    pub(crate) fn run_until_breakpoint_inner(&mut self, max_steps: Option<u32>) -> Result<super::StepResult, JsError> {
+        self.run_until_breakpoint_timed_inner(max_steps, None)
+    }
+
+    /// Like `run_until_breakpoint`, but also returns with a `Running` status once
+    /// `max_millis` of wall-clock time have passed, so a host can yield to its UI
+    /// and resume with another call
+    ///
+    /// # Arguments
+    /// * `max_steps` - Maximum number of steps executed by this call
+    /// * `max_millis` - Maximum wall-clock time of this call in milliseconds
+    pub fn run_until_breakpoint_timed(&mut self, max_steps: Option<u32>, max_millis: Option<u32>) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let result = self.run_until_breakpoint_timed_inner(max_steps, max_millis)?;
+        self.to_json(&result)
+    }
+
+    pub(crate) fn run_until_breakpoint_timed_inner(
+        &mut self,
+        max_steps: Option<u32>,
+        max_millis: Option<u32>,
+    ) -> Result<super::StepResult, JsError> {
+        let traces_before = self.machine.traces.len();
+        let deadline = max_millis.map(|millis| now_millis() + millis as u64);
+        // A cancel only applies to the run it was made during
+        self.cancel_token.clear();
+        let result = self.run_loop(max_steps, deadline)?;
+        self.record_stop(&result, traces_before)?;
+        Ok(result)
+    }
+
+    /// Runs the machine natively until a Compute state reaches `term_id`, as when
+    /// fast-forwarding to a node picked in a script tree. Breakpoints, the budget
+    /// and step limits still stop the run before it gets there.
+    ///
+    /// # Arguments
+    /// * `term_id` - Term to stop at; a `Paused` status with reason "target" reports reaching it
+    /// * `max_steps` - Maximum number of steps executed by this call
+    pub fn run_until_term(&mut self, term_id: i32, max_steps: Option<u32>) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let result = self.run_until_term_inner(term_id, max_steps)?;
+        self.to_json(&result)
+    }
+
+    pub(crate) fn run_until_term_inner(&mut self, term_id: i32, max_steps: Option<u32>) -> Result<super::StepResult, JsError> {
+        if !self.term_ids.contains(&term_id) {
+            return Err(DebuggerError::MachineError(format!("Unknown term id {}", term_id)).into());
+        }
+        self.run_target = Some(term_id);
+        let result = self.run_until_breakpoint_inner(max_steps);
+        self.run_target = None;
+        result
+    }
+
+    /// Runs past the prelude validators compiled from Aiken or Plinth start with
+    /// (applying the arguments, defining helpers and decoding them) to the first
+    /// Case term or the first builtin applied to the script context, and reports
+    /// what the prelude cost. Breakpoints are not checked on the way.
+    ///
+    /// # Arguments
+    /// * `max_steps` - Maximum number of steps executed by this call
+    pub fn skip_prelude(&mut self, max_steps: Option<u32>) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let report = self.skip_prelude_inner(max_steps)?;
+        self.to_json(&report)
+    }
+
+    pub(crate) fn skip_prelude_inner(&mut self, max_steps: Option<u32>) -> Result<PreludeReport, JsError> {
+        let script_context = self.arguments.last().cloned();
+        let steps_before = self.step_count;
+        let spent_before = self.spent_budget();
+        let traces_before = self.machine.traces.len();
+        self.cancel_token.clear();
+
+        let (stopped_at, builtin, status) = loop {
+            if self.cancel_token.take() {
+                let term_id = self.get_current_term_id()?;
+                break ("Cancelled", None, SerializableExecutionStatus::Paused {
+                    reason: super::PauseReason::Cancelled,
+                    term_id,
+                });
+            }
+            if let Some((stopped_at, builtin)) = prelude_end(self.machine.current_state(), script_context.as_ref()) {
+                let status = SerializableExecutionStatus::Paused {
+                    reason: super::PauseReason::Prelude,
+                    term_id: self.get_current_term_id()?,
+                };
+                break (stopped_at, builtin, status);
+            }
+            let run_steps = self.step_count - steps_before;
+            if max_steps.is_some_and(|max_steps| run_steps >= max_steps as u64)
+                || self.step_limit_reached()
+            {
+                break ("StepLimit", None, SerializableExecutionStatus::Running { steps: run_steps });
+            }
+            let result = self.step_inner()?;
+            if !matches!(result.status, SerializableExecutionStatus::Ready) {
+                break ("Finished", None, result.status);
+            }
+        };
+        let term_id = self.get_current_term_id()?;
+        let result = self.step_result(term_id, status);
+        self.record_stop(&result, traces_before)?;
+
+        let spent = self.spent_budget();
+        let (cpu, mem) = (spent.cpu - spent_before.cpu, spent.mem - spent_before.mem);
+        Ok(PreludeReport {
+            stopped_at: stopped_at.to_string(),
+            term_id,
+            builtin,
+            steps: self.step_count - steps_before,
+            cpu,
+            mem,
+            cpu_percent: declared_percent(cpu, self.real_budget.cpu),
+            mem_percent: declared_percent(mem, self.real_budget.mem),
+        })
+    }
+
+    /// Stops the current run (`run_until_breakpoint`, `run_until_term` or
+    /// `skip_prelude`) at its next step with a `Paused` status whose reason is
+    /// "cancelled". A cancel made while no run is in progress is dropped when the
+    /// next run starts.
+    ///
+    /// On wasm the run holds the JS thread, so this can only be reached from the
+    /// progress callback, through the token of `get_cancel_token`. Hosts that
+    /// don't use the callback run in slices with `run_until_breakpoint_timed`
+    /// and stop by not resuming.
+    pub fn cancel_run(&self) {
+        self.cancel_token.cancel();
+    }
+
+    /// Calls `callback` every `interval_steps` steps of `run_until_breakpoint` with
+    /// a JSON `RunProgress`, so a host can show how far a long run has advanced
+    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    pub fn set_progress_callback(&mut self, callback: js_sys::Function, interval_steps: u32) {
+        self.progress
+            .set(super::progress::js_progress_callback(callback), interval_steps as u64);
+    }
+
+    pub fn clear_progress_callback(&mut self) {
+        self.progress.clear();
+    }
+
+    /// Gets a token sharing this session's cancel flag, for hosts that cancel from
+    /// outside the call running the session
+    pub fn get_cancel_token(&self) -> RunCancelToken {
+        self.cancel_token.clone()
+    }
+
+    fn run_loop(&mut self, max_steps: Option<u32>, deadline: Option<u64>) -> Result<super::StepResult, JsError> {
+        let mut steps_taken: u64 = 0;
+        loop {
+            if self.cancel_token.take() {
+                let term_id = self.get_current_term_id()?;
+                return Ok(self.step_result(
+                    term_id,
+                    SerializableExecutionStatus::Paused {
+                        reason: super::PauseReason::Cancelled,
+                        term_id,
+                    },
+                ));
+            }
+            if let Some(deadline) = deadline {
+                if steps_taken % CLOCK_CHECK_INTERVAL == 0 && steps_taken > 0 && now_millis() >= deadline {
+                    return Ok(self.step_result(
+                        self.get_current_term_id()?,
+                        SerializableExecutionStatus::Running { steps: steps_taken },
+                    ));
+                }
+            }
+            if let Some(step_limit) = self.step_limit {
+                if self.step_count >= step_limit {
+                    return Ok(self.step_result(
+                        self.get_current_term_id()?,
+                        SerializableExecutionStatus::StepLimitReached { steps: self.step_count },
+                    ));
+                }
+            }
+            if let Some(max_steps) = max_steps {
+                if steps_taken >= max_steps as u64 {
+                    return Ok(self.step_result(
+                        self.get_current_term_id()?,
+                        SerializableExecutionStatus::Running { steps: steps_taken },
+                    ));
+                }
+            }
+
+            let result = self.step_inner()?;
+            steps_taken += 1;
+            if self.progress.due(steps_taken) {
+                let progress = self.run_progress(steps_taken, result.term_id);
+                self.progress.report(&progress);
+            }
+            if !matches!(result.status, SerializableExecutionStatus::Ready) {
+                return Ok(result);
+            }
+
+            let term_id = self.get_current_term_id()?;
+            if let Some(status) = self.check_stop_conditions(term_id)? {
+                return Ok(self.step_result(term_id, status));
+            }
+        }
+    }
+
+    /// Evaluates the program natively to its end, stopping once more than `limit`
+    /// is spent. Breakpoints and step limits are not consulted and none of the
+    /// per-step records (history, coverage, trace, costs) are kept, so this is
+    /// for sessions that are evaluated once and dropped, as by `run_all`.
+    pub(crate) fn evaluate_inner(&mut self, limit: ExBudget) -> Result<SerializableExecutionStatus, JsError> {
+        self.touch();
+        self.version += 1;
+        let steps_before = self.step_count;
+        let status = loop {
+            self.step_count += 1;
+            let finished = match self.machine.step() {
+                ExecutionStatus::Ready => None,
+                status => Some(SerializableExecutionStatus::from(status)),
+            };
+            let spent = self.spent_budget();
+            if spent.cpu > limit.cpu || spent.mem > limit.mem {
+                break SerializableExecutionStatus::BudgetExceeded {
+                    budget: self.get_budget_inner()?,
+                };
+            }
+            if let Some(status) = finished {
+                break status;
+            }
+        };
+        self.quota.borrow_mut().record_steps(self.step_count - steps_before);
+        Ok(status)
+    }
+
+    /// Checks the state reached by the last step against budget and breakpoints
+    fn check_stop_conditions(&mut self, term_id: i32) -> Result<Option<SerializableExecutionStatus>, JsError> {
+        let budget = self.get_budget_inner()?;
+        let exceeded = budget.ex_units_spent > budget.ex_units_available
+            || budget.memory_units_spent > budget.memory_units_available;
+        if exceeded && !self.budget_exceeded_reported {
+            self.budget_exceeded_reported = true;
+            self.budget_exhaustion = Some(self.recent_steps.report(
+                self.step_count,
+                term_id,
+                &self.machine.ex_budget,
+                self.source_map.as_ref(),
+            ));
+            return Ok(Some(SerializableExecutionStatus::BudgetExceeded { budget }));
+        }
+        if self.run_target == Some(term_id) {
+            return Ok(Some(SerializableExecutionStatus::Paused {
+                reason: super::PauseReason::Target,
+                term_id,
+            }));
+        }
+        if self
+            .last_binding
+            .is_some_and(|binding| self.env_watches.values().any(|watch| watch.matches(&binding)))
+        {
+            return Ok(Some(SerializableExecutionStatus::Paused {
+                reason: super::PauseReason::Watch,
+                term_id,
+            }));
+        }
+        if !self.builtin_breakpoints.is_empty() {
+            if let Some(Value::Builtin { fun, term_id: builtin_term_id, .. }) = saturating_builtin(self.machine.current_state()) {
+                let name = format!("{:?}", fun);
+                if self.builtin_breakpoints.contains(&name) {
+                    return Ok(Some(SerializableExecutionStatus::Paused {
+                        reason: super::PauseReason::Builtin { name },
+                        term_id: *builtin_term_id as i32,
+                    }));
+                }
+            }
+        }
+        if self.breakpoints.contains(&term_id) || !self.conditions.is_empty() {
+            let state = self.machine.current_state();
+            let context = ConditionContext {
+                cpu: budget.ex_units_spent,
+                mem: budget.memory_units_spent,
+                step: self.step_count,
+                term_id,
+                current_builtin: match state {
+                    MachineState::Compute(_, _, Term::Builtin { fun, .. }) => Some(format!("{:?}", fun)),
+                    MachineState::Return(_, Value::Builtin { fun, .. }) => Some(format!("{:?}", fun)),
+                    _ => None,
+                },
+                env: match state {
+                    MachineState::Compute(_, env, _) => Some(env.as_slice()),
+                    _ => None,
+                },
+            };
+
+            if self.breakpoints.contains(&term_id)
+                && self
+                    .breakpoint_conditions
+                    .get(&term_id)
+                    .is_none_or(|condition| condition.evaluate(&context))
+            {
+                return Ok(Some(SerializableExecutionStatus::Paused {
+                    reason: super::PauseReason::Breakpoint,
+                    term_id,
+                }));
+            }
+            if self.conditions.values().any(|condition| condition.evaluate(&context)) {
+                return Ok(Some(SerializableExecutionStatus::Paused {
+                    reason: super::PauseReason::Condition,
+                    term_id,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn run_progress(&self, run_steps: u64, term_id: i32) -> RunProgress {
+        let spent = self.spent_budget();
+        RunProgress {
+            step_count: self.step_count,
+            run_steps,
+            term_id,
+            cpu_spent: spent.cpu,
+            mem_spent: spent.mem,
+            cpu_percent: declared_percent(spent.cpu, self.real_budget.cpu),
+            mem_percent: declared_percent(spent.mem, self.real_budget.mem),
+        }
+    }
+
+    /// Records why execution halted after `result`
+    fn record_stop(&mut self, result: &super::StepResult, traces_before: usize) -> Result<(), JsError> {
+        let kind = super::StopReasonKind::from_status(&result.status);
+        // Only pauses on a breakpoint of some kind have one to report
+        let breakpoint_id = match &result.status {
+            SerializableExecutionStatus::Paused {
+                term_id,
+                reason:
+                    super::PauseReason::Breakpoint
+                    | super::PauseReason::Condition
+                    | super::PauseReason::Watch
+                    | super::PauseReason::Builtin { .. },
+            } => Some(*term_id),
+            _ => None,
+        };
+        let description = match &result.status {
+            SerializableExecutionStatus::Paused { reason, .. } => Some(reason.to_string()),
+            SerializableExecutionStatus::Error { message } => Some(message.clone()),
+            _ => None,
+        };
+        let matched_trace = if self.machine.traces.len() > traces_before {
+            self.machine.traces.last().map(|trace| trace.to_string())
+        } else {
+            None
+        };
+        self.last_stop_reason = Some(super::StopReason {
+            kind,
+            term_id: result.term_id,
+            step: self.step_count,
+            breakpoint_id,
+            matched_trace,
+            description,
+            budget: self.get_budget_inner()?,
+            annotation: self.annotations.get(result.term_id).cloned(),
+        });
+        Ok(())
+    }
+
+    /// Gets the structured reason of the last halt (step, breakpoint, budget, ...)
+    pub fn get_last_stop_reason(&self) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        self.to_json(&self.last_stop_reason)
+    }
+
+    /// Gets the number of steps executed since the session was created or last reset
+    pub fn get_step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Gets where the last steps before the declared budget was exceeded spent it:
+    /// the most expensive terms (with source locations when the source map has
+    /// them), builtins, and any loop repeating at the end. Null until exceeded.
+    pub fn get_budget_exhaustion(&self) -> Result<String, JsError> {
+        self.to_json(&self.budget_exhaustion)
+    }
+
+    /// Gets how far usage exceeds the redeemer's declared ex-units: the overshoot
+    /// in cpu and mem, the first step at which the declared budget would have been
+    /// exhausted and the state summary there, i.e. where the ledger would have cut
+    /// execution. Null while usage is within the declared ex-units.
+    pub fn get_overshoot_analysis(&self) -> Result<String, JsError> {
+        let analysis = self.get_overshoot_analysis_inner()?;
+        self.to_json(&analysis)
+    }
+
+    pub(crate) fn get_overshoot_analysis_inner(&self) -> Result<Option<OvershootAnalysis>, DebuggerError> {
+        self.require_exact_step_costs("The overshoot analysis")?;
+        let Some(crossing) = self.budget_crossing.clone() else {
+            return Ok(None);
+        };
+        let finished = matches!(self.machine.current_state(), MachineState::Done(_));
+        Ok(Some(OvershootAnalysis::new(&self.real_budget, &self.spent_budget(), finished, crossing)))
+    }
+
+    /// Bookmarks a step of the session
+    ///
+    /// # Arguments
+    /// * `name` - Label shown for the bookmark
+    /// * `note` - Optional free-text note
+    /// * `step` - Step count to bookmark; the current step when absent
+    /// * `term_id` - Term the bookmark refers to; the current term when absent
+    pub fn add_bookmark(&mut self, name: String, note: Option<String>, step: Option<u64>, term_id: Option<i32>) -> Result<u32, JsError> {
+        let step = step.unwrap_or(self.step_count);
+        let term_id = match term_id {
+            Some(term_id) => term_id,
+            None => self.get_current_term_id()?,
+        };
+        Ok(self.bookmarks.add(name, note, step, term_id))
+    }
+
+    /// Removes a bookmark, returning whether it existed
+    pub fn remove_bookmark(&mut self, id: u32) -> bool {
+        self.bookmarks.remove(id)
+    }
+
+    /// Replaces the note of a bookmark, returning whether it existed
+    pub fn set_bookmark_note(&mut self, id: u32, note: Option<String>) -> bool {
+        self.bookmarks.set_note(id, note)
+    }
+
+    pub fn get_bookmarks(&self) -> Result<String, JsError> {
+        let bookmarks = self.get_bookmarks_inner();
+        self.to_json(&bookmarks)
+    }
+
+    pub(crate) fn get_bookmarks_inner(&self) -> Vec<Bookmark> {
+        self.bookmarks.list()
+    }
+
+    /// Attaches a host annotation (label, color, note, plugin data) to a term,
+    /// replacing any previous one; an empty annotation removes it. Annotations are
+    /// reported alongside payloads and never change how terms serialize.
+    pub fn set_term_annotation(&mut self, term_id: i32, annotation_json: &str) -> Result<(), JsError> {
+        if !self.term_ids.contains(&term_id) {
+            return Err(DebuggerError::MachineError(format!("Unknown term id {}", term_id)).into());
+        }
+        let annotation = serde_json::from_str::<TermAnnotation>(annotation_json)
+            .map_err(|e| DebuggerError::MachineError(format!("Invalid annotation: {}", e)))?;
+        self.annotations.set(term_id, annotation);
+        Ok(())
+    }
+
+    pub fn remove_term_annotation(&mut self, term_id: i32) -> bool {
+        self.annotations.remove(term_id)
+    }
+
+    pub fn clear_term_annotations(&mut self) {
+        self.annotations.clear();
+    }
+
+    /// Gets annotations by term id, for the given terms (e.g. those of a lazily
+    /// loaded subtree) or for all annotated terms when none are given
+    pub fn get_term_annotations(&self, term_ids: Option<Vec<i32>>) -> Result<String, JsError> {
+        let annotations = self.annotations.select(term_ids.as_deref());
+        self.to_json(&annotations)
+    }
+
+    /// Gets builtins applied with missing forces or excess arguments, both found
+    /// statically in the program and observed while stepping
+    pub fn get_builtin_warnings(&self) -> Result<String, JsError> {
+        let warnings = self.get_builtin_warnings_inner();
+        self.to_json(&warnings)
+    }
+
+    pub(crate) fn get_builtin_warnings_inner(&self) -> BuiltinWarnings {
+        BuiltinWarnings {
+            static_warnings: scan_builtin_misuse(&self.entry_term),
+            runtime_warnings: self.builtin_warnings.clone(),
+        }
+    }
+
+    /// Finds values in the current env, the returned value, the frames of the
+    /// machine context and pending builtin arguments matching a predicate such as
+    /// `{"kind":"ByteString","length":28}`, `{"kind":"Integer","equals":"42"}` or
+    /// `{"kind":"Constr","tag":1}`, returning paths usable with the lazy getters
+    pub fn find_values(&self, predicate_json: &str) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let result = self.find_values_inner(predicate_json)?;
+        self.to_json(&result)
+    }
+
+    pub(crate) fn find_values_inner(&self, predicate_json: &str) -> Result<ValueSearchResult, JsError> {
+        let predicate = ValuePredicate::from_json(predicate_json).map_err(DebuggerError::MachineError)?;
+        let contexts = self.machine.collect_nested_contexts();
+        Ok(find_values(self.machine.current_state(), &contexts, &predicate).map_err(DebuggerError::MachineError)?)
+    }
+
+    /// Stores a copy of the env in scope under `label`, replacing any pin with the same
+    /// label. While a value is returned that is the env of the frame it returns to;
+    /// once execution is done there is none and pinning fails.
+    pub fn pin_env_snapshot(&mut self, label: String) -> Result<(), JsError> {
+        let _options = self.serializer_scope();
+        let pin = self.current_env_pin(label.clone())?;
+        self.env_pins.insert(label, pin);
+        Ok(())
+    }
+
+    /// Diffs the current env against the one pinned under `label`
+    pub fn compare_with_pin(&self, label: &str) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let comparison = self.compare_with_pin_inner(label)?;
+        self.to_json(&comparison)
+    }
+
+    pub(crate) fn compare_with_pin_inner(&self, label: &str) -> Result<EnvPinComparison, JsError> {
+        let pin = self
+            .env_pins
+            .get(label)
+            .ok_or_else(|| DebuggerError::MachineError(format!("No env pinned as {}", label)))?;
+        Ok(pin.compare(&self.current_env_pin(label.to_string())?))
+    }
+
+    pub fn remove_env_pin(&mut self, label: &str) -> bool {
+        self.env_pins.remove(label).is_some()
+    }
+
+    /// Gets the labels of all pinned envs
+    pub fn get_env_pins(&self) -> Vec<String> {
+        let _options = self.serializer_scope();
+        self.env_pins.keys().cloned().collect()
+    }
+
+    /// Pins the value at `index` (outermost binding first) of the env `env_id`, as
+    /// reported by `get_env_identity`, and records where it reappears in later states
+    pub fn pin_env_value(&mut self, label: String, env_id: String, index: usize) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let report = self.pin_value_inner(label, ValuePinTarget::EnvSlot { env_id, index })?;
+        self.to_json(&report)
+    }
+
+    /// Pins the value bound by the Lambda term `term_id` in the current env and
+    /// records where it reappears in later states
+    pub fn pin_binder_value(&mut self, label: String, term_id: i32) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let report = self.pin_value_inner(label, ValuePinTarget::Binder { term_id })?;
+        self.to_json(&report)
+    }
+
+    pub(crate) fn pin_value_inner(&mut self, label: String, target: ValuePinTarget) -> Result<ValuePinReport, JsError> {
+        let contexts = self.machine.collect_nested_contexts();
+        let value = resolve_pin_target(&target, self.machine.current_state(), &contexts, &self.entry_term, &self.env_ids)
+            .map_err(DebuggerError::MachineError)?;
+        self.value_pins.pin(label.clone(), target, self.step_count, value);
+        self.get_value_pin_inner(&label)
+    }
+
+    /// Gets a pinned value with every later state it was bound or returned in,
+    /// marked `Identical` when it is the same value and `Equal` when only its contents match
+    pub fn get_value_pin(&self, label: &str) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let report = self.get_value_pin_inner(label)?;
+        self.to_json(&report)
+    }
+
+    pub(crate) fn get_value_pin_inner(&self, label: &str) -> Result<ValuePinReport, JsError> {
+        Ok(self
+            .value_pins
+            .report(label)
+            .ok_or_else(|| DebuggerError::MachineError(format!("No value pinned as {}", label)))?)
+    }
+
+    pub fn remove_value_pin(&mut self, label: &str) -> bool {
+        self.value_pins.remove(label)
+    }
+
+    /// Gets the labels of all pinned values
+    pub fn get_value_pins(&self) -> Vec<String> {
+        let _options = self.serializer_scope();
+        self.value_pins.labels()
+    }
+
+    fn current_env_pin(&self, label: String) -> Result<EnvPin, JsError> {
+        let env = self.env_in_scope()?;
+        Ok(EnvPin::new(label, self.step_count, self.get_current_term_id()?, &env).map_err(DebuggerError::MachineError)?)
+    }
+
+    /// Env execution is in: the current env while computing, and while returning
+    /// the env of the nearest frame the value is returned to
+    fn env_in_scope(&self) -> Result<SerializableEnv, JsError> {
+        match self.machine.current_state() {
+            MachineState::Compute(..) => self.get_current_env_inner(),
+            MachineState::Return(..) => {
+                let contexts = self.machine.collect_nested_contexts();
+                let env = contexts.iter().find_map(frame_env).ok_or_else(|| {
+                    DebuggerError::MachineError("No env in scope: the value returns to the top level".to_string())
+                })?;
+                Ok(SerializableEnv::from_uplc_env_with_ids(env, &self.term_ids))
+            }
+            MachineState::Done(_) => {
+                Err(DebuggerError::MachineError("Execution is done, there is no env in scope".to_string()).into())
+            }
+        }
+    }
+
+    /// Evaluates a closed UPLC term (textual syntax) on a separate machine with its
+    /// own budget, step cap and result size cap; the session itself is never touched
+    pub fn evaluate_expression(&self, expression: String, limits_json: Option<String>) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let result = self.evaluate_expression_inner(&expression, limits_json.as_deref())?;
+        self.to_json(&result)
+    }
+
+    pub(crate) fn evaluate_expression_inner(&self, expression: &str, limits_json: Option<&str>) -> Result<SandboxResult, JsError> {
+        let limits = match limits_json {
+            Some(limits_json) => serde_json::from_str::<SandboxLimits>(limits_json)
+                .map_err(|e| DebuggerError::MachineError(format!("Invalid sandbox limits: {}", e)))?,
+            None => self.sandbox_limits.clone(),
+        };
+        let program = match parse_expression(expression, self.program_version) {
+            Ok(program) => program,
+            Err(message) => {
+                return Ok(SandboxResult {
+                    outcome: SandboxOutcome::ParseError { message },
+                    steps: 0,
+                    allocated_bytes: 0,
+                    budget: SerializableBudget::new(0, limits.cpu, 0, limits.mem, &Default::default()),
+                })
+            }
+        };
+        Ok(run_sandboxed(&self.language, &self.cost_model, program, &limits).map_err(DebuggerError::MachineError)?)
+    }
+
+    /// Applies a builtin (by its UPLC name, e.g. `addInteger`) to UPLC argument terms
+    /// in the same sandbox as `evaluate_expression`, adding the forces it needs
+    pub fn apply_builtin(&self, builtin: String, arguments: Vec<String>, limits_json: Option<String>) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let expression = builtin_application(&builtin, &arguments).map_err(DebuggerError::MachineError)?;
+        let result = self.evaluate_expression_inner(&expression, limits_json.as_deref())?;
+        self.to_json(&result)
+    }
+
+    /// Gets a static Force/Delay analysis of the program: forces required by each
+    /// polymorphic builtin occurrence and nodes that will fail when evaluated
+    pub fn get_force_balance(&self) -> Result<String, JsError> {
+        let report = analyze_force_balance(&self.entry_term);
+        self.to_json(&report)
+    }
+
+    /// Statically reports wasteful patterns in the loaded program (Force of Delay,
+    /// builtins applied to constants, repeated constant applications) with the
+    /// budget each fix would save. Constant applications are costed by running
+    /// them in the sandbox.
+    pub fn analyze_optimizations(&self) -> Result<String, JsError> {
+        let report = self.analyze_optimizations_inner();
+        self.to_json(&report)
+    }
+
+    pub(crate) fn analyze_optimizations_inner(&self) -> OptimizationReport {
+        analyze_optimizations(&self.entry_term, |term| {
+            let program = Program {
+                version: self.program_version,
+                term: term.clone(),
+            };
+            let result = run_sandboxed(&self.language, &self.cost_model, program, &self.sandbox_limits).ok()?;
+            matches!(result.outcome, SandboxOutcome::Done { .. }).then(|| ExBudget {
+                cpu: result.budget.ex_units_spent,
+                mem: result.budget.memory_units_spent,
+            })
+        })
+    }
+
+    /// Gets a short summary of the current state: its kind, the term about to be
+    /// computed and the innermost frame. When a Case is being decided it lists the
+    /// branches next to the evaluated scrutinee and marks the one the next step takes.
+    pub fn get_state_summary(&self) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let summary = self.get_state_summary_inner();
+        self.to_json(&summary)
+    }
+
+    pub(crate) fn get_state_summary_inner(&self) -> StateSummary {
+        summarize_state(self.machine.current_state(), &self.case_branches)
+    }
+
+    /// Gets the branch choices (Case, ifThenElse, chooseList, chooseData) taken so
+    /// far, in execution order, up to `MAX_DECISIONS`
+    pub fn get_decision_trace(&self) -> Result<String, JsError> {
+        let decisions = self.get_decision_trace_inner();
+        self.to_json(&decisions)
+    }
+
+    pub(crate) fn get_decision_trace_inner(&self) -> &DecisionTrace {
+        &self.decision_trace
+    }
+
+    /// Resets the session program back to its initial state
+    pub fn reset(&mut self) -> Result<(), JsError> {
+        let new_machine = self.new_machine(&self.entry_term)?;
+        self.restart(new_machine);
+        Ok(())
+    }
+
+    /// A machine at the start of `entry_term`, with the session's budget and cost model
+    fn new_machine(&self, entry_term: &Term<NamedDeBruijn>) -> Result<ManualMachine, DebuggerError> {
+        ManualMachine::new(
+            self.language.clone(),
+            self.cost_model.clone(),
+            self.image_budget.clone(),
+            self.slippage,
+            entry_term.clone(),
+        )
+        .map_err(|e| DebuggerError::MachineError(format!("Failed to reset manual machine: {:?}", e)))
+    }
+
+    /// Puts `new_machine` in place and clears every state derived from the old run
+    fn restart(&mut self, new_machine: ManualMachine) {
+        self.version += 1;
+        self.touch();
+        self.release_script_export();
+
+        // Replace the current machine with the new one
+        self.machine = Box::new(new_machine);
+        self.generation += 1;
+        self.previous_env_id = None;
+        self.last_binding = None;
+        self.step_count = 0;
+        self.builtin_warnings.clear();
+        self.decision_trace.clear();
+        self.recent_steps.clear();
+        self.builtin_spend.clear();
+        self.last_step_cost = (0, 0);
+        self.term_costs.clear();
+        self.profile.restart();
+        self.value_pins.restart();
+        self.execution_trace.truncate_after(0);
+        self.builtin_calls.clear();
+        self.coverage.clear();
+        self.budget_exhaustion = None;
+        self.budget_exceeded_reported = false;
+        self.budget_crossing = None;
+        self.step_history.clear();
+        self.cancel_token.clear();
+        self.last_stop_reason = None;
+        
+        // Clear any last error
+        self.last_error = None;
+    }
+
+    /// Gets which terms of the program were computed since the last reset: executed
+    /// against total terms, the same under every Lambda, Delay and Case branch,
+    /// and the roots of the subtrees never reached
+    pub fn get_coverage(&self) -> Result<String, JsError> {
+        let report = self.get_coverage_inner();
+        self.to_json(&report)
+    }
+
+    pub(crate) fn get_coverage_inner(&self) -> CoverageReport {
+        coverage_report(&self.entry_term, &self.case_branches, &|term_id| self.coverage.contains(term_id))
+    }
+
+    /// Exports the terms computed since the last reset as a `CoverageMap`, to be
+    /// merged with the maps of other sessions of the same script
+    pub fn export_coverage_map(&self) -> Result<String, JsError> {
+        let map = self.export_coverage_map_inner();
+        self.to_json(&map)
+    }
+
+    pub(crate) fn export_coverage_map_inner(&self) -> CoverageMap {
+        CoverageMap {
+            script_hash: self.get_script_hash().unwrap_or_else(|_| self.script_hash.clone()),
+            label: Some(self.redeemer.clone()),
+            executed_term_ids: self.coverage.executed(),
+        }
+    }
+
+    /// Makes the term `term_id` emit `message` as a trace each time it is about to
+    /// be computed, for printf-style instrumentation of scripts that can't be
+    /// recompiled. The term is wrapped in the session's copy of the program and the
+    /// session is reset onto it; the inserted terms add a few steps and their cost.
+    pub fn inject_trace_at(&mut self, term_id: i32, message: String) -> Result<String, JsError> {
+        let injection = self.inject_trace_at_inner(term_id, message)?;
+        self.to_json(&injection)
+    }
+
+    pub(crate) fn inject_trace_at_inner(&mut self, term_id: i32, message: String) -> Result<TraceInjection, JsError> {
+        let (term, injection) = inject_trace(&self.entry_term, term_id, &message)
+            .ok_or_else(|| DebuggerError::MachineError(format!("Unknown term id {}", term_id)))?;
+        // Everything that can fail happens before the session is touched
+        let new_machine = self.new_machine(&term)?;
+        let mut term_ids = HashSet::new();
+        collect_term_ids(&term, &mut term_ids);
+
+        self.case_branches = case_branches(&term);
+        self.term_ids = term_ids;
+        self.entry_term = Box::new(term);
+        self.trace_injections.push(injection.clone());
+        self.checkpoints.clear();
+
+        self.restart(new_machine);
+        Ok(injection)
+    }
+
+    /// Gets the traces injected with `inject_trace_at`, oldest first
+    pub fn get_trace_injections(&self) -> Result<String, JsError> {
+        self.to_json(&self.trace_injections)
+    }
+
+    /// Removes every injected trace, resetting the session onto the original program
+    pub fn clear_trace_injections(&mut self) -> Result<(), JsError> {
+        self.reset_with_program(None, None)
+    }
+
+    /// Resets the session onto a replacement program and/or arguments, keeping
+    /// the session id and budget configuration
+    ///
+    /// # Arguments
+    /// * `script_cbor` - Hex CBOR of the replacement script, without its arguments applied (unlike
+    ///   `export_program_flat`); the current program is kept when absent
+    /// * `arguments_json` - JSON array of SerializablePlutusData applied to the program; the current arguments are kept when absent
+    pub fn reset_with_program(&mut self, script_cbor: Option<String>, arguments_json: Option<String>) -> Result<(), JsError> {
+        let base_program = match script_cbor {
+            Some(script_cbor) => {
+                let script = hex::decode(&script_cbor)
+                    .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid script hex: {}", e)))?;
+                let mut buffer = Vec::new();
+                let program = Program::<FakeNamedDeBruijn>::from_cbor(&script, &mut buffer)
+                    .map(Into::<Program<NamedDeBruijn>>::into)
+                    .map_err(|e| DebuggerError::ProgramBuildError(e.to_string()))?;
+                Box::new(program)
+            }
+            None => self.base_program.clone(),
+        };
+
+        let arguments = match arguments_json {
+            Some(arguments_json) => serde_json::from_str::<Vec<SerializablePlutusData>>(&arguments_json)
+                .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid arguments: {}", e)))?
+                .iter()
+                .map(|argument| argument.to_pallas().map_err(DebuggerError::ProgramBuildError))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Arc::new)?,
+            None => self.arguments.clone(),
+        };
+
+        self.replace_program(base_program, arguments)
+    }
+
+    /// Saves what `DebuggerEngine::load_session` needs to rebuild this session, e.g.
+    /// across a page reload: the program and its arguments, injected traces, budgets,
+    /// the step position and the term and builtin breakpoints. Breakpoint conditions,
+    /// checkpoints and the step history are not saved.
+    pub fn save_session(&self) -> Result<Vec<u8>, JsError> {
+        let saved = self.save_session_inner()?;
+        Ok(saved.to_bytes().map_err(DebuggerError::MachineError)?)
+    }
+
+    pub(crate) fn save_session_inner(&self) -> Result<SavedSession, JsError> {
+        let budget = |budget: &ExBudget| FixtureBudget {
+            cpu: budget.cpu,
+            mem: budget.mem,
+        };
+        Ok(SavedSession {
+            session_id: self.session_id.clone(),
+            redeemer: self.redeemer.clone(),
+            script_hash: self.script_hash.clone(),
+            program_flat: encode_program_flat(&self.base_program)?,
+            arguments: encode_arguments(&self.arguments)?,
+            trace_injections: self
+                .trace_injections
+                .iter()
+                .map(|injection| SavedTraceInjection {
+                    term_id: injection.term_id,
+                    message: injection.message.clone(),
+                })
+                .collect(),
+            machine_budget: budget(&self.image_budget),
+            slippage: self.slippage,
+            declared_budget: budget(&self.real_budget),
+            step_count: self.step_count,
+            step_limit: self.step_limit,
+            breakpoints: self.get_breakpoints(),
+            builtin_breakpoints: self.get_builtin_breakpoints(),
+        })
+    }
+
+    fn replace_program(&mut self, base_program: Box<Program<NamedDeBruijn>>, arguments: Arc<Vec<PlutusData>>) -> Result<(), JsError> {
+        // Everything that can fail happens before the session is touched
+        let program = apply_arguments(&base_program, &arguments);
+        let new_machine = self.new_machine(&program.term)?;
+        let mut term_ids = HashSet::new();
+        collect_term_ids(&program.term, &mut term_ids);
+
+        self.program_version = program.version;
+        self.case_branches = case_branches(&program.term);
+        self.entry_term = Box::new(program.term);
+        self.annotations.retain_terms(&term_ids);
+        self.term_ids = term_ids;
+        self.base_program = base_program;
+        self.arguments = arguments;
+        // Checkpoints hold states of the replaced program
+        self.checkpoints.clear();
+        self.trace_injections.clear();
+
+        self.restart(new_machine);
+        Ok(())
+    }
+
+    /// Brings a session just created for `saved.redeemer` to the state `saved`
+    /// describes, replaying the steps it had executed
+    pub(crate) fn load_saved(&mut self, saved: &SavedSession) -> Result<(), JsError> {
+        if normalize_script_hash(&saved.script_hash).ok().as_deref() != Some(self.script_hash.as_str()) {
+            return Err(DebuggerError::ScriptNotFound(format!(
+                "saved session runs script {}, but {} runs {}",
+                saved.script_hash, self.redeemer, self.script_hash
+            ))
+            .into());
+        }
+        self.session_id = saved.session_id.clone();
+        self.image_budget = ExBudget {
+            cpu: saved.machine_budget.cpu,
+            mem: saved.machine_budget.mem,
+        };
+        self.real_budget = ExBudget {
+            cpu: saved.declared_budget.cpu,
+            mem: saved.declared_budget.mem,
+        };
+        self.slippage = saved.slippage;
+        // Programs re-decoded from flat could number their terms differently, so the
+        // current program is kept unless the saved one was replaced
+        if saved.program_flat != encode_program_flat(&self.base_program)?
+            || saved.arguments != encode_arguments(&self.arguments)?
+        {
+            let base_program = decode_program_flat(&saved.program_flat)?;
+            let arguments = decode_arguments(&saved.arguments)?;
+            self.replace_program(Box::new(base_program), Arc::new(arguments))?;
+        } else {
+            self.reset()?;
+        }
+        for injection in &saved.trace_injections {
+            self.inject_trace_at_inner(injection.term_id, injection.message.clone())?;
+        }
+        self.set_breakpoints(saved.breakpoints.clone());
+        self.builtin_breakpoints = saved.builtin_breakpoints.iter().cloned().collect();
+        self.step_limit = saved.step_limit;
+
+        while self.step_count < saved.step_count {
+            if !matches!(self.step_inner()?.status, SerializableExecutionStatus::Ready) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_last_error(&self) -> Option<String> {
+        self.last_error.clone()
+    }
+
+    /// Gets the current version number of the session controller
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+    
+    /// Sets the size in bytes above which lazy getters return a ByteString preview
+    /// (first/last bytes, length and blake2b-256 hash) instead of the full hex.
+    /// The full hex is fetched by navigating to the constant's `value` field.
+    /// `None` disables previews.
+    pub fn set_bytestring_preview_threshold(&mut self, threshold: Option<u32>) {
+        self.serializer_options.bytestring_preview_threshold = threshold.map(|t| t as usize);
+    }
+
+    /// Sets the length above which `get_machine_state`, `get_current_env`,
+    /// `get_machine_context` and `get_machine_context_page` replace ByteString
+    /// constants (in bytes) and Integer constants (in decimal digits) with a
+    /// `Truncated` preview. Its `_root` names the lazy getter ("state", "env" or
+    /// "context") that returns the whole constant at `_path` followed by `value`.
+    /// Other getters keep constants whole. `None` disables truncation.
+    pub fn set_constant_truncation_threshold(&mut self, threshold: Option<u32>) {
+        self.serializer_options.constant_truncation_threshold = threshold.map(|t| t as usize);
+    }
+
+    /// Sets the JSON form of every getter of this session: "json" (the default)
+    /// or "compact", with the short keys of `get_compact_json_keys`. Lazy getters
+    /// keep the full keys their paths name. CBOR is given as bytes by `export_as`.
+    pub fn set_json_format(&mut self, format: &str) -> Result<(), JsError> {
+        self.json_format = SerializationFormat::json_from_name(format).map_err(DebuggerError::MachineError)?;
+        self.release_script_export();
+        Ok(())
+    }
+
+    /// Adds a `utf8` rendering to ByteString constants that are text, and a
+    /// `base64` rendering to all of them, next to their hex, in every getter of this session
+    pub fn set_bytestring_rendering(&mut self, utf8: bool, base64: bool) {
+        self.serializer_options.bytestring_rendering = crate::lazy_loading::ByteStringRendering { utf8, base64 };
+        self.release_script_export();
+    }
+
+    /// Get machine state with lazy loading support
+    /// 
+    /// # Arguments
+    /// * `path` - JSON array of path segments to navigate to specific element
+    /// * `return_full_object` - If true, returns full object at path; if false, returns object with children 1 level deep only
+    pub fn get_machine_state_lazy(&self, path: String, return_full_object: bool) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let path_segments = LazySessionApi::parse_path(&path)?;
+        let json = LazySessionApi::get_machine_state_lazy(&self.machine, &self.term_ids, path_segments, return_full_object)?;
+        let json = self.enveloped(json);
+        self.quota.borrow_mut().record_serialized_bytes(json.len());
+        Ok(json)
+    }
+    
+    /// Get current environment with lazy loading support
+    /// 
+    /// # Arguments
+    /// * `path` - JSON array of path segments to navigate to specific element
+    /// * `return_full_object` - If true, returns full object at path; if false, returns object with children 1 level deep only
+    pub fn get_current_env_lazy(&self, path: String, return_full_object: bool) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let path_segments = LazySessionApi::parse_path(&path)?;
+        let json = LazySessionApi::get_current_env_lazy(&self.machine, &self.term_ids, path_segments, return_full_object)?;
+        let json = self.enveloped(json);
+        self.quota.borrow_mut().record_serialized_bytes(json.len());
+        Ok(json)
+    }
+    
+    /// Get machine context with lazy loading support
+    /// 
+    /// # Arguments
+    /// * `path` - JSON array of path segments to navigate to specific element (e.g., ["0", "env", "values", "2"])
+    /// * `return_full_object` - If true, returns full object at path; if false, returns object with children 1 level deep only
+    pub fn get_machine_context_lazy(&self, path: String, return_full_object: bool) -> Result<String, JsError> {
+        let _options = self.serializer_scope();
+        let path_segments = LazySessionApi::parse_path(&path)?;
+        let json = LazySessionApi::get_machine_context_lazy(
+            &self.machine,
+            &self.term_ids,
+            &self.case_branches,
+            path_segments,
+            return_full_object,
+        )?;
+        let json = self.enveloped(json);
+        self.quota.borrow_mut().record_serialized_bytes(json.len());
+        Ok(json)
+    }
+}
+
+impl SessionController {
+    /// Native counterpart of `set_progress_callback`
+    pub fn set_progress_hook(&mut self, hook: impl Fn(&RunProgress) + 'static, interval_steps: u64) {
+        self.progress.set(std::rc::Rc::new(hook), interval_steps);
+    }
+}
+
+pub(crate) fn apply_arguments(base_program: &Program<NamedDeBruijn>, arguments: &[PlutusData]) -> Program<NamedDeBruijn> {
+    arguments
+        .iter()
+        .fold(base_program.clone(), |program, argument| program.apply_data(argument.clone()))
+}
+
+fn collect_term_ids(term: &Term<NamedDeBruijn>, term_ids: &mut HashSet<i32>) {
+    // First, collect the current term's ID
+    let uniq_id = match term {
+        Term::Var { uniq_id, .. }
+        | Term::Delay { uniq_id, .. }
+        | Term::Lambda { uniq_id, .. }
+        | Term::Apply { uniq_id, .. }
+        | Term::Constant { uniq_id, .. }
+        | Term::Force { uniq_id, .. }
+        | Term::Error { uniq_id, .. }
+        | Term::Builtin { uniq_id, .. }
+        | Term::Constr { uniq_id, .. }
+        | Term::Case { uniq_id, .. } => *uniq_id as i32,
+    };
+    
+    term_ids.insert(uniq_id);
+    
+    // Then recursively collect IDs from nested terms
+    match term {
+        Term::Delay { body, .. } => {
+            collect_term_ids(body, term_ids);
+        }
+        Term::Lambda { body, .. } => {
+            collect_term_ids(body, term_ids);
+        }
+        Term::Apply { function, argument, .. } => {
+            collect_term_ids(function, term_ids);
+            collect_term_ids(argument, term_ids);
+        }
+        Term::Force { body, .. } => {
+            collect_term_ids(body, term_ids);
+        }
+        Term::Constr { fields, .. } => {
+            for field in fields {
+                collect_term_ids(field, term_ids);
+            }
+        }
+        Term::Case { constr, branches, .. } => {
+            collect_term_ids(constr, term_ids);
+            for branch in branches {
+                collect_term_ids(branch, term_ids);
+            }
+        }
+        // These variants don't contain nested terms
+        Term::Var { .. }
+        | Term::Constant { .. }
+        | Term::Error { .. }
+        | Term::Builtin { .. } => {
+            // No nested terms to process
+        }
+    }
+}
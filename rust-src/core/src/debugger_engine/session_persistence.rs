@@ -0,0 +1,103 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::fixture::FixtureBudget;
+use super::session_controller::DEFAULT_SLIPPAGE;
+
+/// Leading bytes of a saved session, followed by a format version
+const SAVED_SESSION_MAGIC: &[u8; 4] = b"UPSS";
+const SAVED_SESSION_VERSION: u8 = 1;
+
+/// Trace inserted with `inject_trace_at`, re-inserted in order when loading
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct SavedTraceInjection {
+    pub term_id: i32,
+    pub message: String,
+}
+
+/// What a session needs to be rebuilt from its transaction and brought back to
+/// where it was. The machine itself is not stored: loading replays the steps.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct SavedSession {
+    pub session_id: String,
+    pub redeemer: String,
+    pub script_hash: String,
+    /// Unapplied program the session runs, flat encoded (hex); differs from the
+    /// transaction's script after `reset_with_program`
+    pub program_flat: String,
+    /// Arguments applied to the program in order, CBOR encoded (hex)
+    pub arguments: Vec<String>,
+    pub trace_injections: Vec<SavedTraceInjection>,
+    /// Budget the machine starts with
+    pub machine_budget: FixtureBudget,
+    #[serde(default = "default_slippage")]
+    pub slippage: u32,
+    /// Ex units declared by the redeemer
+    pub declared_budget: FixtureBudget,
+    /// Steps executed since the last reset
+    pub step_count: u64,
+    pub step_limit: Option<u64>,
+    pub breakpoints: Vec<i32>,
+    pub builtin_breakpoints: Vec<String>,
+}
+
+fn default_slippage() -> u32 {
+    DEFAULT_SLIPPAGE
+}
+
+impl SavedSession {
+    /// `UPSS`, a version byte, then the session as JSON
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let json = serde_json::to_vec(self).map_err(|e| e.to_string())?;
+        let mut out = Vec::with_capacity(5 + json.len());
+        out.extend_from_slice(SAVED_SESSION_MAGIC);
+        out.push(SAVED_SESSION_VERSION);
+        out.extend_from_slice(&json);
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 5 || &bytes[..4] != SAVED_SESSION_MAGIC {
+            return Err("Not a saved session".to_string());
+        }
+        if bytes[4] != SAVED_SESSION_VERSION {
+            return Err(format!("Unsupported saved session version {}", bytes[4]));
+        }
+        serde_json::from_slice(&bytes[5..]).map_err(|e| format!("Invalid saved session: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_session_round_trip() {
+        let budget = FixtureBudget { cpu: 10_000, mem: 500 };
+        let saved = SavedSession {
+            session_id: "id".to_string(),
+            redeemer: "spend:0".to_string(),
+            script_hash: "ab".to_string(),
+            program_flat: "0101".to_string(),
+            arguments: vec!["d87980".to_string()],
+            trace_injections: vec![SavedTraceInjection {
+                term_id: 3,
+                message: "here".to_string(),
+            }],
+            machine_budget: budget.clone(),
+            slippage: DEFAULT_SLIPPAGE,
+            declared_budget: budget,
+            step_count: 42,
+            step_limit: None,
+            breakpoints: vec![1, 7],
+            builtin_breakpoints: vec!["EqualsData".to_string()],
+        };
+
+        let bytes = saved.to_bytes().unwrap();
+        assert_eq!(SavedSession::from_bytes(&bytes).unwrap(), saved);
+        assert!(SavedSession::from_bytes(&bytes[1..]).is_err());
+        let mut newer = bytes;
+        newer[4] = SAVED_SESSION_VERSION + 1;
+        assert!(SavedSession::from_bytes(&newer).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::quota::QuotaLimits;
+use super::sandbox::SandboxLimits;
+use super::session_controller::DEFAULT_SLIPPAGE;
+use super::step_history::DEFAULT_STEP_HISTORY;
+use crate::lazy_loading::{ByteStringRendering, DEFAULT_BYTESTRING_PREVIEW_THRESHOLD};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, JsonSchema)]
+pub enum ScriptHashFormat {
+    #[default]
+    #[serde(rename = "hex")]
+    Hex,
+    /// CIP-5 `script1…`
+    #[serde(rename = "bech32")]
+    Bech32,
+}
+
+/// Options applied to the engine and every session it creates, given once at
+/// construction instead of through individual setters. Missing fields keep their defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(default)]
+pub struct EngineSettings {
+    /// Size in bytes above which ByteStrings are previewed; `null` always returns the full hex
+    pub bytestring_preview_threshold: Option<usize>,
+    /// Length above which the full state, env and context getters truncate ByteString
+    /// and Integer constants (see `set_constant_truncation_threshold`); `null` keeps them whole
+    pub constant_truncation_threshold: Option<usize>,
+    /// Renderings added next to the hex of ByteString constants
+    pub bytestring_rendering: ByteStringRendering,
+    /// Form in which sessions report their script hash
+    pub script_hash_format: ScriptHashFormat,
+    /// Step limit set on every new session
+    pub step_limit: Option<u64>,
+    /// Condition breakpoints (see `add_condition_breakpoint`) added to every new session
+    pub condition_breakpoints: Vec<String>,
+    /// Default limits for `evaluate_expression` and `apply_builtin`
+    pub sandbox: SandboxLimits,
+    pub quota: QuotaLimits,
+    /// Check division and integerToByteString arguments before each step (see `set_arithmetic_guard`)
+    pub arithmetic_guard: bool,
+    /// Number of prior machine states each session keeps for `step_back`; 0, the
+    /// default, disables it
+    pub step_history: usize,
+    /// Record every step of every session for `export_trace` (see `set_trace_recording`)
+    pub record_execution_trace: bool,
+    /// Wrap JSON responses as `{"schema_version": …, "payload": …}` (see `get_schema_version`);
+    /// on by default, `false` returns the bare payloads
+    pub json_envelope: bool,
+    /// Give every session's responses in compact JSON (see `set_json_format`)
+    pub compact_json: bool,
+}
+
+impl Default for EngineSettings {
+    fn default() -> Self {
+        EngineSettings {
+            bytestring_preview_threshold: Some(DEFAULT_BYTESTRING_PREVIEW_THRESHOLD),
+            constant_truncation_threshold: None,
+            bytestring_rendering: ByteStringRendering::default(),
+            script_hash_format: ScriptHashFormat::default(),
+            step_limit: None,
+            condition_breakpoints: vec![],
+            sandbox: SandboxLimits::default(),
+            quota: QuotaLimits::default(),
+            arithmetic_guard: false,
+            step_history: DEFAULT_STEP_HISTORY,
+            record_execution_trace: false,
+            json_envelope: true,
+            compact_json: false,
+        }
+    }
+}
+
+impl EngineSettings {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+}
+
+/// Budget the machine of a new session starts with
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, JsonSchema)]
+#[serde(tag = "mode")]
+pub enum BudgetMode {
+    /// The redeemer's declared ex units, so the machine fails where the ledger would
+    Declared,
+    /// Practically unlimited, so execution goes on past the declared ex units
+    #[default]
+    Max,
+    Custom { cpu: i64, mem: i64 },
+}
+
+/// Options for a single session, given to `init_debug_session` or `open_session`.
+/// Missing fields keep their defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct SessionOptions {
+    /// Number of machine steps whose cost is charged together
+    pub slippage: u32,
+    pub budget_mode: BudgetMode,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        SessionOptions {
+            slippage: DEFAULT_SLIPPAGE,
+            budget_mode: BudgetMode::default(),
+        }
+    }
+}
+
+impl SessionOptions {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let options: SessionOptions = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if options.slippage == 0 {
+            return Err("slippage must be at least 1".to_string());
+        }
+        if let BudgetMode::Custom { cpu, mem } = options.budget_mode {
+            if cpu < 0 || mem < 0 {
+                return Err("custom budget cpu and mem must not be negative".to_string());
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_options_from_json() {
+        let options = SessionOptions::from_json(r#"{"budget_mode":{"mode":"Custom","cpu":10,"mem":5}}"#).unwrap();
+        assert_eq!(options.slippage, DEFAULT_SLIPPAGE);
+        assert_eq!(options.budget_mode, BudgetMode::Custom { cpu: 10, mem: 5 });
+        assert_eq!(SessionOptions::from_json("{}").unwrap(), SessionOptions::default());
+        assert!(SessionOptions::from_json(r#"{"slippage":0}"#).is_err());
+        assert!(SessionOptions::from_json(r#"{"budget_mode":{"mode":"Custom","cpu":-1,"mem":5}}"#).is_err());
+        assert!(SessionOptions::from_json(r#"{"budget_mode":{"mode":"Custom","cpu":10,"mem":-5}}"#).is_err());
+    }
+}
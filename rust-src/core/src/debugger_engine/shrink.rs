@@ -0,0 +1,286 @@
+use std::mem::Discriminant;
+
+use pallas_primitives::conway::Language;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{FakeNamedDeBruijn, NamedDeBruijn, Program},
+    machine::{cost_model::{CostModel, ExBudget}, Error as MachineFailure},
+    manual_machine::{ExecutionStatus, ManualMachine},
+    PlutusData,
+};
+
+use super::debugger_engine::cost_model_for;
+use super::quick_run::QuickRunEnvironment;
+use super::session_controller::{apply_arguments, DEFAULT_SLIPPAGE};
+use super::DebuggerError;
+use crate::plutus_data::{SerializableBigInt, SerializableKeyValuePair, SerializablePlutusData};
+use crate::wasm_tools::{enveloped_json, wasm_bindgen, JsError};
+
+/// Upper bound on program evaluations spent looking for smaller arguments
+const MAX_SHRINK_ATTEMPTS: usize = 2000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ShrinkReport {
+    /// Error raised with the arguments as given
+    pub original_error: String,
+    /// Error raised with the minimized arguments (same kind as the original)
+    pub error: String,
+    /// Minimized reproducer, in the order the arguments are applied
+    pub arguments: Vec<SerializablePlutusData>,
+    /// Number of data nodes across all arguments before and after shrinking
+    pub original_size: usize,
+    pub shrunk_size: usize,
+    /// Evaluations performed and how many of them were accepted as reductions
+    pub attempts: usize,
+    pub reductions: usize,
+    /// Whether the search stopped at the attempt limit rather than at a local minimum
+    pub exhausted: bool,
+}
+
+struct Failure {
+    kind: Discriminant<MachineFailure>,
+    message: String,
+}
+
+/// Standalone program evaluated against candidate arguments
+struct Evaluator {
+    program: Program<NamedDeBruijn>,
+    language: Language,
+    cost_model: CostModel,
+    budget: ExBudget,
+}
+
+impl Evaluator {
+    fn failure(&self, arguments: &[SerializablePlutusData]) -> Result<Option<Failure>, DebuggerError> {
+        let arguments = arguments
+            .iter()
+            .map(|argument| argument.to_pallas())
+            .collect::<Result<Vec<PlutusData>, _>>()
+            .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid argument: {}", e)))?;
+        let program = apply_arguments(&self.program, &arguments);
+        let mut machine = ManualMachine::new(
+            self.language.clone(),
+            self.cost_model.clone(),
+            self.budget,
+            DEFAULT_SLIPPAGE,
+            program.term,
+        )
+        .map_err(|e| DebuggerError::MachineError(format!("Failed to create manual machine: {:?}", e)))?;
+
+        loop {
+            match machine.step() {
+                ExecutionStatus::Ready => continue,
+                ExecutionStatus::Done(_) => return Ok(None),
+                ExecutionStatus::Error(error) => {
+                    return Ok(Some(Failure {
+                        kind: std::mem::discriminant(error),
+                        message: error.to_string(),
+                    }))
+                }
+            }
+        }
+    }
+}
+
+/// Number of nodes in a data value, used to report how much was removed
+fn data_size(data: &SerializablePlutusData) -> usize {
+    1 + match data {
+        SerializablePlutusData::Constr { fields, .. } => fields.iter().map(data_size).sum(),
+        SerializablePlutusData::Map { key_value_pairs } => key_value_pairs
+            .iter()
+            .map(|pair| data_size(&pair.key) + data_size(&pair.value))
+            .sum(),
+        SerializablePlutusData::Array { values } => values.iter().map(data_size).sum(),
+        SerializablePlutusData::BigInt(_) | SerializablePlutusData::BoundedBytes { .. } => 0,
+    }
+}
+
+/// Values one reduction step smaller than `data`: dropped list elements and map
+/// entries first, then zeroed integers and truncated bytes, then the same
+/// reductions applied to nested values
+fn shrink_candidates(data: &SerializablePlutusData) -> Vec<SerializablePlutusData> {
+    let mut candidates = vec![];
+    match data {
+        SerializablePlutusData::Array { values } => {
+            candidates.extend((0..values.len()).map(|i| {
+                let mut values = values.clone();
+                values.remove(i);
+                SerializablePlutusData::Array { values }
+            }));
+            for (i, value) in values.iter().enumerate() {
+                candidates.extend(shrink_candidates(value).into_iter().map(|candidate| {
+                    let mut values = values.clone();
+                    values[i] = candidate;
+                    SerializablePlutusData::Array { values }
+                }));
+            }
+        }
+        SerializablePlutusData::Map { key_value_pairs } => {
+            candidates.extend((0..key_value_pairs.len()).map(|i| {
+                let mut key_value_pairs = key_value_pairs.clone();
+                key_value_pairs.remove(i);
+                SerializablePlutusData::Map { key_value_pairs }
+            }));
+            for (i, pair) in key_value_pairs.iter().enumerate() {
+                let keys = shrink_candidates(&pair.key).into_iter().map(|key| SerializableKeyValuePair {
+                    key,
+                    value: pair.value.clone(),
+                });
+                let values = shrink_candidates(&pair.value).into_iter().map(|value| SerializableKeyValuePair {
+                    key: pair.key.clone(),
+                    value,
+                });
+                candidates.extend(keys.chain(values).map(|candidate| {
+                    let mut key_value_pairs = key_value_pairs.clone();
+                    key_value_pairs[i] = candidate;
+                    SerializablePlutusData::Map { key_value_pairs }
+                }));
+            }
+        }
+        SerializablePlutusData::Constr { tag, any_constructor, fields } => {
+            // Fields are never dropped: a constructor with a different arity is a
+            // different type and would mostly trade the failure for a decoding error
+            for (i, field) in fields.iter().enumerate() {
+                candidates.extend(shrink_candidates(field).into_iter().map(|candidate| {
+                    let mut fields = fields.clone();
+                    fields[i] = candidate;
+                    SerializablePlutusData::Constr {
+                        tag: *tag,
+                        any_constructor: *any_constructor,
+                        fields,
+                    }
+                }));
+            }
+        }
+        SerializablePlutusData::BigInt(SerializableBigInt::Int(value)) if value == "0" => {}
+        SerializablePlutusData::BigInt(_) => candidates.push(SerializablePlutusData::integer(0)),
+        SerializablePlutusData::BoundedBytes { value } => {
+            let length = value.len() / 2;
+            if length > 1 {
+                candidates.push(SerializablePlutusData::BoundedBytes {
+                    value: value[..length / 2 * 2].to_string(),
+                });
+            }
+            if length > 0 {
+                candidates.push(SerializablePlutusData::BoundedBytes { value: String::new() });
+            }
+        }
+    }
+    candidates
+}
+
+fn shrink_arguments(evaluator: &Evaluator, arguments: Vec<SerializablePlutusData>) -> Result<ShrinkReport, DebuggerError> {
+    let original = evaluator.failure(&arguments)?.ok_or_else(|| {
+        DebuggerError::MachineError("Program does not fail with the given arguments".to_string())
+    })?;
+    let original_size = arguments.iter().map(data_size).sum();
+
+    let mut arguments = arguments;
+    let mut error = original.message.clone();
+    let mut attempts = 1;
+    let mut reductions = 0;
+    let mut exhausted = false;
+
+    'search: loop {
+        for index in 0..arguments.len() {
+            for candidate in shrink_candidates(&arguments[index]) {
+                if attempts >= MAX_SHRINK_ATTEMPTS {
+                    exhausted = true;
+                    break 'search;
+                }
+                attempts += 1;
+
+                let mut trial = arguments.clone();
+                trial[index] = candidate;
+                match evaluator.failure(&trial)? {
+                    Some(failure) if failure.kind == original.kind => {
+                        arguments = trial;
+                        error = failure.message;
+                        reductions += 1;
+                        continue 'search;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        break;
+    }
+
+    Ok(ShrinkReport {
+        original_error: original.message,
+        error,
+        shrunk_size: arguments.iter().map(data_size).sum(),
+        arguments,
+        original_size,
+        attempts,
+        reductions,
+        exhausted,
+    })
+}
+
+/// Experimental: for a script that fails when applied to the given data
+/// arguments (datum, redeemer, context...), repeatedly drops list elements and
+/// map entries, zeroes integers and truncates bytes while the same kind of
+/// failure persists, and reports the minimized reproducer
+#[wasm_bindgen]
+pub fn shrink_failure(
+    script_cbor: &str,
+    arguments_json: &str,
+    environment_json: &str,
+) -> Result<String, JsError> {
+    let environment = serde_json::from_str::<QuickRunEnvironment>(environment_json)
+        .map_err(|e| DebuggerError::TransactionParseError(format!("Invalid environment: {}", e)))?;
+    let arguments = serde_json::from_str::<Vec<SerializablePlutusData>>(arguments_json)
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid arguments: {}", e)))?;
+
+    let script = hex::decode(script_cbor)
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Invalid script hex: {}", e)))?;
+    let mut buffer = Vec::new();
+    let program = Program::<FakeNamedDeBruijn>::from_cbor(&script, &mut buffer)
+        .map(Into::<Program<NamedDeBruijn>>::into)
+        .map_err(|e| DebuggerError::ProgramBuildError(e.to_string()))?;
+
+    let language = environment.language()?;
+    let ex_units = environment.ex_units();
+    let evaluator = Evaluator {
+        program,
+        cost_model: cost_model_for(&environment.protocol_params, &language)?,
+        language,
+        budget: ExBudget {
+            mem: ex_units.mem as i64,
+            cpu: ex_units.steps as i64,
+        },
+    };
+
+    let report = shrink_arguments(&evaluator, arguments)?;
+    Ok(enveloped_json(&report).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrink_candidates() {
+        let data = SerializablePlutusData::constructor(
+            0,
+            vec![
+                SerializablePlutusData::array(vec![SerializablePlutusData::integer(5), SerializablePlutusData::integer(0)]),
+                SerializablePlutusData::bytes_from_hex("deadbeef").unwrap(),
+            ],
+        );
+        assert_eq!(data_size(&data), 5);
+
+        let candidates = shrink_candidates(&data);
+        // two element drops, one zeroed int, two byte truncations
+        assert_eq!(candidates.len(), 5);
+        assert!(candidates.iter().all(|candidate| data_size(candidate) <= data_size(&data)));
+
+        let SerializablePlutusData::Constr { fields, .. } = &candidates[4] else {
+            panic!("expected a constructor");
+        };
+        assert!(matches!(&fields[1], SerializablePlutusData::BoundedBytes { value } if value.is_empty()));
+        assert!(shrink_candidates(&SerializablePlutusData::integer(0)).is_empty());
+    }
+}
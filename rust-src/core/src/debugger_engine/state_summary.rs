@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{NamedDeBruijn, Term},
+    machine::{Context, MachineState},
+};
+
+use super::decisions::{case_choice, term_id};
+
+/// A branch of the Case being decided
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CaseBranchPreview {
+    pub index: usize,
+    pub term_id: i32,
+    /// Term kind of the branch, e.g. "Lambda"
+    pub term_type: String,
+    /// Whether the next step continues with this branch
+    pub selected: bool,
+}
+
+/// The branches of a Case with the scrutinee that will pick one of them
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CasePreview {
+    /// Id of the Case term, when known
+    pub case_term_id: Option<i32>,
+    /// The evaluated scrutinee, e.g. `Constr 1 (2 fields)`; None while it is still being computed
+    pub scrutinee: Option<String>,
+    /// Lazy getter path (under the machine state) of the full scrutinee value
+    pub scrutinee_path: Option<Vec<String>>,
+    pub branches: Vec<CaseBranchPreview>,
+    pub selected_branch: Option<usize>,
+    /// Set when the scrutinee selects no branch, so the next step fails
+    pub note: Option<String>,
+}
+
+/// What the next step will do, in a form cheap enough to fetch after every step
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct StateSummary {
+    /// "Compute", "Return" or "Done"
+    pub state_type: String,
+    /// Term about to be computed, -1 outside Compute states
+    pub term_id: i32,
+    /// Kind of the innermost frame, e.g. "FrameCases"
+    pub frame_type: Option<String>,
+    pub case_preview: Option<CasePreview>,
+}
+
+fn term_type(term: &Term<NamedDeBruijn>) -> &'static str {
+    match term {
+        Term::Var { .. } => "Var",
+        Term::Delay { .. } => "Delay",
+        Term::Lambda { .. } => "Lambda",
+        Term::Apply { .. } => "Apply",
+        Term::Constant { .. } => "Constant",
+        Term::Force { .. } => "Force",
+        Term::Error { .. } => "Error",
+        Term::Builtin { .. } => "Builtin",
+        Term::Constr { .. } => "Constr",
+        Term::Case { .. } => "Case",
+    }
+}
+
+pub(crate) fn frame_type(context: &Context) -> &'static str {
+    match context {
+        Context::FrameAwaitArg(..) => "FrameAwaitArg",
+        Context::FrameAwaitFunTerm(..) => "FrameAwaitFunTerm",
+        Context::FrameAwaitFunValue(..) => "FrameAwaitFunValue",
+        Context::FrameForce(..) => "FrameForce",
+        Context::FrameConstr(..) => "FrameConstr",
+        Context::FrameCases(..) => "FrameCases",
+        Context::NoFrame => "NoFrame",
+    }
+}
+
+fn branch_previews(branches: &[Term<NamedDeBruijn>], selected: Option<usize>) -> Vec<CaseBranchPreview> {
+    branches
+        .iter()
+        .enumerate()
+        .map(|(index, branch)| CaseBranchPreview {
+            index,
+            term_id: term_id(branch),
+            term_type: term_type(branch).to_string(),
+            selected: selected == Some(index),
+        })
+        .collect()
+}
+
+fn case_preview(state: &MachineState, case_branches: &HashMap<i32, (i32, usize)>) -> Option<CasePreview> {
+    match state {
+        // The scrutinee is computed first; the branches are known but none is chosen yet
+        MachineState::Compute(_, _, Term::Case { branches, uniq_id, .. }) => Some(CasePreview {
+            case_term_id: Some(*uniq_id as i32),
+            scrutinee: None,
+            scrutinee_path: None,
+            branches: branch_previews(branches, None),
+            selected_branch: None,
+            note: None,
+        }),
+        MachineState::Return(Context::FrameCases(_, branches, ..), value) => {
+            let choice = case_choice(value);
+            let selected = choice.as_ref().map(|(index, _)| *index).filter(|index| *index < branches.len());
+            let note = match &choice {
+                Some((index, _)) if selected.is_none() => {
+                    Some(format!("No branch {} among {}: the next step fails", index, branches.len()))
+                }
+                None => Some("The scrutinee is not a constructor: the next step fails".to_string()),
+                _ => None,
+            };
+            Some(CasePreview {
+                case_term_id: branches
+                    .first()
+                    .and_then(|branch| case_branches.get(&term_id(branch)))
+                    .map(|(case_id, _)| *case_id),
+                scrutinee: choice.map(|(_, description)| description),
+                scrutinee_path: Some(vec!["value".to_string()]),
+                branches: branch_previews(branches, selected),
+                selected_branch: selected,
+                note,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The fields of a [`StateSummary`] that can be read without allocating, taken
+/// before a step so the summary is only built when the step turns out to need it
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StateSketch {
+    state_type: &'static str,
+    term_id: i32,
+    frame_type: Option<&'static str>,
+}
+
+impl StateSketch {
+    pub(crate) fn of(state: &MachineState) -> Self {
+        let (state_type, term_id, frame) = match state {
+            MachineState::Compute(context, _, term) => ("Compute", term_id(term), Some(context)),
+            MachineState::Return(context, _) => ("Return", -1, Some(context)),
+            MachineState::Done(_) => ("Done", -1, None),
+        };
+        StateSketch {
+            state_type,
+            term_id,
+            frame_type: frame.map(frame_type),
+        }
+    }
+
+    /// The summary of the sketched state; the Case preview needs the state itself, so it is left out
+    pub(crate) fn summary(self) -> StateSummary {
+        StateSummary {
+            state_type: self.state_type.to_string(),
+            term_id: self.term_id,
+            frame_type: self.frame_type.map(str::to_string),
+            case_preview: None,
+        }
+    }
+}
+
+/// Summarizes the current machine state, previewing the branch a Case is about to take
+pub fn summarize_state(state: &MachineState, case_branches: &HashMap<i32, (i32, usize)>) -> StateSummary {
+    StateSummary {
+        case_preview: case_preview(state, case_branches),
+        ..StateSketch::of(state).summary()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas_primitives::conway::Language;
+    use uplc::{
+        machine::cost_model::{CostModel, ExBudget},
+        manual_machine::ManualMachine,
+    };
+
+    use super::*;
+    use crate::debugger_engine::decisions::case_branches;
+    use crate::debugger_engine::sandbox::parse_expression;
+    use crate::debugger_engine::session_controller::DEFAULT_SLIPPAGE;
+
+    /// Summaries of every state `expression` goes through, with its Case branches
+    fn summaries(expression: &str) -> Vec<StateSummary> {
+        let program = parse_expression(expression, (1, 1, 0)).unwrap();
+        let branches = case_branches(&program.term);
+        let mut machine =
+            ManualMachine::new(Language::PlutusV3, CostModel::default(), ExBudget::max(), DEFAULT_SLIPPAGE, program.term)
+                .unwrap();
+        let mut summaries = vec![summarize_state(machine.current_state(), &branches)];
+        while !matches!(machine.current_state(), MachineState::Done(_)) && summaries.len() < 50 {
+            machine.step();
+            summaries.push(summarize_state(machine.current_state(), &branches));
+        }
+        summaries
+    }
+
+    #[test]
+    fn test_summaries_follow_the_machine() {
+        let summaries = summaries("[(lam x x) (con integer 1)]");
+        assert_eq!(summaries[0].state_type, "Compute");
+        assert_eq!(summaries[0].frame_type.as_deref(), Some("NoFrame"));
+        assert!(summaries.iter().any(|summary| summary.state_type == "Return" && summary.term_id == -1));
+        let done = summaries.last().unwrap();
+        assert_eq!((done.state_type.as_str(), done.term_id, done.frame_type.as_deref()), ("Done", -1, None));
+        assert!(summaries.iter().all(|summary| summary.case_preview.is_none()));
+    }
+
+    #[test]
+    fn test_case_preview_selects_the_branch() {
+        let summaries = summaries("(case (constr 1) (con integer 10) (con integer 11))");
+
+        // Before the scrutinee is computed the branches are known, none chosen
+        let preview = summaries[0].case_preview.as_ref().unwrap();
+        assert_eq!((preview.branches.len(), preview.selected_branch), (2, None));
+        assert_eq!(preview.case_term_id, Some(summaries[0].term_id));
+
+        // Once it returns, the second branch is selected
+        let preview = summaries
+            .iter()
+            .filter_map(|summary| summary.case_preview.as_ref())
+            .find(|preview| preview.scrutinee.is_some())
+            .unwrap();
+        assert_eq!(preview.selected_branch, Some(1));
+        assert!(preview.branches[1].selected && !preview.branches[0].selected);
+        assert_eq!(preview.branches[1].term_type, "Constant");
+        assert_eq!(preview.scrutinee.as_deref(), Some("Constr 1 (0 fields)"));
+        assert!(preview.note.is_none());
+    }
+
+    #[test]
+    fn test_case_preview_notes_a_missing_branch() {
+        let summaries = summaries("(case (constr 3) (con integer 10))");
+        let preview = summaries
+            .iter()
+            .filter_map(|summary| summary.case_preview.as_ref())
+            .find(|preview| preview.scrutinee.is_some())
+            .unwrap();
+        assert_eq!(preview.selected_branch, None);
+        assert_eq!(preview.note.as_deref(), Some("No branch 3 among 1: the next step fails"));
+    }
+}
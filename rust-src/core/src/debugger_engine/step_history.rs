@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::manual_machine::ManualMachine;
+
+use super::budget_exhaustion::{BudgetExhaustionReport, RecentSteps};
+use super::builtin_checks::BuiltinMisuse;
+use super::coverage::CoverageTracker;
+use super::decisions::DecisionTrace;
+use super::execution_trace::TraceRecorder;
+use super::overshoot::BudgetCrossing;
+use super::reprice::BuiltinCallRecorder;
+use super::term_costs::TermCosts;
+use super::value_pins::ValuePins;
+use crate::budget::BuiltinSpend;
+
+/// Number of prior states a session keeps for `step_back` unless configured
+/// otherwise. Each kept state is a full copy of the machine taken on every step,
+/// so the history is off until a size is set.
+pub const DEFAULT_STEP_HISTORY: usize = 0;
+
+/// The machine as it was before a step. Traces are left out of the copy, as they
+/// only ever grow: restoring cuts the live list back to `trace_count` instead.
+#[derive(Debug, Clone)]
+pub struct StepSnapshot {
+    pub machine: ManualMachine,
+    pub step_count: u64,
+    pub trace_count: usize,
+}
+
+/// A full copy of the machine, traces included, kept under a name until removed
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub machine: ManualMachine,
+    pub info: CheckpointInfo,
+    pub records: StepRecords,
+}
+
+/// What a session recorded over the steps up to a checkpoint. Stepping back can
+/// cut these down to an earlier step, but a checkpoint may be restored after a
+/// reset or from an earlier step, so it keeps its own copy.
+#[derive(Debug, Clone)]
+pub struct StepRecords {
+    pub decision_trace: DecisionTrace,
+    pub builtin_warnings: Vec<BuiltinMisuse>,
+    pub recent_steps: RecentSteps,
+    pub builtin_spend: BuiltinSpend,
+    pub term_costs: TermCosts,
+    pub value_pins: ValuePins,
+    pub execution_trace: TraceRecorder,
+    pub builtin_calls: BuiltinCallRecorder,
+    pub coverage: CoverageTracker,
+    pub budget_crossing: Option<BudgetCrossing>,
+    pub budget_exhaustion: Option<BudgetExhaustionReport>,
+    pub budget_exceeded_reported: bool,
+}
+
+/// The point of execution a named checkpoint returns to
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CheckpointInfo {
+    pub name: String,
+    /// Step count at the checkpoint
+    pub step: u64,
+    pub term_id: i32,
+    pub trace_count: usize,
+    pub spent_cpu: i64,
+    pub spent_mem: i64,
+    /// Milliseconds since the Unix epoch
+    pub created_at: u64,
+}
+
+/// Ring buffer of the states before the most recent steps
+#[derive(Debug, Clone)]
+pub struct StepHistory {
+    snapshots: VecDeque<StepSnapshot>,
+    capacity: usize,
+}
+
+impl Default for StepHistory {
+    fn default() -> Self {
+        StepHistory::new(DEFAULT_STEP_HISTORY)
+    }
+}
+
+impl StepHistory {
+    pub fn new(capacity: usize) -> Self {
+        StepHistory {
+            snapshots: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of steps that can currently be undone
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Changes the number of snapshots kept, dropping the oldest ones beyond it
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.snapshots.len() > capacity {
+            self.snapshots.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, snapshot: StepSnapshot) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Removes the snapshots of the last `steps` steps, or of as many as are kept,
+    /// and returns the oldest of them
+    pub fn rewind(&mut self, steps: usize) -> Option<StepSnapshot> {
+        let keep = self.snapshots.len().saturating_sub(steps);
+        self.snapshots.drain(keep..).next()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+/// Where `step_back` left the session
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct StepBackResult {
+    /// Steps undone; fewer than requested when the history ran out
+    pub steps_back: u64,
+    /// Step count of the restored state
+    pub step: u64,
+    pub term_id: i32,
+    /// Steps that can still be undone
+    pub remaining_history: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debugger_engine::session_controller::DEFAULT_SLIPPAGE;
+    use pallas_primitives::conway::Language;
+    use uplc::{
+        ast::{NamedDeBruijn, Term},
+        machine::cost_model::{CostModel, ExBudget},
+    };
+
+    fn snapshot(step_count: u64) -> StepSnapshot {
+        let machine = ManualMachine::new(
+            Language::PlutusV3,
+            CostModel::default(),
+            ExBudget::max(),
+            DEFAULT_SLIPPAGE,
+            Term::<NamedDeBruijn>::Error { uniq_id: 0 },
+        )
+        .unwrap();
+        StepSnapshot {
+            machine,
+            step_count,
+            trace_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_step_history_ring_buffer() {
+        let mut history = StepHistory::new(3);
+        for step in 0..5 {
+            history.push(snapshot(step));
+        }
+        assert_eq!(history.len(), 3);
+
+        let restored = history.rewind(2).unwrap();
+        assert_eq!(restored.step_count, 3);
+        assert_eq!(history.len(), 1);
+
+        let restored = history.rewind(10).unwrap();
+        assert_eq!(restored.step_count, 2);
+        assert!(history.is_empty());
+        assert!(history.rewind(1).is_none());
+    }
+
+    #[test]
+    fn test_step_history_capacity() {
+        let mut history = StepHistory::default();
+        history.push(snapshot(0));
+        assert!(history.is_empty());
+
+        history.set_capacity(4);
+        for step in 0..4 {
+            history.push(snapshot(step));
+        }
+        history.set_capacity(2);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.rewind(2).unwrap().step_count, 2);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::step_history::DEFAULT_STEP_HISTORY;
+
+/// Budget attributed to one term
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, JsonSchema)]
+pub struct TermCost {
+    pub cpu: i64,
+    pub mem: i64,
+    /// Steps attributed to the term: computing it and, for a Builtin term,
+    /// applying the builtin to its last argument
+    pub hits: u64,
+}
+
+/// Outcome of `get_cost_by_term`
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TermCostMap {
+    /// Step the costs are counted from: 0 unless the session went back further
+    /// than the steps it keeps costs of
+    pub from_step: u64,
+    pub terms: BTreeMap<i32, TermCost>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AttributedStep {
+    step: u64,
+    term_id: i32,
+    cpu: i64,
+    mem: i64,
+}
+
+/// Budget spent per term id, kept as the session steps. The latest steps are
+/// remembered so that stepping back can take their cost off again.
+#[derive(Debug, Clone)]
+pub struct TermCosts {
+    totals: HashMap<i32, TermCost>,
+    latest: VecDeque<AttributedStep>,
+    latest_capacity: usize,
+    from_step: u64,
+    step: u64,
+}
+
+impl Default for TermCosts {
+    fn default() -> Self {
+        TermCosts {
+            totals: HashMap::new(),
+            latest: VecDeque::new(),
+            latest_capacity: DEFAULT_STEP_HISTORY,
+            from_step: 0,
+            step: 0,
+        }
+    }
+}
+
+impl TermCosts {
+    /// Number of latest steps whose cost can be taken off again, matching the step history
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.latest_capacity = capacity;
+        while self.latest.len() > capacity {
+            self.latest.pop_front();
+        }
+    }
+
+    /// Attributes the budget of `step` to `term_id`, when there is a term to attribute it to
+    pub fn record(&mut self, step: u64, term_id: Option<i32>, cpu: i64, mem: i64) {
+        self.step = step;
+        let Some(term_id) = term_id else {
+            return;
+        };
+        let total = self.totals.entry(term_id).or_default();
+        total.cpu += cpu;
+        total.mem += mem;
+        total.hits += 1;
+        if self.latest_capacity > 0 {
+            if self.latest.len() == self.latest_capacity {
+                self.latest.pop_front();
+            }
+            self.latest.push_back(AttributedStep { step, term_id, cpu, mem });
+        }
+    }
+
+    /// Takes off the cost of the steps after `step`. When those are no longer all
+    /// known, or `step` lies ahead, counting starts over from `step`.
+    pub fn restore_to(&mut self, step: u64) {
+        if step == self.step {
+            return;
+        }
+        let oldest_known = self.latest.front().map_or(self.step, |attributed| attributed.step - 1);
+        if step > self.step || step < oldest_known {
+            self.totals.clear();
+            self.latest.clear();
+            self.from_step = step;
+            self.step = step;
+            return;
+        }
+        while let Some(attributed) = self.latest.back().copied().filter(|attributed| attributed.step > step) {
+            self.latest.pop_back();
+            let total = self.totals.entry(attributed.term_id).or_default();
+            total.cpu -= attributed.cpu;
+            total.mem -= attributed.mem;
+            total.hits -= 1;
+            if total.hits == 0 {
+                self.totals.remove(&attributed.term_id);
+            }
+        }
+        self.step = step;
+    }
+
+    pub fn clear(&mut self) {
+        self.totals.clear();
+        self.latest.clear();
+        self.from_step = 0;
+        self.step = 0;
+    }
+
+    pub fn map(&self) -> TermCostMap {
+        TermCostMap {
+            from_step: self.from_step,
+            terms: self.totals.iter().map(|(term_id, cost)| (*term_id, *cost)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_term_costs_step_back() {
+        let mut costs = TermCosts::default();
+        costs.set_capacity(2);
+        costs.record(1, Some(4), 100, 10);
+        costs.record(2, None, 0, 0);
+        costs.record(3, Some(4), 100, 10);
+        costs.record(4, Some(9), 300, 1);
+
+        let map = costs.map();
+        assert_eq!(map.terms[&4], TermCost { cpu: 200, mem: 20, hits: 2 });
+        assert_eq!(map.terms[&9].hits, 1);
+
+        costs.restore_to(3);
+        assert!(!costs.map().terms.contains_key(&9));
+        costs.restore_to(2);
+        assert_eq!(costs.map().terms[&4].hits, 1);
+
+        // Step 1 is no longer among the latest steps
+        costs.restore_to(0);
+        let map = costs.map();
+        assert_eq!((map.from_step, map.terms.len()), (0, 0));
+        costs.record(1, Some(4), 100, 10);
+        costs.restore_to(5);
+        assert_eq!(costs.map().from_step, 5);
+    }
+}
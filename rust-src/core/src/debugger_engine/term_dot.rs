@@ -0,0 +1,138 @@
+use crate::serializer::{SerializableConstant, SerializableTerm};
+
+/// Longest constant value shown in a node label, in chars
+const LABEL_VALUE_CHARS: usize = 24;
+
+fn shortened(value: &str) -> String {
+    if value.chars().count() <= LABEL_VALUE_CHARS {
+        value.to_string()
+    } else {
+        format!("{}...", value.chars().take(LABEL_VALUE_CHARS).collect::<String>())
+    }
+}
+
+fn constant_label(constant: &SerializableConstant) -> String {
+    match constant {
+        SerializableConstant::Integer { value } => shortened(value),
+        SerializableConstant::ByteString { value, .. } => format!("#{}", shortened(value)),
+        SerializableConstant::Truncated { preview, .. } => format!("{}...", preview),
+        SerializableConstant::String { value } => format!("{:?}", shortened(value)),
+        SerializableConstant::Bool { value } => value.to_string(),
+        SerializableConstant::Unit => "()".to_string(),
+        SerializableConstant::ProtoList { values, .. } => format!("list of {}", values.len()),
+        SerializableConstant::ProtoPair { .. } => "pair".to_string(),
+        SerializableConstant::Data { .. } => "data".to_string(),
+        SerializableConstant::Bls12_381G1Element { .. } => "G1 element".to_string(),
+        SerializableConstant::Bls12_381G2Element { .. } => "G2 element".to_string(),
+        SerializableConstant::Bls12_381MlResult { .. } => "Miller loop result".to_string(),
+    }
+}
+
+fn node_label(term: &SerializableTerm) -> (i32, String) {
+    match term {
+        SerializableTerm::Var { id, name } => (*id, format!("Var {}", name)),
+        SerializableTerm::Delay { id, .. } => (*id, "Delay".to_string()),
+        SerializableTerm::Lambda { id, parameter_name, .. } => (*id, format!("\u{3bb}{}", parameter_name)),
+        SerializableTerm::Apply { id, .. } => (*id, "Apply".to_string()),
+        SerializableTerm::Constant { id, constant } => (*id, format!("Constant {}", constant_label(constant))),
+        SerializableTerm::Force { id, .. } => (*id, "Force".to_string()),
+        SerializableTerm::Error { id } => (*id, "Error".to_string()),
+        SerializableTerm::Builtin { id, fun } => (*id, fun.clone()),
+        SerializableTerm::Constr { id, constructor_tag, .. } => (*id, format!("Constr {}", constructor_tag)),
+        SerializableTerm::Case { id, .. } => (*id, "Case".to_string()),
+    }
+}
+
+fn escaped(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+struct DotWriter {
+    out: String,
+    current_term_id: Option<i32>,
+}
+
+impl DotWriter {
+    fn edge(&mut self, from: i32, to: &SerializableTerm, label: &str) {
+        let to = self.node(to);
+        self.out.push_str(&format!("  t{} -> t{} [label=\"{}\"];\n", from, to, label));
+    }
+
+    /// Draws `term` and the terms under it, returning its id
+    fn node(&mut self, term: &SerializableTerm) -> i32 {
+        let (id, label) = node_label(term);
+        let highlight = if self.current_term_id == Some(id) {
+            ", style=filled, fillcolor=gold, penwidth=2"
+        } else {
+            ""
+        };
+        self.out.push_str(&format!("  t{} [label=\"{}\\n#{}\"{}];\n", id, escaped(&label), id, highlight));
+        match term {
+            SerializableTerm::Delay { term: body, .. } | SerializableTerm::Force { term: body, .. } => {
+                self.edge(id, body, "term")
+            }
+            SerializableTerm::Lambda { body, .. } => self.edge(id, body, "body"),
+            SerializableTerm::Apply { function, argument, .. } => {
+                self.edge(id, function, "function");
+                self.edge(id, argument, "argument");
+            }
+            SerializableTerm::Constr { fields, .. } => {
+                for (index, field) in fields.iter().enumerate() {
+                    self.edge(id, field, &format!("field {}", index));
+                }
+            }
+            SerializableTerm::Case { constr, branches, .. } => {
+                self.edge(id, constr, "constr");
+                for (index, branch) in branches.iter().enumerate() {
+                    self.edge(id, branch, &format!("branch {}", index));
+                }
+            }
+            SerializableTerm::Var { .. }
+            | SerializableTerm::Constant { .. }
+            | SerializableTerm::Error { .. }
+            | SerializableTerm::Builtin { .. } => {}
+        }
+        id
+    }
+}
+
+/// Renders `term` as a Graphviz DOT digraph: one node per term id, labelled
+/// with the term and its id, and edges from each term to its subterms. The
+/// program is a tree, so the graph is one too. The term with
+/// `current_term_id` is filled in.
+pub fn term_dot(term: &SerializableTerm, current_term_id: Option<i32>) -> String {
+    let mut writer = DotWriter {
+        out: "digraph uplc {\n  node [shape=box, fontname=\"monospace\"];\n".to_string(),
+        current_term_id,
+    };
+    writer.node(term);
+    writer.out.push_str("}\n");
+    writer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_term_dot() {
+        let term = SerializableTerm::Apply {
+            id: 0,
+            function: Box::new(SerializableTerm::Lambda {
+                id: 1,
+                parameter_name: "x".to_string(),
+                body: Box::new(SerializableTerm::Var { id: 2, name: "x".to_string() }),
+            }),
+            argument: Box::new(SerializableTerm::Constant {
+                id: 3,
+                constant: SerializableConstant::String { value: "a \"b\"".to_string() },
+            }),
+        };
+        let dot = term_dot(&term, Some(2));
+        assert!(dot.starts_with("digraph uplc {\n"));
+        assert!(dot.contains("  t0 -> t1 [label=\"function\"];\n"));
+        assert!(dot.contains("  t2 [label=\"Var x\\n#2\", style=filled"));
+        assert!(dot.contains(r#"Constant \"a \\\"b\\\"\""#));
+        assert_eq!(dot.matches("fillcolor").count(), 1);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, HashMap};
+
+use pallas_crypto::hash::Hasher;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::ast::{FakeNamedDeBruijn, NamedDeBruijn, Program, Term};
+
+use super::aiken_traces::AikenSourceMap;
+use super::decisions::term_id;
+use super::DebuggerError;
+
+/// How the terms of a session's program are numbered.
+///
+/// Term ids are given when a script is decoded. `reset` reuses the numbered
+/// program as is, so ids hold for the whole session, but decoding the same bytes
+/// again is not guaranteed to number them the same way. `layout_hash` lets hosts
+/// check whether two sessions agree: equal layout hashes mean equal ids on equal
+/// terms. Replacing the program (`reset_with_program`, a recompile) renumbers it;
+/// `remap_term_ids` carries breakpoints, bookmarks, annotations and env watches
+/// over when source maps relate both programs.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TermIdInfo {
+    /// Blake2b-256 of the flat encoding of the unapplied program (hex)
+    pub program_hash: String,
+    /// Blake2b-256 of every term id and term kind in program order, arguments included (hex)
+    pub layout_hash: String,
+    pub term_count: usize,
+    pub min_term_id: i32,
+    pub max_term_id: i32,
+    /// Programs whose source maps were set in this session and can be remapped from
+    pub known_programs: Vec<String>,
+}
+
+/// Outcome of `remap_term_ids`
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TermIdRemapping {
+    pub from_program_hash: String,
+    pub to_program_hash: String,
+    /// New id of every old id the source maps relate, for ids the session referred to
+    pub remapped: BTreeMap<i32, i32>,
+    /// Ids the session referred to that the source maps don't relate; the breakpoints,
+    /// bookmarks, annotations and env watches on them are dropped
+    pub unmapped: Vec<i32>,
+}
+
+pub fn program_hash(program: &Program<NamedDeBruijn>) -> Result<String, DebuggerError> {
+    let flat = Program::<FakeNamedDeBruijn>::from(program.clone())
+        .to_flat()
+        .map_err(|e| DebuggerError::ProgramBuildError(format!("Failed to flat encode program: {}", e)))?;
+    Ok(hex::encode(Hasher::<256>::hash(&flat)))
+}
+
+fn kind_code(term: &Term<NamedDeBruijn>) -> u8 {
+    match term {
+        Term::Var { .. } => 0,
+        Term::Delay { .. } => 1,
+        Term::Lambda { .. } => 2,
+        Term::Apply { .. } => 3,
+        Term::Constant { .. } => 4,
+        Term::Force { .. } => 5,
+        Term::Error { .. } => 6,
+        Term::Builtin { .. } => 7,
+        Term::Constr { .. } => 8,
+        Term::Case { .. } => 9,
+    }
+}
+
+/// Term ids in program order, each with its kind
+fn layout(term: &Term<NamedDeBruijn>, out: &mut Vec<(i32, u8)>) {
+    out.push((term_id(term), kind_code(term)));
+    match term {
+        Term::Delay { body, .. } | Term::Lambda { body, .. } | Term::Force { body, .. } => layout(body, out),
+        Term::Apply { function, argument, .. } => {
+            layout(function, out);
+            layout(argument, out);
+        }
+        Term::Constr { fields, .. } => fields.iter().for_each(|field| layout(field, out)),
+        Term::Case { constr, branches, .. } => {
+            layout(constr, out);
+            branches.iter().for_each(|branch| layout(branch, out));
+        }
+        Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } | Term::Builtin { .. } => {}
+    }
+}
+
+pub fn term_id_info(
+    program: &Program<NamedDeBruijn>,
+    entry_term: &Term<NamedDeBruijn>,
+    known_programs: Vec<String>,
+) -> Result<TermIdInfo, DebuggerError> {
+    let mut terms = vec![];
+    layout(entry_term, &mut terms);
+    let bytes: Vec<u8> = terms
+        .iter()
+        .flat_map(|(id, kind)| id.to_le_bytes().into_iter().chain([*kind]))
+        .collect();
+    Ok(TermIdInfo {
+        program_hash: program_hash(program)?,
+        layout_hash: hex::encode(Hasher::<256>::hash(&bytes)),
+        term_count: terms.len(),
+        min_term_id: terms.iter().map(|(id, _)| *id).min().unwrap_or(0),
+        max_term_id: terms.iter().map(|(id, _)| *id).max().unwrap_or(0),
+        known_programs,
+    })
+}
+
+/// Relates the terms of two programs compiled from the same sources: terms at
+/// the same source location are paired in id order, and surplus terms at a
+/// location stay unrelated
+pub fn remap_by_source(old: &AikenSourceMap, new: &AikenSourceMap) -> HashMap<i32, i32> {
+    let by_location = |map: &AikenSourceMap| {
+        let mut terms: BTreeMap<(String, u32, u32), Vec<i32>> = BTreeMap::new();
+        for (id, location) in &map.terms {
+            terms
+                .entry((location.file.clone(), location.line, location.column))
+                .or_default()
+                .push(*id);
+        }
+        terms.values_mut().for_each(|ids| ids.sort_unstable());
+        terms
+    };
+    let new_terms = by_location(new);
+    by_location(old)
+        .into_iter()
+        .filter_map(|(location, old_ids)| new_terms.get(&location).map(|new_ids| (old_ids, new_ids)))
+        .flat_map(|(old_ids, new_ids)| old_ids.into_iter().zip(new_ids.iter().copied()).collect::<Vec<_>>())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debugger_engine::aiken_traces::AikenSourceLocation;
+
+    #[test]
+    fn test_remap_by_source() {
+        let location = |line: u32| AikenSourceLocation {
+            file: "validators/spend.ak".to_string(),
+            line,
+            column: 5,
+        };
+        let map = |terms: Vec<(i32, u32)>| AikenSourceMap {
+            expressions: HashMap::new(),
+            terms: terms.into_iter().map(|(id, line)| (id, location(line))).collect(),
+        };
+        let old = map(vec![(10, 1), (11, 1), (12, 2), (13, 3)]);
+        let new = map(vec![(20, 1), (21, 1), (30, 2), (31, 2)]);
+
+        let remapped = remap_by_source(&old, &new);
+        assert_eq!(remapped.get(&10), Some(&20));
+        assert_eq!(remapped.get(&11), Some(&21));
+        assert_eq!(remapped.get(&12), Some(&30));
+        assert_eq!(remapped.get(&13), None);
+    }
+}
@@ -0,0 +1,230 @@
+use num_bigint::{BigInt as NumBigInt, Sign};
+use pallas_primitives::BigInt;
+use uplc::{
+    ast::{Constant, NamedDeBruijn, Term, Type},
+    PlutusData,
+};
+
+use super::decisions::term_id;
+use crate::serializer::{serialize_bls_g1_element_compressed, serialize_bls_g2_element_compressed};
+
+const INDENT: &str = "  ";
+
+fn type_text(uplc_type: &Type) -> String {
+    match uplc_type {
+        Type::Bool => "bool".to_string(),
+        Type::Integer => "integer".to_string(),
+        Type::String => "string".to_string(),
+        Type::ByteString => "bytestring".to_string(),
+        Type::Unit => "unit".to_string(),
+        Type::List(element_type) => format!("(list {})", type_text(element_type)),
+        Type::Pair(first_type, second_type) => format!("(pair {} {})", type_text(first_type), type_text(second_type)),
+        Type::Data => "data".to_string(),
+        Type::Bls12_381G1Element => "bls12_381_G1_element".to_string(),
+        Type::Bls12_381G2Element => "bls12_381_G2_element".to_string(),
+        Type::Bls12_381MlResult => "bls12_381_MlResult".to_string(),
+    }
+}
+
+fn constant_type(constant: &Constant) -> Type {
+    match constant {
+        Constant::Integer(_) => Type::Integer,
+        Constant::ByteString(_) => Type::ByteString,
+        Constant::String(_) => Type::String,
+        Constant::Bool(_) => Type::Bool,
+        Constant::Unit => Type::Unit,
+        Constant::ProtoList(element_type, _) => Type::List(element_type.clone().into()),
+        Constant::ProtoPair(first_type, second_type, ..) => Type::Pair(first_type.clone().into(), second_type.clone().into()),
+        Constant::Data(_) => Type::Data,
+        Constant::Bls12_381G1Element(_) => Type::Bls12_381G1Element,
+        Constant::Bls12_381G2Element(_) => Type::Bls12_381G2Element,
+        Constant::Bls12_381MlResult(_) => Type::Bls12_381MlResult,
+    }
+}
+
+/// Constructor index of a Constr, decoded from its CBOR tag
+fn constr_index(tag: u64, any_constructor: Option<u64>) -> u64 {
+    match tag {
+        121..=127 => tag - 121,
+        1280..=1400 => tag - 1280 + 7,
+        _ => any_constructor.unwrap_or(tag),
+    }
+}
+
+fn big_int_text(big_int: &BigInt) -> String {
+    match big_int {
+        BigInt::Int(int_val) => int_val.to_string(),
+        BigInt::BigUInt(bytes) => NumBigInt::from_bytes_be(Sign::Plus, bytes.as_slice()).to_string(),
+        // Bignums below zero are encoded as -1 - n
+        BigInt::BigNInt(bytes) => (-NumBigInt::from_bytes_be(Sign::Plus, bytes.as_slice()) - 1).to_string(),
+    }
+}
+
+fn data_text(data: &PlutusData) -> String {
+    let list = |items: Vec<String>| format!("[{}]", items.join(", "));
+    match data {
+        PlutusData::Constr(constr) => format!(
+            "Constr {} {}",
+            constr_index(constr.tag, constr.any_constructor),
+            list(constr.fields.iter().map(data_text).collect())
+        ),
+        PlutusData::Map(pairs) => format!(
+            "Map {}",
+            list(pairs.iter().map(|(key, value)| format!("({}, {})", data_text(key), data_text(value))).collect())
+        ),
+        PlutusData::Array(items) => format!("List {}", list(items.iter().map(data_text).collect())),
+        PlutusData::BigInt(big_int) => format!("I {}", big_int_text(big_int)),
+        PlutusData::BoundedBytes(bytes) => format!("B #{}", hex::encode(bytes.as_slice())),
+    }
+}
+
+/// Value of a constant as written after its type
+fn constant_value(constant: &Constant) -> String {
+    match constant {
+        Constant::Integer(i) => i.to_string(),
+        Constant::ByteString(bytes) => format!("#{}", hex::encode(bytes)),
+        Constant::String(s) => format!("{:?}", s),
+        Constant::Bool(true) => "True".to_string(),
+        Constant::Bool(false) => "False".to_string(),
+        Constant::Unit => "()".to_string(),
+        Constant::ProtoList(_, values) => {
+            format!("[{}]", values.iter().map(constant_value).collect::<Vec<_>>().join(", "))
+        }
+        Constant::ProtoPair(_, _, first, second) => format!("({}, {})", constant_value(first), constant_value(second)),
+        Constant::Data(data) => format!("({})", data_text(data)),
+        Constant::Bls12_381G1Element(element) => format!("0x{}", serialize_bls_g1_element_compressed(element)),
+        Constant::Bls12_381G2Element(element) => format!("0x{}", serialize_bls_g2_element_compressed(element)),
+        // Miller loop results have no literal form
+        Constant::Bls12_381MlResult(_) => "<Miller loop result>".to_string(),
+    }
+}
+
+pub fn constant_text(constant: &Constant) -> String {
+    format!("(con {} {})", type_text(&constant_type(constant)), constant_value(constant))
+}
+
+struct Printer {
+    out: String,
+    term_ids: bool,
+    /// Names of the enclosing lambdas' parameters, innermost last
+    scope: Vec<String>,
+    next_unique: usize,
+}
+
+impl Printer {
+    fn line(&mut self, depth: usize, text: &str, term: Option<&Term<NamedDeBruijn>>) {
+        for _ in 0..depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text);
+        if let Some(term) = term.filter(|_| self.term_ids) {
+            self.out.push_str(&format!(" -- #{}", term_id(term)));
+        }
+        self.out.push('\n');
+    }
+
+    fn var_name(&self, name: &NamedDeBruijn) -> String {
+        let index: usize = name.index.into();
+        match self.scope.len().checked_sub(index) {
+            Some(position) if index > 0 => self.scope[position].clone(),
+            _ => format!("{}_free{}", name.text, index),
+        }
+    }
+
+    fn term(&mut self, term: &Term<NamedDeBruijn>, depth: usize) {
+        match term {
+            Term::Var { name, .. } => {
+                let name = self.var_name(name);
+                self.line(depth, &name, Some(term));
+            }
+            Term::Constant { value, .. } => self.line(depth, &constant_text(value), Some(term)),
+            Term::Builtin { fun, .. } => self.line(depth, &format!("(builtin {})", fun), Some(term)),
+            Term::Error { .. } => self.line(depth, "(error)", Some(term)),
+            Term::Lambda { parameter_name, body, .. } => {
+                let name = format!("{}_{}", parameter_name.text, self.next_unique);
+                self.next_unique += 1;
+                self.line(depth, &format!("(lam {}", name), Some(term));
+                self.scope.push(name);
+                self.term(body, depth + 1);
+                self.scope.pop();
+                self.line(depth, ")", None);
+            }
+            Term::Delay { body, .. } => self.wrapped(term, "(delay", [body.as_ref()], ")", depth),
+            Term::Force { body, .. } => self.wrapped(term, "(force", [body.as_ref()], ")", depth),
+            Term::Apply { function, argument, .. } => {
+                self.wrapped(term, "[", [function.as_ref(), argument.as_ref()], "]", depth)
+            }
+            Term::Constr { tag, fields, .. } => self.wrapped(term, &format!("(constr {}", tag), fields, ")", depth),
+            Term::Case { constr, branches, .. } => self.wrapped(
+                term,
+                "(case",
+                std::iter::once(constr.as_ref()).chain(branches.iter()),
+                ")",
+                depth,
+            ),
+        }
+    }
+
+    fn wrapped<'a>(
+        &mut self,
+        term: &Term<NamedDeBruijn>,
+        open: &str,
+        children: impl IntoIterator<Item = &'a Term<NamedDeBruijn>>,
+        close: &str,
+        depth: usize,
+    ) {
+        self.line(depth, open, Some(term));
+        for child in children {
+            self.term(child, depth + 1);
+        }
+        self.line(depth, close, None);
+    }
+}
+
+/// Renders `term` as a UPLC program in the textual syntax, one term per line.
+/// Lambda parameters are named `<name>_<n>` with `n` unique in the program; with
+/// `term_ids` every term's id follows it as a `--` comment.
+pub fn program_text(term: &Term<NamedDeBruijn>, version: (usize, usize, usize), term_ids: bool) -> String {
+    let mut printer = Printer {
+        out: format!("(program {}.{}.{}\n", version.0, version.1, version.2),
+        term_ids,
+        scope: vec![],
+        next_unique: 0,
+    };
+    printer.term(term, 1);
+    printer.out.push_str(")\n");
+    printer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use uplc::builtins::DefaultFunction;
+
+    #[test]
+    fn test_program_text() {
+        let term: Term<NamedDeBruijn> = Term::Lambda {
+            parameter_name: Rc::new(NamedDeBruijn { text: "x".to_string(), index: 0.into() }),
+            body: Rc::new(Term::Apply {
+                function: Rc::new(Term::Builtin { fun: DefaultFunction::AddInteger, uniq_id: 2 }),
+                argument: Rc::new(Term::Var {
+                    name: Rc::new(NamedDeBruijn { text: "x".to_string(), index: 1.into() }),
+                    uniq_id: 3,
+                }),
+                uniq_id: 1,
+            }),
+            uniq_id: 0,
+        };
+        assert_eq!(
+            program_text(&term, (1, 1, 0), true),
+            "(program 1.1.0\n  (lam x_0 -- #0\n    [ -- #1\n      (builtin addInteger) -- #2\n      x_0 -- #3\n    ]\n  )\n)\n"
+        );
+        assert!(!program_text(&term, (1, 1, 0), false).contains("--"));
+
+        let list = Constant::ProtoList(Type::Integer, vec![Constant::Integer(1.into()), Constant::Integer((-2).into())]);
+        assert_eq!(constant_text(&list), "(con (list integer) [1, -2])");
+        assert_eq!(constr_index(122, None), 1);
+        assert_eq!(constr_index(1281, None), 8);
+    }
+}
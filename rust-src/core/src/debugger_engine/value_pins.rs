@@ -0,0 +1,458 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::{Constant, NamedDeBruijn, Term},
+    machine::{value::Value, Context, MachineState},
+};
+
+use super::decisions::term_id;
+use crate::env_sharing::{frame_env, EnvIds};
+use crate::serializer::constant_content_hash;
+
+/// Upper bound on the occurrences kept per pin
+const MAX_OCCURRENCES: usize = 500;
+
+/// Where the value to pin is taken from
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "by")]
+pub enum ValuePinTarget {
+    /// Position `index` (outermost binding first) of the env `env_id`, as reported by `get_env_identity`
+    EnvSlot { env_id: String, index: usize },
+    /// The value bound by the Lambda term `term_id`, looked up in the current env
+    Binder { term_id: i32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub enum PinMatch {
+    /// The very same value, shared through an Rc
+    Identical,
+    /// A distinct value with the same structure and contents
+    Equal,
+}
+
+/// A later state in which the pinned value showed up again
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct PinOccurrence {
+    pub step: u64,
+    /// Term about to be computed, -1 when the value was returned
+    pub term_id: i32,
+    /// "bound" when pushed into an env, "returned" when returned to a frame
+    pub location: String,
+    /// Env the value was bound in and its position there, outermost binding first
+    pub env_id: Option<String>,
+    pub index: Option<usize>,
+    pub matched: PinMatch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ValuePinReport {
+    pub label: String,
+    pub target: ValuePinTarget,
+    pub pinned_step: u64,
+    /// Short rendering of the pinned value, e.g. `Constr 0 (2 fields)`
+    pub preview: String,
+    /// Hex of the structural hash equal values are recognised by
+    pub structural_hash: String,
+    pub occurrences: Vec<PinOccurrence>,
+    /// Whether later occurrences were dropped at the occurrence limit
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone)]
+struct ValuePin {
+    target: ValuePinTarget,
+    pinned_step: u64,
+    /// Kept alive so the allocations it is identified by cannot be reused by other values
+    value: Value,
+    hash: u64,
+    occurrences: Vec<PinOccurrence>,
+    truncated: bool,
+}
+
+impl ValuePin {
+    fn push(&mut self, occurrence: PinOccurrence) {
+        // An env slot is reported once, however often its env becomes current again
+        if occurrence.env_id.is_some()
+            && self
+                .occurrences
+                .iter()
+                .any(|seen| seen.env_id == occurrence.env_id && seen.index == occurrence.index)
+        {
+            return;
+        }
+        if self.occurrences.len() < MAX_OCCURRENCES {
+            self.occurrences.push(occurrence);
+        } else {
+            self.truncated = true;
+        }
+    }
+}
+
+/// Values pinned by label, with the states they reappeared in since
+#[derive(Debug, Clone, Default)]
+pub struct ValuePins {
+    pins: BTreeMap<String, ValuePin>,
+    /// Env whose innermost binding was inspected last
+    last_env: Option<Rc<Vec<Value>>>,
+}
+
+impl ValuePins {
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        self.pins.keys().cloned().collect()
+    }
+
+    /// Pins `value` under `label`, replacing any pin with the same label
+    pub fn pin(&mut self, label: String, target: ValuePinTarget, step: u64, value: Value) {
+        let hash = structural_hash(&value);
+        self.pins.insert(
+            label,
+            ValuePin {
+                target,
+                pinned_step: step,
+                value,
+                hash,
+                occurrences: vec![],
+                truncated: false,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, label: &str) -> bool {
+        self.pins.remove(label).is_some()
+    }
+
+    pub fn report(&self, label: &str) -> Option<ValuePinReport> {
+        self.pins.get(label).map(|pin| ValuePinReport {
+            label: label.to_string(),
+            target: pin.target.clone(),
+            pinned_step: pin.pinned_step,
+            preview: preview(&pin.value),
+            structural_hash: format!("{:016x}", pin.hash),
+            occurrences: pin.occurrences.clone(),
+            truncated: pin.truncated,
+        })
+    }
+
+    /// Records the pinned values returned by, or newly bound at, the state reached by step `step`
+    pub fn observe(&mut self, state: &MachineState, step: u64, env_ids: &EnvIds) {
+        if self.pins.is_empty() {
+            return;
+        }
+        match state {
+            MachineState::Return(_, value) => self.record(value, |matched| PinOccurrence {
+                step,
+                term_id: -1,
+                location: "returned".to_string(),
+                env_id: None,
+                index: None,
+                matched,
+            }),
+            MachineState::Compute(_, env, term) => {
+                if self.last_env.as_ref().is_some_and(|last| Rc::ptr_eq(last, env)) {
+                    return;
+                }
+                self.last_env = Some(env.clone());
+                if let Some(value) = env.last() {
+                    let id = env_ids.id(env);
+                    self.record(value, |matched| PinOccurrence {
+                        step,
+                        term_id: term_id(term),
+                        location: "bound".to_string(),
+                        env_id: Some(id.clone()),
+                        index: Some(env.len() - 1),
+                        matched,
+                    });
+                }
+            }
+            MachineState::Done(_) => {}
+        }
+    }
+
+    /// Drops the occurrences recorded after `step`, for stepping back
+    pub fn truncate_after(&mut self, step: u64) {
+        self.last_env = None;
+        for pin in self.pins.values_mut() {
+            pin.occurrences.retain(|occurrence| occurrence.step <= step);
+            pin.truncated = false;
+        }
+    }
+
+    /// Takes the occurrences up to `step` from `saved` for the pins it has too,
+    /// e.g. when restoring a checkpoint; pins made since keep those up to `step`
+    pub fn restore_occurrences(&mut self, saved: &ValuePins, step: u64) {
+        self.truncate_after(step);
+        for (label, pin) in &mut self.pins {
+            if let Some(saved) = saved.pins.get(label).filter(|saved| saved.pinned_step == pin.pinned_step && saved.hash == pin.hash) {
+                pin.occurrences = saved.occurrences.iter().filter(|occurrence| occurrence.step <= step).cloned().collect();
+                pin.truncated = saved.truncated;
+            }
+        }
+    }
+
+    /// Forgets every occurrence while keeping the pins, for a new run
+    pub fn restart(&mut self) {
+        self.truncate_after(0);
+    }
+
+    fn record(&mut self, value: &Value, occurrence: impl Fn(PinMatch) -> PinOccurrence) {
+        let mut hash = None;
+        for pin in self.pins.values_mut() {
+            let matched = if identical(&pin.value, value) {
+                PinMatch::Identical
+            } else if same_shape(&pin.value, value) && *hash.get_or_insert_with(|| structural_hash(value)) == pin.hash {
+                PinMatch::Equal
+            } else {
+                continue;
+            };
+            pin.push(occurrence(matched));
+        }
+    }
+}
+
+/// Whether both values share the allocations they were built from
+fn identical(pinned: &Value, value: &Value) -> bool {
+    match (pinned, value) {
+        (Value::Con(pinned), Value::Con(value)) => Rc::ptr_eq(pinned, value),
+        (Value::Delay { body: pinned_body, env: pinned_env, .. }, Value::Delay { body, env, .. })
+        | (Value::Lambda { body: pinned_body, env: pinned_env, .. }, Value::Lambda { body, env, .. }) => {
+            Rc::ptr_eq(pinned_body, body) && Rc::ptr_eq(pinned_env, env)
+        }
+        _ => false,
+    }
+}
+
+/// Cheap check ruling out most values before hashing them
+fn same_shape(pinned: &Value, value: &Value) -> bool {
+    match (pinned, value) {
+        (Value::Con(pinned), Value::Con(value)) => {
+            std::mem::discriminant(pinned.as_ref()) == std::mem::discriminant(value.as_ref())
+        }
+        (Value::Delay { body: pinned, .. }, Value::Delay { body, .. })
+        | (Value::Lambda { body: pinned, .. }, Value::Lambda { body, .. }) => term_id(pinned) == term_id(body),
+        (Value::Builtin { fun: pinned, .. }, Value::Builtin { fun, .. }) => pinned == fun,
+        (Value::Constr { tag: pinned_tag, fields: pinned_fields, .. }, Value::Constr { tag, fields, .. }) => {
+            pinned_tag == tag && pinned_fields.len() == fields.len()
+        }
+        _ => false,
+    }
+}
+
+fn hash_constant(constant: &Constant, state: &mut DefaultHasher) {
+    match constant_content_hash(constant) {
+        Some(content_hash) => content_hash.hash(state),
+        None => format!("{:?}", constant).hash(state),
+    }
+}
+
+fn hash_value(value: &Value, state: &mut DefaultHasher) {
+    match value {
+        Value::Con(constant) => {
+            0u8.hash(state);
+            std::mem::discriminant(constant.as_ref()).hash(state);
+            hash_constant(constant, state);
+        }
+        // Closures are equal when they run the same body in the same env
+        Value::Delay { body, env, .. } => {
+            1u8.hash(state);
+            term_id(body).hash(state);
+            Rc::as_ptr(env).hash(state);
+        }
+        Value::Lambda { body, env, .. } => {
+            2u8.hash(state);
+            term_id(body).hash(state);
+            Rc::as_ptr(env).hash(state);
+        }
+        Value::Builtin { fun, runtime, .. } => {
+            3u8.hash(state);
+            format!("{:?}", fun).hash(state);
+            runtime.forces.hash(state);
+            for arg in &runtime.args {
+                hash_value(arg, state);
+            }
+        }
+        Value::Constr { tag, fields, .. } => {
+            4u8.hash(state);
+            tag.hash(state);
+            fields.len().hash(state);
+            for field in fields {
+                hash_value(field, state);
+            }
+        }
+    }
+}
+
+/// Hash of a value's structure and contents, ignoring term ids of constructed values
+pub fn structural_hash(value: &Value) -> u64 {
+    let mut state = DefaultHasher::new();
+    hash_value(value, &mut state);
+    state.finish()
+}
+
+fn preview(value: &Value) -> String {
+    match value {
+        Value::Con(constant) => match constant.as_ref() {
+            Constant::Integer(integer) => integer.to_string(),
+            Constant::ByteString(bytes) if bytes.len() <= 32 => format!("#{}", hex::encode(bytes)),
+            Constant::ByteString(bytes) => format!("#{}… ({} bytes)", hex::encode(&bytes[..32]), bytes.len()),
+            Constant::String(text) => format!("{:?}", text),
+            Constant::Bool(b) => b.to_string(),
+            Constant::Unit => "()".to_string(),
+            Constant::ProtoList(_, values) => format!("List ({} items)", values.len()),
+            Constant::ProtoPair(..) => "Pair".to_string(),
+            Constant::Data(_) => "Data".to_string(),
+            Constant::Bls12_381G1Element(_) => "G1 point".to_string(),
+            Constant::Bls12_381G2Element(_) => "G2 point".to_string(),
+            Constant::Bls12_381MlResult(_) => "ML result".to_string(),
+        },
+        Value::Delay { body, .. } => format!("Delay (term {})", term_id(body)),
+        Value::Lambda { parameter_name, body, .. } => format!("λ{} (term {})", parameter_name.text, term_id(body)),
+        Value::Builtin { fun, runtime, .. } => format!("{:?} ({} args)", fun, runtime.args.len()),
+        Value::Constr { tag, fields, .. } => format!("Constr {} ({} fields)", tag, fields.len()),
+    }
+}
+
+/// Lambda term ids enclosing the term `target`, outermost first. The env a term is
+/// computed in holds exactly the values bound by these Lambdas, in the same order.
+pub fn enclosing_binders(term: &Term<NamedDeBruijn>, target: i32) -> Option<Vec<i32>> {
+    fn walk(term: &Term<NamedDeBruijn>, target: i32, binders: &mut Vec<i32>) -> bool {
+        if term_id(term) == target {
+            return true;
+        }
+        match term {
+            Term::Lambda { body, uniq_id, .. } => {
+                binders.push(*uniq_id as i32);
+                if walk(body, target, binders) {
+                    return true;
+                }
+                binders.pop();
+                false
+            }
+            Term::Delay { body, .. } | Term::Force { body, .. } => walk(body, target, binders),
+            Term::Apply { function, argument, .. } => {
+                walk(function, target, binders) || walk(argument, target, binders)
+            }
+            Term::Constr { fields, .. } => fields.iter().any(|field| walk(field, target, binders)),
+            Term::Case { constr, branches, .. } => {
+                walk(constr, target, binders) || branches.iter().any(|branch| walk(branch, target, binders))
+            }
+            Term::Var { .. } | Term::Constant { .. } | Term::Error { .. } | Term::Builtin { .. } => false,
+        }
+    }
+
+    let mut binders = vec![];
+    walk(term, target, &mut binders).then_some(binders)
+}
+
+fn find_env_in_value(value: &Value, wanted: &str, env_ids: &EnvIds, seen: &mut HashSet<*const Vec<Value>>) -> Option<Rc<Vec<Value>>> {
+    match value {
+        Value::Delay { env, .. } | Value::Lambda { env, .. } => find_env(env, wanted, env_ids, seen),
+        Value::Builtin { runtime, .. } => runtime
+            .args
+            .iter()
+            .find_map(|arg| find_env_in_value(arg, wanted, env_ids, seen)),
+        Value::Constr { fields, .. } => fields
+            .iter()
+            .find_map(|field| find_env_in_value(field, wanted, env_ids, seen)),
+        Value::Con(_) => None,
+    }
+}
+
+fn find_env(env: &Rc<Vec<Value>>, wanted: &str, env_ids: &EnvIds, seen: &mut HashSet<*const Vec<Value>>) -> Option<Rc<Vec<Value>>> {
+    if !seen.insert(Rc::as_ptr(env)) {
+        return None;
+    }
+    if env_ids.known(env).as_deref() == Some(wanted) {
+        return Some(env.clone());
+    }
+    env.iter().find_map(|value| find_env_in_value(value, wanted, env_ids, seen))
+}
+
+/// Looks up the value a pin target refers to in the current machine state
+pub fn resolve_pin_target(
+    target: &ValuePinTarget,
+    state: &MachineState,
+    contexts: &[Context],
+    program: &Term<NamedDeBruijn>,
+    env_ids: &EnvIds,
+) -> Result<Value, String> {
+    match target {
+        ValuePinTarget::EnvSlot { env_id: wanted, index } => {
+            let mut seen = HashSet::new();
+            let current = match state {
+                MachineState::Compute(_, env, _) => find_env(env, wanted, env_ids, &mut seen),
+                MachineState::Return(_, value) => find_env_in_value(value, wanted, env_ids, &mut seen),
+                MachineState::Done(_) => None,
+            };
+            let env = current
+                .or_else(|| {
+                    contexts.iter().find_map(|context| match context {
+                        Context::FrameAwaitArg(value, ..) | Context::FrameAwaitFunValue(value, ..) => {
+                            find_env_in_value(value, wanted, env_ids, &mut seen)
+                        }
+                        _ => frame_env(context).and_then(|env| find_env(env, wanted, env_ids, &mut seen)),
+                    })
+                })
+                .ok_or_else(|| format!("No env {} is reachable from the current state", wanted))?;
+            env.get(*index)
+                .cloned()
+                .ok_or_else(|| format!("Env {} has {} values, no index {}", wanted, env.len(), index))
+        }
+        ValuePinTarget::Binder { term_id: binder } => {
+            let MachineState::Compute(_, env, term) = state else {
+                return Err("Binders can only be looked up while a term is being computed".to_string());
+            };
+            let binders = enclosing_binders(program, term_id(term))
+                .ok_or_else(|| format!("Term {} is not part of the program", term_id(term)))?;
+            let position = binders
+                .iter()
+                .position(|id| id == binder)
+                .ok_or_else(|| format!("Term {} is not a Lambda in scope of the current term", binder))?;
+            env.get(position)
+                .cloned()
+                .ok_or_else(|| format!("The current env has no value for binder {}", binder))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integer(value: i64) -> Value {
+        Value::Con(Rc::new(Constant::Integer(value.into())))
+    }
+
+    #[test]
+    fn test_pinned_value_occurrences() {
+        let pinned = integer(42);
+        let env_ids = EnvIds::default();
+        let mut pins = ValuePins::default();
+        pins.pin("answer".to_string(), ValuePinTarget::Binder { term_id: 1 }, 3, pinned.clone());
+
+        let env = Rc::new(vec![integer(1), pinned.clone()]);
+        let state = MachineState::Compute(Context::NoFrame, env.clone(), Term::Error { uniq_id: 5 });
+        pins.observe(&state, 4, &env_ids);
+        // The same env again adds nothing
+        pins.observe(&state, 5, &env_ids);
+        pins.observe(&MachineState::Return(Context::NoFrame, integer(42)), 6, &env_ids);
+        pins.observe(&MachineState::Return(Context::NoFrame, integer(7)), 7, &env_ids);
+
+        let report = pins.report("answer").unwrap();
+        let matched: Vec<_> = report.occurrences.iter().map(|o| (o.step, o.matched.clone())).collect();
+        assert_eq!(matched, vec![(4, PinMatch::Identical), (6, PinMatch::Equal)]);
+
+        assert_eq!(report.occurrences[0].env_id, Some(env_ids.id(&env)));
+
+        pins.truncate_after(5);
+        assert_eq!(pins.report("answer").unwrap().occurrences.len(), 1);
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use num_bigint::{BigInt, Sign};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::{
+    ast::Constant,
+    machine::{value::Value, Context, MachineState},
+    PlutusData,
+};
+
+use super::cert_encoding::constr_fields;
+use crate::hash_format::normalize_script_hash;
+
+/// Upper bound on reported matches
+const MAX_MATCHES: usize = 200;
+
+/// Upper bound on values and data nodes visited in one search
+const MAX_VISITED: usize = 200_000;
+
+/// What a searched value must look like. Matches both UPLC constants and the
+/// corresponding Plutus data nodes (`BoundedBytes`, `BigInt`, data `Constr`).
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "kind")]
+pub enum ValuePredicate {
+    /// A ByteString, optionally of a given length (e.g. 28 for key and script hashes) or
+    /// content, given as hex or, for script hashes, as `script1…`
+    ByteString {
+        #[serde(default)]
+        length: Option<usize>,
+        #[serde(default)]
+        equals: Option<String>,
+    },
+    /// An integer equal to the given decimal value
+    Integer { equals: String },
+    /// A SOP or data constructor with the given tag
+    Constr { tag: u64 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ValueMatch {
+    /// "env", "context" or "state": the lazy getter `path` applies to
+    pub root: String,
+    /// Path segments in the format of the lazy getters; below a Data constant the
+    /// segments follow its serialized JSON
+    pub path: Vec<String>,
+    /// "ByteString", "Integer", "Constr" or "Data"
+    pub kind: String,
+    /// Short rendering of the matched value
+    pub preview: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ValueSearchResult {
+    pub matches: Vec<ValueMatch>,
+    /// Whether the search stopped at the match or visit limit
+    pub truncated: bool,
+}
+
+enum Matcher {
+    ByteString { length: Option<usize>, equals: Option<Vec<u8>> },
+    Integer(BigInt),
+    Constr(u64),
+}
+
+impl ValuePredicate {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid predicate: {}", e))
+    }
+
+    fn matcher(&self) -> Result<Matcher, String> {
+        Ok(match self {
+            ValuePredicate::ByteString { length, equals } => Matcher::ByteString {
+                length: *length,
+                equals: equals.as_deref().map(searched_bytes).transpose()?,
+            },
+            ValuePredicate::Integer { equals } => {
+                Matcher::Integer(equals.parse().map_err(|_| format!("Invalid integer: {}", equals))?)
+            }
+            ValuePredicate::Constr { tag } => Matcher::Constr(*tag),
+        })
+    }
+}
+
+impl Matcher {
+    fn bytes(&self, bytes: &[u8]) -> bool {
+        match self {
+            Matcher::ByteString { length, equals } => {
+                length.is_none_or(|length| bytes.len() == length)
+                    && equals.as_ref().is_none_or(|equals| equals.as_slice() == bytes)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn preview_bytes(bytes: &[u8]) -> String {
+    if bytes.len() <= 32 {
+        format!("#{}", hex::encode(bytes))
+    } else {
+        format!("#{}… ({} bytes)", hex::encode(&bytes[..32]), bytes.len())
+    }
+}
+
+fn data_integer(data: &PlutusData) -> Option<BigInt> {
+    let PlutusData::BigInt(big_int) = data else {
+        return None;
+    };
+    Some(match big_int {
+        pallas_primitives::BigInt::Int(int) => BigInt::from(i128::from(*int)),
+        pallas_primitives::BigInt::BigUInt(bytes) => BigInt::from_bytes_be(Sign::Plus, bytes),
+        pallas_primitives::BigInt::BigNInt(bytes) => -BigInt::from_bytes_be(Sign::Plus, bytes) - 1,
+    })
+}
+
+struct Search {
+    matcher: Matcher,
+    matches: Vec<ValueMatch>,
+    visited: usize,
+    /// Envs already searched; closures share them, so each is reported once
+    seen_envs: HashSet<*const Vec<Value>>,
+}
+
+impl Search {
+    fn new(predicate: &ValuePredicate) -> Result<Self, String> {
+        Ok(Search {
+            matcher: predicate.matcher()?,
+            matches: vec![],
+            visited: 0,
+            seen_envs: HashSet::new(),
+        })
+    }
+
+    fn exhausted(&self) -> bool {
+        self.matches.len() >= MAX_MATCHES || self.visited >= MAX_VISITED
+    }
+
+    fn report(&mut self, root: &str, path: &[String], kind: &str, preview: String) {
+        if self.matches.len() < MAX_MATCHES {
+            self.matches.push(ValueMatch {
+                root: root.to_string(),
+                path: path.to_vec(),
+                kind: kind.to_string(),
+                preview,
+            });
+        }
+    }
+
+    fn with<F: FnOnce(&mut Self, &mut Vec<String>)>(&mut self, path: &mut Vec<String>, segments: &[&str], f: F) {
+        let depth = path.len();
+        path.extend(segments.iter().map(|segment| segment.to_string()));
+        f(self, path);
+        path.truncate(depth);
+    }
+
+    fn env(&mut self, root: &str, path: &mut Vec<String>, env: &Rc<Vec<Value>>) {
+        if !self.seen_envs.insert(Rc::as_ptr(env)) {
+            return;
+        }
+        for (index, value) in env.iter().enumerate() {
+            self.with(path, &["values", &index.to_string()], |search, path| search.value(root, path, value));
+        }
+    }
+
+    fn value(&mut self, root: &str, path: &mut Vec<String>, value: &Value) {
+        if self.exhausted() {
+            return;
+        }
+        self.visited += 1;
+        match value {
+            Value::Con(constant) => {
+                self.with(path, &["constant"], |search, path| search.constant(root, path, constant))
+            }
+            Value::Delay { env, .. } | Value::Lambda { env, .. } => {
+                self.with(path, &["env"], |search, path| search.env(root, path, env))
+            }
+            Value::Builtin { runtime, .. } => {
+                for (index, arg) in runtime.args.iter().enumerate() {
+                    self.with(path, &["runtime", "args", &index.to_string()], |search, path| {
+                        search.value(root, path, arg)
+                    });
+                }
+            }
+            Value::Constr { tag, fields, .. } => {
+                if matches!(self.matcher, Matcher::Constr(wanted) if wanted == *tag as u64) {
+                    self.report(root, path, "Constr", format!("Constr {} ({} fields)", tag, fields.len()));
+                }
+                for (index, field) in fields.iter().enumerate() {
+                    self.with(path, &["fields", &index.to_string()], |search, path| {
+                        search.value(root, path, field)
+                    });
+                }
+            }
+        }
+    }
+
+    fn constant(&mut self, root: &str, path: &mut Vec<String>, constant: &Constant) {
+        if self.exhausted() {
+            return;
+        }
+        self.visited += 1;
+        match constant {
+            Constant::ByteString(bytes) if self.matcher.bytes(bytes) => {
+                self.report(root, path, "ByteString", preview_bytes(bytes))
+            }
+            Constant::Integer(integer) if matches!(&self.matcher, Matcher::Integer(wanted) if wanted == integer) => {
+                self.report(root, path, "Integer", integer.to_string())
+            }
+            Constant::ProtoList(_, values) => {
+                for (index, value) in values.iter().enumerate() {
+                    self.with(path, &["values", &index.to_string()], |search, path| {
+                        search.constant(root, path, value)
+                    });
+                }
+            }
+            Constant::ProtoPair(_, _, first, second) => {
+                self.with(path, &["first_element"], |search, path| search.constant(root, path, first));
+                self.with(path, &["second_element"], |search, path| search.constant(root, path, second));
+            }
+            Constant::Data(data) => self.with(path, &["data"], |search, path| search.data(root, path, data)),
+            _ => {}
+        }
+    }
+
+    fn data(&mut self, root: &str, path: &mut Vec<String>, data: &PlutusData) {
+        if self.exhausted() {
+            return;
+        }
+        self.visited += 1;
+        match data {
+            PlutusData::BoundedBytes(bytes) if self.matcher.bytes(bytes) => {
+                self.report(root, path, "Data", format!("B {}", preview_bytes(bytes)))
+            }
+            PlutusData::BigInt(_) => {
+                if let (Matcher::Integer(wanted), Some(integer)) = (&self.matcher, data_integer(data)) {
+                    if *wanted == integer {
+                        self.report(root, path, "Data", format!("I {}", integer));
+                    }
+                }
+            }
+            PlutusData::Constr(_) => {
+                let Some((tag, fields)) = constr_fields(data) else {
+                    return;
+                };
+                if matches!(self.matcher, Matcher::Constr(wanted) if wanted == tag) {
+                    self.report(root, path, "Data", format!("Constr {} ({} fields)", tag, fields.len()));
+                }
+                for (index, field) in fields.iter().enumerate() {
+                    self.with(path, &["fields", &index.to_string()], |search, path| search.data(root, path, field));
+                }
+            }
+            PlutusData::Map(pairs) => {
+                for (index, (key, value)) in pairs.iter().enumerate() {
+                    let index = index.to_string();
+                    self.with(path, &["key_value_pairs", &index, "key"], |search, path| search.data(root, path, key));
+                    self.with(path, &["key_value_pairs", &index, "value"], |search, path| {
+                        search.data(root, path, value)
+                    });
+                }
+            }
+            PlutusData::Array(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    self.with(path, &["values", &index.to_string()], |search, path| search.data(root, path, value));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Searches the current env, the returned value, every frame of the machine
+/// context and the arguments of partially applied builtins for values matching
+/// `predicate`. Envs shared by several closures are searched once.
+pub fn find_values(
+    state: &MachineState,
+    contexts: &[Context],
+    predicate: &ValuePredicate,
+) -> Result<ValueSearchResult, String> {
+    let mut search = Search::new(predicate)?;
+    let mut path = vec![];
+
+    match state {
+        MachineState::Compute(_, env, _) => search.env("env", &mut path, env),
+        MachineState::Return(_, value) => {
+            search.with(&mut path, &["value"], |search, path| search.value("state", path, value))
+        }
+        MachineState::Done(_) => {}
+    }
+
+    for (index, context) in contexts.iter().enumerate() {
+        let index = index.to_string();
+        match context {
+            Context::FrameAwaitArg(value, ..) | Context::FrameAwaitFunValue(value, ..) => {
+                search.with(&mut path, &[&index, "value"], |search, path| search.value("context", path, value))
+            }
+            Context::FrameConstr(env, _, _, values, ..) => {
+                for (value_index, value) in values.iter().enumerate() {
+                    search.with(&mut path, &[&index, "values", &value_index.to_string()], |search, path| {
+                        search.value("context", path, value)
+                    });
+                }
+                search.with(&mut path, &[&index, "env"], |search, path| search.env("context", path, env));
+            }
+            Context::FrameAwaitFunTerm(env, ..) | Context::FrameCases(env, ..) => {
+                search.with(&mut path, &[&index, "env"], |search, path| search.env("context", path, env))
+            }
+            _ => {}
+        }
+    }
+
+    let truncated = search.exhausted();
+    Ok(ValueSearchResult {
+        matches: search.matches,
+        truncated,
+    })
+}
+
+/// Bytes a ByteString predicate looks for, from hex or a `script1…` script hash
+fn searched_bytes(text: &str) -> Result<Vec<u8>, String> {
+    let hex_bytes = if text.trim().to_lowercase().starts_with("script1") {
+        normalize_script_hash(text)?
+    } else {
+        text.to_string()
+    };
+    hex::decode(hex_bytes).map_err(|e| format!("Invalid hex: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_values_in_env() {
+        let env = Rc::new(vec![
+            Value::Con(Rc::new(Constant::ByteString(vec![7; 28]))),
+            Value::Con(Rc::new(Constant::Integer(42.into()))),
+            Value::Con(Rc::new(Constant::Data(PlutusData::BoundedBytes(vec![1; 28].into())))),
+        ]);
+        let predicate = ValuePredicate::from_json(r#"{"kind":"ByteString","length":28}"#).unwrap();
+        let mut search = Search::new(&predicate).unwrap();
+        search.env("env", &mut vec![], &env);
+        // A second visit of the same env reports nothing new
+        search.env("env", &mut vec![], &env);
+
+        let paths: Vec<_> = search.matches.iter().map(|m| m.path.join("/")).collect();
+        assert_eq!(paths, vec!["values/0/constant", "values/2/constant/data"]);
+    }
+
+    #[test]
+    fn test_bytestrings_searched_by_bech32_script_hash() {
+        let hash = vec![7; 28];
+        let bech32 = crate::hash_format::script_hash_to_bech32(&hex::encode(&hash)).unwrap();
+        assert_eq!(searched_bytes(&bech32).unwrap(), hash);
+        assert_eq!(searched_bytes(&hex::encode(&hash)).unwrap(), hash);
+        assert!(searched_bytes("script1").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::{Rc, Weak};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uplc::machine::{value::Value, Context};
+
+use crate::serializer::{term_to_either_term_or_id, EitherTermOrId, SerializableConstant};
+
+/// Value representation where closure environments are replaced by references
+/// into the shared env table
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "value_type")]
+pub enum SerializableSharedValue {
+    #[serde(rename = "Con")]
+    Con {
+        constant: SerializableConstant,
+    },
+    #[serde(rename = "Delay")]
+    Delay {
+        body: Box<EitherTermOrId>,
+        env_id: String,
+        term_id: i32,
+    },
+    #[serde(rename = "Lambda")]
+    Lambda {
+        #[serde(rename = "parameterName")]
+        parameter_name: String,
+        body: Box<EitherTermOrId>,
+        env_id: String,
+        term_id: i32,
+    },
+    #[serde(rename = "Builtin")]
+    Builtin {
+        fun: String,
+        args: Vec<SerializableSharedValue>,
+        forces: u32,
+        arity: u32,
+        term_id: i32,
+    },
+    #[serde(rename = "Constr")]
+    Constr {
+        tag: usize,
+        fields: Vec<SerializableSharedValue>,
+        term_id: i32,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SerializableSharedEnv {
+    pub values: Vec<SerializableSharedValue>,
+    /// Number of times this env is referenced from frames, values and other envs
+    pub references: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "context_type")]
+pub enum SerializableSharedFrame {
+    #[serde(rename = "FrameAwaitArg")]
+    FrameAwaitArg { value: SerializableSharedValue },
+    #[serde(rename = "FrameAwaitFunTerm")]
+    FrameAwaitFunTerm {
+        env_id: String,
+        term: EitherTermOrId,
+    },
+    #[serde(rename = "FrameAwaitFunValue")]
+    FrameAwaitFunValue { value: SerializableSharedValue },
+    #[serde(rename = "FrameForce")]
+    FrameForce,
+    #[serde(rename = "FrameConstr")]
+    FrameConstr {
+        env_id: String,
+        tag: usize,
+        terms: Vec<EitherTermOrId>,
+        values: Vec<SerializableSharedValue>,
+        term_id: i32,
+    },
+    #[serde(rename = "FrameCases")]
+    FrameCases {
+        env_id: String,
+        terms: Vec<EitherTermOrId>,
+    },
+    #[serde(rename = "NoFrame")]
+    NoFrame,
+}
+
+/// Machine context where every distinct env is serialized exactly once
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SerializableSharedContext {
+    pub frames: Vec<SerializableSharedFrame>,
+    pub envs: BTreeMap<String, SerializableSharedEnv>,
+}
+
+/// Identity of the envs visible in the current machine state
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SerializableEnvIdentity {
+    /// Env the current term executes under (Compute states only)
+    pub current_env_id: Option<String>,
+    /// Env captured by each frame of the context chain, outermost last
+    pub frame_env_ids: Vec<Option<String>>,
+    /// Whether the current env differs from the one of the previous step
+    pub changed_since_last_step: bool,
+    pub generation: u64,
+}
+
+/// Entries at which `EnvIds` first drops the ids of freed envs
+const ENV_ID_PRUNE_THRESHOLD: usize = 1024;
+
+#[derive(Debug, Clone)]
+struct EnvIdTable {
+    ids: HashMap<*const Vec<Value>, (Weak<Vec<Value>>, u64)>,
+    next: u64,
+    prune_at: usize,
+}
+
+/// Ids of the env instances a session has seen, numbered in the order they were
+/// first seen. Each id is kept with a weak reference to its env, which keeps the
+/// allocation from being reused while the id is known; ids of freed envs are
+/// dropped from time to time, so a later env at the same address gets a new id.
+#[derive(Debug, Clone)]
+pub struct EnvIds {
+    table: RefCell<EnvIdTable>,
+}
+
+impl Default for EnvIds {
+    fn default() -> Self {
+        EnvIds {
+            table: RefCell::new(EnvIdTable {
+                ids: HashMap::new(),
+                next: 0,
+                prune_at: ENV_ID_PRUNE_THRESHOLD,
+            }),
+        }
+    }
+}
+
+impl EnvIds {
+    /// Id of `env`, numbering it when it is seen for the first time
+    pub fn id(&self, env: &Rc<Vec<Value>>) -> String {
+        let mut table = self.table.borrow_mut();
+        if let Some((_, id)) = table.ids.get(&Rc::as_ptr(env)) {
+            return format!("env-{}", id);
+        }
+        if table.ids.len() >= table.prune_at {
+            table.ids.retain(|_, (env, _)| env.strong_count() > 0);
+            table.prune_at = (table.ids.len() * 2).max(ENV_ID_PRUNE_THRESHOLD);
+        }
+        let id = table.next;
+        table.next += 1;
+        table.ids.insert(Rc::as_ptr(env), (Rc::downgrade(env), id));
+        format!("env-{}", id)
+    }
+
+    /// Id of `env` if it was numbered before
+    pub fn known(&self, env: &Rc<Vec<Value>>) -> Option<String> {
+        let table = self.table.borrow();
+        table.ids.get(&Rc::as_ptr(env)).map(|(_, id)| format!("env-{}", id))
+    }
+}
+
+/// Env captured by a frame, if any
+pub fn frame_env(context: &Context) -> Option<&Rc<Vec<Value>>> {
+    match context {
+        Context::FrameAwaitFunTerm(env, ..)
+        | Context::FrameConstr(env, ..)
+        | Context::FrameCases(env, ..) => Some(env),
+        _ => None,
+    }
+}
+
+/// Serializes envs into a table keyed by env id, visiting each Rc only once
+pub struct EnvInterner<'a> {
+    term_ids: &'a HashSet<i32>,
+    env_ids: &'a EnvIds,
+    envs: BTreeMap<String, SerializableSharedEnv>,
+}
+
+impl<'a> EnvInterner<'a> {
+    pub fn new(term_ids: &'a HashSet<i32>, env_ids: &'a EnvIds) -> Self {
+        EnvInterner {
+            term_ids,
+            env_ids,
+            envs: BTreeMap::new(),
+        }
+    }
+
+    pub fn intern_env(&mut self, env: &Rc<Vec<Value>>) -> String {
+        let id = self.env_ids.id(env);
+        if let Some(shared) = self.envs.get_mut(&id) {
+            shared.references += 1;
+            return id;
+        }
+
+        // Reserve the slot before descending so nested references to the same env resolve
+        self.envs.insert(
+            id.clone(),
+            SerializableSharedEnv {
+                values: vec![],
+                references: 1,
+            },
+        );
+        let values = env.iter().map(|value| self.intern_value(value)).collect();
+        if let Some(shared) = self.envs.get_mut(&id) {
+            shared.values = values;
+        }
+        id
+    }
+
+    pub fn intern_value(&mut self, value: &Value) -> SerializableSharedValue {
+        match value {
+            Value::Con(constant) => SerializableSharedValue::Con {
+                constant: SerializableConstant::from_uplc_constant(constant.as_ref()),
+            },
+            Value::Delay { body, env, term_id } => SerializableSharedValue::Delay {
+                body: Box::new(term_to_either_term_or_id(body.as_ref(), self.term_ids)),
+                env_id: self.intern_env(env),
+                term_id: *term_id as i32,
+            },
+            Value::Lambda { parameter_name, body, env, term_id } => SerializableSharedValue::Lambda {
+                parameter_name: parameter_name.text.clone(),
+                body: Box::new(term_to_either_term_or_id(body.as_ref(), self.term_ids)),
+                env_id: self.intern_env(env),
+                term_id: *term_id as i32,
+            },
+            Value::Builtin { fun, runtime, term_id } => SerializableSharedValue::Builtin {
+                fun: format!("{:?}", fun),
+                args: runtime.args.iter().map(|arg| self.intern_value(arg)).collect(),
+                forces: runtime.forces,
+                arity: runtime.fun.arity() as u32,
+                term_id: *term_id as i32,
+            },
+            Value::Constr { tag, fields, term_id } => SerializableSharedValue::Constr {
+                tag: *tag,
+                fields: fields.iter().map(|field| self.intern_value(field)).collect(),
+                term_id: *term_id as i32,
+            },
+        }
+    }
+
+    pub fn intern_frame(&mut self, context: &Context) -> SerializableSharedFrame {
+        match context {
+            Context::FrameAwaitArg(value, ..) => SerializableSharedFrame::FrameAwaitArg {
+                value: self.intern_value(value),
+            },
+            Context::FrameAwaitFunTerm(env, term, ..) => SerializableSharedFrame::FrameAwaitFunTerm {
+                env_id: self.intern_env(env),
+                term: term_to_either_term_or_id(term, self.term_ids),
+            },
+            Context::FrameAwaitFunValue(value, ..) => SerializableSharedFrame::FrameAwaitFunValue {
+                value: self.intern_value(value),
+            },
+            Context::FrameForce(..) => SerializableSharedFrame::FrameForce,
+            Context::FrameConstr(env, tag, terms, values, .., term_id) => SerializableSharedFrame::FrameConstr {
+                env_id: self.intern_env(env),
+                tag: *tag,
+                terms: terms
+                    .iter()
+                    .map(|term| term_to_either_term_or_id(term, self.term_ids))
+                    .collect(),
+                values: values.iter().map(|value| self.intern_value(value)).collect(),
+                term_id: *term_id as i32,
+            },
+            Context::FrameCases(env, terms, ..) => SerializableSharedFrame::FrameCases {
+                env_id: self.intern_env(env),
+                terms: terms
+                    .iter()
+                    .map(|term| term_to_either_term_or_id(term, self.term_ids))
+                    .collect(),
+            },
+            Context::NoFrame => SerializableSharedFrame::NoFrame,
+        }
+    }
+
+    pub fn into_envs(self) -> BTreeMap<String, SerializableSharedEnv> {
+        self.envs
+    }
+}
+
+impl SerializableSharedContext {
+    /// Serialize a chain of nested contexts, sharing envs between frames
+    pub fn from_uplc_contexts(contexts: &[Context], term_ids: &HashSet<i32>, env_ids: &EnvIds) -> Self {
+        let mut interner = EnvInterner::new(term_ids, env_ids);
+        let frames = contexts
+            .iter()
+            .map(|context| interner.intern_frame(context))
+            .collect();
+        SerializableSharedContext {
+            frames,
+            envs: interner.into_envs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_ids() {
+        let env_ids = EnvIds::default();
+        let first = Rc::new(vec![]);
+        let second = Rc::new(vec![]);
+        assert_eq!(env_ids.known(&first), None);
+        assert_eq!(env_ids.id(&first), "env-0");
+        assert_eq!(env_ids.id(&second), "env-1");
+        assert_eq!(env_ids.id(&first.clone()), "env-0");
+        assert_eq!(env_ids.known(&second).as_deref(), Some("env-1"));
+
+        drop(first);
+        for _ in 0..ENV_ID_PRUNE_THRESHOLD {
+            let id = env_ids.id(&Rc::new(vec![]));
+            assert_ne!(id, "env-0");
+        }
+        assert_eq!(env_ids.id(&second), "env-1");
+    }
+}
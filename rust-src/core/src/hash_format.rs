@@ -0,0 +1,143 @@
+use bech32::{FromBase32, ToBase32, Variant};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::debugger_engine::DebuggerError;
+use crate::script_context::utils::{parse_stake_credential, reward_account_to_bech32, stake_credential_to_reward_account};
+use crate::script_context::SerializableStakeCredential;
+use crate::wasm_tools::{enveloped_json, wasm_bindgen, JsError};
+
+/// CIP-5 prefix for script hashes (policy ids are script hashes too)
+const SCRIPT_HASH_HRP: &str = "script";
+
+/// CIP-14 prefix for asset fingerprints
+const ASSET_FINGERPRINT_HRP: &str = "asset";
+
+const SCRIPT_HASH_LENGTH: usize = 28;
+
+/// A script hash or policy id in both of its textual forms
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct HashForms {
+    pub hex: String,
+    pub bech32: String,
+}
+
+fn encode_bech32(hrp: &str, bytes: &[u8]) -> Result<String, String> {
+    bech32::encode(hrp, bytes.to_base32(), Variant::Bech32).map_err(|e| e.to_string())
+}
+
+/// Encodes a hex script hash or policy id as `script1…`
+pub fn script_hash_to_bech32(hash_hex: &str) -> Result<String, String> {
+    let bytes = hex::decode(hash_hex).map_err(|e| format!("Invalid hash {}: {}", hash_hex, e))?;
+    if bytes.len() != SCRIPT_HASH_LENGTH {
+        return Err(format!("Invalid hash {}: expected {} bytes", hash_hex, SCRIPT_HASH_LENGTH));
+    }
+    encode_bech32(SCRIPT_HASH_HRP, &bytes)
+}
+
+/// Accepts a script hash or policy id either as hex or as CIP-5 `script1…`
+/// and returns its lowercase hex form, which is what every API compares against
+pub fn normalize_script_hash(hash: &str) -> Result<String, String> {
+    let hash = hash.trim();
+    let bytes = if hash.to_lowercase().starts_with(&format!("{}1", SCRIPT_HASH_HRP)) {
+        let (hrp, data, _) = bech32::decode(hash).map_err(|e| format!("Invalid bech32 hash {}: {}", hash, e))?;
+        if hrp != SCRIPT_HASH_HRP {
+            return Err(format!("Unexpected bech32 prefix {} in {}", hrp, hash));
+        }
+        Vec::<u8>::from_base32(&data).map_err(|e| format!("Invalid bech32 hash {}: {}", hash, e))?
+    } else {
+        hex::decode(hash).map_err(|e| format!("Invalid hash {}: {}", hash, e))?
+    };
+    if bytes.len() != SCRIPT_HASH_LENGTH {
+        return Err(format!("Invalid hash {}: expected {} bytes", hash, SCRIPT_HASH_LENGTH));
+    }
+    Ok(hex::encode(bytes))
+}
+
+impl HashForms {
+    pub fn from_script_hash(hash: &str) -> Result<Self, String> {
+        let hex = normalize_script_hash(hash)?;
+        let bech32 = script_hash_to_bech32(&hex)?;
+        Ok(HashForms { hex, bech32 })
+    }
+
+    /// Whether `query` names this hash, in either form
+    pub fn matches(&self, query: &str) -> bool {
+        normalize_script_hash(query).is_ok_and(|query| query == self.hex)
+    }
+}
+
+/// A stake credential with its reward account in both textual forms
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct StakeCredentialForms {
+    pub credential: SerializableStakeCredential,
+    pub reward_account: String,
+    pub stake_address: String,
+}
+
+impl StakeCredentialForms {
+    /// Accepts any form understood by `parse_stake_credential`
+    pub fn from_input(input: &str, network: &str) -> Result<Self, String> {
+        let network_tag = if network.trim().eq_ignore_ascii_case("mainnet") { 1 } else { 0 };
+        let credential = parse_stake_credential(input).map_err(|e| e.to_string())?;
+        let reward_account = stake_credential_to_reward_account(&credential, network_tag).map_err(|e| e.to_string())?;
+        Ok(StakeCredentialForms {
+            credential,
+            stake_address: reward_account_to_bech32(&reward_account).map_err(|e| e.to_string())?,
+            reward_account: hex::encode(reward_account),
+        })
+    }
+}
+
+/// CIP-14 fingerprint (`asset1…`) of the asset with the given policy id and hex asset name
+pub fn asset_fingerprint(policy_id: &str, asset_name_hex: &str) -> Result<String, String> {
+    use pallas_crypto::hash::Hasher;
+    let mut bytes = hex::decode(normalize_script_hash(policy_id)?).map_err(|e| e.to_string())?;
+    bytes.extend(hex::decode(asset_name_hex).map_err(|e| format!("Invalid asset name {}: {}", asset_name_hex, e))?);
+    encode_bech32(ASSET_FINGERPRINT_HRP, Hasher::<160>::hash(&bytes).as_ref())
+}
+
+/// Returns the hex and `script1…` forms of a script hash or policy id given in either form
+#[wasm_bindgen]
+pub fn script_hash_forms(hash: &str) -> Result<String, JsError> {
+    let forms = HashForms::from_script_hash(hash).map_err(DebuggerError::TransactionParseError)?;
+    Ok(enveloped_json(&forms).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+}
+
+/// Returns the CIP-14 fingerprint of an asset; the policy id may be hex or `script1…`
+#[wasm_bindgen]
+pub fn get_asset_fingerprint(policy_id: &str, asset_name_hex: &str) -> Result<String, JsError> {
+    Ok(asset_fingerprint(policy_id, asset_name_hex).map_err(DebuggerError::TransactionParseError)?)
+}
+
+/// Returns the credential, reward account hex and stake address for a stake
+/// credential given as any of them (or as a `script1…` hash) on `network`
+#[wasm_bindgen]
+pub fn stake_credential_forms(input: &str, network: &str) -> Result<String, JsError> {
+    let forms = StakeCredentialForms::from_input(input, network).map_err(DebuggerError::TransactionParseError)?;
+    Ok(enveloped_json(&forms).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_hash_forms() {
+        let hex = "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209";
+        let forms = HashForms::from_script_hash(hex).unwrap();
+        assert!(forms.bech32.starts_with("script1"));
+        assert_eq!(normalize_script_hash(&forms.bech32).unwrap(), hex);
+        assert!(forms.matches(&forms.bech32.to_uppercase()));
+        assert!(normalize_script_hash("addr1").is_err());
+    }
+
+    #[test]
+    fn test_asset_fingerprint() {
+        // Test vector from CIP-14
+        assert_eq!(
+            asset_fingerprint("7eae28af2208be856f7a119668ae52a49b73725e326dc16579dcc373", "").unwrap(),
+            "asset1rjklcrnsdzqp65wjgrg55sy9723kw09mlgvlc3"
+        );
+    }
+}
@@ -0,0 +1,665 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::cell::Cell;
+
+/// Default size (in bytes) above which ByteString constants are serialized as a preview
+pub const DEFAULT_BYTESTRING_PREVIEW_THRESHOLD: usize = 1024;
+
+/// Number of bytes shown at each end of a ByteString preview
+pub const BYTESTRING_PREVIEW_EDGE_BYTES: usize = 32;
+
+/// Renderings added next to the hex of every ByteString constant
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct ByteStringRendering {
+    /// Add the bytes as text when they are valid UTF-8 without control characters
+    pub utf8: bool,
+    /// Add the bytes in standard padded base64
+    pub base64: bool,
+}
+
+/// How a session serializes constants. Each session keeps its own options and
+/// puts them in effect for the duration of a call with `use_serializer_options`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SerializerOptions {
+    /// Size in bytes above which lazy getters preview ByteStrings instead of
+    /// encoding them fully; `None` always gives the full hex
+    pub bytestring_preview_threshold: Option<usize>,
+    /// Renderings added next to the hex of ByteString constants
+    pub bytestring_rendering: ByteStringRendering,
+    /// Length above which full getters replace ByteString constants (in bytes)
+    /// and Integer constants (in decimal digits) with a `Truncated` preview;
+    /// `None` keeps every constant whole
+    pub constant_truncation_threshold: Option<usize>,
+}
+
+const DEFAULT_SERIALIZER_OPTIONS: SerializerOptions = SerializerOptions {
+    bytestring_preview_threshold: Some(DEFAULT_BYTESTRING_PREVIEW_THRESHOLD),
+    bytestring_rendering: ByteStringRendering { utf8: false, base64: false },
+    constant_truncation_threshold: None,
+};
+
+impl Default for SerializerOptions {
+    fn default() -> Self {
+        DEFAULT_SERIALIZER_OPTIONS
+    }
+}
+
+thread_local! {
+    static SERIALIZER_OPTIONS: Cell<SerializerOptions> = const { Cell::new(DEFAULT_SERIALIZER_OPTIONS) };
+}
+
+/// Puts back the serializer options that were in effect before `use_serializer_options`
+pub struct SerializerOptionsGuard {
+    previous: SerializerOptions,
+}
+
+impl Drop for SerializerOptionsGuard {
+    fn drop(&mut self) {
+        SERIALIZER_OPTIONS.with(|cell| cell.set(self.previous));
+    }
+}
+
+/// Puts `options` in effect for every constant serialized until the returned
+/// guard is dropped
+#[must_use]
+pub fn use_serializer_options(options: SerializerOptions) -> SerializerOptionsGuard {
+    SerializerOptionsGuard {
+        previous: SERIALIZER_OPTIONS.with(|cell| cell.replace(options)),
+    }
+}
+
+pub fn bytestring_preview_threshold() -> Option<usize> {
+    SERIALIZER_OPTIONS.with(|cell| cell.get().bytestring_preview_threshold)
+}
+
+/// Characters of the hex or decimal digits kept in a truncated constant's preview
+pub const CONSTANT_PREVIEW_CHARS: usize = 64;
+
+pub fn constant_truncation_threshold() -> Option<usize> {
+    SERIALIZER_OPTIONS.with(|cell| cell.get().constant_truncation_threshold)
+}
+
+pub fn bytestring_rendering() -> ByteStringRendering {
+    SERIALIZER_OPTIONS.with(|cell| cell.get().bytestring_rendering)
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let n = group.iter().enumerate().fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= group.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// The UTF-8 text and base64 renderings of `bytes` the serializer options in effect ask for
+pub fn render_bytestring(bytes: &[u8]) -> (Option<String>, Option<String>) {
+    let rendering = bytestring_rendering();
+    let utf8 = rendering
+        .utf8
+        .then(|| std::str::from_utf8(bytes).ok())
+        .flatten()
+        .filter(|text| !text.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')))
+        .map(str::to_string);
+    let base64 = rendering.base64.then(|| base64(bytes));
+    (utf8, base64)
+}
+
+/// Base wrapper type that supports lazy loading for complex data structures
+/// When depth is 0, only type information is included
+/// When depth > 0, actual data is loaded up to the specified depth
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum LazyLoadable<T> {
+    /// Fully loaded data
+    Loaded(T),
+    /// Only type information, no actual data
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+        /// Blake2b-256 content hash (ByteString and Data constants only), for equality checks
+        #[serde(rename = "_hash", default, skip_serializing_if = "Option::is_none")]
+        hash: Option<String>,
+    },
+}
+
+impl<T> LazyLoadable<T> {
+    pub fn type_only(type_name: String, kind: String, length: Option<usize>) -> Self {
+        LazyLoadable::TypeOnly {
+            type_name,
+            kind,
+            length,
+            hash: None,
+        }
+    }
+
+    pub fn type_only_with_hash(type_name: String, kind: String, length: Option<usize>, hash: Option<String>) -> Self {
+        LazyLoadable::TypeOnly {
+            type_name,
+            kind,
+            length,
+            hash,
+        }
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> LazyLoadable<U> {
+        match self {
+            LazyLoadable::Loaded(value) => LazyLoadable::Loaded(f(value)),
+            LazyLoadable::TypeOnly { type_name, kind, length, hash } => {
+                LazyLoadable::TypeOnly { type_name, kind, length, hash }
+            }
+        }
+    }
+}
+
+/// Trait for types that support lazy loading
+pub trait SupportsLazyLoading {
+    /// Get type information for this value
+    fn get_type_info(&self) -> (String, String, Option<usize>);
+}
+
+// Define concrete lazy loadable types for JSON schemas
+// These are designed to prevent the LazyLoadable2, LazyLoadable3, etc. naming issue
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableValue {
+    Loaded(crate::value::SerializableValueLazy),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+        /// Blake2b-256 content hash (ByteString and Data constants only), for equality checks
+        #[serde(rename = "_hash", default, skip_serializing_if = "Option::is_none")]
+        hash: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableConstant {
+    Loaded(crate::serializer::SerializableConstantLazy),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+        /// Blake2b-256 content hash (ByteString and Data constants only), for equality checks
+        #[serde(rename = "_hash", default, skip_serializing_if = "Option::is_none")]
+        hash: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableTermOrId {
+    Loaded(crate::serializer::EitherTermOrIdLazy),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableEnv {
+    Loaded(crate::value::SerializableEnvLazy),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableContext {
+    Loaded(crate::context::SerializableMachineContextLazy),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableBuiltinRuntime {
+    Loaded(crate::value::SerializableBuiltinRuntimeLazy),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableTerm {
+    Loaded(crate::serializer::SerializableTermLazy),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum LazyLoadableData {
+    Loaded(crate::plutus_data::SerializablePlutusData),
+    TypeOnly {
+        #[serde(rename = "_type")]
+        type_name: String,
+        #[serde(rename = "_kind")]
+        kind: String,
+        #[serde(rename = "_length")]
+        length: Option<usize>,
+        /// Blake2b-256 content hash (ByteString and Data constants only), for equality checks
+        #[serde(rename = "_hash", default, skip_serializing_if = "Option::is_none")]
+        hash: Option<String>,
+    },
+}
+
+// Special types for Box<T> variants used in recursive structures
+
+// Conversion implementations from LazyLoadable<T> to concrete enum types
+impl From<LazyLoadable<crate::value::SerializableValueLazy>> for LazyLoadableValue {
+    fn from(lazy: LazyLoadable<crate::value::SerializableValueLazy>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableValue::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, hash } => {
+                LazyLoadableValue::TypeOnly { type_name, kind, length, hash }
+            }
+        }
+    }
+}
+
+impl From<LazyLoadable<crate::serializer::SerializableConstantLazy>> for LazyLoadableConstant {
+    fn from(lazy: LazyLoadable<crate::serializer::SerializableConstantLazy>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableConstant::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, hash } => {
+                LazyLoadableConstant::TypeOnly { type_name, kind, length, hash }
+            }
+        }
+    }
+}
+
+impl From<LazyLoadable<crate::serializer::EitherTermOrIdLazy>> for LazyLoadableTermOrId {
+    fn from(lazy: LazyLoadable<crate::serializer::EitherTermOrIdLazy>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableTermOrId::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, .. } => {
+                LazyLoadableTermOrId::TypeOnly { type_name, kind, length }
+            }
+        }
+    }
+}
+
+impl From<LazyLoadable<crate::value::SerializableEnvLazy>> for LazyLoadableEnv {
+    fn from(lazy: LazyLoadable<crate::value::SerializableEnvLazy>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableEnv::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, .. } => {
+                LazyLoadableEnv::TypeOnly { type_name, kind, length }
+            }
+        }
+    }
+}
+
+impl From<LazyLoadable<crate::context::SerializableMachineContextLazy>> for LazyLoadableContext {
+    fn from(lazy: LazyLoadable<crate::context::SerializableMachineContextLazy>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableContext::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, .. } => {
+                LazyLoadableContext::TypeOnly { type_name, kind, length }
+            }
+        }
+    }
+}
+
+impl From<LazyLoadable<crate::value::SerializableBuiltinRuntimeLazy>> for LazyLoadableBuiltinRuntime {
+    fn from(lazy: LazyLoadable<crate::value::SerializableBuiltinRuntimeLazy>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableBuiltinRuntime::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, .. } => {
+                LazyLoadableBuiltinRuntime::TypeOnly { type_name, kind, length }
+            }
+        }
+    }
+}
+
+impl From<LazyLoadable<crate::serializer::SerializableTermLazy>> for LazyLoadableTerm {
+    fn from(lazy: LazyLoadable<crate::serializer::SerializableTermLazy>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableTerm::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, .. } => {
+                LazyLoadableTerm::TypeOnly { type_name, kind, length }
+            }
+        }
+    }
+}
+
+impl From<LazyLoadable<crate::plutus_data::SerializablePlutusData>> for LazyLoadableData {
+    fn from(lazy: LazyLoadable<crate::plutus_data::SerializablePlutusData>) -> Self {
+        match lazy {
+            LazyLoadable::Loaded(v) => LazyLoadableData::Loaded(v),
+            LazyLoadable::TypeOnly { type_name, kind, length, hash } => {
+                LazyLoadableData::TypeOnly { type_name, kind, length, hash }
+            }
+        }
+    }
+}
+
+
+/// Configuration for lazy loading
+#[derive(Debug, Clone)]
+pub struct LazyLoadConfig {
+    /// Path to the specific element to load
+    pub path: Vec<PathSegment>,
+    /// If true, return the full object at the path
+    /// If false, return only the object at the path + children 1 level deep
+    pub return_full_object: bool,
+}
+
+/// Represents a segment in the path
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    /// Named field in a struct/object
+    Field(String),
+    /// Index in an array/vector
+    Index(usize),
+}
+
+impl PathSegment {
+    fn to_path_string(&self) -> String {
+        match self {
+            PathSegment::Field(name) => name.clone(),
+            PathSegment::Index(index) => index.to_string(),
+        }
+    }
+}
+
+/// Lazy getter a path is relative to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathRoot {
+    /// `get_machine_state_lazy`
+    MachineState,
+    /// `get_current_env_lazy`
+    Env,
+    /// `get_machine_context_lazy`
+    MachineContext,
+}
+
+impl PathRoot {
+    pub fn parse(root: &str) -> Result<Self, String> {
+        match root {
+            "state" | "machine_state" => Ok(PathRoot::MachineState),
+            "env" => Ok(PathRoot::Env),
+            "context" | "machine_context" => Ok(PathRoot::MachineContext),
+            _ => Err(format!("Unknown path root: {}", root)),
+        }
+    }
+
+    /// Name `parse` reads back, as given in `_root`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PathRoot::MachineState => "state",
+            PathRoot::Env => "env",
+            PathRoot::MachineContext => "context",
+        }
+    }
+}
+
+/// Rewrites a path into the single form lazy payloads report in `_path`. Env
+/// values can be reached as `["env", "values", N]` or `["env", N]`; the canonical
+/// form always names `values`.
+pub fn canonicalize_path(root: PathRoot, path: &[PathSegment]) -> Vec<PathSegment> {
+    let env = PathSegment::Field("env".to_string());
+    let mut canonical = Vec::with_capacity(path.len() + 1);
+    for (position, segment) in path.iter().enumerate() {
+        let indexes_env = match position {
+            0 => root == PathRoot::Env,
+            _ => path[position - 1] == env,
+        };
+        if indexes_env && matches!(segment, PathSegment::Index(_)) {
+            canonical.push(PathSegment::Field("values".to_string()));
+        }
+        canonical.push(segment.clone());
+    }
+    canonical
+}
+
+pub fn path_to_strings(path: &[PathSegment]) -> Vec<String> {
+    path.iter().map(PathSegment::to_path_string).collect()
+}
+
+/// Adds `_path`, the canonical path from the getter root, to every type-only stub
+/// of a serialized lazy payload found at `prefix`, so a client can load it as is
+pub fn embed_stub_paths(payload: &mut serde_json::Value, prefix: &[PathSegment]) {
+    embed_paths(payload, prefix, None);
+}
+
+/// Adds `_root` and `_path` to every `Truncated` constant of a full getter
+/// payload found at `prefix` of `root`, so the lazy getter `_root` names returns
+/// the whole constant at `_path` followed by `value`
+pub fn embed_truncated_paths(payload: &mut serde_json::Value, root: PathRoot, prefix: &[PathSegment]) {
+    embed_paths(payload, prefix, Some(root));
+}
+
+fn embed_paths(payload: &mut serde_json::Value, prefix: &[PathSegment], root: Option<PathRoot>) {
+    fn walk(value: &mut serde_json::Value, path: &mut Vec<String>, root: Option<PathRoot>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if map.contains_key("_type") {
+                    map.insert("_path".to_string(), serde_json::json!(path));
+                    if let Some(root) = root {
+                        map.insert("_root".to_string(), serde_json::json!(root.as_str()));
+                    }
+                    return;
+                }
+                // `{"type": "Term", "term": ...}` wraps a term without adding a path segment
+                let transparent = map.get("type").and_then(|tag| tag.as_str()) == Some("Term");
+                for (key, child) in map.iter_mut() {
+                    if key.starts_with('_') {
+                        continue;
+                    }
+                    if transparent && key == "term" {
+                        walk(child, path, root);
+                    } else {
+                        path.push(key.clone());
+                        walk(child, path, root);
+                        path.pop();
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for (index, child) in values.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    walk(child, path, root);
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+    walk(payload, &mut path_to_strings(prefix), root);
+}
+
+/// Trait for types that can be navigated using a path
+pub trait NavigablePath {
+    /// The output type when navigating
+    type Output;
+    
+    /// Navigate to a specific element using the given path
+    fn navigate(&self, path: &[PathSegment]) -> NavigationResult<Self::Output>;
+}
+
+/// Result of a navigation operation
+#[derive(Debug)]
+pub enum NavigationResult<T> {
+    /// Successfully found the target element
+    Found(T),
+    /// Path is invalid for this structure
+    InvalidPath(String),
+    /// Navigation incomplete - need to go deeper
+    Incomplete,
+}
+
+impl<T> NavigationResult<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> NavigationResult<U> {
+        match self {
+            NavigationResult::Found(value) => NavigationResult::Found(f(value)),
+            NavigationResult::InvalidPath(err) => NavigationResult::InvalidPath(err),
+            NavigationResult::Incomplete => NavigationResult::Incomplete,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializer_options_scope() {
+        use crate::serializer::SerializableConstantLazy;
+
+        let bytes = vec![0xab; 64];
+        let outer = use_serializer_options(SerializerOptions {
+            bytestring_preview_threshold: Some(16),
+            ..SerializerOptions::default()
+        });
+        assert!(matches!(
+            SerializableConstantLazy::from_bytestring(&bytes, false),
+            SerializableConstantLazy::ByteStringPreview { .. }
+        ));
+        {
+            let _inner = use_serializer_options(SerializerOptions {
+                bytestring_preview_threshold: None,
+                ..SerializerOptions::default()
+            });
+            assert!(matches!(
+                SerializableConstantLazy::from_bytestring(&bytes, false),
+                SerializableConstantLazy::ByteString { .. }
+            ));
+        }
+        assert_eq!(bytestring_preview_threshold(), Some(16));
+        drop(outer);
+        assert_eq!(bytestring_preview_threshold(), Some(DEFAULT_BYTESTRING_PREVIEW_THRESHOLD));
+    }
+
+    #[test]
+    fn test_bytestring_renderings() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+
+        assert_eq!(render_bytestring(b"token"), (None, None));
+        let options = use_serializer_options(SerializerOptions {
+            bytestring_rendering: ByteStringRendering { utf8: true, base64: true },
+            ..SerializerOptions::default()
+        });
+        assert_eq!(render_bytestring(b"token"), (Some("token".to_string()), Some("dG9rZW4=".to_string())));
+        assert_eq!(render_bytestring(&[0xff, 0x00]).0, None);
+        assert_eq!(render_bytestring(&[0x00]).0, None);
+        drop(options);
+        assert_eq!(render_bytestring(b"token"), (None, None));
+    }
+
+    #[test]
+    fn test_constant_truncation() {
+        use crate::serializer::SerializableConstant;
+        use uplc::ast::Constant;
+
+        let options = use_serializer_options(SerializerOptions {
+            constant_truncation_threshold: Some(4),
+            ..SerializerOptions::default()
+        });
+        let bytes = SerializableConstant::from_uplc_constant(&Constant::ByteString(vec![0xab; 40]));
+        let json = serde_json::to_value(&bytes).unwrap();
+        assert_eq!(json["preview"].as_str().unwrap().len(), CONSTANT_PREVIEW_CHARS);
+        assert_eq!((json["total_length"].as_u64(), json["_type"].as_str()), (Some(40), Some("ByteString")));
+
+        let integer = SerializableConstant::from_uplc_constant(&Constant::Integer((-123456).into()));
+        let json = serde_json::to_value(&integer).unwrap();
+        assert_eq!((json["preview"].as_str(), json["total_length"].as_u64()), (Some("-123456"), Some(6)));
+        assert!(matches!(
+            SerializableConstant::from_uplc_constant(&Constant::Integer(1234.into())),
+            SerializableConstant::Integer { .. }
+        ));
+        drop(options);
+        assert!(matches!(
+            SerializableConstant::from_uplc_constant(&Constant::ByteString(vec![0xab; 40])),
+            SerializableConstant::ByteString { .. }
+        ));
+    }
+
+    #[test]
+    fn test_canonicalize_env_aliases() {
+        let path = vec![
+            PathSegment::Field("env".to_string()),
+            PathSegment::Index(2),
+            PathSegment::Field("env".to_string()),
+            PathSegment::Field("values".to_string()),
+            PathSegment::Index(0),
+        ];
+        let canonical = canonicalize_path(PathRoot::MachineState, &path);
+        assert_eq!(path_to_strings(&canonical), vec!["env", "values", "2", "env", "values", "0"]);
+        assert_eq!(canonicalize_path(PathRoot::MachineState, &canonical), canonical);
+
+        let canonical = canonicalize_path(PathRoot::Env, &[PathSegment::Index(1)]);
+        assert_eq!(path_to_strings(&canonical), vec!["values", "1"]);
+        // Context indices are not env indices
+        let canonical = canonicalize_path(PathRoot::MachineContext, &[PathSegment::Index(1)]);
+        assert_eq!(path_to_strings(&canonical), vec!["1"]);
+    }
+
+    #[test]
+    fn test_stub_paths_round_trip() {
+        let mut payload = serde_json::json!({
+            "values": [
+                {"value_type": "Lambda", "body": {"type": "Term", "term": {"_type": "Term", "_kind": "Term"}},
+                 "env": {"_type": "Env", "_kind": "Environment (1 values)", "_length": 1}}
+            ]
+        });
+        embed_stub_paths(&mut payload, &[]);
+        assert_eq!(payload["values"][0]["env"]["_path"], serde_json::json!(["values", "0", "env"]));
+        assert_eq!(payload["values"][0]["body"]["term"]["_path"], serde_json::json!(["values", "0", "body"]));
+    }
+}
@@ -0,0 +1,128 @@
+pub mod serializer;
+pub mod plutus_data;
+pub mod value;
+pub mod context;
+pub mod machine_state;
+pub mod utxo;
+pub mod utxo_snapshot;
+pub mod protocol_params;
+pub mod cost_model_upgrade;
+pub mod script_context;
+pub mod debugger_engine;
+pub mod budget;
+pub mod wasm_tools;
+pub mod tx_utils;
+pub mod lazy_loading;
+pub mod env_sharing;
+pub mod hash_format;
+pub mod serialization_format;
+#[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+pub mod schemas;
+
+#[cfg(test)]
+mod tests;
+
+// Re-export main functions and types for easy use
+pub use serializer::{
+    SerializableTerm, 
+    SerializableConstant, 
+    SerializableType,
+    // BLS compressed serialization functions
+    serialize_bls_g1_element_compressed,
+    serialize_bls_g2_element_compressed,
+};
+pub use plutus_data::{
+    SerializablePlutusData, 
+    SerializableBigInt, 
+    SerializableKeyValuePair
+};
+pub use value::{
+    SerializableValue,
+    SerializableEnv,
+    SerializableBuiltinRuntime,
+    value_to_json,
+    value_to_json_value,
+    value_from_json,
+};
+pub use context::{
+    SerializableFrame,
+    SerializableMachineContext,
+    SerializableMachineContextPage,
+    context_to_json,
+};
+pub use machine_state::{
+    SerializableMachineState,
+};
+pub use utxo::{
+    UtxoOutput,
+    UtxoValue,
+    ReferenceScript,
+    ScriptType,
+};
+pub use utxo_snapshot::{
+    utxos_from_snapshot,
+    convert_utxo_snapshot,
+};
+pub use protocol_params::{
+    ProtocolParameters,
+    ParameterChange,
+    ProtocolVersion,
+    CostModels,
+};
+pub use cost_model_upgrade::{
+    CostModelDefaults,
+    CostModelUpgrade,
+    upgrade_cost_model_v2_to_v3,
+};
+pub use script_context::{
+    SerializableTxInfo,
+    SerializableTxInfoV1,
+    SerializableTxInfoV2,
+    SerializableTxInfoV3,
+    SerializableScriptContext,
+    CipScriptContext,
+    script_context_field_names,
+    SerializableScriptInfo,
+    SerializableScriptPurpose,
+    SerializableTxInInfo,
+    SerializableTransactionInput,
+    SerializableTransactionOutput,
+    SerializableDatumOption,
+    SerializableTimeRange,
+    SerializableCertificate,
+    SerializableStakeCredential,
+    SerializableVoter,
+    SerializableVotingProcedure,
+    SerializableRedeemer,
+};
+pub use debugger_engine::{
+    DebuggerEngine,
+    SessionController,
+    Budget,
+    DebuggerError,
+    SerializableExecutionStatus,
+    PauseReason,
+    StepResult,
+    StepBatchResult,
+    StopReason,
+    SessionInfo,
+    StopReasonKind,
+    debug_minting_policy,
+    debug_withdrawal_validator,
+    shrink_failure,
+    canonicalize_path,
+};
+pub use hash_format::{
+    HashForms,
+    StakeCredentialForms,
+    script_hash_forms,
+    stake_credential_forms,
+    get_asset_fingerprint,
+};
+pub use serialization_format::{SerializationFormat, get_compact_json_keys};
+pub use wasm_tools::{
+    JsError,
+    WasmResult,
+    is_wasm_target,
+    get_schema_version,
+};
\ No newline at end of file
@@ -3,10 +3,11 @@ use schemars::JsonSchema;
 use crate::{
     serializer::{EitherTermOrId, term_to_either_term_or_id},
     value::{SerializableValue, SerializableEnv},
-    context::{SerializableMachineContext},
+    context::{serializable_frames, SerializableFrame, SerializableMachineContext},
+    budget::SerializableBudget,
     lazy_loading::{LazyLoadableValue, LazyLoadableTermOrId, LazyLoadableEnv, LazyLoadableContext, LazyLoadConfig, SupportsLazyLoading},
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(tag = "machine_state_type")]
@@ -15,6 +16,9 @@ pub enum SerializableMachineState {
     Return {
         context: SerializableMachineContext,
         value: SerializableValue,
+        /// Full chain of nested contexts, innermost first
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        context_chain: Option<Vec<SerializableFrame<SerializableMachineContext>>>,
     },
     #[serde(rename = "Compute")]
     Compute {
@@ -25,6 +29,9 @@ pub enum SerializableMachineState {
     #[serde(rename = "Done")]
     Done {
         term: EitherTermOrId,
+        /// Budget spent by the whole evaluation
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        budget: Option<SerializableBudget>,
     },
 }
 
@@ -41,6 +48,7 @@ impl SerializableMachineState {
                 SerializableMachineState::Return {
                     context: SerializableMachineContext::from_uplc_context_with_ids(context, term_ids),
                     value: SerializableValue::from_uplc_value_with_ids(value, term_ids),
+                    context_chain: None,
                 }
             },
             uplc::machine::MachineState::Compute(context, env, term) => {
@@ -53,10 +61,36 @@ impl SerializableMachineState {
             uplc::machine::MachineState::Done(term) => {
                 SerializableMachineState::Done {
                     term: term_to_either_term_or_id(term, term_ids),
+                    budget: None,
                 }
             },
         }
     }
+
+    /// Convert a UPLC MachineState to a serializable format where Return states
+    /// carry the full context chain and Done states carry the spent budget
+    pub fn from_uplc_machine_state_structured(
+        state: &uplc::machine::MachineState,
+        contexts: &[uplc::machine::Context],
+        budget: SerializableBudget,
+        term_ids: &HashSet<i32>,
+        case_branches: &HashMap<i32, (i32, usize)>,
+    ) -> Self {
+        match Self::from_uplc_machine_state_with_ids(state, term_ids) {
+            SerializableMachineState::Return { context, value, .. } => SerializableMachineState::Return {
+                context,
+                value,
+                context_chain: Some(serializable_frames(contexts, 0..contexts.len(), case_branches, |ctx| {
+                    SerializableMachineContext::from_uplc_context_with_ids(ctx, term_ids)
+                })),
+            },
+            SerializableMachineState::Done { term, .. } => SerializableMachineState::Done {
+                term,
+                budget: Some(budget),
+            },
+            compute => compute,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
@@ -89,6 +89,42 @@ impl SerializablePlutusData {
         }
     }
 
+    /// Convert back to Pallas PlutusData (inverse of `from_pallas`)
+    pub fn to_pallas(&self) -> Result<PallasPlutusData, String> {
+        use pallas_codec::utils::{KeyValuePairs, MaybeIndefArray};
+        match self {
+            SerializablePlutusData::Constr { tag, any_constructor, fields } => {
+                let fields = fields.iter()
+                    .map(|field| field.to_pallas())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PallasPlutusData::Constr(pallas_primitives::Constr {
+                    tag: *tag,
+                    any_constructor: *any_constructor,
+                    fields: MaybeIndefArray::Indef(fields),
+                }))
+            },
+            SerializablePlutusData::Map { key_value_pairs } => {
+                let pairs = key_value_pairs.iter()
+                    .map(|pair| Ok((pair.key.to_pallas()?, pair.value.to_pallas()?)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(PallasPlutusData::Map(KeyValuePairs::Def(pairs)))
+            },
+            SerializablePlutusData::BigInt(big_int) => {
+                Ok(PallasPlutusData::BigInt(big_int.to_pallas()?))
+            },
+            SerializablePlutusData::BoundedBytes { value } => {
+                let bytes = hex::decode(value).map_err(|e| format!("Invalid bytes {}: {}", value, e))?;
+                Ok(PallasPlutusData::BoundedBytes(bytes.into()))
+            },
+            SerializablePlutusData::Array { values } => {
+                let values = values.iter()
+                    .map(|value| value.to_pallas())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PallasPlutusData::Array(MaybeIndefArray::Indef(values)))
+            },
+        }
+    }
+
     /// Convert to JSON Value
     pub fn to_json_value(&self) -> Result<Value, serde_json::Error> {
         serde_json::to_value(self)
@@ -120,6 +156,26 @@ impl SerializableBigInt {
             },
         }
     }
+
+    fn to_pallas(&self) -> Result<PallasBigInt, String> {
+        use num_bigint::BigUint as NumBigUint;
+        use pallas_codec::minicbor::data::Int;
+        let parse_magnitude = |value: &str| {
+            value.parse::<NumBigUint>()
+                .map(|magnitude| magnitude.to_bytes_be())
+                .map_err(|e| format!("Invalid integer {}: {}", value, e))
+        };
+        match self {
+            SerializableBigInt::Int(value) => {
+                let int = value.parse::<i128>()
+                    .map_err(|e| format!("Invalid integer {}: {}", value, e))
+                    .and_then(|value| Int::try_from(value).map_err(|e| format!("Integer {} out of range: {}", value, e)))?;
+                Ok(PallasBigInt::Int(int.into()))
+            },
+            SerializableBigInt::BigUInt(value) => Ok(PallasBigInt::BigUInt(parse_magnitude(value)?.into())),
+            SerializableBigInt::BigNInt(value) => Ok(PallasBigInt::BigNInt(parse_magnitude(value)?.into())),
+        }
+    }
 }
 
 /// Utility functions for working with PlutusData
@@ -263,4 +319,19 @@ mod tests {
         assert!(json.contains("\"type\": \"Array\""));
         assert!(json.contains("\"type\": \"BoundedBytes\""));
     }
+
+    #[test]
+    fn test_pallas_round_trip() {
+        let data = SerializablePlutusData::constructor(1, vec![
+            SerializablePlutusData::integer(-7),
+            SerializablePlutusData::bytes_from_hex("cafe").unwrap(),
+            SerializablePlutusData::map(vec![
+                (SerializablePlutusData::integer(1), SerializablePlutusData::array(vec![])),
+            ]),
+        ]);
+
+        let pallas = data.to_pallas().unwrap();
+        let round_trip = SerializablePlutusData::from_pallas(&pallas);
+        assert_eq!(data.to_json_string().unwrap(), round_trip.to_json_string().unwrap());
+    }
 } 
\ No newline at end of file
@@ -69,6 +69,9 @@ pub struct ProtocolParameters {
     pub price_mem: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub price_step: Option<f64>,
+    /// Base price of the Conway tiered reference script fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_fee_ref_script_cost_per_byte: Option<f64>,
     
     // Protocol version
     pub protocol_version: ProtocolVersion,
@@ -102,7 +105,72 @@ pub struct ProtocolParameters {
     pub additional_fields: HashMap<String, serde_json::Value>,
 }
 
+/// A protocol parameter whose value differs between two parameter sets
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterChange {
+    /// Field name as in the JSON form, e.g. `maxTxExMem` or `costModels.PlutusV3[42]`
+    pub field: String,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+}
+
+/// Push a change for every key of `old` or `new` whose value differs, descending
+/// into cost model arrays so each differing index is reported on its own
+fn diff_objects(
+    prefix: &str,
+    old: &serde_json::Map<String, serde_json::Value>,
+    new: &serde_json::Map<String, serde_json::Value>,
+    changes: &mut Vec<ParameterChange>,
+) {
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        let field = format!("{}{}", prefix, key);
+        match (old.get(key), new.get(key)) {
+            (old_value, new_value) if old_value == new_value => {}
+            (Some(serde_json::Value::Object(old_map)), Some(serde_json::Value::Object(new_map)))
+                if prefix.is_empty() && key == "costModels" =>
+            {
+                diff_objects("costModels.", old_map, new_map, changes)
+            }
+            (Some(serde_json::Value::Array(old_array)), Some(serde_json::Value::Array(new_array)))
+                if prefix == "costModels." =>
+            {
+                for index in 0..old_array.len().max(new_array.len()) {
+                    if old_array.get(index) != new_array.get(index) {
+                        changes.push(ParameterChange {
+                            field: format!("{}[{}]", field, index),
+                            old_value: old_array.get(index).cloned(),
+                            new_value: new_array.get(index).cloned(),
+                        });
+                    }
+                }
+            }
+            (old_value, new_value) => changes.push(ParameterChange {
+                field,
+                old_value: old_value.cloned(),
+                new_value: new_value.cloned(),
+            }),
+        }
+    }
+}
+
 impl ProtocolParameters {
+    /// Lists the parameters that changed from `self` to `other`, with cost models
+    /// compared index by index
+    pub fn diff(&self, other: &ProtocolParameters) -> Vec<ParameterChange> {
+        let mut changes = vec![];
+        if let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) =
+            (serde_json::to_value(self), serde_json::to_value(other))
+        {
+            diff_objects("", &old, &new, &mut changes);
+        }
+        changes
+    }
+
     /// Deserialize ProtocolParameters from JSON string
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
@@ -191,6 +259,7 @@ mod tests {
             )),
             price_mem: Some(0.0577),
             price_step: Some(0.0000721),
+            min_fee_ref_script_cost_per_byte: Some(15.0),
             protocol_version: ProtocolVersion::new(8, 0),
             epoch_no: Some(365),
             max_epoch: Some(18),
@@ -230,6 +299,31 @@ mod tests {
         assert_eq!(cost_models, deserialized);
     }
 
+    #[test]
+    fn test_protocol_parameters_diff() {
+        let old: ProtocolParameters = serde_json::from_value(serde_json::json!({
+            "minFeeA": 44, "minFeeB": 155381, "maxTxSize": 16384,
+            "keyDeposit": "2000000", "poolDeposit": "500000000", "minPoolCost": "340000000",
+            "utxoCostPerWord": 4310,
+            "costModels": { "PlutusV2": [1, 2, 3] },
+            "protocolVersion": { "major": 8, "minor": 0 }
+        }))
+        .unwrap();
+        let mut new = old.clone();
+        new.min_fee_a = 45;
+        new.cost_models = Some(CostModels::new(None, Some(vec![1, 5, 3, 4]), None));
+        new.protocol_version = ProtocolVersion::new(9, 0);
+
+        let changes = new.diff(&new);
+        assert!(changes.is_empty());
+
+        let fields: Vec<String> = old.diff(&new).into_iter().map(|change| change.field).collect();
+        assert_eq!(
+            fields,
+            vec!["costModels.PlutusV2[1]", "costModels.PlutusV2[3]", "minFeeA", "protocolVersion"]
+        );
+    }
+
     #[test]
     fn test_protocol_version() {
         let version = ProtocolVersion::new(8, 1);
@@ -265,6 +359,7 @@ mod tests {
             cost_models: None,
             price_mem: None,
             price_step: None,
+            min_fee_ref_script_cost_per_byte: None,
             protocol_version: ProtocolVersion::new(7, 0),
             epoch_no: None,
             max_epoch: None,
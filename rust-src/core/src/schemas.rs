@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use schemars::{schema_for, JsonSchema};
+use serde_json::{Map, Value};
+
+use crate::budget::{BudgetBreakdown, BudgetProjection, SerializableBudget};
+use crate::context::{SerializableFrame, SerializableMachineContextLazy, SerializableMachineContextPage};
+use crate::cost_model_upgrade::{CostModelReport, CostModelUpgrade};
+use crate::debugger_engine::{
+    aiken_traces::AikenTraceReport,
+    annotations::TermAnnotation,
+    audit::DeterminismAudit,
+    blueprint::BlueprintValidationReport,
+    bookmarks::Bookmark,
+    budget_exhaustion::BudgetExhaustionReport,
+    builtin_checks::BuiltinWarnings,
+    cert_encoding::CertificateEncoding,
+    construction::ConstructionReport,
+    context_cache::ContextCacheStats,
+    context_compare::{ContextComparison, TxInfoVersionComparison},
+    datums::DecodedDatums,
+    decisions::DecisionTrace,
+    call_stack::CallStackFrame,
+    chunked_export::ExportHandle,
+    coverage::{AggregatedCoverage, CoverageMap, CoverageReport},
+    env_pins::EnvPinComparison,
+    env_watch::EnvWatch,
+    instrumentation::TraceInjection,
+    execution_trace::ExecutionTrace,
+    fixture::ExportedFixture,
+    force_balance::ForceDelayReport,
+    log_search::LogSearchResult,
+    observers::StakeObserver,
+    optimizations::OptimizationReport,
+    ordering::OrderingCheck,
+    overshoot::OvershootAnalysis,
+    prelude::PreludeReport,
+    progress::RunProgress,
+    quota::QuotaUsage,
+    redeemer_graph::RedeemerGraph,
+    ref_script_fees::RefScriptFeeBreakdown,
+    reprice::RepriceReport,
+    run_all::TransactionRun,
+    sandbox::SandboxResult,
+    settings::{EngineSettings, SessionOptions},
+    shrink::ShrinkReport,
+    state_summary::StateSummary,
+    step_history::{CheckpointInfo, StepBackResult},
+    term_costs::TermCostMap,
+    term_ids::{TermIdInfo, TermIdRemapping},
+    value_pins::ValuePinReport,
+    value_search::ValueSearchResult,
+    SessionInfo,
+    StepBatchResult,
+    StopReason,
+};
+use crate::env_sharing::{SerializableEnvIdentity, SerializableSharedContext};
+use crate::hash_format::{HashForms, StakeCredentialForms};
+use crate::machine_state::SerializableMachineStateLazy;
+use crate::script_context::{CipScriptContext, SerializableExUnits, SerializableMintPolicy};
+use crate::value::{SerializableEnvLazy, SerializableValueLazy};
+use crate::{
+    SerializableEnv, SerializableExecutionStatus, SerializableMachineContext, SerializableMachineState,
+    SerializableScriptContext, SerializableTerm, SerializableValue, StepResult,
+};
+
+const BUNDLE_FILE: &str = "combined_schema.json";
+
+/// Root types returned from the DebuggerEngine and SessionController public API.
+/// Everything they reference is pulled in through their `$defs`.
+fn root_schemas() -> Vec<(&'static str, fn() -> Result<Value, String>)> {
+    fn root<T: JsonSchema>() -> Result<Value, String> {
+        serde_json::to_value(schema_for!(T)).map_err(|e| e.to_string())
+    }
+
+    vec![
+        ("SerializableScriptContext", root::<SerializableScriptContext>),
+        ("CipScriptContext", root::<CipScriptContext>),
+        ("SerializableMachineContext", root::<SerializableMachineContext>),
+        ("SerializableMachineContextPage", root::<SerializableMachineContextPage>),
+        ("SerializableFrame", root::<SerializableFrame<SerializableMachineContext>>),
+        ("SerializableFrameLazy", root::<SerializableFrame<SerializableMachineContextLazy>>),
+        ("SerializableMachineState", root::<SerializableMachineState>),
+        ("SerializableBudget", root::<SerializableBudget>),
+        ("BudgetBreakdown", root::<BudgetBreakdown>),
+        ("BudgetProjection", root::<BudgetProjection>),
+        ("SerializableTerm", root::<SerializableTerm>),
+        ("SerializableValue", root::<SerializableValue>),
+        ("SerializableEnv", root::<SerializableEnv>),
+        ("SerializableExecutionStatus", root::<SerializableExecutionStatus>),
+        ("StepResult", root::<StepResult>),
+        ("StepBatchResult", root::<StepBatchResult>),
+        ("StopReason", root::<StopReason>),
+        ("SessionInfo", root::<SessionInfo>),
+        ("SerializableMachineStateLazy", root::<SerializableMachineStateLazy>),
+        ("SerializableMachineContextLazy", root::<SerializableMachineContextLazy>),
+        ("SerializableValueLazy", root::<SerializableValueLazy>),
+        ("SerializableEnvLazy", root::<SerializableEnvLazy>),
+        ("SerializableSharedContext", root::<SerializableSharedContext>),
+        ("SerializableEnvIdentity", root::<SerializableEnvIdentity>),
+        ("SerializableMintPolicy", root::<SerializableMintPolicy>),
+        ("SerializableExUnits", root::<SerializableExUnits>),
+        ("AikenTraceReport", root::<AikenTraceReport>),
+        ("Bookmark", root::<Bookmark>),
+        ("BudgetExhaustionReport", root::<BudgetExhaustionReport>),
+        ("BuiltinWarnings", root::<BuiltinWarnings>),
+        ("DecisionTrace", root::<DecisionTrace>),
+        ("EnvPinComparison", root::<EnvPinComparison>),
+        ("ForceDelayReport", root::<ForceDelayReport>),
+        ("OptimizationReport", root::<OptimizationReport>),
+        ("SandboxResult", root::<SandboxResult>),
+        ("TermAnnotation", root::<TermAnnotation>),
+        ("CertificateEncoding", root::<CertificateEncoding>),
+        ("ConstructionReport", root::<ConstructionReport>),
+        ("ContextCacheStats", root::<ContextCacheStats>),
+        ("ContextComparison", root::<ContextComparison>),
+        ("TxInfoVersionComparison", root::<TxInfoVersionComparison>),
+        ("DecodedDatums", root::<DecodedDatums>),
+        ("DeterminismAudit", root::<DeterminismAudit>),
+        ("BlueprintValidationReport", root::<BlueprintValidationReport>),
+        ("ExportedFixture", root::<ExportedFixture>),
+        ("ShrinkReport", root::<ShrinkReport>),
+        ("StateSummary", root::<StateSummary>),
+        ("StepBackResult", root::<StepBackResult>),
+        ("CheckpointInfo", root::<CheckpointInfo>),
+        ("ValueSearchResult", root::<ValueSearchResult>),
+        ("LogSearchResult", root::<LogSearchResult>),
+        ("OrderingCheck", root::<OrderingCheck>),
+        ("OvershootAnalysis", root::<OvershootAnalysis>),
+        ("RunProgress", root::<RunProgress>),
+        ("StakeObserver", root::<StakeObserver>),
+        ("QuotaUsage", root::<QuotaUsage>),
+        ("RedeemerGraph", root::<RedeemerGraph>),
+        ("ValuePinReport", root::<ValuePinReport>),
+        ("ExecutionTrace", root::<ExecutionTrace>),
+        ("CallStackFrame", root::<CallStackFrame>),
+        ("PreludeReport", root::<PreludeReport>),
+        ("EnvWatch", root::<EnvWatch>),
+        ("TermIdInfo", root::<TermIdInfo>),
+        ("TermIdRemapping", root::<TermIdRemapping>),
+        ("TermCostMap", root::<TermCostMap>),
+        ("TransactionRun", root::<TransactionRun>),
+        ("RepriceReport", root::<RepriceReport>),
+        ("TraceInjection", root::<TraceInjection>),
+        ("CoverageReport", root::<CoverageReport>),
+        ("CoverageMap", root::<CoverageMap>),
+        ("AggregatedCoverage", root::<AggregatedCoverage>),
+        ("ExportHandle", root::<ExportHandle>),
+        ("RefScriptFeeBreakdown", root::<RefScriptFeeBreakdown>),
+        ("EngineSettings", root::<EngineSettings>),
+        ("SessionOptions", root::<SessionOptions>),
+        ("CostModelUpgrade", root::<CostModelUpgrade>),
+        ("CostModelReport", root::<CostModelReport>),
+        ("HashForms", root::<HashForms>),
+        ("StakeCredentialForms", root::<StakeCredentialForms>),
+    ]
+}
+
+/// All root schemas plus the combined bundle, each with self-references fixed
+pub struct SchemaBundle {
+    /// Root schemas by type name
+    pub schemas: BTreeMap<String, Value>,
+    /// Single document holding every root schema and, at top level, every
+    /// definition they reference, so `#/$defs/...` resolves from the bundle root
+    pub bundle: Value,
+}
+
+/// Replace "$ref": "#" with "$ref": "#/$defs/TypeName". When a schema becomes a
+/// $def in another schema, "#" would otherwise reference the parent schema.
+fn fix_self_references(value: &mut Value, type_name: &str) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(ref_str)) = map.get("$ref") {
+                if ref_str == "#" {
+                    map.insert("$ref".to_string(), Value::String(format!("#/$defs/{}", type_name)));
+                }
+            }
+            for (_, v) in map.iter_mut() {
+                fix_self_references(v, type_name);
+            }
+        }
+        Value::Array(arr) => {
+            for item in arr.iter_mut() {
+                fix_self_references(item, type_name);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn fix_schema_and_defs(schema: &mut Value, type_name: &str) {
+    fix_self_references(schema, type_name);
+    if let Some(Value::Object(defs)) = schema.get_mut("$defs") {
+        for (def_name, def_value) in defs.iter_mut() {
+            fix_self_references(def_value, def_name);
+        }
+    }
+}
+
+/// A definition without the keys that differ between a root schema and the same
+/// type embedded as a $def
+fn comparable_definition(value: &Value) -> Value {
+    let mut value = value.clone();
+    if let Value::Object(map) = &mut value {
+        map.remove("$schema");
+        map.remove("title");
+        map.remove("$defs");
+    }
+    value
+}
+
+fn insert_definition(defs: &mut Map<String, Value>, name: &str, definition: Value) -> Result<(), String> {
+    let definition = comparable_definition(&definition);
+    match defs.get(name) {
+        Some(existing) if *existing != definition => {
+            Err(format!("Two different types are exported under the schema name {}", name))
+        }
+        Some(_) => Ok(()),
+        None => {
+            defs.insert(name.to_string(), definition);
+            Ok(())
+        }
+    }
+}
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                refs.push(reference);
+            }
+            map.values().for_each(|v| collect_refs(v, refs));
+        }
+        Value::Array(values) => values.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+/// Generates every root schema and the bundle. Schemas are generated in
+/// parallel; output is keyed by name, so it is identical from run to run.
+pub fn bundle() -> Result<SchemaBundle, String> {
+    let roots = root_schemas();
+    let generated: Vec<(&str, Value)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = roots
+            .iter()
+            .map(|(name, generate)| scope.spawn(move || (*name, generate())))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                let (name, schema) = handle.join().map_err(|_| "Schema generation panicked".to_string())?;
+                schema.map(|schema| (name, schema)).map_err(|e| format!("Schema of {} failed: {}", name, e))
+            })
+            .collect::<Result<_, _>>()
+    })?;
+
+    let mut schemas = BTreeMap::new();
+    let mut defs = Map::new();
+    for (name, mut schema) in generated {
+        fix_schema_and_defs(&mut schema, name);
+        if let Some(Value::Object(schema_defs)) = schema.get("$defs") {
+            for (def_name, definition) in schema_defs {
+                insert_definition(&mut defs, def_name, definition.clone())?;
+            }
+        }
+        insert_definition(&mut defs, name, schema.clone())?;
+        schemas.insert(name.to_string(), schema);
+    }
+
+    let mut refs = vec![];
+    defs.values().for_each(|definition| collect_refs(definition, &mut refs));
+    if let Some(missing) = refs
+        .iter()
+        .find(|reference| !reference.strip_prefix("#/$defs/").is_some_and(|name| defs.contains_key(name)))
+    {
+        return Err(format!("Unresolved schema reference {}", missing));
+    }
+
+    let bundle = serde_json::json!({
+        "title": "DE-UPLC Public API Root Schemas",
+        "description": "Root JSON Schemas for types returned from DebuggerEngine and SessionController public API",
+        "version": "1.0.0",
+        "schema_version": crate::wasm_tools::SCHEMA_VERSION,
+        "note": "Every definition referenced by a root schema is also hoisted into the top-level $defs",
+        "$defs": defs,
+        "schemas": schemas,
+    });
+    Ok(SchemaBundle { schemas, bundle })
+}
+
+/// Writes one `<TypeName>.json` per root schema and the combined bundle into
+/// `out_dir`, returning the paths written. Usable from build scripts and the CLI.
+pub fn generate_all(out_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let SchemaBundle { schemas, bundle } = bundle()?;
+    fs::create_dir_all(out_dir)?;
+
+    let mut written = vec![];
+    for (name, schema) in &schemas {
+        let path = out_dir.join(format!("{}.json", name));
+        fs::write(&path, serde_json::to_string_pretty(schema)?)?;
+        written.push(path);
+    }
+    let path = out_dir.join(BUNDLE_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&bundle)?)?;
+    written.push(path);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_is_deterministic() {
+        let first = bundle().unwrap();
+        let second = bundle().unwrap();
+        assert_eq!(first.bundle, second.bundle);
+        assert!(first.bundle["$defs"]["StepResult"].is_object());
+    }
+
+    #[test]
+    fn test_type_generator_lists_every_root() {
+        let generator = include_str!("../../../scripts/generate-types.js");
+        for (name, _) in root_schemas() {
+            assert!(generator.contains(&format!("'{}'", name)), "{} is missing from ROOT_SCHEMAS", name);
+        }
+    }
+
+    #[test]
+    fn test_checked_in_bundle_is_up_to_date() {
+        let checked_in: Value = serde_json::from_str(include_str!("../../schemas/combined_schema.json")).unwrap();
+        assert!(
+            bundle().unwrap().bundle == checked_in,
+            "rust-src/schemas is stale: run `npm run generate-all`"
+        );
+    }
+}
@@ -118,6 +118,37 @@ impl SerializableMintValue {
     pub fn is_empty(&self) -> bool {
         self.mint_value.is_empty()
     }
+
+    /// Annotates each policy with the redeemer key and script kind `governance` returns
+    /// for its policy id; policies without one are run by native scripts
+    pub fn policies(&self, governance: impl Fn(&str) -> Option<(String, String)>) -> Vec<SerializableMintPolicy> {
+        self.mint_value
+            .iter()
+            .map(|asset| {
+                let (redeemer, script) = match governance(&asset.policy_id) {
+                    Some((redeemer, script)) => (Some(redeemer), script),
+                    None => (None, "native".to_string()),
+                };
+                SerializableMintPolicy {
+                    policy_id: asset.policy_id.clone(),
+                    tokens: asset.tokens.clone(),
+                    redeemer,
+                    script,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Tokens minted or burnt under one policy, with the script that must approve them
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SerializableMintPolicy {
+    pub policy_id: String,
+    pub tokens: Vec<SerializableToken>,
+    /// Key of the redeemer running the policy script (e.g. `Mint:0`)
+    pub redeemer: Option<String>,
+    /// Plutus version of the policy script, e.g. "PlutusV2", or "native"
+    pub script: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
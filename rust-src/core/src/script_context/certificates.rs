@@ -3,7 +3,7 @@ use schemars::JsonSchema;
 use super::governance::SerializableAnchor;
 use super::basic_types::SerializableRational;
 
-use super::utils::{hash_to_hex, bytes_to_hex};
+use super::utils::{hash_to_hex, bytes_to_hex, reward_account_display};
 use pallas_primitives::{conway, StakeCredential};
 use pallas_codec::utils::Nullable;
 
@@ -280,7 +280,7 @@ impl From<conway::Certificate> for SerializableCertificate {
                         pledge,
                         cost,
                         margin: margin.into(),
-                        reward_account: bytes_to_hex(&reward_account),
+                        reward_account: reward_account_display(&reward_account),
                         pool_owners: pool_owners.iter().map(|o| hash_to_hex(o)).collect(),
                         relays: relays.iter().cloned().map(|r| r.into()).collect(),
                         pool_metadata: match pool_metadata {
@@ -0,0 +1,357 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::plutus_data::SerializablePlutusData;
+use crate::wasm_tools::{envelope_json, wasm_bindgen};
+
+use super::basic_types::*;
+use super::certificates::{SerializableCertificate, SerializableStakeCredential};
+use super::governance::*;
+use super::script_types::{SerializableScriptContext, SerializableScriptInfo, SerializableScriptPurpose};
+use super::tx_info::SerializableTxInfo;
+
+/// Internal field names of `SerializableScriptContext` and the names the CIP view
+/// gives them. The CIP names follow the Plutus ledger API records (`txInfoInputs`,
+/// `scriptContextScriptInfo`, ...) without their record prefix, as in CIP-69.
+pub const CIP_FIELD_NAMES: &[(&str, &str)] = &[
+    ("tx_info", "txInfo"),
+    ("purpose", "purpose"),
+    ("redeemer", "redeemer"),
+    ("inputs", "inputs"),
+    ("reference_inputs", "referenceInputs"),
+    ("outputs", "outputs"),
+    ("fee", "fee"),
+    ("mint", "mint"),
+    ("certificates", "txCerts"),
+    ("withdrawals", "wdrl"),
+    ("valid_range", "validRange"),
+    ("signatories", "signatories"),
+    ("redeemers", "redeemers"),
+    ("data", "data"),
+    ("id", "id"),
+    ("votes", "votes"),
+    ("proposal_procedures", "proposalProcedures"),
+    ("current_treasury_amount", "currentTreasuryAmount"),
+    ("treasury_donation", "treasuryDonation"),
+    // Fields of the script purposes and script infos
+    ("policy_id", "currencySymbol"),
+    ("utxo_ref", "txOutRef"),
+    ("datum", "datum"),
+    ("stake_credential", "credential"),
+    ("index", "index"),
+    ("certificate", "txCert"),
+    ("voter", "voter"),
+    ("proposal", "proposalProcedure"),
+];
+
+/// Names a V3 context gives differently: its purpose is the script info
+pub const CIP_V3_FIELD_NAMES: &[(&str, &str)] = &[("purpose", "scriptInfo")];
+
+/// Why a script runs, as `ScriptPurpose` of the ledger API
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "type")]
+pub enum CipScriptPurpose {
+    #[serde(rename_all = "camelCase")]
+    Minting { currency_symbol: String },
+    #[serde(rename_all = "camelCase")]
+    Spending { tx_out_ref: SerializableTransactionInput },
+    /// A `StakingCredential` in V1 and V2
+    #[serde(rename_all = "camelCase")]
+    Rewarding { credential: SerializableStakeCredential },
+    /// V1 and V2 give only the `DCert`; the index is the certificate's position in the transaction
+    #[serde(rename_all = "camelCase")]
+    Certifying { index: usize, tx_cert: SerializableCertificate },
+    #[serde(rename_all = "camelCase")]
+    Voting { voter: SerializableVoter },
+    #[serde(rename_all = "camelCase")]
+    Proposing { index: usize, proposal_procedure: SerializableProposalProcedure },
+}
+
+/// What a V3 script runs for, as `ScriptInfo` of the ledger API
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "type")]
+pub enum CipScriptInfo {
+    #[serde(rename_all = "camelCase")]
+    MintingScript { currency_symbol: String },
+    #[serde(rename_all = "camelCase")]
+    SpendingScript {
+        tx_out_ref: SerializableTransactionInput,
+        datum: Option<SerializablePlutusData>,
+    },
+    #[serde(rename_all = "camelCase")]
+    RewardingScript { credential: SerializableStakeCredential },
+    #[serde(rename_all = "camelCase")]
+    CertifyingScript { index: usize, tx_cert: SerializableCertificate },
+    #[serde(rename_all = "camelCase")]
+    VotingScript { voter: SerializableVoter },
+    #[serde(rename_all = "camelCase")]
+    ProposingScript { index: usize, proposal_procedure: SerializableProposalProcedure },
+}
+
+/// Fee of a TxInfo: a Value up to V2, a plain Coin from V3
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum CipFee {
+    Coin(u64),
+    Value(SerializableCardanoValue),
+}
+
+/// TxInfo under the field names of the ledger specification. Fields a Plutus
+/// version doesn't have are left out.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CipTxInfo {
+    pub inputs: Vec<SerializableTxInInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_inputs: Option<Vec<SerializableTxInInfo>>,
+    pub outputs: Vec<SerializableTransactionOutput>,
+    pub fee: CipFee,
+    pub mint: SerializableMintValue,
+    /// `dCert` in V1 and V2
+    pub tx_certs: Vec<SerializableCertificate>,
+    pub wdrl: Vec<(String, u64)>,
+    pub valid_range: SerializableTimeRange,
+    pub signatories: Vec<String>,
+    pub redeemers: Vec<(CipScriptPurpose, SerializableRedeemer)>,
+    pub data: Vec<(String, SerializablePlutusData)>,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub votes: Option<Vec<(SerializableVoter, Vec<(SerializableGovActionId, SerializableVotingProcedure)>)>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposal_procedures: Option<Vec<SerializableProposalProcedure>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_treasury_amount: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub treasury_donation: Option<u64>,
+}
+
+/// Script context with the field names integrators know from the ledger CDDL and
+/// the CIPs, next to the internal `SerializableScriptContext`
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "version")]
+pub enum CipScriptContext {
+    #[serde(rename = "V1V2", rename_all = "camelCase")]
+    V1V2 {
+        tx_info: CipTxInfo,
+        purpose: CipScriptPurpose,
+    },
+    #[serde(rename = "V3", rename_all = "camelCase")]
+    V3 {
+        tx_info: CipTxInfo,
+        redeemer: SerializablePlutusData,
+        script_info: CipScriptInfo,
+    },
+}
+
+impl From<SerializableScriptPurpose> for CipScriptPurpose {
+    fn from(purpose: SerializableScriptPurpose) -> Self {
+        match purpose {
+            SerializableScriptPurpose::Minting { policy_id } => CipScriptPurpose::Minting { currency_symbol: policy_id },
+            SerializableScriptPurpose::Spending { utxo_ref } => CipScriptPurpose::Spending { tx_out_ref: utxo_ref },
+            SerializableScriptPurpose::Rewarding { stake_credential } => {
+                CipScriptPurpose::Rewarding { credential: stake_credential }
+            }
+            SerializableScriptPurpose::Certifying { index, certificate } => {
+                CipScriptPurpose::Certifying { index, tx_cert: certificate }
+            }
+            SerializableScriptPurpose::Voting { voter } => CipScriptPurpose::Voting { voter },
+            SerializableScriptPurpose::Proposing { index, proposal } => {
+                CipScriptPurpose::Proposing { index, proposal_procedure: proposal }
+            }
+        }
+    }
+}
+
+impl From<SerializableScriptInfo> for CipScriptInfo {
+    fn from(info: SerializableScriptInfo) -> Self {
+        match info {
+            SerializableScriptInfo::Minting { policy_id } => CipScriptInfo::MintingScript { currency_symbol: policy_id },
+            SerializableScriptInfo::Spending { utxo_ref, datum } => {
+                CipScriptInfo::SpendingScript { tx_out_ref: utxo_ref, datum }
+            }
+            SerializableScriptInfo::Rewarding { stake_credential } => {
+                CipScriptInfo::RewardingScript { credential: stake_credential }
+            }
+            SerializableScriptInfo::Certifying { index, certificate } => {
+                CipScriptInfo::CertifyingScript { index, tx_cert: certificate }
+            }
+            SerializableScriptInfo::Voting { voter } => CipScriptInfo::VotingScript { voter },
+            SerializableScriptInfo::Proposing { index, proposal } => {
+                CipScriptInfo::ProposingScript { index, proposal_procedure: proposal }
+            }
+        }
+    }
+}
+
+fn cip_redeemers(redeemers: Vec<(SerializableScriptPurpose, SerializableRedeemer)>) -> Vec<(CipScriptPurpose, SerializableRedeemer)> {
+    redeemers.into_iter().map(|(purpose, redeemer)| (purpose.into(), redeemer)).collect()
+}
+
+impl From<SerializableTxInfo> for CipTxInfo {
+    fn from(tx_info: SerializableTxInfo) -> Self {
+        match tx_info {
+            SerializableTxInfo::V1(info) => CipTxInfo {
+                inputs: info.inputs,
+                reference_inputs: None,
+                outputs: info.outputs,
+                fee: CipFee::Value(info.fee),
+                mint: info.mint,
+                tx_certs: info.certificates,
+                wdrl: info.withdrawals,
+                valid_range: info.valid_range,
+                signatories: info.signatories,
+                redeemers: cip_redeemers(info.redeemers),
+                data: info.data,
+                id: info.id,
+                votes: None,
+                proposal_procedures: None,
+                current_treasury_amount: None,
+                treasury_donation: None,
+            },
+            SerializableTxInfo::V2(info) => CipTxInfo {
+                inputs: info.inputs,
+                reference_inputs: Some(info.reference_inputs),
+                outputs: info.outputs,
+                fee: CipFee::Value(info.fee),
+                mint: info.mint,
+                tx_certs: info.certificates,
+                wdrl: info.withdrawals,
+                valid_range: info.valid_range,
+                signatories: info.signatories,
+                redeemers: cip_redeemers(info.redeemers),
+                data: info.data,
+                id: info.id,
+                votes: None,
+                proposal_procedures: None,
+                current_treasury_amount: None,
+                treasury_donation: None,
+            },
+            SerializableTxInfo::V3(info) => CipTxInfo {
+                inputs: info.inputs,
+                reference_inputs: Some(info.reference_inputs),
+                outputs: info.outputs,
+                fee: CipFee::Coin(info.fee),
+                mint: info.mint,
+                tx_certs: info.certificates,
+                wdrl: info.withdrawals,
+                valid_range: info.valid_range,
+                signatories: info.signatories,
+                redeemers: cip_redeemers(info.redeemers),
+                data: info.data,
+                id: info.id,
+                votes: Some(info.votes),
+                proposal_procedures: Some(info.proposal_procedures),
+                current_treasury_amount: info.current_treasury_amount,
+                treasury_donation: info.treasury_donation,
+            },
+        }
+    }
+}
+
+impl From<SerializableScriptContext> for CipScriptContext {
+    fn from(context: SerializableScriptContext) -> Self {
+        match context {
+            SerializableScriptContext::V1V2 { tx_info, purpose } => CipScriptContext::V1V2 {
+                tx_info: (*tx_info).into(),
+                purpose: (*purpose).into(),
+            },
+            SerializableScriptContext::V3 { tx_info, redeemer, purpose } => CipScriptContext::V3 {
+                tx_info: (*tx_info).into(),
+                redeemer,
+                script_info: (*purpose).into(),
+            },
+        }
+    }
+}
+
+/// CIP name of an internal script context field, for hosts translating paths
+/// between the two views
+pub fn cip_field_name(internal: &str, v3: bool) -> Option<&'static str> {
+    let overrides: &[(&str, &str)] = if v3 { CIP_V3_FIELD_NAMES } else { &[] };
+    overrides
+        .iter()
+        .chain(CIP_FIELD_NAMES)
+        .find(|(name, _)| *name == internal)
+        .map(|(_, cip)| *cip)
+}
+
+fn field_names_json(v3: bool) -> serde_json::Value {
+    let names: serde_json::Map<String, serde_json::Value> = CIP_FIELD_NAMES
+        .iter()
+        .filter_map(|(internal, _)| {
+            cip_field_name(internal, v3).map(|cip| (internal.to_string(), serde_json::Value::String(cip.to_string())))
+        })
+        .collect();
+    serde_json::Value::Object(names)
+}
+
+/// Gets the CIP name of every internal script context field as JSON, one object
+/// from internal to CIP field name for each of "V1V2" and "V3"
+#[wasm_bindgen]
+pub fn script_context_field_names() -> String {
+    let names = serde_json::json!({
+        "V1V2": field_names_json(false),
+        "V3": field_names_json(true),
+    });
+    envelope_json(&names.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cip_names_serialized() {
+        let info = CipTxInfo {
+            inputs: vec![],
+            reference_inputs: None,
+            outputs: vec![],
+            fee: CipFee::Coin(170_000),
+            mint: SerializableMintValue::empty(),
+            tx_certs: vec![],
+            wdrl: vec![],
+            valid_range: SerializableTimeRange::always(),
+            signatories: vec![],
+            redeemers: vec![],
+            data: vec![],
+            id: "00".repeat(32),
+            votes: None,
+            proposal_procedures: None,
+            current_treasury_amount: Some(0),
+            treasury_donation: None,
+        };
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["fee"], 170_000);
+        assert!(json.get("txCerts").is_some() && json.get("validRange").is_some());
+        assert!(json.get("currentTreasuryAmount").is_some());
+        assert!(json.get("referenceInputs").is_none());
+        assert_eq!(cip_field_name("withdrawals", false), Some("wdrl"));
+    }
+
+    #[test]
+    fn test_purposes_use_ledger_names() {
+        let purpose = CipScriptPurpose::from(SerializableScriptPurpose::Minting { policy_id: "ab".repeat(28) });
+        let json = serde_json::to_value(&purpose).unwrap();
+        assert_eq!(json["type"], "Minting");
+        assert_eq!(json["currencySymbol"], "ab".repeat(28));
+
+        let info = CipScriptInfo::from(SerializableScriptInfo::Minting { policy_id: "ab".repeat(28) });
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["type"], "MintingScript");
+        assert!(json.get("policy_id").is_none() && json.get("currencySymbol").is_some());
+    }
+
+    #[test]
+    fn test_field_names_per_version() {
+        assert_eq!(cip_field_name("purpose", false), Some("purpose"));
+        assert_eq!(cip_field_name("purpose", true), Some("scriptInfo"));
+        assert_eq!(cip_field_name("utxo_ref", true), Some("txOutRef"));
+        assert_eq!(cip_field_name("purpose (V3)", true), None);
+
+        let envelope: serde_json::Value = serde_json::from_str(&script_context_field_names()).unwrap();
+        let names = &envelope["payload"];
+        assert_eq!(names["V1V2"]["purpose"], "purpose");
+        assert_eq!(names["V3"]["purpose"], "scriptInfo");
+        assert_eq!(names["V3"]["certificate"], "txCert");
+    }
+}
@@ -6,7 +6,7 @@ use crate::SerializableStakeCredential;
 use super::basic_types::SerializableRational;
 use super::basic_types::SerializableExUnits;
 
-use super::utils::{hash_to_hex, address_from_bytes, address_to_bech32};
+use super::utils::{hash_to_hex, reward_account_display};
 use pallas_primitives::conway;
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
@@ -272,10 +272,7 @@ impl From<conway::ProposalProcedure> for SerializableProposalProcedure {
     fn from(proc: conway::ProposalProcedure) -> Self {
         SerializableProposalProcedure {
             deposit: proc.deposit,
-            reward_account: {
-                let addr = address_from_bytes(proc.reward_account.as_ref()).unwrap();
-                address_to_bech32(&addr).unwrap_or_else(|_| hex::encode(proc.reward_account.as_ref() as &[u8]))
-            },
+            reward_account: reward_account_display(proc.reward_account.as_ref()),
             gov_action: proc.gov_action.into(),
             anchor: proc.anchor.into(),
         }
@@ -428,8 +425,7 @@ impl From<conway::GovAction> for SerializableGovAction {
             conway::GovAction::TreasuryWithdrawals(withdrawals, guardrail_script) => {
                 SerializableGovAction::TreasuryWithdrawals {
                     withdrawals: withdrawals.iter().map(|(c, q)| {
-                        let addr = address_from_bytes(c.as_ref()).unwrap();
-                        (address_to_bech32(&addr).unwrap_or_else(|_| hex::encode(c.as_ref() as &[u8])), *q)
+                        (reward_account_display(c.as_ref()), *q)
                     }).collect(),
                     policy_hash: nullable_to_option(guardrail_script, |s| hash_to_hex(&s)),
                 }
@@ -1,4 +1,5 @@
 pub mod basic_types;
+pub mod cip;
 pub mod utils;
 pub mod certificates;
 pub mod governance;
@@ -7,6 +8,10 @@ pub mod tx_info;
 
 pub use basic_types::*;
 pub use certificates::*;
+pub use cip::{
+    CipFee, CipScriptContext, CipScriptInfo, CipScriptPurpose, CipTxInfo, CIP_FIELD_NAMES, CIP_V3_FIELD_NAMES,
+    script_context_field_names,
+};
 pub use governance::*;
 pub use script_types::*;
 pub use tx_info::*;
\ No newline at end of file
@@ -0,0 +1,153 @@
+use hex;
+use pallas_addresses::Address;
+use pallas_crypto::hash::Hash;
+use thiserror::Error;
+
+use super::certificates::SerializableStakeCredential;
+
+#[derive(Error, Debug)]
+pub enum ConversionError {
+    #[error("Invalid address format: {0}")]
+    InvalidAddress(String),
+    
+    #[error("Missing required field: {0}")]
+    MissingField(String),
+    
+    #[error("Invalid hex encoding: {0}")]
+    InvalidHex(#[from] hex::FromHexError),
+    
+    #[error("Unsupported variant: {0}")]
+    UnsupportedVariant(String),
+    
+    #[error("Pallas error: {0}")]
+    PallasError(String),
+}
+
+/// High nibble of a reward account header (CIP-19 address types 14 and 15)
+const REWARD_ACCOUNT_KEY_HEADER: u8 = 0xe0;
+const REWARD_ACCOUNT_SCRIPT_HEADER: u8 = 0xf0;
+
+const CREDENTIAL_HASH_LENGTH: usize = 28;
+
+pub fn hash_to_hex<const N: usize>(hash: &Hash<N>) -> String {
+    hex::encode(hash)
+}
+
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    hex::encode(bytes)
+}
+
+pub fn address_to_bech32(address: &Address) -> Result<String, ConversionError> {
+    address.to_bech32()
+        .map_err(|e| ConversionError::InvalidAddress(e.to_string()))
+}
+
+pub fn address_from_bytes(bytes: &[u8]) -> Result<Address, ConversionError> {
+    Address::from_bytes(bytes)
+        .map_err(|e| ConversionError::InvalidAddress(e.to_string()))
+}
+
+/// Splits reward account bytes into their network tag and stake credential
+pub fn reward_account_to_stake_credential(bytes: &[u8]) -> Result<(u8, SerializableStakeCredential), ConversionError> {
+    if bytes.len() != CREDENTIAL_HASH_LENGTH + 1 {
+        return Err(ConversionError::InvalidAddress(format!(
+            "Reward account must be {} bytes, got {}",
+            CREDENTIAL_HASH_LENGTH + 1,
+            bytes.len()
+        )));
+    }
+    let hash = bytes_to_hex(&bytes[1..]);
+    let credential = match bytes[0] & 0xf0 {
+        REWARD_ACCOUNT_KEY_HEADER => SerializableStakeCredential::KeyHash { hash },
+        REWARD_ACCOUNT_SCRIPT_HEADER => SerializableStakeCredential::ScriptHash { hash },
+        _ => {
+            return Err(ConversionError::InvalidAddress(format!(
+                "Not a reward account header: {:#04x}",
+                bytes[0]
+            )))
+        }
+    };
+    Ok((bytes[0] & 0x0f, credential))
+}
+
+/// Reward account bytes for a stake credential on the given network (1 = mainnet)
+pub fn stake_credential_to_reward_account(
+    credential: &SerializableStakeCredential,
+    network_tag: u8,
+) -> Result<Vec<u8>, ConversionError> {
+    let (header, hash) = match credential {
+        SerializableStakeCredential::KeyHash { hash } => (REWARD_ACCOUNT_KEY_HEADER, hash),
+        SerializableStakeCredential::ScriptHash { hash } => (REWARD_ACCOUNT_SCRIPT_HEADER, hash),
+    };
+    let hash = hex::decode(hash)?;
+    if hash.len() != CREDENTIAL_HASH_LENGTH {
+        return Err(ConversionError::InvalidAddress(format!(
+            "Stake credential hash must be {} bytes, got {}",
+            CREDENTIAL_HASH_LENGTH,
+            hash.len()
+        )));
+    }
+    let mut bytes = vec![header | (network_tag & 0x0f)];
+    bytes.extend(hash);
+    Ok(bytes)
+}
+
+/// `stake1…` / `stake_test1…` form of reward account bytes
+pub fn reward_account_to_bech32(bytes: &[u8]) -> Result<String, ConversionError> {
+    reward_account_to_stake_credential(bytes)?;
+    address_to_bech32(&address_from_bytes(bytes)?)
+}
+
+/// Bech32 form of a reward account as shown in serialized contexts, falling back
+/// to hex for bytes that aren't a valid reward account
+pub fn reward_account_display(bytes: &[u8]) -> String {
+    reward_account_to_bech32(bytes).unwrap_or_else(|_| bytes_to_hex(bytes))
+}
+
+/// Reads a stake credential given as a bech32 stake address, reward account hex,
+/// `script1…` script hash or `SerializableStakeCredential` JSON
+pub fn parse_stake_credential(input: &str) -> Result<SerializableStakeCredential, ConversionError> {
+    let input = input.trim();
+    if input.starts_with('{') {
+        return serde_json::from_str(input)
+            .map_err(|e| ConversionError::InvalidAddress(format!("Invalid stake credential {}: {}", input, e)));
+    }
+    if input.starts_with("stake") {
+        let address = Address::from_bech32(input).map_err(|e| ConversionError::InvalidAddress(e.to_string()))?;
+        return Ok(reward_account_to_stake_credential(&address.to_vec())?.1);
+    }
+    if input.starts_with("script1") {
+        let hash = crate::hash_format::normalize_script_hash(input).map_err(ConversionError::InvalidAddress)?;
+        return Ok(SerializableStakeCredential::ScriptHash { hash });
+    }
+
+    let bytes = hex::decode(input)?;
+    if bytes.len() == CREDENTIAL_HASH_LENGTH {
+        return Err(ConversionError::InvalidAddress(format!(
+            "{} is a bare hash; give a reward account, stake address or credential JSON to tell key from script",
+            input
+        )));
+    }
+    Ok(reward_account_to_stake_credential(&bytes)?.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reward_account_round_trip() {
+        let credential = SerializableStakeCredential::ScriptHash {
+            hash: "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209".to_string(),
+        };
+        let reward_account = stake_credential_to_reward_account(&credential, 1).unwrap();
+        assert_eq!(reward_account[0], 0xf1);
+        assert_eq!(reward_account_to_stake_credential(&reward_account).unwrap(), (1, credential.clone()));
+
+        let bech32 = reward_account_to_bech32(&reward_account).unwrap();
+        assert!(bech32.starts_with("stake1"));
+        assert_eq!(parse_stake_credential(&bech32).unwrap(), credential);
+        assert_eq!(parse_stake_credential(&hex::encode(&reward_account)).unwrap(), credential);
+        assert!(parse_stake_credential("1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209").is_err());
+    }
+}
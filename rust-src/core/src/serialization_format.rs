@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::Serialize;
+use serde_json::ser::{CharEscape, CompactFormatter, Formatter};
+
+use crate::debugger_engine::DebuggerError;
+use crate::wasm_tools::{enveloped_json, wasm_bindgen, JsError};
+
+/// Encoding of what getters return: JSON text, or CBOR for large payloads that
+/// JS hosts decode faster than they parse JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SerializationFormat {
+    #[default]
+    Json,
+    /// JSON with the object keys of `COMPACT_KEYS` shortened, keys sorted
+    CompactJson,
+    Cbor,
+}
+
+/// Keys shortened in compact JSON, with their short form. Keys not listed are
+/// kept as they are; no short form is also a key of its own.
+pub const COMPACT_KEYS: &[(&str, &str)] = &[
+    ("type", "t"),
+    ("term_type", "tt"),
+    ("value_type", "vt"),
+    ("context_type", "ct"),
+    ("machine_state_type", "mt"),
+    ("value", "v"),
+    ("values", "vs"),
+    ("term_id", "tid"),
+    ("parameterName", "pn"),
+    ("constructorTag", "ctg"),
+    ("tag", "tg"),
+    ("name", "n"),
+    ("term", "tm"),
+    ("terms", "ts"),
+    ("body", "b"),
+    ("function", "f"),
+    ("argument", "a"),
+    ("constant", "k"),
+    ("fun", "fn"),
+    ("fields", "fs"),
+    ("constr", "c"),
+    ("branches", "br"),
+    ("env", "e"),
+    ("context", "cx"),
+    ("context_chain", "cc"),
+    ("runtime", "rt"),
+    ("elementType", "et"),
+    ("first_type", "t1"),
+    ("second_type", "t2"),
+    ("first_element", "e1"),
+    ("second_element", "e2"),
+    ("data", "d"),
+    ("key_value_pairs", "kv"),
+    ("key", "ky"),
+    ("any_constructor", "ac"),
+    ("serialized", "s"),
+    ("compressed", "cz"),
+    ("uncompressed", "uz"),
+    ("bytes", "by"),
+];
+
+/// Formats JSON as `CompactFormatter` does, writing the object keys of
+/// `COMPACT_KEYS` in their short form. Keys are buffered until their closing
+/// quote, so a key is only replaced when it matches as a whole.
+struct CompactKeyFormatter {
+    keys: BTreeMap<&'static str, &'static str>,
+    in_key: bool,
+    key: Vec<u8>,
+}
+
+impl Formatter for CompactKeyFormatter {
+    fn begin_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        self.in_key = true;
+        CompactFormatter.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.in_key = false;
+        CompactFormatter.end_object_key(writer)
+    }
+
+    fn write_string_fragment<W: ?Sized + io::Write>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()> {
+        if self.in_key {
+            self.key.extend_from_slice(fragment.as_bytes());
+            Ok(())
+        } else {
+            writer.write_all(fragment.as_bytes())
+        }
+    }
+
+    fn write_char_escape<W: ?Sized + io::Write>(&mut self, writer: &mut W, char_escape: CharEscape) -> io::Result<()> {
+        if self.in_key {
+            CompactFormatter.write_char_escape(&mut self.key, char_escape)
+        } else {
+            CompactFormatter.write_char_escape(writer, char_escape)
+        }
+    }
+
+    fn end_string<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.in_key {
+            // Integer map keys are written straight through and leave the buffer empty
+            let key = std::mem::take(&mut self.key);
+            let short = std::str::from_utf8(&key).ok().and_then(|key| self.keys.get(key));
+            writer.write_all(short.map_or(&key[..], |short| short.as_bytes()))?;
+        }
+        CompactFormatter.end_string(writer)
+    }
+}
+
+/// Encodes `value` as compact JSON: `COMPACT_KEYS` shortened and no whitespace.
+/// Keys keep their serialization order (struct fields as declared, ordered maps
+/// sorted), so equal values always give equal bytes.
+pub fn to_compact_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let formatter = CompactKeyFormatter {
+        keys: COMPACT_KEYS.iter().copied().collect(),
+        in_key: false,
+        key: Vec::new(),
+    };
+    let mut json = Vec::new();
+    value
+        .serialize(&mut serde_json::Serializer::with_formatter(&mut json, formatter))
+        .map_err(|e| e.to_string())?;
+    Ok(json)
+}
+
+/// Gets the object keys compact JSON shortens, as a JSON object from each key to its short form
+#[wasm_bindgen]
+pub fn get_compact_json_keys() -> Result<String, JsError> {
+    let keys: BTreeMap<&str, &str> = COMPACT_KEYS.iter().copied().collect();
+    Ok(enveloped_json(&keys).map_err(|e| DebuggerError::MachineError(e.to_string()))?)
+}
+
+impl SerializationFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "json" => Ok(SerializationFormat::Json),
+            "compact" => Ok(SerializationFormat::CompactJson),
+            "cbor" => Ok(SerializationFormat::Cbor),
+            other => Err(format!("Unknown serialization format {}, expected json, compact or cbor", other)),
+        }
+    }
+
+    /// A text format by name, "json" or "compact"
+    pub fn json_from_name(name: &str) -> Result<Self, String> {
+        match Self::from_name(name)? {
+            SerializationFormat::Cbor => Err("CBOR is binary; use export_as to get it".to_string()),
+            format => Ok(format),
+        }
+    }
+}
+
+/// Encodes `value` as CBOR with the structure of its JSON form: structs become
+/// maps with text keys, and numbers integers or floats. Unlike JSON, integer map
+/// keys stay integers.
+pub fn to_cbor<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let mut cbor = Vec::new();
+    ciborium::into_writer(value, &mut cbor).map_err(|e| e.to_string())?;
+    Ok(cbor)
+}
+
+/// Encodes `value` in `format`; JSON comes out as its UTF-8 bytes
+pub fn serialize<T: Serialize>(value: &T, format: SerializationFormat) -> Result<Vec<u8>, String> {
+    match format {
+        SerializationFormat::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+        SerializationFormat::CompactJson => to_compact_json(value),
+        SerializationFormat::Cbor => to_cbor(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cbor_matches_json_structure() {
+        let value = json!({ "a": [1, -2, null, true], "b": "x" });
+        assert_eq!(hex::encode(to_cbor(&value).unwrap()), "a26161840121f6f561626178");
+        assert_eq!(serialize(&value, SerializationFormat::Json).unwrap(), br#"{"a":[1,-2,null,true],"b":"x"}"#);
+        assert!(SerializationFormat::from_name("xml").is_err());
+        assert!(SerializationFormat::json_from_name("cbor").is_err());
+
+        let keyed: BTreeMap<i32, &str> = [(3, "x")].into_iter().collect();
+        assert_eq!(hex::encode(to_cbor(&keyed).unwrap()), "a1036178");
+    }
+
+    #[test]
+    fn test_compact_json() {
+        let value = json!({ "term_type": "Lambda", "parameterName": "x", "id": 3, "body": { "term_type": "Error", "id": 4 } });
+        assert_eq!(
+            serialize(&value, SerializationFormat::CompactJson).unwrap(),
+            br#"{"b":{"id":4,"tt":"Error"},"id":3,"pn":"x","tt":"Lambda"}"#
+        );
+
+        // Only whole keys are replaced, map keys included, and string values never
+        let value = json!({ "typed": "type", "ty\npe": 1, "7": { "type": 2 } });
+        assert_eq!(
+            serialize(&value, SerializationFormat::CompactJson).unwrap(),
+            br#"{"7":{"t":2},"ty\npe":1,"typed":"type"}"#
+        );
+        let keyed: BTreeMap<i32, &str> = [(3, "value")].into_iter().collect();
+        assert_eq!(serialize(&keyed, SerializationFormat::CompactJson).unwrap(), br#"{"3":"value"}"#);
+
+        let short: std::collections::HashSet<&str> = COMPACT_KEYS.iter().map(|(_, short)| *short).collect();
+        assert_eq!(short.len(), COMPACT_KEYS.len());
+        assert!(COMPACT_KEYS.iter().all(|(key, _)| !short.contains(key)));
+    }
+}
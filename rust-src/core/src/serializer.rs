@@ -2,10 +2,11 @@ use uplc::{
     ast::{Term, Constant, NamedDeBruijn, Type},
 };
 use std::collections::HashSet;
+use std::rc::Rc;
 use serde::{Serialize, Deserialize};
 use schemars::JsonSchema;
 use crate::plutus_data::SerializablePlutusData;
-use crate::lazy_loading::{LazyLoadable, LazyLoadableConstant, LazyLoadableTermOrId, LazyLoadableTerm, LazyLoadableData, LazyLoadConfig};
+use crate::lazy_loading::{render_bytestring, LazyLoadable, LazyLoadableConstant, LazyLoadableTermOrId, LazyLoadableTerm, LazyLoadableData, LazyLoadConfig};
 use crate::value::{from_uplc_constant_lazy, should_load_field, advance_config};
 
 // BLS serialization constants
@@ -94,7 +95,29 @@ pub enum SerializableConstant {
     #[serde(rename = "Integer")]
     Integer { value: String },
     #[serde(rename = "ByteString")]
-    ByteString { value: String },
+    ByteString {
+        value: String,
+        /// The bytes as text, when enabled with `set_bytestring_rendering` and they are text
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        utf8: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base64: Option<String>,
+    },
+    /// ByteString or Integer longer than the truncation threshold (see
+    /// `set_constant_truncation_threshold`). The getter adds `_root`, the lazy
+    /// getter to ask ("state", "env" or "context"), and `_path`; that getter
+    /// returns the whole value at `_path` followed by `value`.
+    #[serde(rename = "Truncated")]
+    Truncated {
+        /// "ByteString" or "Integer"
+        #[serde(rename = "_type")]
+        constant_type: String,
+        /// Leading hex or decimal digits (with the sign)
+        preview: String,
+        /// Length in bytes of a ByteString, or in decimal digits of an Integer
+        total_length: usize,
+        truncated: bool,
+    },
     #[serde(rename = "String")]
     String { value: String },
     #[serde(rename = "Bool")]
@@ -124,13 +147,19 @@ pub enum SerializableConstant {
     },
     #[serde(rename = "Bls12_381G1Element")]
     Bls12_381G1Element { 
-        #[serde(rename = "serialized")]
-        serialized: String, // hex-encoded full point (96 bytes)
+        /// Same as `uncompressed`, kept for clients reading this field
+        #[serde(rename = "serialized", default)]
+        serialized: String,
+        #[serde(flatten)]
+        element: SerializableBlsElement,
     },
     #[serde(rename = "Bls12_381G2Element")]
     Bls12_381G2Element { 
-        #[serde(rename = "serialized")]
-        serialized: String, // hex-encoded full point (192 bytes)
+        /// Same as `uncompressed`, kept for clients reading this field
+        #[serde(rename = "serialized", default)]
+        serialized: String,
+        #[serde(flatten)]
+        element: SerializableBlsElement,
     },
     #[serde(rename = "Bls12_381MlResult")]
     Bls12_381MlResult { 
@@ -139,6 +168,42 @@ pub enum SerializableConstant {
     },
 }
 
+/// A BLS12-381 G1 or G2 element in both of its encodings, hex-encoded. Eager
+/// and lazy constants carry the same fields.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+pub struct SerializableBlsElement {
+    /// Compressed point: 48 bytes for G1, 96 for G2
+    #[serde(default)]
+    pub compressed: String,
+    /// Uncompressed point: 96 bytes for G1, 192 for G2
+    #[serde(default)]
+    pub uncompressed: String,
+}
+
+impl SerializableBlsElement {
+    pub fn from_g1(element: &blst::blst_p1) -> Self {
+        SerializableBlsElement {
+            compressed: serialize_bls_g1_element_compressed(element),
+            uncompressed: serialize_bls_g1_element_full(element),
+        }
+    }
+
+    pub fn from_g2(element: &blst::blst_p2) -> Self {
+        SerializableBlsElement {
+            compressed: serialize_bls_g2_element_compressed(element),
+            uncompressed: serialize_bls_g2_element_full(element),
+        }
+    }
+
+    /// The encoding a client gave: `uncompressed`, else `serialized`, else `compressed`
+    fn given<'a>(&'a self, serialized: &'a str) -> &'a str {
+        [self.uncompressed.as_str(), serialized, self.compressed.as_str()]
+            .into_iter()
+            .find(|hex| !hex.is_empty())
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(tag = "type")]
 pub enum SerializableType {
@@ -252,14 +317,43 @@ impl SerializableTerm {
 }
 
 impl SerializableConstant {
+    fn truncated(constant_type: &str, preview: String, total_length: usize) -> Self {
+        SerializableConstant::Truncated {
+            constant_type: constant_type.to_string(),
+            preview,
+            total_length,
+            truncated: true,
+        }
+    }
+
     pub fn from_uplc_constant(constant: &Constant) -> Self {
+        use crate::lazy_loading::{constant_truncation_threshold, CONSTANT_PREVIEW_CHARS};
+
+        let threshold = constant_truncation_threshold();
         match constant {
-            Constant::Integer(i) => SerializableConstant::Integer {
-                value: i.to_string(),
-            },
-            Constant::ByteString(bytes) => SerializableConstant::ByteString {
-                value: hex::encode(bytes),
-            },
+            Constant::Integer(i) => {
+                let value = i.to_string();
+                let digits = value.trim_start_matches('-').len();
+                match threshold {
+                    Some(threshold) if digits > threshold => {
+                        let preview = value.chars().take(CONSTANT_PREVIEW_CHARS).collect();
+                        Self::truncated("Integer", preview, digits)
+                    }
+                    _ => SerializableConstant::Integer { value },
+                }
+            }
+            Constant::ByteString(bytes) if threshold.is_some_and(|threshold| bytes.len() > threshold) => {
+                let edge = (CONSTANT_PREVIEW_CHARS / 2).min(bytes.len());
+                Self::truncated("ByteString", hex::encode(&bytes[..edge]), bytes.len())
+            }
+            Constant::ByteString(bytes) => {
+                let (utf8, base64) = render_bytestring(bytes);
+                SerializableConstant::ByteString {
+                    value: hex::encode(bytes),
+                    utf8,
+                    base64,
+                }
+            }
             Constant::String(s) => SerializableConstant::String {
                 value: s.clone(),
             },
@@ -282,12 +376,14 @@ impl SerializableConstant {
             Constant::Data(data) => SerializableConstant::Data {
                 data: SerializablePlutusData::from_pallas(data),
             },
-            Constant::Bls12_381G1Element(element) => SerializableConstant::Bls12_381G1Element {
-                serialized: serialize_bls_g1_element_full(element),
-            },
-            Constant::Bls12_381G2Element(element) => SerializableConstant::Bls12_381G2Element {
-                serialized: serialize_bls_g2_element_full(element),
-            },
+            Constant::Bls12_381G1Element(element) => {
+                let element = SerializableBlsElement::from_g1(element);
+                SerializableConstant::Bls12_381G1Element { serialized: element.uncompressed.clone(), element }
+            }
+            Constant::Bls12_381G2Element(element) => {
+                let element = SerializableBlsElement::from_g2(element);
+                SerializableConstant::Bls12_381G2Element { serialized: element.uncompressed.clone(), element }
+            }
             Constant::Bls12_381MlResult(result) => SerializableConstant::Bls12_381MlResult {
                 bytes: serialize_bls_fp12_element(result),
             },
@@ -316,9 +412,71 @@ impl SerializableType {
             Type::Bls12_381MlResult => SerializableType::Bls12_381MlResult,
         }
     }
+
+    /// Convert back to a UPLC type (inverse of `from_uplc_type`)
+    pub fn to_uplc_type(&self) -> Type {
+        match self {
+            SerializableType::Bool => Type::Bool,
+            SerializableType::Integer => Type::Integer,
+            SerializableType::String => Type::String,
+            SerializableType::ByteString => Type::ByteString,
+            SerializableType::Unit => Type::Unit,
+            SerializableType::List { element_type } => Type::List(Rc::new(element_type.to_uplc_type())),
+            SerializableType::Pair { first_type, second_type } => {
+                Type::Pair(Rc::new(first_type.to_uplc_type()), Rc::new(second_type.to_uplc_type()))
+            }
+            SerializableType::Data => Type::Data,
+            SerializableType::Bls12_381G1Element => Type::Bls12_381G1Element,
+            SerializableType::Bls12_381G2Element => Type::Bls12_381G2Element,
+            SerializableType::Bls12_381MlResult => Type::Bls12_381MlResult,
+        }
+    }
+}
+
+impl SerializableConstant {
+    /// Convert back to a UPLC constant (inverse of `from_uplc_constant`). Miller loop
+    /// results have no serialized form to read back, so they are rejected.
+    pub fn to_uplc_constant(&self) -> Result<Constant, String> {
+        Ok(match self {
+            SerializableConstant::Integer { value } => {
+                Constant::Integer(value.parse().map_err(|_| format!("Invalid integer: {}", value))?)
+            }
+            SerializableConstant::ByteString { value, .. } => {
+                Constant::ByteString(hex::decode(value).map_err(|e| format!("Invalid bytestring {}: {}", value, e))?)
+            }
+            SerializableConstant::String { value } => Constant::String(value.clone()),
+            SerializableConstant::Bool { value } => Constant::Bool(*value),
+            SerializableConstant::Unit => Constant::Unit,
+            SerializableConstant::ProtoList { element_type, values } => Constant::ProtoList(
+                element_type.to_uplc_type(),
+                values.iter().map(|value| value.to_uplc_constant()).collect::<Result<_, _>>()?,
+            ),
+            SerializableConstant::ProtoPair { first_type, second_type, first_element, second_element } => {
+                Constant::ProtoPair(
+                    first_type.to_uplc_type(),
+                    second_type.to_uplc_type(),
+                    Rc::new(first_element.to_uplc_constant()?),
+                    Rc::new(second_element.to_uplc_constant()?),
+                )
+            }
+            SerializableConstant::Data { data } => Constant::Data(data.to_pallas()?),
+            SerializableConstant::Bls12_381G1Element { serialized, element } => {
+                Constant::Bls12_381G1Element(Box::new(deserialize_bls_g1_element(element.given(serialized))?))
+            }
+            SerializableConstant::Bls12_381G2Element { serialized, element } => {
+                Constant::Bls12_381G2Element(Box::new(deserialize_bls_g2_element(element.given(serialized))?))
+            }
+            SerializableConstant::Bls12_381MlResult { .. } => {
+                return Err("Bls12_381MlResult constants can't be built from JSON".to_string())
+            }
+            SerializableConstant::Truncated { constant_type, .. } => {
+                return Err(format!("Truncated {} constants can't be built from JSON", constant_type))
+            }
+        })
+    }
 }
 
-// === COMPRESSED SERIALIZATION FUNCTIONS (for optional use) ===
+// === COMPRESSED SERIALIZATION FUNCTIONS ===
 
 /// Serialize BLS G1 element to compressed hex string (48 bytes)
 pub fn serialize_bls_g1_element_compressed(element: &blst::blst_p1) -> String {
@@ -338,7 +496,63 @@ pub fn serialize_bls_g2_element_compressed(element: &blst::blst_p2) -> String {
     }
 }
 
-// === FULL SERIALIZATION FUNCTIONS (used by default) ===
+// === FULL SERIALIZATION FUNCTIONS ===
+
+/// Deserialize a BLS G1 element from its full (96 bytes) or compressed (48 bytes)
+/// hex form, checking it is on the curve and in the G1 subgroup
+fn deserialize_bls_g1_element(serialized: &str) -> Result<blst::blst_p1, String> {
+    let bytes = hex::decode(serialized).map_err(|e| format!("Invalid G1 element {}: {}", serialized, e))?;
+    unsafe {
+        let mut affine = blst::blst_p1_affine::default();
+        let result = match bytes.len() {
+            BLS12_381_G1_SERIALIZED_SIZE => blst::blst_p1_deserialize(&mut affine, bytes.as_ptr()),
+            BLS12_381_G1_COMPRESSED_SIZE => blst::blst_p1_uncompress(&mut affine, bytes.as_ptr()),
+            len => {
+                return Err(format!(
+                    "G1 element must be {} or {} bytes, got {}",
+                    BLS12_381_G1_SERIALIZED_SIZE, BLS12_381_G1_COMPRESSED_SIZE, len
+                ))
+            }
+        };
+        if result != blst::BLST_ERROR::BLST_SUCCESS {
+            return Err(format!("Invalid G1 element {}", serialized));
+        }
+        if !blst::blst_p1_affine_in_g1(&affine) {
+            return Err(format!("G1 element {} is not in the G1 subgroup", serialized));
+        }
+        let mut point = blst::blst_p1::default();
+        blst::blst_p1_from_affine(&mut point, &affine);
+        Ok(point)
+    }
+}
+
+/// Deserialize a BLS G2 element from its full (192 bytes) or compressed (96 bytes)
+/// hex form, checking it is on the curve and in the G2 subgroup
+fn deserialize_bls_g2_element(serialized: &str) -> Result<blst::blst_p2, String> {
+    let bytes = hex::decode(serialized).map_err(|e| format!("Invalid G2 element {}: {}", serialized, e))?;
+    unsafe {
+        let mut affine = blst::blst_p2_affine::default();
+        let result = match bytes.len() {
+            BLS12_381_G2_SERIALIZED_SIZE => blst::blst_p2_deserialize(&mut affine, bytes.as_ptr()),
+            BLS12_381_G2_COMPRESSED_SIZE => blst::blst_p2_uncompress(&mut affine, bytes.as_ptr()),
+            len => {
+                return Err(format!(
+                    "G2 element must be {} or {} bytes, got {}",
+                    BLS12_381_G2_SERIALIZED_SIZE, BLS12_381_G2_COMPRESSED_SIZE, len
+                ))
+            }
+        };
+        if result != blst::BLST_ERROR::BLST_SUCCESS {
+            return Err(format!("Invalid G2 element {}", serialized));
+        }
+        if !blst::blst_p2_affine_in_g2(&affine) {
+            return Err(format!("G2 element {} is not in the G2 subgroup", serialized));
+        }
+        let mut point = blst::blst_p2::default();
+        blst::blst_p2_from_affine(&mut point, &affine);
+        Ok(point)
+    }
+}
 
 /// Serialize BLS G1 element to full hex string (96 bytes)
 fn serialize_bls_g1_element_full(element: &blst::blst_p1) -> String {
@@ -448,7 +662,21 @@ pub enum SerializableConstantLazy {
     #[serde(rename = "Integer")]
     Integer { value: String },
     #[serde(rename = "ByteString")]
-    ByteString { value: String },
+    ByteString {
+        value: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        utf8: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base64: Option<String>,
+    },
+    /// Large ByteString; navigate to `<constant path>.value` to fetch the full hex
+    #[serde(rename = "ByteStringPreview")]
+    ByteStringPreview {
+        head: String,
+        tail: String,
+        length: usize,
+        hash: String,
+    },
     #[serde(rename = "String")]
     String { value: String },
     #[serde(rename = "Bool")]
@@ -478,13 +706,19 @@ pub enum SerializableConstantLazy {
     },
     #[serde(rename = "Bls12_381G1Element")]
     Bls12_381G1Element { 
+        /// Same as `compressed`, kept for clients reading this field
         #[serde(rename = "serialized")]
         serialized: String,
+        #[serde(flatten)]
+        element: SerializableBlsElement,
     },
     #[serde(rename = "Bls12_381G2Element")]
     Bls12_381G2Element { 
+        /// Same as `compressed`, kept for clients reading this field
         #[serde(rename = "serialized")]
         serialized: String,
+        #[serde(flatten)]
+        element: SerializableBlsElement,
     },
     #[serde(rename = "Bls12_381MlResult")]
     Bls12_381MlResult { 
@@ -493,6 +727,48 @@ pub enum SerializableConstantLazy {
     },
 }
 
+/// Blake2b-256 of a ByteString or the CBOR encoding of a Data constant (hex).
+/// Equal hashes mean equal contents without transferring either value.
+pub fn constant_content_hash(constant: &Constant) -> Option<String> {
+    let hash = match constant {
+        Constant::ByteString(bytes) => pallas_crypto::hash::Hasher::<256>::hash(bytes),
+        Constant::Data(data) => {
+            let cbor = pallas_codec::minicbor::to_vec(data).ok()?;
+            pallas_crypto::hash::Hasher::<256>::hash(&cbor)
+        }
+        _ => return None,
+    };
+    Some(hash.to_string())
+}
+
+impl SerializableConstantLazy {
+    /// Serialize a ByteString, replacing it with a preview when it is larger than
+    /// the configured threshold and `full` is not requested
+    pub fn from_bytestring(bytes: &[u8], full: bool) -> Self {
+        use crate::lazy_loading::{bytestring_preview_threshold, BYTESTRING_PREVIEW_EDGE_BYTES};
+
+        match bytestring_preview_threshold() {
+            Some(threshold) if !full && bytes.len() > threshold => {
+                let edge = BYTESTRING_PREVIEW_EDGE_BYTES.min(bytes.len());
+                SerializableConstantLazy::ByteStringPreview {
+                    head: hex::encode(&bytes[..edge]),
+                    tail: hex::encode(&bytes[bytes.len() - edge..]),
+                    length: bytes.len(),
+                    hash: pallas_crypto::hash::Hasher::<256>::hash(bytes).to_string(),
+                }
+            }
+            _ => {
+                let (utf8, base64) = crate::lazy_loading::render_bytestring(bytes);
+                SerializableConstantLazy::ByteString {
+                    value: hex::encode(bytes),
+                    utf8,
+                    base64,
+                }
+            }
+        }
+    }
+}
+
 impl SerializableTermLazy {
     /// Convert a UPLC Term to a lazy-loadable serializable format
     pub fn from_uplc_term_lazy(
@@ -587,6 +863,7 @@ impl SerializableTermLazy {
                         type_name: "Constant".to_string(),
                         kind: "Constant".to_string(),
                         length: None,
+                        hash: constant_content_hash(value),
                     }
                 };
                 SerializableTermLazy::Constant {
@@ -811,6 +1088,14 @@ pub fn navigate_to_constant_lazy(
                 NavigationResult::InvalidPath("Expected index after 'values'".to_string())
             }
         }
+        // ByteString: an explicit 'value' segment fetches the full hex of a previewed ByteString
+        (Constant::ByteString(bytes), PathSegment::Field(field_name)) if field_name == "value" => {
+            NavigationResult::Found(SerializableConstantLazy::from_bytestring(bytes, true))
+        }
+        // Integer: the same for an Integer truncated in a full getter
+        (Constant::Integer(i), PathSegment::Field(field_name)) if field_name == "value" => {
+            NavigationResult::Found(SerializableConstantLazy::Integer { value: i.to_string() })
+        }
         // ProtoPair: navigate to first_element or second_element
         (Constant::ProtoPair(_, _, first, _second), PathSegment::Field(field_name)) if field_name == "first_element" => {
             navigate_to_constant_lazy(first, &path[1..], term_ids, return_full_object)
@@ -840,4 +1125,83 @@ pub fn navigate_to_constant_lazy(
         // Invalid path for constant type
         _ => NavigationResult::InvalidPath(format!("Invalid navigation path {:?} for constant", path))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn g1_generator() -> blst::blst_p1 {
+        unsafe { *blst::blst_p1_generator() }
+    }
+
+    fn g2_generator() -> blst::blst_p2 {
+        unsafe { *blst::blst_p2_generator() }
+    }
+
+    #[test]
+    fn test_bls_elements_read_back_from_either_encoding() {
+        let g1 = SerializableBlsElement::from_g1(&g1_generator());
+        assert_eq!(g1.compressed.len(), 2 * BLS12_381_G1_COMPRESSED_SIZE);
+        assert_eq!(g1.uncompressed.len(), 2 * BLS12_381_G1_SERIALIZED_SIZE);
+        for hex in [&g1.compressed, &g1.uncompressed] {
+            let point = deserialize_bls_g1_element(hex).unwrap();
+            assert!(unsafe { blst::blst_p1_is_equal(&point, &g1_generator()) });
+        }
+
+        let g2 = SerializableBlsElement::from_g2(&g2_generator());
+        assert_eq!(g2.compressed.len(), 2 * BLS12_381_G2_COMPRESSED_SIZE);
+        assert_eq!(g2.uncompressed.len(), 2 * BLS12_381_G2_SERIALIZED_SIZE);
+        for hex in [&g2.compressed, &g2.uncompressed] {
+            let point = deserialize_bls_g2_element(hex).unwrap();
+            assert!(unsafe { blst::blst_p2_is_equal(&point, &g2_generator()) });
+        }
+
+        assert!(deserialize_bls_g1_element(&g1.compressed[2..]).is_err());
+    }
+
+    #[test]
+    fn test_bls_given_encoding_precedence() {
+        let element = |compressed: &str, uncompressed: &str| SerializableBlsElement {
+            compressed: compressed.to_string(),
+            uncompressed: uncompressed.to_string(),
+        };
+        assert_eq!(element("c", "u").given("s"), "u");
+        assert_eq!(element("c", "").given("s"), "s");
+        assert_eq!(element("c", "").given(""), "c");
+        assert_eq!(element("", "").given(""), "");
+
+        // A constant given only in compressed form
+        let g1 = SerializableBlsElement::from_g1(&g1_generator());
+        let constant = SerializableConstant::Bls12_381G1Element {
+            serialized: String::new(),
+            element: element(&g1.compressed, ""),
+        };
+        assert!(matches!(constant.to_uplc_constant(), Ok(Constant::Bls12_381G1Element(_))));
+    }
+
+    #[test]
+    fn test_bls_points_outside_the_subgroup_are_rejected() {
+        // Mapping a field element to the curve without clearing the cofactor
+        // lands outside G1
+        let point = unsafe {
+            let mut u = blst::blst_fp::default();
+            blst::blst_fp_from_uint64(&mut u, [7u64, 0, 0, 0, 0, 0].as_ptr());
+            let mut point = blst::blst_p1::default();
+            blst::blst_map_to_g1(&mut point, &u, std::ptr::null());
+            point
+        };
+        let encoded = SerializableBlsElement::from_g1(&point);
+        let error = deserialize_bls_g1_element(&encoded.uncompressed).unwrap_err();
+        assert!(error.contains("subgroup"), "{}", error);
+        assert!(deserialize_bls_g1_element(&encoded.compressed).is_err());
+    }
+
+    #[test]
+    fn test_content_hash_is_full_blake2b_256() {
+        let hash = constant_content_hash(&Constant::ByteString(vec![1, 2, 3])).unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, pallas_crypto::hash::Hasher::<256>::hash(&[1, 2, 3]).to_string());
+        assert_ne!(Some(hash), constant_content_hash(&Constant::ByteString(vec![1, 2, 4])));
+        assert_eq!(constant_content_hash(&Constant::Bool(true)), None);
+    }
+}
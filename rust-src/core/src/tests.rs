@@ -0,0 +1,697 @@
+use crate::DebuggerEngine;
+
+const TX_HEX: &str = "84a900838258206153c1e9a628fc2bc1ebc82d1f6b6d360c3f5a895171cfd8a792541ab51f7509018258206153c1e9a628fc2bc1ebc82d1f6b6d360c3f5a895171cfd8a792541ab51f750902825820cb78d0612a8a54e4cbe43c90e23dc7739f0b27fffbd7caa5368d9b98047601b1000183a400581d7118c91bdff54ad8f4d3618818f36b99e401caa7eab153b42f51311cb001821a002297b4a2581c9e3ca7a4d3ae25b02b1ce833b8d85bd8a6a8fda186a93a0bc504d454a14001581cd30ee8c513b3fabada55d802a5ca5bb12e43b42027017309ed71ed4ba14001028201d8184dd8799f1b00003b1e458e2080ff03d81858c68200830304868200581cde4abaf30e894ec9243b8bb97ad7414b1d3086833ad8bf12d10130d08200581caf62c226e169c1fb4e84eb4286bac6bfda702ecf3a49f9fdbe5bf78f8200581cd8c1b4ad263333c687291894fb466a3bc1429565c2541e54b8901e158200581c5d5bbb9f55ea3524307dd6ed28e72b156f60ffc181cc20b89f2f13338200581cb6ee5605641b0f573312699348d19e71765d0010a4cac2c4fc9678178200581cde398fc701ce1b4adb7119d68c1d9710cfb70dec6989e31acc6d274f82583901de4abaf30e894ec9243b8bb97ad7414b1d3086833ad8bf12d10130d06a60e1593e83c2753d38ee6183d1d7cdb0a1c5cba657c798885122a6821a001b9f18a4581c6ac8ef33b510ec004fe11585f7c5a9f0c07f0c23428ab4f29c1d7d10a1444d454c441a004c4b40581ca0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235a145484f534b591a0134fd9a581ca2944573e99d2ed3055b808eaa264f0bf119e01fc6b18863067c63e4a1444d454c441b000000037e2100d0581cb06729158210bf1ba13f8f3d7d422a918d3eaa82561a705552a2568ba158194d656c642042616e6b204d616e6167657220763120333939370182583901de4abaf30e894ec9243b8bb97ad7414b1d3086833ad8bf12d10130d06a60e1593e83c2753d38ee6183d1d7cdb0a1c5cba657c798885122a61a1a895ce7021a00042ee309a2581c4d564c6e31f771d71471437ea9f0e60038be09619755f8720eb093cda14020581ca2944573e99d2ed3055b808eaa264f0bf119e01fc6b18863067c63e4a1444d454c443b00000da475abefff0b58201b22ceb7916b9a373af4c0bcd14d59bfc3f0bb88702f5f6214463ae2dc94a9df0d818258206153c1e9a628fc2bc1ebc82d1f6b6d360c3f5a895171cfd8a792541ab51f7509021082583901de4abaf30e894ec9243b8bb97ad7414b1d3086833ad8bf12d10130d06a60e1593e83c2753d38ee6183d1d7cdb0a1c5cba657c798885122a61a1a87aa79111a000646551283825820125192e26ab0fedcfb32d54176d4cffdbde39b22f68d13f64c85bc7d64bd71a501825820125192e26ab0fedcfb32d54176d4cffdbde39b22f68d13f64c85bc7d64bd71a503825820cb78d0612a8a54e4cbe43c90e23dc7739f0b27fffbd7caa5368d9b98047601b100a2008482582059b84d8bda14d930893c601db44a4868206bb86e4d413637e19f9e8fb48e1103584051a8a5f8725842b78877e9852c6277bae5bb4b67cdbfe12311b63a64b1097eccafb8c26012e713b45842652fa5e2490f9ecdbdb4cb8a5b5006a529e1d814a20f825820772009ced9a45528d3a8b917eedd09949b724c07d7e0ffb1bced31dd0692acc85840cf655e22856912723907977cade87a887cba8e9e928d61efa6c600c03d4155f9b0caa76ec59270a6dbfb9091258106584416ea24ac68a3ea6de9c33065782405825820485e86a93ed036d20336391aab0419bf4cf2a7b47fbe7b62f8a0fa912954ecb8584051af180e31ce6baca4b404e7a3d4b942362dd36938dc2e3fe86dfb9388bc3ddd290821850080d8bf2e92f1d43e36c6cfcedb6f2bbf1823bec0e6aafbe113c3008258209e0fc2bceae4ffe26fffd8a91dcc7cd4578843415434ff565696b5ffd5ec1e725840dc28ca94bfd9070507e4ed8223afdd3f08e10790f6a86c08111523defa7fa84b6851c7f4276d25757a2f945cb72b9609e687e39085905ce33f04986b9c83af010582840002d87980821a0004bd501a07f45cee840101d87a80821a000191e71a02b3b3f4F5F6";
+const UTXO_JSON: &str = "[{\"txHash\":\"125192e26ab0fedcfb32d54176d4cffdbde39b22f68d13f64c85bc7d64bd71a5\",\"outputIndex\":1,\"address\":\"addr1wypan0u8f2jsewz97nw0qydzy0k5k8xd2x6gtxgt4fuavastxq94l\",\"value\":{\"lovelace\":\"6646020\"},\"datumHash\":null,\"referenceScript\":{\"type\":\"PlutusV2\",\"script\":\"59052d01000032323232323232323232323232323232323232323232323232323232323232322223232323232323232323232323232323232323232325333033533303330363037002132323253302b33301d3021003012011153302b3301f33026002037375860660282a66056607060386eacc0cc04c4cdd79819981700d9819981700598101817998159981280081b1bac30320133031302c00130303031302b302d3302923302603530313032302c001375860600242646464a6605666603a00c0240222a6605666e3c00401854cc0accdc4801a410101a163a5fd341c2a6605666e252000003132323232323232533032533303d303f304100214a029444cdc398119bab303a01a0093041001375400aa6660746078004264068a66607200220662c2a666074607a004264068a66607200220662c2c607c00460780026ea8004ccccc0788c888c00800cc0f0004cdd2a40006605a98011e581ca2944573e99d2ed3055b808eaa264f0bf119e01fc6b18863067c63e4004bd701bab3033303430223034013200116302000f337026eb4c0c4c0b0064004dd69818181580408160b181b8009baa015301b302d302e3028301e013533302f3031002132029533302e0011028161533302f3032002132029533302e001102816163033002303100137540206030008a6660546058605c0042646404aa666054605200420482c6eb4c09c00458c0b8004dd5180a800981218129812980f80098101980e1980b180a980a004a5eb851e581c9e3ca7a4d3ae25b02b1ce833b8d85bd8a6a8fda186a93a0bc504d45400810140001bac3023004375660440046eb0c08400cc084c084004c080c080004c064c078c0640114ccc080c088c0900084c8c806d4ccc080c07c008406858dd6980e8008b18120009baa003233300e00148811ca2944573e99d2ed3055b808eaa264f0bf119e01fc6b18863067c63e4004881044d454c4400222533006330052323233533301f30223023002132223002003375c6038002244a002466e3c00401c52818118009baa301a3015300c001002133333004232223002003375660440026ea400c0049288a502223301722533301c00112250011533301d3375e604260340020082600a60340022600460360020024602844a666032002294054cc010c00cc05c0044c008c0600048cc05c00452891919299980c180d180e00109bae301500116301c001375460266004601c002460266026602600246464a66602c66e1d2004301a0021301300116301a0013754002464646666020600ca66602a60306032004260240022c6eb0c048c034c048c03400c800458c064004dd5180818089805800918079805180798081805000911299804198028010008998020018009119baf374e60120046e9cc024c038c02400488c94ccc044cdc4240040022c260280026660066eacc034c038c020004dd718068011bae300d300e0022223333004002480008cccc014009200075a6eac00400c8c8888cc034894ccc048004401454ccc04ccdd7980b980800080309802180c18080008980118088008009ba90012233300d00200114a04600e44a666018002297ae0132533300e30040011330050013003300c00213003300c002300a00157404601c6ea8005263002225333007001161533300835746600c0022600a0022c6002444a66600e002244a0022a6660106004600a002264446004006600a002266006004600c002464600446600400400246004466004004002ae855d12ab9f573497ae1011e581c1400f6b65c323065b3cad0bc73437884e3b9c4714624bde5cfe0132000810140001b8748000dc3a4004aae7555cf01\"}},{\"txHash\":\"125192e26ab0fedcfb32d54176d4cffdbde39b22f68d13f64c85bc7d64bd71a5\",\"outputIndex\":3,\"address\":\"addr1wypan0u8f2jsewz97nw0qydzy0k5k8xd2x6gtxgt4fuavastxq94l\",\"value\":{\"lovelace\":\"7779550\"},\"datumHash\":null,\"referenceScript\":{\"type\":\"PlutusV2\",\"script\":\"59063401000032323232323232323232323232323232323232323232323232322232323232323253330145333014301c302200213301622533301900114a0264a66602ea66602e66e212002001161301f00114a22600660360046660246eacc050c068c044c050c068c044c050004dd7180a00d9bae3014301a01b37586024601e6024601e00e26464646464646464a66603860466054004264646464a6602c604e60426603246603201a603e604a60380026eb0c07802854cc058c0a0c08400c54cc058cdc3998111129998128008a4000264a66604666ebcc0c400401c4c94ccc09c0045854ccc090d5d198140008a99981219baf303230220014c0105444d454c44001375a606260440022c2c6eacc0c00044c00cc09c008c080004dd5980f0040008991919191919191929980f2999814299981419b8800200114a22a66605066e1c0080044c8c94ccc0a8c0c80104cdc41bad3028002375a60500022940c0d8010c0d40105280a511323232533302b533302b303300213232533302d3035303b00213375e6e9c00cdd38008a50303900137540102a666056606800426464a66605a606c6076004266ebcdd38019ba700114a060720026ea80204c8c94ccc0b4c0d0c0ec0084cdd79ba7003374e0022940c0e4004dd5004099981598101814981780598101814981780525114a0606e004606e0026ea80184c8c8c8c8c8c8c8cdc49bad302d3033302a00433302633029035330290354bd702400000ea66605c606a60780042646464646464066a66606c606e00420642c6eb4c0c0004c0d40114ccc0c0c0dcc0f80084c8c8c8c8c8c8c8c8c8c80e54ccc0f0c0f401440e0594ccc0e0c0fc0084c8c8c8c8c80f14ccc0fcc10001440ec594ccc0ecc1080084c8c8c8c8c80fd4ccc108c10c01440f8594ccc0f8c11400854ccc0f8c120dc69bae303c00113203e53330413042001103d16161533303e30460021533303e3048371a6eb8c0f00044c80f94ccc104c10800440f4585858c128008c128004dd5181c8008a99981d9821801099191919191902029998219822001081f8b1bad303d0013042002375a607600260800046eb4c0e400458c11c008c11c004dd5181b0008a99981c182000109901c299981d800881b8b0b182200118220009baa303300130380055333033303a00215333033303d371a6eb8c0c40044c80cd4ccc0d8c0dc00440c8585854ccc0ccc0ec00854ccc0ccc0f4dc69bae3031001132033533303630370011032161616303f002303f0013754605c0022c60780026ea8c0b000458c0e8004dd5000a999815981a181c801098148008b181b8009baa3027302d302d3024001302600b3035002303400237540046ea8008c084008c080008c07000cc06c01cccc0848894ccc09400440084cc00cccc0652f5c211e581c6ac8ef33b510ec004fe11585f7c5a9f0c07f0c23428ab4f29c1d7d10008105444d454c440000118100009813000a4000004605266030605466030981091b000001941f2891a0004bd701980c260103d87980004bd701980b180a8049bac301b006301a00116302800137546030603c00a602e603a6028602e603a603a603a0026038603800260366036002602260280026020010603e6601c60406601c98011e581cd612be7ab0bdbd3d728b922e422da843de33ee71bd13c19e78b32080004bd7019807181025eb812f5c020262c60400026ea80194ccc044c0600084c80454ccc05000440405854ccc044c0640084c80454ccc05000440405858c074008c074004dd50011180a180d1baa0012233300c00200114a044466e00ccc020dd59805180818038009bae300a003375c601460200060044466ebcdd398028011ba73005300830050012300a22533300d00114bd7009929998059802000899802800980198078010980198078011804000aba0230133754002444666600800490001199980280124000eb4dd58008019191111980511299980680088028a99980519baf30183008001006130043017300800113002300e0010013752002ae8526573466002444a66600a002200426600666e0000920023006001480008c8c0088cc0080080048c0088cc00800800555cfaba24bd70811e581c9e3ca7a4d3ae25b02b1ce833b8d85bd8a6a8fda186a93a0bc504d45400810140002601014000370e90001b8748008dc3a40086e1d2038374a90001ba54800955cf2ab9d1\"}},{\"txHash\":\"cb78d0612a8a54e4cbe43c90e23dc7739f0b27fffbd7caa5368d9b98047601b1\",\"outputIndex\":0,\"address\":\"addr1wyvvjx7l749d3axnvxyp3umtn8jqrj48a2c48dp02yc3evqzypx02\",\"value\":{\"lovelace\":\"2267060\",\"assets\":{\"4d564c6e31f771d71471437ea9f0e60038be09619755f8720eb093cd.\":\"1\",\"9e3ca7a4d3ae25b02b1ce833b8d85bd8a6a8fda186a93a0bc504d454.\":\"1\",\"d30ee8c513b3fabada55d802a5ca5bb12e43b42027017309ed71ed4b.\":\"1\"}},\"datumHash\":\"671a25e519487eeb4a3ec3abf857d5f0f561edae50386966e456572541ac7451\",\"inlineDatum\":\"d8799f1b00002d79cfe23080ff\",\"referenceScript\":{\"type\":\"NativeScript\",\"script\":\"830304868200581cde4abaf30e894ec9243b8bb97ad7414b1d3086833ad8bf12d10130d08200581caf62c226e169c1fb4e84eb4286bac6bfda702ecf3a49f9fdbe5bf78f8200581cd8c1b4ad263333c687291894fb466a3bc1429565c2541e54b8901e158200581c5d5bbb9f55ea3524307dd6ed28e72b156f60ffc181cc20b89f2f13338200581cb6ee5605641b0f573312699348d19e71765d0010a4cac2c4fc9678178200581cde398fc701ce1b4adb7119d68c1d9710cfb70dec6989e31acc6d274f\"}},{\"txHash\":\"6153c1e9a628fc2bc1ebc82d1f6b6d360c3f5a895171cfd8a792541ab51f7509\",\"outputIndex\":1,\"address\":\"addr1q80y4whnp6y5ajfy8w9mj7khg9936vyxsvad30cj6yqnp5r2vrs4j05rcf6n6w8wvxpar47dkzsutjax2lre3zz3y2nqh3h7xa\",\"value\":{\"lovelace\":\"1784340\",\"assets\":{\"6ac8ef33b510ec004fe11585f7c5a9f0c07f0c23428ab4f29c1d7d10.4d454c44\":\"5000000\",\"a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235.484f534b59\":\"20250010\",\"a2944573e99d2ed3055b808eaa264f0bf119e01fc6b18863067c63e4.4d454c44\":\"15015000994000\",\"b06729158210bf1ba13f8f3d7d422a918d3eaa82561a705552a2568b.4d656c642042616e6b204d616e616765722076312033393937\":\"1\"}},\"datumHash\":null},{\"txHash\":\"6153c1e9a628fc2bc1ebc82d1f6b6d360c3f5a895171cfd8a792541ab51f7509\",\"outputIndex\":2,\"address\":\"addr1q80y4whnp6y5ajfy8w9mj7khg9936vyxsvad30cj6yqnp5r2vrs4j05rcf6n6w8wvxpar47dkzsutjax2lre3zz3y2nqh3h7xa\",\"value\":{\"lovelace\":\"445509838\"},\"datumHash\":null}]";
+const PROTOCOL_PARAMS: &str = "{\"minFeeA\":155381,\"minFeeB\":44,\"maxTxSize\":16384,\"maxValSize\":\"5000\",\"keyDeposit\":\"2000000\",\"poolDeposit\":\"500000000\",\"minPoolCost\":\"170000000\",\"utxoCostPerWord\":0,\"maxTxExMem\":\"14000000\",\"maxTxExSteps\":\"10000000000\",\"maxBlockExMem\":\"62000000\",\"maxBlockExSteps\":\"20000000000\",\"maxCollateralInputs\":3,\"costModels\":{\"PlutusV1\":[100788,420,1,1,1000,173,0,1,1000,59957,4,1,11183,32,201305,8356,4,16000,100,16000,100,16000,100,16000,100,16000,100,16000,100,100,100,16000,100,94375,32,132994,32,61462,4,72010,178,0,1,22151,32,91189,769,4,2,85848,228465,122,0,1,1,1000,42921,4,2,24548,29498,38,1,898148,27279,1,51775,558,1,39184,1000,60594,1,141895,32,83150,32,15299,32,76049,1,13169,4,22100,10,28999,74,1,28999,74,1,43285,552,1,44749,541,1,33852,32,68246,32,72362,32,7243,32,7391,32,11546,32,85848,228465,122,0,1,1,90434,519,0,1,74433,32,85848,228465,122,0,1,1,85848,228465,122,0,1,1,270652,22588,4,1457325,64566,4,20467,1,4,0,141992,32,100788,420,1,1,81663,32,59498,32,20142,32,24588,32,20744,32,25933,32,24623,32,53384111,14333,10],\"PlutusV2\":[100788,420,1,1,1000,173,0,1,1000,59957,4,1,11183,32,201305,8356,4,16000,100,16000,100,16000,100,16000,100,16000,100,16000,100,100,100,16000,100,94375,32,132994,32,61462,4,72010,178,0,1,22151,32,91189,769,4,2,85848,228465,122,0,1,1,1000,42921,4,2,24548,29498,38,1,898148,27279,1,51775,558,1,39184,1000,60594,1,141895,32,83150,32,15299,32,76049,1,13169,4,22100,10,28999,74,1,28999,74,1,43285,552,1,44749,541,1,33852,32,68246,32,72362,32,7243,32,7391,32,11546,32,85848,228465,122,0,1,1,90434,519,0,1,74433,32,85848,228465,122,0,1,1,85848,228465,122,0,1,1,955506,213312,0,2,270652,22588,4,1457325,64566,4,20467,1,4,0,141992,32,100788,420,1,1,81663,32,59498,32,20142,32,24588,32,20744,32,25933,32,24623,32,43053543,10,53384111,14333,10,43574283,26308,10],\"PlutusV3\":[100788,420,1,1,1000,173,0,1,1000,59957,4,1,11183,32,201305,8356,4,16000,100,16000,100,16000,100,16000,100,16000,100,16000,100,100,100,16000,100,94375,32,132994,32,61462,4,72010,178,0,1,22151,32,91189,769,4,2,85848,123203,7305,-900,1716,549,57,85848,0,1,1,1000,42921,4,2,24548,29498,38,1,898148,27279,1,51775,558,1,39184,1000,60594,1,141895,32,83150,32,15299,32,76049,1,13169,4,22100,10,28999,74,1,28999,74,1,43285,552,1,44749,541,1,33852,32,68246,32,72362,32,7243,32,7391,32,11546,32,85848,123203,7305,-900,1716,549,57,85848,0,1,90434,519,0,1,74433,32,85848,123203,7305,-900,1716,549,57,85848,0,1,1,85848,123203,7305,-900,1716,549,57,85848,0,1,955506,213312,0,2,270652,22588,4,1457325,64566,4,20467,1,4,0,141992,32,100788,420,1,1,81663,32,59498,32,20142,32,24588,32,20744,32,25933,32,24623,32,43053543,10,53384111,14333,10,43574283,26308,10,16000,100,16000,100,962335,18,2780678,6,442008,1,52538055,3756,18,267929,18,76433006,8868,18,52948122,18,1995836,36,3227919,12,901022,1,166917843,4307,36,284546,36,158221314,26549,36,74698472,36,333849714,1,254006273,72,2174038,72,2261318,64571,4,207616,8310,4,1293828,28716,63,0,1,1006041,43623,251,0,1,100181,726,719,0,1,100181,726,719,0,1,100181,726,719,0,1,107878,680,0,1,95336,1,281145,18848,0,1,180194,159,1,1,158519,8942,0,1,159378,8813,0,1,107490,3298,1,106057,655,1,1964219,24520,3]},\"protocolVersion\":{\"major\":10,\"minor\":0}}";
+
+fn engine() -> DebuggerEngine {
+    DebuggerEngine::new(TX_HEX, UTXO_JSON, PROTOCOL_PARAMS, "mainnet").unwrap()
+}
+
+/// The payload of a JSON response, checking it comes in the current schema version envelope
+fn payload<T: serde::de::DeserializeOwned>(json: &str) -> T {
+    let mut envelope: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(envelope["schema_version"], crate::wasm_tools::SCHEMA_VERSION);
+    serde_json::from_value(envelope["payload"].take()).unwrap()
+}
+
+#[test]
+fn open_test() {
+    let mut dd = engine();
+    let session = dd.init_debug_session("Spend:2", None).unwrap();
+    let status = session.get_script().unwrap();
+}
+
+#[test]
+fn test_slippage_hides_per_step_costs() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", Some(r#"{"slippage":10}"#.to_string())).unwrap();
+    let result = session.step_inner().unwrap();
+    assert_eq!((result.cpu_delta, result.mem_delta), (None, None));
+    assert!(session.get_budget_breakdown_inner().is_err());
+    assert!(session.get_cost_by_term_inner().is_err());
+    assert!(session.get_overshoot_analysis_inner().is_err());
+
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let result = session.step_inner().unwrap();
+    assert!(result.cpu_delta.is_some() && result.mem_delta.is_some());
+    assert!(session.get_budget_breakdown_inner().is_ok());
+    assert!(session.get_overshoot_analysis_inner().unwrap().is_none());
+}
+
+#[test]
+fn test_step_back_is_opt_in() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    session.step_inner().unwrap();
+    assert_eq!(session.step_back_inner(0).unwrap().steps_back, 0);
+
+    session.set_step_history_size(8);
+    let start = session.get_current_term_id().unwrap();
+    session.step_inner().unwrap();
+    session.step_inner().unwrap();
+    let back = session.step_back_inner(2).unwrap();
+    assert_eq!((back.steps_back, back.step, back.term_id), (2, 1, start));
+    assert_eq!(back.remaining_history, 0);
+}
+
+#[test]
+fn test_run_all_matches_stepping() {
+    let mut dd = engine();
+    let run = dd.run_all_inner().unwrap();
+    let spend = run.redeemers.iter().find(|run| run.redeemer == "Spend:2").unwrap();
+    assert_eq!(spend.script_hash.as_ref().map(|hash| hash.len()), Some(56));
+
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    while matches!(session.step_inner().unwrap().status, crate::SerializableExecutionStatus::Ready) {}
+    let budget = session.get_budget_inner().unwrap();
+    assert_eq!(spend.steps, session.get_step_count());
+    assert_eq!((spend.spent_cpu, spend.spent_mem), (budget.ex_units_spent, budget.memory_units_spent));
+    assert_eq!(run.total_spent_cpu, run.redeemers.iter().map(|run| run.spent_cpu).sum::<i64>());
+}
+
+#[test]
+fn test_run_all_reports_hex_script_hashes() {
+    let settings = r#"{"script_hash_format":"bech32"}"#;
+    let dd = DebuggerEngine::new_with_settings(TX_HEX, UTXO_JSON, PROTOCOL_PARAMS, "mainnet", settings).unwrap();
+    let run = dd.run_all_inner().unwrap();
+    for hash in run.redeemers.iter().filter_map(|run| run.script_hash.as_ref()) {
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
+
+#[test]
+fn test_estimate_ex_units() {
+    let dd = engine();
+    let run = dd.run_all_inner().unwrap();
+    let spend = run.redeemers.iter().find(|run| run.redeemer == "Spend:2").unwrap();
+
+    let estimate = dd.estimate_ex_units_inner("Spend:2", 0).unwrap();
+    assert_eq!((estimate.steps, estimate.mem), (spend.spent_cpu as u64, spend.spent_mem as u64));
+    let with_margin = dd.estimate_ex_units_inner("Spend:2", 10).unwrap();
+    assert_eq!(with_margin.steps, (estimate.steps * 110).div_ceil(100));
+}
+
+#[test]
+fn test_step_n_stops_when_finished() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let batch = session.step_n_inner(u32::MAX).unwrap();
+    assert!(matches!(batch.status, crate::SerializableExecutionStatus::Done { .. }));
+    assert_eq!(batch.steps.len() as u64, session.get_step_count());
+
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let batch = session.step_n_inner(3).unwrap();
+    assert_eq!(batch.steps.len(), 3);
+    assert!(matches!(batch.status, crate::SerializableExecutionStatus::Ready));
+}
+
+#[test]
+fn test_reprice_under_own_cost_model() {
+    let params: crate::protocol_params::ProtocolParameters = serde_json::from_str(PROTOCOL_PARAMS).unwrap();
+    let cost_models = params.cost_models.unwrap();
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    session.set_trace_recording(true);
+    while matches!(session.step_inner().unwrap().status, crate::SerializableExecutionStatus::Ready) {}
+
+    let report = session.reprice_inner(&cost_models).unwrap();
+    assert_eq!((report.cpu, report.mem), (report.original_cpu, report.original_mem));
+    assert_eq!(report.steps, session.get_step_count());
+}
+
+#[test]
+fn test_bytestring_rendering_is_per_session() {
+    let mut dd = engine();
+    let mut rendered = dd.init_debug_session("Spend:2", None).unwrap();
+    let plain = dd.init_debug_session("Spend:2", None).unwrap();
+    rendered.set_bytestring_rendering(false, true);
+
+    assert!(rendered.get_script().unwrap().contains("\"base64\""));
+    assert!(!plain.get_script().unwrap().contains("\"base64\""));
+    assert!(rendered.get_script().unwrap().contains("\"base64\""));
+}
+
+/// Fetches every truncated constant of a full getter response through the lazy
+/// getter its `_root` names and checks it against the same constant left whole
+fn resolve_truncated(
+    session: &crate::debugger_engine::SessionController,
+    truncated: &serde_json::Value,
+    whole: &serde_json::Value,
+) -> usize {
+    use serde_json::Value;
+
+    match truncated {
+        Value::Object(map) if map.get("truncated") == Some(&Value::Bool(true)) => {
+            let mut path = map["_path"].as_array().unwrap().clone();
+            path.push("value".into());
+            let path = Value::Array(path).to_string();
+            let lazy = match map["_root"].as_str().unwrap() {
+                "state" => session.get_machine_state_lazy(path.clone(), false),
+                "env" => session.get_current_env_lazy(path.clone(), false),
+                "context" => session.get_machine_context_lazy(path.clone(), false),
+                root => panic!("unknown root {}", root),
+            };
+            let lazy: Value = payload(&lazy.unwrap());
+            assert_eq!(lazy["constant"]["value"], whole["value"], "{}", path);
+            1
+        }
+        Value::Object(map) => map
+            .iter()
+            .filter(|(key, _)| !key.starts_with('_'))
+            .map(|(key, value)| resolve_truncated(session, value, &whole[key.as_str()]))
+            .sum(),
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| resolve_truncated(session, value, &whole[index]))
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[test]
+fn test_truncated_constants_resolve_through_lazy_getters() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let mut resolved = 0;
+    for _ in 0..2000 {
+        session.set_constant_truncation_threshold(None);
+        let whole = [session.get_machine_state(), session.get_current_env(), session.get_machine_context()];
+        // Every non-empty ByteString and every Integer is truncated
+        session.set_constant_truncation_threshold(Some(0));
+        let truncated = [session.get_machine_state(), session.get_current_env(), session.get_machine_context()];
+        for (truncated, whole) in truncated.into_iter().zip(whole) {
+            let truncated = payload(&truncated.unwrap());
+            resolved += resolve_truncated(&session, &truncated, &payload(&whole.unwrap()));
+        }
+        if !matches!(session.step_inner().unwrap().status, crate::SerializableExecutionStatus::Ready) {
+            break;
+        }
+    }
+    assert!(resolved > 0);
+
+    session.set_constant_truncation_threshold(Some(0));
+    assert!(!session.get_script().unwrap().contains("\"Truncated\""));
+}
+
+#[test]
+fn test_export_program_flat_leaves_out_trace_injections() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let exported = session.export_program_flat(None).unwrap();
+    let term_id = session.get_current_term_id().unwrap();
+    session.inject_trace_at_inner(term_id, "injected".to_string()).unwrap();
+    assert_eq!(session.export_program_flat(None).unwrap(), exported);
+}
+
+#[test]
+fn test_script_chunks_concatenate_to_get_script() {
+    let mut dd = DebuggerEngine::new_with_settings(TX_HEX, UTXO_JSON, PROTOCOL_PARAMS, "mainnet", r#"{"json_envelope":true}"#).unwrap();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let mut script = vec![];
+    loop {
+        let chunk = session.get_script_chunk(script.len() as u32, 4096).unwrap();
+        if chunk.is_empty() {
+            break;
+        }
+        script.extend(chunk);
+    }
+    assert_eq!(String::from_utf8(script).unwrap(), session.get_script().unwrap());
+    assert!(session.get_script_chunk(u32::MAX, 1).is_err());
+}
+
+#[test]
+fn test_json_format_reaches_every_getter() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    session.step_inner().unwrap();
+    let json = session.get_budget().unwrap();
+    assert!(session.set_json_format("cbor").is_err());
+    session.set_json_format("compact").unwrap();
+    assert_eq!(session.get_script().unwrap().into_bytes(), session.export_as("script", Some("compact".to_string())).unwrap());
+    assert_eq!(session.export_as("budget", None).unwrap(), json.into_bytes());
+    let state: serde_json::Value = payload(&session.get_machine_state().unwrap());
+    assert!(state.get("mt").is_some() && state.get("machine_state_type").is_none());
+
+    let cbor = session.export_as("current_env", Some("cbor".to_string())).unwrap();
+    let env: serde_json::Value = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(env, payload::<serde_json::Value>(&String::from_utf8(session.export_as("current_env", None).unwrap()).unwrap()));
+}
+
+#[test]
+fn test_restore_brings_back_the_records_of_the_checkpoint() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let records = |session: &crate::debugger_engine::SessionController| {
+        serde_json::to_string(&(
+            session.get_coverage_inner(),
+            session.get_cost_by_term_inner().unwrap(),
+            session.get_builtin_warnings_inner(),
+            session.get_decision_trace_inner(),
+        ))
+        .unwrap()
+    };
+    for _ in 0..200 {
+        session.step_inner().unwrap();
+    }
+    session.snapshot_inner("mid".to_string()).unwrap();
+    let at_checkpoint = records(&session);
+
+    // Restoring ahead, from fewer steps than the checkpoint was taken at
+    session.reset().unwrap();
+    for _ in 0..20 {
+        session.step_inner().unwrap();
+    }
+    session.restore_inner("mid").unwrap();
+    assert_eq!(records(&session), at_checkpoint);
+
+    // Restoring back, from more steps
+    for _ in 0..100 {
+        session.step_inner().unwrap();
+    }
+    session.restore_inner("mid").unwrap();
+    assert_eq!(records(&session), at_checkpoint);
+}
+
+#[test]
+fn test_cancel_between_runs_is_dropped() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    session.cancel_run();
+    let result = session.run_until_breakpoint_inner(Some(50)).unwrap();
+    assert!(matches!(result.status, crate::SerializableExecutionStatus::Running { steps: 50 }));
+
+    session.get_cancel_token().cancel();
+    let report = session.skip_prelude_inner(Some(50)).unwrap();
+    assert_ne!(report.stopped_at, "Cancelled");
+    assert!(!session.get_cancel_token().is_cancelled());
+}
+
+#[test]
+fn test_pauses_report_typed_reasons() {
+    use crate::{PauseReason, SerializableExecutionStatus};
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    for _ in 0..10 {
+        session.step_inner().unwrap();
+    }
+    let term_id = session.get_current_term_id().unwrap();
+    session.reset().unwrap();
+    session.add_breakpoint(term_id);
+    let result = session.run_until_breakpoint_inner(None).unwrap();
+    assert!(matches!(result.status, SerializableExecutionStatus::Paused { reason: PauseReason::Breakpoint, term_id: id } if id == term_id));
+    assert!(session.get_last_stop_reason().unwrap().contains(r#""kind":"Breakpoint""#));
+    assert!(session.get_last_stop_reason().unwrap().contains(&format!(r#""breakpoint_id":{}"#, term_id)));
+
+    session.reset().unwrap();
+    session.set_breakpoints(vec![]);
+    session.break_on_builtin("UnConstrData".to_string()).unwrap();
+    let result = session.run_until_breakpoint_inner(None).unwrap();
+    match result.status {
+        SerializableExecutionStatus::Paused { reason: PauseReason::Builtin { name }, .. } => assert_eq!(name, "UnConstrData"),
+        status => panic!("expected a builtin pause, got {:?}", status),
+    }
+    assert!(session.get_last_stop_reason().unwrap().contains(r#""kind":"BuiltinBreakpoint""#));
+    assert!(session.get_last_stop_reason().unwrap().contains(r#""description":"builtin UnConstrData""#));
+
+    // A cancelled run was not stopped by a breakpoint
+    session.reset().unwrap();
+    session.remove_builtin_breakpoint("UnConstrData".to_string());
+    let token = session.get_cancel_token();
+    session.set_progress_hook(move |_| token.cancel(), 5);
+    let result = session.run_until_breakpoint_inner(None).unwrap();
+    assert!(matches!(result.status, SerializableExecutionStatus::Paused { reason: PauseReason::Cancelled, .. }));
+    let stop: serde_json::Value = payload(&session.get_last_stop_reason().unwrap());
+    assert_eq!(stop["kind"], "Cancelled");
+    assert!(stop["breakpoint_id"].is_null());
+}
+
+#[test]
+fn test_run_until_term_stops_at_the_target() {
+    use crate::{PauseReason, SerializableExecutionStatus};
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    for _ in 0..10 {
+        session.step_inner().unwrap();
+    }
+    let target = session.get_current_term_id().unwrap();
+    session.reset().unwrap();
+
+    let result = session.run_until_term_inner(target, None).unwrap();
+    assert!(matches!(result.status, SerializableExecutionStatus::Paused { reason: PauseReason::Target, term_id } if term_id == target));
+    assert_eq!(session.get_current_term_id().unwrap(), target);
+    assert!(session.get_step_count() <= 10);
+    assert!(session.get_last_stop_reason().unwrap().contains(r#""kind":"Target""#));
+
+    // The target is only set for that run: the next one goes to the end
+    let result = session.run_until_breakpoint_inner(None).unwrap();
+    assert!(!matches!(result.status, SerializableExecutionStatus::Paused { .. }));
+    assert!(session.run_until_term_inner(-2, None).is_err());
+}
+
+#[test]
+fn test_session_registry() {
+    let mut dd = engine();
+    let first = dd.open_session("Spend:2", None).unwrap();
+    let second = dd.open_session("Spend:2", None).unwrap();
+    assert_ne!(first, second);
+
+    dd.session_mut(&first).unwrap().step_inner().unwrap();
+    assert_eq!(dd.session(&first).unwrap().get_step_count(), 1);
+    assert_eq!(dd.session(&second).unwrap().get_step_count(), 0);
+    let info: serde_json::Value = payload(&dd.get_session(&first).unwrap());
+    assert_eq!((info["session_id"].as_str(), info["step_count"].as_u64()), (Some(first.as_str()), Some(1)));
+
+    let listed: Vec<serde_json::Value> = payload(&dd.list_sessions().unwrap());
+    assert_eq!(listed.len(), 2);
+
+    let mut taken = dd.take_session(&second).unwrap();
+    assert!(dd.get_session(&second).is_err());
+    taken.step_inner().unwrap();
+    assert_eq!(dd.store_session(taken), second);
+    assert_eq!(dd.session(&second).unwrap().get_step_count(), 1);
+
+    assert!(dd.close_session(&first));
+    assert!(!dd.close_session(&first));
+    assert!(dd.session(&first).is_none());
+    assert!(dd.take_session(&first).is_err());
+}
+
+#[test]
+fn test_term_dot_highlights_returned_values() {
+    use crate::{SerializableMachineState, SerializableValue};
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let mut returns_checked = 0;
+    for _ in 0..300 {
+        session.step_inner().unwrap();
+        let highlighted = |term_id: i32| {
+            let dot = session.export_term_dot().unwrap();
+            dot.contains(&format!("  t{} [label=", term_id))
+                && dot.lines().filter(|line| line.contains("fillcolor")).all(|line| line.starts_with(&format!("  t{} ", term_id)))
+        };
+        match session.get_machine_state_inner().unwrap() {
+            SerializableMachineState::Return { value: SerializableValue::Lambda { term_id, .. }, .. }
+            | SerializableMachineState::Return { value: SerializableValue::Delay { term_id, .. }, .. } => {
+                assert!(highlighted(term_id));
+                assert_eq!(session.export_term_dot().unwrap().matches("fillcolor").count(), 1);
+                returns_checked += 1;
+            }
+            SerializableMachineState::Compute { .. } => {
+                assert!(highlighted(session.get_current_term_id().unwrap()));
+            }
+            _ => {}
+        }
+    }
+    assert!(returns_checked > 0);
+}
+
+#[test]
+fn test_context_frames_carry_ids_and_summaries() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let mut previous = session.get_machine_context_inner().unwrap();
+    let mut pushes_checked = 0;
+    for _ in 0..300 {
+        session.step_inner().unwrap();
+        let frames = session.get_machine_context_inner().unwrap();
+        let call_stack = session.get_call_stack_inner();
+        assert_eq!(frames.len(), call_stack.len());
+        for (depth, (frame, call)) in frames.iter().zip(&call_stack).enumerate() {
+            assert_eq!((frame.depth, frame.frame_id), (depth, frames.len() - 1 - depth));
+            assert_eq!((&frame.kind, &frame.summary, frame.term_id), (&call.frame_type, &call.description, call.term_id));
+        }
+        // A pushed frame leaves the ids of the frames under it as they were
+        if frames.len() == previous.len() + 1 {
+            for (frame, before) in frames[1..].iter().zip(&previous) {
+                assert_eq!((frame.frame_id, &frame.kind, &frame.summary), (before.frame_id, &before.kind, &before.summary));
+            }
+            pushes_checked += 1;
+        }
+        previous = frames;
+    }
+    assert!(pushes_checked > 0);
+
+    let json: serde_json::Value = payload(&session.get_machine_context().unwrap());
+    let frame = &json.as_array().unwrap()[0];
+    for field in ["frame_id", "depth", "kind", "summary", "term_id"] {
+        assert!(frame.get(field).is_some(), "frame without {}", field);
+    }
+    assert_eq!(frame["payload"]["context_type"], frame["kind"]);
+}
+
+#[test]
+fn test_skip_prelude_stops_where_the_script_context_is_used() {
+    use crate::{PauseReason, StopReasonKind};
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let report = session.skip_prelude_inner(None).unwrap();
+    // A V2 script has no Case terms, so its prelude ends at a builtin
+    assert_eq!(report.stopped_at, "ContextBuiltin");
+    assert!(report.builtin.is_some());
+    assert_eq!(report.steps, session.get_step_count());
+    assert!(report.cpu > 0 && report.mem > 0 && report.cpu_percent > 0.0);
+    let stop: crate::StopReason = payload(&session.get_last_stop_reason().unwrap());
+    assert!(matches!(stop.kind, StopReasonKind::Target));
+    assert_eq!(stop.description.as_deref(), Some(PauseReason::Prelude.to_string().as_str()));
+
+    // Skipping again from there stops right away
+    assert_eq!(session.skip_prelude_inner(None).unwrap().steps, 0);
+
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let report = session.skip_prelude_inner(Some(5)).unwrap();
+    assert_eq!((report.stopped_at.as_str(), report.steps), ("StepLimit", 5));
+}
+
+#[test]
+fn test_reset_with_program_keeps_the_session_on_failure() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    let program = session.export_program_flat(None).unwrap();
+    let script_cbor = hex::encode(session.export_program_flat(Some(true)).unwrap());
+    for _ in 0..3 {
+        session.step_inner().unwrap();
+    }
+
+    assert!(session.reset_with_program(Some("not hex".to_string()), None).is_err());
+    assert!(session.reset_with_program(None, Some("[{\"bogus\": 1}]".to_string())).is_err());
+    assert_eq!(session.get_step_count(), 3);
+    assert_eq!(session.export_program_flat(None).unwrap(), program);
+
+    // The exported program has its arguments applied already
+    session.reset_with_program(Some(script_cbor), Some("[]".to_string())).unwrap();
+    assert_eq!(session.get_step_count(), 0);
+    assert_eq!(session.export_program_flat(None).unwrap(), program);
+    session.step_inner().unwrap();
+}
+
+#[test]
+fn test_audit_stops_at_the_step_limit() {
+    use crate::debugger_engine::audit::DeterminismAudit;
+    let dd = engine();
+    let audit: DeterminismAudit = payload(&dd.audit_determinism("Spend:2").unwrap());
+    assert!(audit.deterministic);
+    assert!(matches!(audit.result, crate::SerializableExecutionStatus::Done { .. }));
+
+    let settings = r#"{"step_limit":10}"#;
+    let dd = DebuggerEngine::new_with_settings(TX_HEX, UTXO_JSON, PROTOCOL_PARAMS, "mainnet", settings).unwrap();
+    let audit: DeterminismAudit = payload(&dd.audit_determinism("Spend:2").unwrap());
+    assert_eq!(audit.steps, 10);
+    assert!(matches!(audit.result, crate::SerializableExecutionStatus::StepLimitReached { steps: 10 }));
+}
+
+#[test]
+fn test_script_hashes_follow_the_format_setting() {
+    let settings = r#"{"script_hash_format":"bech32"}"#;
+    let mut dd = DebuggerEngine::new_with_settings(TX_HEX, UTXO_JSON, PROTOCOL_PARAMS, "mainnet", settings).unwrap();
+    let session = dd.init_debug_session("Spend:2", None).unwrap();
+    let bech32 = session.get_script_hash().unwrap();
+    assert!(bech32.starts_with("script1"));
+    assert_eq!(session.get_session_info_inner().unwrap().script_hash, bech32);
+    assert_eq!(session.export_coverage_map_inner().script_hash, bech32);
+    assert!(dd.find_redeemers_by_script(&bech32).unwrap().contains(&"Spend:2".to_string()));
+
+    // A saved session naming its script as script1… loads too
+    let mut saved = session.save_session_inner().unwrap();
+    saved.script_hash = bech32;
+    let mut dd = engine();
+    let mut restored = dd.init_debug_session("Spend:2", None).unwrap();
+    restored.load_saved(&saved).unwrap();
+}
+
+#[test]
+fn test_decision_trace_records_branches_in_order() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    while matches!(session.step_inner().unwrap().status, crate::SerializableExecutionStatus::Ready) {}
+    let trace = session.get_decision_trace_inner();
+    assert!(!trace.decisions.is_empty() && !trace.truncated);
+    assert!(trace.decisions.windows(2).all(|pair| pair[0].step < pair[1].step));
+    // A V2 script decides through builtins only
+    assert!(trace.decisions.iter().all(|decision| decision.kind != "Case"));
+}
+
+#[test]
+fn test_env_pins_outside_compute_states() {
+    let mut dd = engine();
+    let mut session = dd.init_debug_session("Spend:2", None).unwrap();
+    session.pin_env_snapshot("start".to_string()).unwrap();
+
+    // In a Return state the pin holds the env of the frame the value returns to
+    while session.get_current_term_id().unwrap() != -1 {
+        session.step_inner().unwrap();
+    }
+    session.pin_env_snapshot("returning".to_string()).unwrap();
+    let comparison = session.compare_with_pin_inner("start").unwrap();
+    assert_eq!(comparison.pinned_size, 0);
+
+    while matches!(session.step_inner().unwrap().status, crate::SerializableExecutionStatus::Ready) {}
+    assert!(session.pin_env_snapshot("done".to_string()).is_err());
+    assert!(session.compare_with_pin_inner("start").is_err());
+    assert_eq!(session.get_env_pins(), vec!["returning".to_string(), "start".to_string()]);
+}
+
+#[test]
+fn test_mint_policies_stay_out_of_the_script_context() {
+    let mut dd = engine();
+    let policies: Vec<crate::script_context::SerializableMintPolicy> = payload(&dd.get_mint_policies().unwrap());
+    assert!(policies.iter().all(|policy| policy.redeemer.is_some() != (policy.script == "native")));
+
+    let session = dd.init_debug_session("Spend:2", None).unwrap();
+    let context = serde_json::to_string(&session.get_tx_script_context_inner().unwrap()).unwrap();
+    assert!(!context.contains("mint_policies"));
+}
+
+#[test]
+fn test_construction_reports_each_stage() {
+    use crate::debugger_engine::construction::{ConstructionReport, StageStatus};
+    let dd = engine();
+    let report: ConstructionReport = payload(&dd.get_construction_report().unwrap());
+    assert!(report.completed);
+    let stages: Vec<&str> = report.stages.iter().map(|stage| stage.stage.as_str()).collect();
+    assert_eq!(stages[..4], ["decode_hex", "decode_transaction", "convert_utxos", "find_scripts"]);
+    assert!(stages.contains(&"build_tx_info_v2"));
+
+    let report = DebuggerEngine::diagnose_construction("zz", UTXO_JSON, PROTOCOL_PARAMS, "mainnet").unwrap();
+    let report: ConstructionReport = payload(&report);
+    assert!(!report.completed);
+    let last = report.stages.last().unwrap();
+    assert_eq!((last.stage.as_str(), &last.status), ("decode_hex", &StageStatus::Failed));
+}
+
+#[test]
+fn test_context_cache_hits_misses_and_invalidation() {
+    use crate::debugger_engine::context_cache::ContextCacheStats;
+    let mut dd = engine();
+    let stats = |dd: &DebuggerEngine| -> ContextCacheStats { payload(&dd.get_context_cache_stats().unwrap()) };
+    let counts = |stats: ContextCacheStats| (stats.tx_info_clones, stats.hits, stats.cached_redeemers);
+    assert_eq!(counts(stats(&dd)), (0, 0, 0));
+
+    // The first session builds the context, the next ones reuse it
+    let first = dd.init_debug_session("Spend:2", None).unwrap();
+    assert_eq!(counts(stats(&dd)), (1, 0, 1));
+    let second = dd.init_debug_session("Spend:2", None).unwrap();
+    assert_eq!(counts(stats(&dd)), (1, 1, 1));
+    assert_eq!(
+        serde_json::to_string(&first.get_tx_script_context_inner().unwrap()).unwrap(),
+        serde_json::to_string(&second.get_tx_script_context_inner().unwrap()).unwrap()
+    );
+
+    // Clearing drops the context, so the next session builds it again
+    dd.clear_program_cache();
+    assert_eq!(counts(stats(&dd)), (1, 1, 0));
+    dd.init_debug_session("Spend:2", None).unwrap();
+    assert_eq!(counts(stats(&dd)), (2, 1, 1));
+}
+
+/// A V3 script that ignores its context, CBOR wrapped as in a transaction witness
+fn quick_run_script() -> String {
+    use crate::debugger_engine::fixture::{cbor_wrap, program_flat};
+    let program = crate::debugger_engine::sandbox::parse_expression("(lam ctx (con unit ()))", (1, 1, 0)).unwrap();
+    hex::encode(cbor_wrap(&program_flat(&program).unwrap()).unwrap())
+}
+
+const QUICK_RUN_REDEEMER: &str = r#"{"type":"Constr","tag":121,"any_constructor":null,"fields":[]}"#;
+
+#[test]
+fn test_debug_minting_policy_mints_and_burns_under_the_script_hash() {
+    use crate::debugger_engine::quick_run::{debug_minting_policy, script_hash};
+    use crate::{SerializableScriptContext, SerializableScriptInfo, SerializableTxInfo};
+    let script = quick_run_script();
+    let policy_id = hex::encode(script_hash(&hex::decode(&script).unwrap(), 3));
+    let spec = |assets: &str| format!(r#"{{"assets":{},"protocolParams":{}}}"#, assets, PROTOCOL_PARAMS);
+
+    let session = debug_minting_policy(&script, QUICK_RUN_REDEEMER, &spec(r#"{"746f6b656e":5,"6275726e":-3}"#)).unwrap();
+    assert_eq!(session.get_session_info_inner().unwrap().redeemer, "Mint:0");
+    assert_eq!(session.script_hash_hex(), policy_id);
+    let SerializableScriptContext::V3 { tx_info, purpose, .. } = session.get_tx_script_context_inner().unwrap() else {
+        panic!("expected a V3 script context");
+    };
+    let SerializableScriptInfo::Minting { policy_id: purpose_policy } = *purpose else {
+        panic!("expected a minting purpose");
+    };
+    assert_eq!(purpose_policy, policy_id);
+    let SerializableTxInfo::V3(tx_info) = *tx_info else {
+        panic!("expected a V3 tx info");
+    };
+    assert_eq!(tx_info.mint.mint_value.len(), 1);
+    let minted = &tx_info.mint.mint_value[0];
+    assert_eq!(minted.policy_id, policy_id);
+    let tokens: Vec<(&str, i64)> = minted.tokens.iter().map(|token| (token.asset_name.as_str(), token.quantity)).collect();
+    assert_eq!(tokens, [("6275726e", -3), ("746f6b656e", 5)]);
+
+    // A mint needs at least one asset, none of them with a zero quantity
+    assert!(debug_minting_policy(&script, QUICK_RUN_REDEEMER, &spec("{}")).is_err());
+    assert!(debug_minting_policy(&script, QUICK_RUN_REDEEMER, &spec(r#"{"746f6b656e":0}"#)).is_err());
+}
+
+#[test]
+fn test_debug_withdrawal_validator_withdraws_from_the_script_reward_account() {
+    use crate::debugger_engine::quick_run::{debug_withdrawal_validator, script_hash};
+    use crate::{SerializableScriptContext, SerializableTxInfo};
+    use pallas_addresses::Address;
+    let script = quick_run_script();
+    let hash = script_hash(&hex::decode(&script).unwrap(), 3);
+
+    // Mainnet with the default amount, then a testnet with an explicit one
+    for (network, amount, header) in [("mainnet", None, 0xf1u8), ("preprod", Some(1_500_000u64), 0xf0u8)] {
+        let amount_field = amount.map(|amount| format!(r#""amount":{},"#, amount)).unwrap_or_default();
+        let spec = format!(r#"{{{}"network":"{}","protocolParams":{}}}"#, amount_field, network, PROTOCOL_PARAMS);
+        let session = debug_withdrawal_validator(&script, QUICK_RUN_REDEEMER, &spec).unwrap();
+        assert_eq!(session.get_session_info_inner().unwrap().redeemer, "Reward:0");
+
+        let SerializableScriptContext::V3 { tx_info, .. } = session.get_tx_script_context_inner().unwrap() else {
+            panic!("expected a V3 script context");
+        };
+        let SerializableTxInfo::V3(tx_info) = *tx_info else {
+            panic!("expected a V3 tx info");
+        };
+        let mut reward_account = vec![header];
+        reward_account.extend_from_slice(&hash);
+        let withdrawals: Vec<(Vec<u8>, u64)> = tx_info
+            .withdrawals
+            .iter()
+            .map(|(address, coin)| (Address::from_bech32(address).unwrap().to_vec(), *coin))
+            .collect();
+        assert_eq!(withdrawals, [(reward_account, amount.unwrap_or(0))], "{}", network);
+    }
+}
+
+#[test]
+fn test_responses_are_enveloped_unless_turned_off() {
+    let mut dd = engine();
+    let session = dd.init_debug_session("Spend:2", None).unwrap();
+    let budget: crate::budget::SerializableBudget = payload(&session.get_budget().unwrap());
+    assert_eq!(budget.ex_units_spent, 0);
+    let _: Vec<serde_json::Value> = payload(&dd.list_sessions().unwrap());
+
+    // Free functions have no settings and always use the envelope
+    let forms: serde_json::Value = payload(&crate::script_hash_forms("1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209").unwrap());
+    assert!(forms["bech32"].as_str().unwrap().starts_with("script1"));
+
+    let mut dd = DebuggerEngine::new_with_settings(TX_HEX, UTXO_JSON, PROTOCOL_PARAMS, "mainnet", r#"{"json_envelope":false}"#).unwrap();
+    let session = dd.init_debug_session("Spend:2", None).unwrap();
+    let budget: serde_json::Value = serde_json::from_str(&session.get_budget().unwrap()).unwrap();
+    assert!(budget.get("payload").is_none() && budget.get("schema_version").is_none());
+}
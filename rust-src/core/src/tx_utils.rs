@@ -4,7 +4,7 @@ use pallas_primitives::{conway::{MintedTx, PseudoScript, PseudoTransactionOutput
 use hex;
 use serde::{Deserialize, Serialize};
 use uplc::Fragment;
-use crate::wasm_tools::wasm_bindgen;
+use crate::wasm_tools::{enveloped_json, wasm_bindgen};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -93,5 +93,5 @@ pub fn get_required_utxos(tx_hex: &str) -> Result<String, JsError> {
         }
     }
 
-    Ok(serde_json::to_string(&utxo_refs).map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?)
+    Ok(enveloped_json(&utxo_refs).map_err(|e| DebuggerError::TransactionParseError(e.to_string()))?)
 }
\ No newline at end of file
@@ -30,6 +30,8 @@ pub enum UtxoConversionError {
     InvalidReferenceScript(String),
     #[error("Multiasset conversion error: {0}")]
     MultiassetConversion(String),
+    #[error("Invalid UTxO snapshot: {0}")]
+    InvalidSnapshot(String),
 }
 
 /// Script type for reference scripts
@@ -0,0 +1,338 @@
+use num_bigint::BigInt as NumBigInt;
+use pallas_codec::minicbor;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::debugger_engine::DebuggerError;
+use crate::plutus_data::{SerializableBigInt, SerializablePlutusData};
+use crate::utxo::{ReferenceScript, ScriptType, UtxoConversionError, UtxoOutput, UtxoValue};
+use crate::wasm_tools::{envelope_json, wasm_bindgen, JsError};
+
+/// Length in hex of a policy id, used to split concatenated asset units
+const POLICY_ID_HEX_LENGTH: usize = 56;
+
+fn snapshot_error(key: &str, message: impl std::fmt::Display) -> UtxoConversionError {
+    UtxoConversionError::InvalidSnapshot(format!("{}: {}", key, message))
+}
+
+/// Asset id (`policy.asset`) of a flat unit: either already dotted, or the hex
+/// policy id followed by the hex asset name
+fn flat_asset_id(key: &str, unit: &str) -> Result<String, UtxoConversionError> {
+    if unit.contains('.') {
+        return Ok(unit.to_string());
+    }
+    if unit.len() < POLICY_ID_HEX_LENGTH || !unit.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(snapshot_error(key, format!("invalid asset unit {}", unit)));
+    }
+    // All hex, so every byte is a character boundary
+    let (policy_id, asset_name) = unit.split_at(POLICY_ID_HEX_LENGTH);
+    Ok(format!("{}.{}", policy_id, asset_name))
+}
+
+fn amount_string(amount: &Value) -> Option<String> {
+    match amount {
+        Value::Number(number) => number.as_u64().map(|amount| amount.to_string()),
+        Value::String(amount) => amount.parse::<u64>().ok().map(|amount| amount.to_string()),
+        _ => None,
+    }
+}
+
+/// Converts a snapshot value: lovelace as a number, or an object with "lovelace"
+/// and either nested `policy -> asset name -> quantity` maps (Hydra, cardano-cli)
+/// or flat units (`policy.asset` or policy and asset name concatenated)
+fn snapshot_value(key: &str, value: &Value) -> Result<UtxoValue, UtxoConversionError> {
+    if let Some(lovelace) = amount_string(value) {
+        return Ok(UtxoValue::new_lovelace_only(lovelace));
+    }
+    let Value::Object(entries) = value else {
+        return Err(snapshot_error(key, "value must be a number or an object"));
+    };
+
+    let mut lovelace = "0".to_string();
+    let mut assets = HashMap::new();
+    for (unit, amount) in entries {
+        if unit == "lovelace" {
+            lovelace = amount_string(amount).ok_or_else(|| snapshot_error(key, format!("invalid lovelace {}", amount)))?;
+            continue;
+        }
+        match amount {
+            Value::Object(tokens) => {
+                for (asset_name, quantity) in tokens {
+                    let quantity = amount_string(quantity)
+                        .ok_or_else(|| snapshot_error(key, format!("invalid quantity {}", quantity)))?;
+                    assets.insert(format!("{}.{}", unit, asset_name), quantity);
+                }
+            }
+            quantity => {
+                let quantity = amount_string(quantity)
+                    .ok_or_else(|| snapshot_error(key, format!("invalid quantity {}", quantity)))?;
+                assets.insert(flat_asset_id(key, unit)?, quantity);
+            }
+        }
+    }
+    Ok(UtxoValue::new_with_assets(lovelace, assets))
+}
+
+/// CBOR tag of a data constructor index
+fn constr_tag(index: u64) -> (u64, Option<u64>) {
+    match index {
+        0..=6 => (121 + index, None),
+        7..=127 => (1280 + index - 7, None),
+        _ => (102, Some(index)),
+    }
+}
+
+/// Integer of detailed schema script data, as a JSON integer or, beyond 64 bits,
+/// a decimal string; JSON numbers that aren't exact integers are rejected
+fn script_data_int(int: &Value) -> Result<SerializableBigInt, String> {
+    let int: NumBigInt = match int {
+        Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+            (Some(int), _) => int.into(),
+            (None, Some(int)) => int.into(),
+            _ => return Err(format!("Invalid int {}: integers beyond 64 bits must be given as strings", int)),
+        },
+        Value::String(int) => int.parse().map_err(|e| format!("Invalid int {}: {}", int, e))?,
+        _ => return Err(format!("Invalid int {}", int)),
+    };
+    // CBOR integers hold -2^64 to 2^64 - 1; beyond that they are tagged bignums,
+    // negative ones encoding -1 - n
+    let cbor_int_max = NumBigInt::from(u64::MAX);
+    Ok(if int > cbor_int_max {
+        SerializableBigInt::BigUInt(int.to_string())
+    } else if int < -(cbor_int_max.clone() + 1) {
+        SerializableBigInt::BigNInt((-int - 1).to_string())
+    } else {
+        SerializableBigInt::Int(int.to_string())
+    })
+}
+
+/// Converts cardano-api "detailed schema" script data (`constructor`/`fields`,
+/// `map` of `k`/`v`, `list`, `int`, `bytes`)
+fn detailed_script_data(data: &Value) -> Result<SerializablePlutusData, String> {
+    let Value::Object(object) = data else {
+        return Err(format!("Invalid script data {}", data));
+    };
+    let items = |items: &Value| -> Result<Vec<SerializablePlutusData>, String> {
+        items
+            .as_array()
+            .ok_or_else(|| format!("Expected a list in {}", data))?
+            .iter()
+            .map(detailed_script_data)
+            .collect()
+    };
+
+    if let Some(constructor) = object.get("constructor") {
+        let index = constructor.as_u64().ok_or_else(|| format!("Invalid constructor {}", constructor))?;
+        let (tag, any_constructor) = constr_tag(index);
+        let fields = items(object.get("fields").unwrap_or(&Value::Array(vec![])))?;
+        return Ok(SerializablePlutusData::Constr { tag, any_constructor, fields });
+    }
+    if let Some(pairs) = object.get("map") {
+        let pairs = pairs
+            .as_array()
+            .ok_or_else(|| format!("Expected a list in {}", data))?
+            .iter()
+            .map(|pair| {
+                let field = |name: &str| pair.get(name).ok_or_else(|| format!("Missing {} in {}", name, pair));
+                Ok((detailed_script_data(field("k")?)?, detailed_script_data(field("v")?)?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        return Ok(SerializablePlutusData::map(pairs));
+    }
+    if let Some(values) = object.get("list") {
+        return Ok(SerializablePlutusData::array(items(values)?));
+    }
+    if let Some(int) = object.get("int") {
+        return script_data_int(int).map(SerializablePlutusData::BigInt);
+    }
+    if let Some(Value::String(bytes)) = object.get("bytes") {
+        return SerializablePlutusData::bytes_from_hex(bytes).map_err(|e| format!("Invalid bytes {}: {}", bytes, e));
+    }
+    Err(format!("Invalid script data {}", data))
+}
+
+fn string_field<'a>(output: &'a Map<String, Value>, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| output.get(*name).and_then(Value::as_str))
+}
+
+/// Inline datum CBOR hex from `inlineDatumRaw`, `inlineDatum` (hex or detailed
+/// schema JSON) or, when the output carries no datum hash, `datum`
+fn snapshot_inline_datum(key: &str, output: &Map<String, Value>, has_datum_hash: bool) -> Result<Option<String>, UtxoConversionError> {
+    if let Some(raw) = string_field(output, &["inlineDatumRaw", "inlineDatumCbor"]) {
+        return Ok(Some(raw.to_string()));
+    }
+    match output.get("inlineDatum") {
+        Some(Value::String(cbor)) => return Ok(Some(cbor.clone())),
+        Some(data @ Value::Object(_)) => {
+            let data = detailed_script_data(data)
+                .and_then(|data| data.to_pallas())
+                .map_err(|e| snapshot_error(key, e))?;
+            let cbor = minicbor::to_vec(&data).map_err(|e| snapshot_error(key, e))?;
+            return Ok(Some(hex::encode(cbor)));
+        }
+        _ => {}
+    }
+    // Hydra's `datum` is the datum a `datumhash` refers to; emulators use it for inline datums
+    Ok(string_field(output, &["datum"]).filter(|_| !has_datum_hash).map(str::to_string))
+}
+
+fn script_type(key: &str, name: &str) -> Result<ScriptType, UtxoConversionError> {
+    if name.contains("Simple") || name.contains("Native") || name.contains("Timelock") {
+        Ok(ScriptType::NativeScript)
+    } else if name.contains("V3") {
+        Ok(ScriptType::PlutusV3)
+    } else if name.contains("V2") {
+        Ok(ScriptType::PlutusV2)
+    } else if name.contains("V1") || name == "PlutusScript" {
+        Ok(ScriptType::PlutusV1)
+    } else {
+        Err(snapshot_error(key, format!("unknown script type {}", name)))
+    }
+}
+
+/// Reference script either as a text envelope (`{"script": {"cborHex", "type"}}`,
+/// as Hydra and cardano-cli write it) or as `{"type", "script"}` with the script hex
+fn snapshot_reference_script(key: &str, script: &Value) -> Result<Option<ReferenceScript>, UtxoConversionError> {
+    let Value::Object(script) = script else {
+        return Ok(None);
+    };
+    match script.get("script") {
+        Some(Value::Object(envelope)) => {
+            let cbor_hex = string_field(envelope, &["cborHex"]).ok_or_else(|| snapshot_error(key, "script without cborHex"))?;
+            let r#type = script_type(key, string_field(envelope, &["type"]).unwrap_or_default())?;
+            // Envelopes wrap Plutus scripts in one more CBOR byte string than the ledger stores
+            let script = if r#type == ScriptType::NativeScript {
+                cbor_hex.to_string()
+            } else {
+                let bytes = hex::decode(cbor_hex).map_err(|e| snapshot_error(key, e))?;
+                let inner = minicbor::Decoder::new(&bytes).bytes().map_err(|e| snapshot_error(key, e))?;
+                hex::encode(inner)
+            };
+            Ok(Some(ReferenceScript { r#type, script }))
+        }
+        Some(Value::String(script_hex)) => Ok(Some(ReferenceScript {
+            r#type: script_type(key, string_field(script, &["type"]).unwrap_or_default())?,
+            script: script_hex.clone(),
+        })),
+        _ => Err(snapshot_error(key, "reference script without script")),
+    }
+}
+
+fn snapshot_output(key: &str, output: &Value) -> Result<UtxoOutput, UtxoConversionError> {
+    let (tx_hash, output_index) = key
+        .split_once('#')
+        .ok_or_else(|| snapshot_error(key, "expected a \"txid#ix\" key"))?;
+    let output_index = output_index.parse::<u32>().map_err(|e| snapshot_error(key, e))?;
+    let Value::Object(output) = output else {
+        return Err(snapshot_error(key, "output must be an object"));
+    };
+
+    let address = string_field(output, &["address"]).ok_or_else(|| snapshot_error(key, "missing address"))?;
+    let value = snapshot_value(key, output.get("value").ok_or_else(|| snapshot_error(key, "missing value"))?)?;
+    let datum_hash = string_field(output, &["datumhash", "datumHash"]).map(str::to_string);
+    let inline_datum = snapshot_inline_datum(key, output, datum_hash.is_some())?;
+    let reference_script = match output.get("referenceScript").or_else(|| output.get("scriptRef")) {
+        Some(script) => snapshot_reference_script(key, script)?,
+        None => None,
+    };
+
+    Ok(UtxoOutput {
+        tx_hash: tx_hash.to_string(),
+        output_index,
+        address: address.to_string(),
+        value,
+        datum_hash,
+        inline_datum,
+        reference_script,
+    })
+}
+
+/// Converts a UTxO snapshot as served by Hydra heads and written by common
+/// emulators (`{"<txid>#<ix>": {"address", "value", "datum", "referenceScript", ...}}`)
+/// into UtxoOutputs, ordered by transaction hash and output index
+pub fn utxos_from_snapshot(snapshot_json: &str) -> Result<Vec<UtxoOutput>, UtxoConversionError> {
+    let snapshot: Map<String, Value> = serde_json::from_str(snapshot_json)
+        .map_err(|e| UtxoConversionError::InvalidSnapshot(e.to_string()))?;
+    let mut utxos = snapshot
+        .iter()
+        .map(|(key, output)| snapshot_output(key, output))
+        .collect::<Result<Vec<_>, _>>()?;
+    utxos.sort_by(|a, b| (&a.tx_hash, a.output_index).cmp(&(&b.tx_hash, b.output_index)));
+    Ok(utxos)
+}
+
+/// Converts a Hydra or emulator UTxO snapshot into the JSON UtxoOutput array the
+/// DebuggerEngine takes, as the payload of the schema version envelope
+#[wasm_bindgen]
+pub fn convert_utxo_snapshot(snapshot_json: &str) -> Result<String, JsError> {
+    let utxos = utxos_from_snapshot(snapshot_json)?;
+    let json = UtxoOutput::vec_to_json(&utxos).map_err(|e| DebuggerError::MachineError(e.to_string()))?;
+    Ok(envelope_json(&json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hydra_snapshot() {
+        let policy = "ab".repeat(28);
+        let snapshot = format!(
+            r#"{{
+                "{tx}#1": {{
+                    "address": "addr_test1vz",
+                    "value": {{ "lovelace": 2000000, "{policy}": {{ "74657374": 3 }} }},
+                    "inlineDatum": {{ "constructor": 0, "fields": [{{ "int": 42 }}] }}
+                }},
+                "{tx}#0": {{
+                    "address": "addr_test1vz",
+                    "value": {{ "lovelace": 1000000 }},
+                    "datumhash": "{hash}",
+                    "datum": "d87980"
+                }}
+            }}"#,
+            tx = "00".repeat(32),
+            policy = policy,
+            hash = "11".repeat(32),
+        );
+
+        let utxos = utxos_from_snapshot(&snapshot).unwrap();
+        assert_eq!(utxos[0].output_index, 0);
+        assert_eq!(utxos[0].inline_datum, None);
+        assert_eq!(utxos[1].value.assets.as_ref().unwrap()[&format!("{}.74657374", policy)], "3");
+        // Constr 0 [42], indefinite fields
+        assert_eq!(utxos[1].inline_datum.as_deref(), Some("d8799f182aff"));
+    }
+
+    #[test]
+    fn test_emulator_units() {
+        let mut entries = Map::new();
+        entries.insert("lovelace".to_string(), Value::from("5"));
+        entries.insert(format!("{}74", "ab".repeat(28)), Value::from(1));
+        let value = snapshot_value("k", &Value::Object(entries)).unwrap();
+        assert_eq!(value.lovelace, "5");
+        assert!(value.assets.unwrap().contains_key(&format!("{}.74", "ab".repeat(28))));
+
+        for unit in ["ab".to_string(), format!("{}\u{e9}", "a".repeat(55)), format!("{}zz", "ab".repeat(28))] {
+            let mut entries = Map::new();
+            entries.insert(unit, Value::from(1));
+            assert!(snapshot_value("k", &Value::Object(entries)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_script_data_ints() {
+        let int = |json: &str| match script_data_int(&serde_json::from_str(json).unwrap()).unwrap() {
+            SerializableBigInt::Int(int) => ("Int", int),
+            SerializableBigInt::BigUInt(int) => ("BigUInt", int),
+            SerializableBigInt::BigNInt(int) => ("BigNInt", int),
+        };
+        assert_eq!(int("-42"), ("Int", "-42".to_string()));
+        assert_eq!(int("18446744073709551615"), ("Int", u64::MAX.to_string()));
+        assert_eq!(int(r#""18446744073709551616""#), ("BigUInt", "18446744073709551616".to_string()));
+        assert_eq!(int(r#""-18446744073709551617""#), ("BigNInt", "18446744073709551616".to_string()));
+        let invalid = |json: &str| script_data_int(&serde_json::from_str(json).unwrap()).is_err();
+        assert!(invalid("1.5"));
+        assert!(invalid("1e30"));
+    }
+}
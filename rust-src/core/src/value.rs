@@ -7,7 +7,7 @@ use schemars::JsonSchema;
 use std::{rc::Rc, collections::HashSet};
 use crate::serializer::{
     SerializableConstant, EitherTermOrId, term_to_either_term_or_id,
-    SerializableConstantLazy, EitherTermOrIdLazy, SerializableTermLazy
+    SerializableConstantLazy, EitherTermOrIdLazy, SerializableTermLazy, constant_content_hash
 };
 use crate::lazy_loading::{LazyLoadable, LazyLoadableConstant, LazyLoadableTermOrId, LazyLoadableEnv, LazyLoadableValue, LazyLoadableBuiltinRuntime, LazyLoadableTerm, LazyLoadableData, SupportsLazyLoading, LazyLoadConfig, PathSegment, NavigablePath, NavigationResult};
 
@@ -158,6 +158,25 @@ impl SerializableValue {
     }
 }
 
+impl SerializableValue {
+    /// Convert back to a UPLC Value (inverse of `from_uplc_value`). Only constants
+    /// (including lists, pairs and data) and SOP constructors of them can be built;
+    /// closures and partially applied builtins need terms and envs JSON doesn't carry.
+    pub fn to_uplc_value(&self) -> Result<uplc::machine::value::Value, String> {
+        match self {
+            SerializableValue::Con { constant } => Ok(uplc::machine::value::Value::Con(Rc::new(constant.to_uplc_constant()?))),
+            SerializableValue::Constr { tag, fields, term_id } => Ok(uplc::machine::value::Value::Constr {
+                tag: *tag,
+                fields: fields.iter().map(|field| field.to_uplc_value()).collect::<Result<_, _>>()?,
+                term_id: *term_id as _,
+            }),
+            SerializableValue::Delay { .. } | SerializableValue::Lambda { .. } | SerializableValue::Builtin { .. } => {
+                Err("Only Con and Constr values can be built from JSON".to_string())
+            }
+        }
+    }
+}
+
 impl SerializableEnv {
     pub fn from_uplc_env(env: &Rc<Vec<uplc::machine::value::Value>>) -> Self {
         Self::from_uplc_env_with_ids(env, &HashSet::new())
@@ -193,6 +212,13 @@ pub fn value_to_json(value: &uplc::machine::value::Value) -> Result<String, serd
     serde_json::to_string_pretty(&serializable_value)
 }
 
+/// Build a UPLC Value from SerializableValue JSON (inverse of `value_to_json`)
+pub fn value_from_json(json: &str) -> Result<uplc::machine::value::Value, String> {
+    serde_json::from_str::<SerializableValue>(json)
+        .map_err(|e| format!("Invalid value: {}", e))?
+        .to_uplc_value()
+}
+
 /// Convert a UPLC Value to JSON Value
 pub fn value_to_json_value(value: &uplc::machine::value::Value) -> Result<serde_json::Value, serde_json::Error> {
     let serializable_value = SerializableValue::from_uplc_value(value);
@@ -219,6 +245,25 @@ mod tests {
         assert!(json.contains("\"value\": \"42\""));
     }
 
+    #[test]
+    fn test_value_from_json_round_trip() {
+        let pair = Constant::ProtoPair(
+            uplc::ast::Type::Integer,
+            uplc::ast::Type::Data,
+            Rc::new(Constant::Integer((-7).into())),
+            Rc::new(Constant::Data(uplc::PlutusData::BoundedBytes(vec![1, 2].into()))),
+        );
+        let list = Constant::ProtoList(uplc::ast::Type::ByteString, vec![Constant::ByteString(vec![0xab])]);
+        let value = Value::Constr {
+            tag: 1,
+            fields: vec![Value::Con(Rc::new(pair)), Value::Con(Rc::new(list))],
+            term_id: 9,
+        };
+
+        let json = value_to_json(&value).unwrap();
+        assert_eq!(value_to_json(&value_from_json(&json).unwrap()).unwrap(), json);
+    }
+
     #[test]
     fn test_constr_value_serialization() {
         let field1 = Value::Con(Rc::new(Constant::Integer(1.into())));
@@ -273,6 +318,55 @@ mod tests {
         assert!(json.contains("\"args\": []"));
     }
 
+    #[test]
+    fn test_nested_builtin_runtime_navigation() {
+        use uplc::{builtins::DefaultFunction, machine::runtime::BuiltinRuntime};
+
+        let builtin = |arg: Value| {
+            let mut runtime = BuiltinRuntime::new(DefaultFunction::AppendByteString);
+            runtime.args.push(arg);
+            Value::Builtin {
+                fun: DefaultFunction::AppendByteString,
+                runtime,
+                term_id: 1,
+            }
+        };
+        let leaf = Value::Con(Rc::new(Constant::Integer(7.into())));
+        let outer = builtin(builtin(builtin(leaf)));
+        let segments = |path: &[&str]| -> Vec<PathSegment> {
+            path.iter()
+                .map(|segment| match segment.parse::<usize>() {
+                    Ok(index) => PathSegment::Index(index),
+                    Err(_) => PathSegment::Field(segment.to_string()),
+                })
+                .collect()
+        };
+        let term_ids = HashSet::new();
+
+        let deep = segments(&["runtime", "args", "0", "runtime", "args", "0", "runtime", "args", "0"]);
+        match navigate_value_to_any(&outer, &deep, &term_ids, false) {
+            NavigationResult::Found(json) => assert_eq!(json["value_type"], "Con"),
+            other => panic!("unexpected navigation result: {:?}", other),
+        }
+
+        let runtime = segments(&["runtime", "args", "0", "runtime"]);
+        match navigate_value_to_any(&outer, &runtime, &term_ids, false) {
+            NavigationResult::Found(json) => {
+                assert_eq!(json["arity"], 2);
+                assert_eq!(json["args"].as_array().map(Vec::len), Some(1));
+            }
+            other => panic!("unexpected navigation result: {:?}", other),
+        }
+
+        // The lazy serializer loads every level the path goes through
+        let config = LazyLoadConfig {
+            path: deep,
+            return_full_object: false,
+        };
+        let json = serde_json::to_value(from_uplc_value_lazy(&outer, &term_ids, &config)).unwrap();
+        assert_eq!(json["runtime"]["args"][0]["runtime"]["args"][0]["runtime"]["args"][0]["value_type"], "Con");
+    }
+
     #[test]
     fn test_empty_env() {
         let env: Rc<Vec<Value>> = Rc::new(vec![]);
@@ -433,7 +527,7 @@ impl SerializableEnv {
                     LazyLoadable::Loaded(from_uplc_value_lazy(value, term_ids, &inner_config))
                 },
                 |value| match get_value_type_only_new(value) {
-                    LazyLoadableValue::TypeOnly { type_name, kind, length } => LazyLoadable::TypeOnly { type_name, kind, length },
+                    LazyLoadableValue::TypeOnly { type_name, kind, length, hash } => LazyLoadable::TypeOnly { type_name, kind, length, hash },
                     _ => unreachable!()
                 }
             )
@@ -454,7 +548,7 @@ impl SerializableEnv {
 fn get_value_type_only(value: &uplc::machine::value::Value) -> LazyLoadable<SerializableValue> {
     use uplc::machine::value::Value;
     
-    let (type_name, kind, length) = match value {
+    let (type_name, kind, length, hash) = match value {
         Value::Con(constant) => {
             let const_type = match constant.as_ref() {
                 uplc::ast::Constant::Integer(_) => "Integer",
@@ -467,21 +561,21 @@ fn get_value_type_only(value: &uplc::machine::value::Value) -> LazyLoadable<Seri
                 uplc::ast::Constant::Data(_) => "Data",
                 _ => "Unknown",
             };
-            ("Con".to_string(), const_type.to_string(), None)
+            ("Con".to_string(), const_type.to_string(), None, constant_content_hash(constant))
         }
-        Value::Delay { .. } => ("Delay".to_string(), "Delayed computation".to_string(), None),
+        Value::Delay { .. } => ("Delay".to_string(), "Delayed computation".to_string(), None, None),
         Value::Lambda { parameter_name, .. } => {
-            ("Lambda".to_string(), format!("λ{}", parameter_name.text), None)
+            ("Lambda".to_string(), format!("λ{}", parameter_name.text), None, None)
         }
         Value::Builtin { fun, .. } => {
-            ("Builtin".to_string(), format!("{:?}", fun), None)
+            ("Builtin".to_string(), format!("{:?}", fun), None, None)
         }
         Value::Constr { tag, fields, .. } => {
-            ("Constr".to_string(), format!("Constructor #{}", tag), Some(fields.len()))
+            ("Constr".to_string(), format!("Constructor #{}", tag), Some(fields.len()), None)
         }
     };
     
-    LazyLoadable::type_only(type_name, kind, length)
+    LazyLoadable::type_only_with_hash(type_name, kind, length, hash)
 }
 
 // New functions for lazy loading with nested LazyLoadable types
@@ -489,7 +583,7 @@ fn get_value_type_only(value: &uplc::machine::value::Value) -> LazyLoadable<Seri
 pub fn get_value_type_only_new(value: &uplc::machine::value::Value) -> LazyLoadableValue {
     use uplc::machine::value::Value;
     
-    let (type_name, kind, length) = match value {
+    let (type_name, kind, length, hash) = match value {
         Value::Con(constant) => {
             let const_type = match constant.as_ref() {
                 uplc::ast::Constant::Integer(_) => "Integer",
@@ -502,21 +596,21 @@ pub fn get_value_type_only_new(value: &uplc::machine::value::Value) -> LazyLoada
                 uplc::ast::Constant::Data(_) => "Data",
                 _ => "Unknown",
             };
-            ("Con".to_string(), const_type.to_string(), None)
+            ("Con".to_string(), const_type.to_string(), None, constant_content_hash(constant))
         }
-        Value::Delay { .. } => ("Delay".to_string(), "Delayed computation".to_string(), None),
+        Value::Delay { .. } => ("Delay".to_string(), "Delayed computation".to_string(), None, None),
         Value::Lambda { parameter_name, .. } => {
-            ("Lambda".to_string(), format!("λ{}", parameter_name.text), None)
+            ("Lambda".to_string(), format!("λ{}", parameter_name.text), None, None)
         }
         Value::Builtin { fun, .. } => {
-            ("Builtin".to_string(), format!("{:?}", fun), None)
+            ("Builtin".to_string(), format!("{:?}", fun), None, None)
         }
         Value::Constr { tag, fields, .. } => {
-            ("Constr".to_string(), format!("Constructor #{}", tag), Some(fields.len()))
+            ("Constr".to_string(), format!("Constructor #{}", tag), Some(fields.len()), None)
         }
     };
     
-    LazyLoadableValue::TypeOnly { type_name, kind, length }
+    LazyLoadableValue::TypeOnly { type_name, kind, length, hash }
 }
 
 pub fn from_uplc_value_lazy(
@@ -538,7 +632,7 @@ pub fn from_uplc_value_lazy(
                 LazyLoadableConstant::Loaded(from_uplc_constant_lazy(constant.as_ref(), term_ids, &const_config))
             } else {
                 match get_constant_type_only(constant.as_ref()) {
-                    LazyLoadable::TypeOnly { type_name, kind, length } => LazyLoadableConstant::TypeOnly { type_name, kind, length },
+                    LazyLoadable::TypeOnly { type_name, kind, length, hash } => LazyLoadableConstant::TypeOnly { type_name, kind, length, hash },
                     _ => unreachable!()
                 }
             };
@@ -668,7 +762,7 @@ pub fn from_uplc_value_lazy(
                         LazyLoadable::Loaded(from_uplc_value_lazy(field, term_ids, &field_config))
                     },
                     |field| match get_value_type_only_new(field) {
-                        LazyLoadableValue::TypeOnly { type_name, kind, length } => LazyLoadable::TypeOnly { type_name, kind, length },
+                        LazyLoadableValue::TypeOnly { type_name, kind, length, hash } => LazyLoadable::TypeOnly { type_name, kind, length, hash },
                         _ => unreachable!()
                     }
                 )
@@ -831,7 +925,7 @@ fn get_constant_type_only(constant: &uplc::ast::Constant) -> LazyLoadable<Serial
         uplc::ast::Constant::Bls12_381MlResult(_) => ("Bls12_381MlResult".to_string(), "ML result".to_string(), None),
     };
     
-    LazyLoadable::type_only(type_name, kind, length)
+    LazyLoadable::type_only_with_hash(type_name, kind, length, constant_content_hash(constant))
 }
 
 pub fn from_uplc_constant_lazy(
@@ -843,7 +937,10 @@ pub fn from_uplc_constant_lazy(
     
     match constant {
         Constant::Integer(i) => SerializableConstantLazy::Integer { value: i.to_string() },
-        Constant::ByteString(bs) => SerializableConstantLazy::ByteString { value: hex::encode(bs) },
+        Constant::ByteString(bs) => {
+            let full = matches!(config.path.first(), Some(PathSegment::Field(f)) if f == "value");
+            SerializableConstantLazy::from_bytestring(bs, full)
+        }
         Constant::String(s) => SerializableConstantLazy::String { value: s.clone() },
         Constant::Bool(b) => SerializableConstantLazy::Bool { value: *b },
         Constant::Unit => SerializableConstantLazy::Unit,
@@ -895,8 +992,8 @@ pub fn from_uplc_constant_lazy(
                 Box::new(LazyLoadableConstant::Loaded(from_uplc_constant_lazy(first.as_ref(), _term_ids, &first_config)))
             } else {
                 Box::new(match get_constant_type_only(first.as_ref()) {
-                    LazyLoadable::TypeOnly { type_name, kind, length } => 
-                        LazyLoadableConstant::TypeOnly { type_name, kind, length },
+                    LazyLoadable::TypeOnly { type_name, kind, length, hash } => 
+                        LazyLoadableConstant::TypeOnly { type_name, kind, length, hash },
                     _ => unreachable!()
                 })
             };
@@ -910,8 +1007,8 @@ pub fn from_uplc_constant_lazy(
                 Box::new(LazyLoadableConstant::Loaded(from_uplc_constant_lazy(second.as_ref(), _term_ids, &second_config)))
             } else {
                 Box::new(match get_constant_type_only(second.as_ref()) {
-                    LazyLoadable::TypeOnly { type_name, kind, length } => 
-                        LazyLoadableConstant::TypeOnly { type_name, kind, length },
+                    LazyLoadable::TypeOnly { type_name, kind, length, hash } => 
+                        LazyLoadableConstant::TypeOnly { type_name, kind, length, hash },
                     _ => unreachable!()
                 })
             };
@@ -927,21 +1024,24 @@ pub fn from_uplc_constant_lazy(
             let lazy_data = if should_load_field(&config.path, "data") {
                 LazyLoadable::Loaded(crate::plutus_data::SerializablePlutusData::from(data))
             } else {
-                LazyLoadable::type_only(
+                LazyLoadable::type_only_with_hash(
                     "PlutusData".to_string(),
                     "Data".to_string(),
-                    None
+                    None,
+                    constant_content_hash(constant)
                 )
             };
             
             SerializableConstantLazy::Data { data: LazyLoadableData::from(lazy_data) }
         }
-        Constant::Bls12_381G1Element(elem) => SerializableConstantLazy::Bls12_381G1Element {
-            serialized: crate::serializer::serialize_bls_g1_element_compressed(elem),
-        },
-        Constant::Bls12_381G2Element(elem) => SerializableConstantLazy::Bls12_381G2Element {
-            serialized: crate::serializer::serialize_bls_g2_element_compressed(elem),
-        },
+        Constant::Bls12_381G1Element(elem) => {
+            let element = crate::serializer::SerializableBlsElement::from_g1(elem);
+            SerializableConstantLazy::Bls12_381G1Element { serialized: element.compressed.clone(), element }
+        }
+        Constant::Bls12_381G2Element(elem) => {
+            let element = crate::serializer::SerializableBlsElement::from_g2(elem);
+            SerializableConstantLazy::Bls12_381G2Element { serialized: element.compressed.clone(), element }
+        }
         Constant::Bls12_381MlResult(elem) => SerializableConstantLazy::Bls12_381MlResult {
             bytes: hex::encode(unsafe {
                 let mut buffer = [0u8; 576];
@@ -972,9 +1072,15 @@ fn from_uplc_runtime_lazy(
             "args",
             &runtime.args,
             |arg| {
-                let arg_config = if matches!(config.path.first(), Some(PathSegment::Field(f)) if f == "args") {
+                // load_vec_lazy only loads the arg the path indexes, so the rest of the
+                // path (after "args" and the index) continues into that arg, which may
+                // be a partially applied builtin with args of its own
+                let arg_config = if matches!(
+                    config.path.as_slice(),
+                    [PathSegment::Field(f), PathSegment::Index(_), ..] if f == "args"
+                ) {
                     LazyLoadConfig {
-                        path: config.path[1..].to_vec(),
+                        path: config.path[2..].to_vec(),
                         return_full_object: config.return_full_object,
                     }
                 } else {
@@ -986,7 +1092,7 @@ fn from_uplc_runtime_lazy(
                 LazyLoadable::Loaded(from_uplc_value_lazy(arg, term_ids, &arg_config))
             },
             |arg| match get_value_type_only_new(arg) {
-                LazyLoadableValue::TypeOnly { type_name, kind, length } => LazyLoadable::TypeOnly { type_name, kind, length },
+                LazyLoadableValue::TypeOnly { type_name, kind, length, hash } => LazyLoadable::TypeOnly { type_name, kind, length, hash },
                 _ => unreachable!()
             }
         )
@@ -1032,7 +1138,7 @@ pub fn term_to_either_term_or_id_lazy(
         };
         EitherTermOrIdLazy::Term { term: match lazy_term {
             LazyLoadable::Loaded(v) => LazyLoadableTerm::Loaded(v),
-            LazyLoadable::TypeOnly { type_name, kind, length } => LazyLoadableTerm::TypeOnly { type_name, kind, length },
+            LazyLoadable::TypeOnly { type_name, kind, length, .. } => LazyLoadableTerm::TypeOnly { type_name, kind, length },
         } }
     }
 }
@@ -1062,30 +1168,30 @@ pub fn navigate_value_to_any(
         );
     }
 
-    // Check if we're navigating to env
+    // Envs, fields, bodies and runtimes are navigated here, so every element of
+    // the value can be reached whatever it is nested in
     match (value, path.first().unwrap()) {
-        (Value::Lambda { env, .. }, PathSegment::Field(field_name)) if field_name == "env" => {
-            if path.len() > 1 {
-                // Continue navigating into env values
-                navigate_to_value_from_env(env, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v).unwrap())
-            } else {
-                // Return the env itself
-                navigate_to_env_lazy(env, &[], term_ids, return_full_object)
-                    .map(|e| serde_json::to_value(e).unwrap())
-            }
+        (Value::Lambda { env, .. }, PathSegment::Field(field_name))
+        | (Value::Delay { env, .. }, PathSegment::Field(field_name)) if field_name == "env" => {
+            navigate_env_to_any(env, &path[1..], term_ids, return_full_object)
         }
-        (Value::Delay { env, .. }, PathSegment::Field(field_name)) if field_name == "env" => {
-            if path.len() > 1 {
-                // Continue navigating into env values
-                navigate_to_value_from_env(env, &path[1..], term_ids, return_full_object)
-                    .map(|v| serde_json::to_value(v).unwrap())
-            } else {
-                // Return the env itself
-                navigate_to_env_lazy(env, &[], term_ids, return_full_object)
-                    .map(|e| serde_json::to_value(e).unwrap())
+        (Value::Lambda { body, .. }, PathSegment::Field(field_name))
+        | (Value::Delay { body, .. }, PathSegment::Field(field_name)) if field_name == "body" => {
+            crate::serializer::navigate_to_term_lazy(body, &path[1..], term_ids, return_full_object)
+                .map(|t| serde_json::to_value(t).unwrap())
+        }
+        (Value::Constr { fields, .. }, PathSegment::Field(field_name)) if field_name == "fields" => {
+            match path.get(1) {
+                Some(PathSegment::Index(idx)) => match fields.get(*idx) {
+                    Some(field) => navigate_value_to_any(field, &path[2..], term_ids, return_full_object),
+                    None => NavigationResult::InvalidPath(format!("Index {} out of bounds", idx)),
+                },
+                _ => NavigationResult::InvalidPath("Expected index after 'fields'".to_string()),
             }
         }
+        (Value::Builtin { runtime, .. }, PathSegment::Field(field_name)) if field_name == "runtime" => {
+            navigate_runtime_to_any(runtime, &path[1..], term_ids, return_full_object)
+        }
         _ => {
             // Regular value navigation
             navigate_to_value(value, path, term_ids, return_full_object)
@@ -1094,6 +1200,29 @@ pub fn navigate_value_to_any(
     }
 }
 
+/// Navigate to any element within an env, given as `values` and an index or
+/// the index alone; an empty path returns the env itself
+pub fn navigate_env_to_any(
+    env: &Rc<Vec<uplc::machine::value::Value>>,
+    path: &[PathSegment],
+    term_ids: &HashSet<i32>,
+    return_full_object: bool
+) -> NavigationResult<serde_json::Value> {
+    let (idx, rest) = match path {
+        [] => {
+            return navigate_to_env_lazy(env, &[], term_ids, return_full_object)
+                .map(|e| serde_json::to_value(e).unwrap())
+        }
+        [PathSegment::Field(field_name), PathSegment::Index(idx), rest @ ..] if field_name == "values" => (idx, rest),
+        [PathSegment::Index(idx), rest @ ..] => (idx, rest),
+        _ => return NavigationResult::InvalidPath(format!("Invalid path segment for env: {:?}", path)),
+    };
+    match env.get(*idx) {
+        Some(value) => navigate_value_to_any(value, rest, term_ids, return_full_object),
+        None => NavigationResult::InvalidPath(format!("Index {} out of bounds in env", idx)),
+    }
+}
+
 pub fn navigate_to_value(
     value: &uplc::machine::value::Value,
     path: &[PathSegment],
@@ -1176,6 +1305,41 @@ pub fn navigate_to_value(
       }
 }
 
+/// Navigate within a BuiltinRuntime, where the path may also end at the runtime
+/// itself or at its args. Args are navigated with `navigate_value_to_any`, so
+/// builtins nested in args expand at any depth.
+pub fn navigate_runtime_to_any(
+    runtime: &BuiltinRuntime,
+    path: &[PathSegment],
+    term_ids: &HashSet<i32>,
+    return_full_object: bool
+) -> NavigationResult<serde_json::Value> {
+    let config = LazyLoadConfig {
+        path: vec![],
+        return_full_object,
+    };
+    match path {
+        [] => NavigationResult::Found(
+            serde_json::to_value(from_uplc_runtime_lazy(runtime, term_ids, &config)).unwrap()
+        ),
+        [PathSegment::Field(field_name)] if field_name == "args" => {
+            let args: Vec<SerializableValueLazy> = runtime
+                .args
+                .iter()
+                .map(|arg| from_uplc_value_lazy(arg, term_ids, &config))
+                .collect();
+            NavigationResult::Found(serde_json::to_value(args).unwrap())
+        }
+        [PathSegment::Field(field_name), PathSegment::Index(idx), rest @ ..] if field_name == "args" => {
+            match runtime.args.get(*idx) {
+                Some(arg) => navigate_value_to_any(arg, rest, term_ids, return_full_object),
+                None => NavigationResult::InvalidPath(format!("Index {} out of bounds in runtime args", idx)),
+            }
+        }
+        _ => NavigationResult::InvalidPath(format!("Cannot navigate path {:?} in runtime", path))
+    }
+}
+
 /// Navigate to a specific element within a BuiltinRuntime
 pub fn navigate_to_runtime(
     runtime: &uplc::machine::runtime::BuiltinRuntime,
@@ -83,6 +83,43 @@ pub fn is_wasm_target() -> bool {
 
 pub type WasmResult<T> = Result<T, JsError>;
 
+/// Version of the shapes of the JSON responses and schemas; raised whenever a
+/// serialized type changes incompatibly
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Version of the response shapes this build produces, to be compared with the
+/// version the host's types were generated for
+#[wasm_bindgen]
+pub fn get_schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+/// Wraps a JSON response as `{"schema_version": …, "payload": …}`
+pub fn envelope_json(payload: &str) -> String {
+    format!("{{\"schema_version\":{},\"payload\":{}}}", SCHEMA_VERSION, payload)
+}
+
+/// Serializes the response of a free function in the envelope; free functions
+/// have no engine settings, so they always use it
+pub fn enveloped_json<T: serde::Serialize>(value: &T) -> Result<String, serde_json::Error> {
+    serde_json::to_string(value).map(|payload| envelope_json(&payload))
+}
+
+/// Milliseconds since the Unix epoch; `std::time` panics on wasm32-unknown-unknown
+pub fn now_millis() -> u64 {
+    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    {
+        js_sys::Date::now() as u64
+    }
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
 #[macro_export]
 macro_rules! wasm_only {
     ($($code:tt)*) => {
@@ -6,19 +6,88 @@ const { compile } = require('json-schema-to-typescript');
 const OUTPUT_DIR = path.join(__dirname, '..', 'src', 'debugger-types');
 const COMBINED_SCHEMA_PATH = path.join(__dirname, '..', 'rust-src', 'schemas', 'combined_schema.json');
 
-// Root schemas that are returned from public API methods
+// Root schemas that are returned from public API methods; keep in sync with
+// `root_schemas` in rust-src/core/src/schemas.rs
 const ROOT_SCHEMAS = [
     'SerializableScriptContext',
+    'CipScriptContext',
     'SerializableMachineContext',
-    'SerializableMachineState', 
+    'SerializableMachineContextPage',
+    'SerializableFrame',
+    'SerializableFrameLazy',
+    'SerializableMachineState',
     'SerializableBudget',
+    'BudgetBreakdown',
+    'BudgetProjection',
     'SerializableTerm',
     'SerializableValue',
+    'SerializableEnv',
     'SerializableExecutionStatus',
+    'StepResult',
+    'StepBatchResult',
+    'StopReason',
+    'SessionInfo',
     'SerializableMachineStateLazy',
     'SerializableMachineContextLazy',
     'SerializableValueLazy',
-    'SerializableEnvLazy'
+    'SerializableEnvLazy',
+    'SerializableSharedContext',
+    'SerializableEnvIdentity',
+    'SerializableMintPolicy',
+    'SerializableExUnits',
+    'AikenTraceReport',
+    'Bookmark',
+    'BudgetExhaustionReport',
+    'BuiltinWarnings',
+    'DecisionTrace',
+    'EnvPinComparison',
+    'ForceDelayReport',
+    'OptimizationReport',
+    'SandboxResult',
+    'TermAnnotation',
+    'CertificateEncoding',
+    'ConstructionReport',
+    'ContextCacheStats',
+    'ContextComparison',
+    'TxInfoVersionComparison',
+    'DecodedDatums',
+    'DeterminismAudit',
+    'BlueprintValidationReport',
+    'ExportedFixture',
+    'ShrinkReport',
+    'StateSummary',
+    'StepBackResult',
+    'CheckpointInfo',
+    'ValueSearchResult',
+    'LogSearchResult',
+    'OrderingCheck',
+    'OvershootAnalysis',
+    'RunProgress',
+    'StakeObserver',
+    'QuotaUsage',
+    'RedeemerGraph',
+    'ValuePinReport',
+    'ExecutionTrace',
+    'CallStackFrame',
+    'PreludeReport',
+    'EnvWatch',
+    'TermIdInfo',
+    'TermIdRemapping',
+    'TermCostMap',
+    'TransactionRun',
+    'RepriceReport',
+    'TraceInjection',
+    'CoverageReport',
+    'CoverageMap',
+    'AggregatedCoverage',
+    'ExportHandle',
+    'RefScriptFeeBreakdown',
+    'EngineSettings',
+    'SessionOptions',
+    'CostModelUpgrade',
+    'CostModelReport',
+    'HashForms',
+    'StakeCredentialForms'
 ];
 
 /**
@@ -4,6 +4,55 @@
 
 
 
+interface AggregatedCoverage {
+  report: CoverageReport;
+  
+  runs: RunCoverage[];
+  script_hash: string;
+}
+
+interface AikenSourceLocation {
+  column: number;
+  file: string;
+  line: number;
+}
+
+interface AikenTraceEntry {
+  index: number;
+  kind: AikenTraceKind;
+  raw: string;
+  rendered: string;
+  source?: AikenSourceLocation | null;
+}
+
+interface AikenTraceGroup {
+  entries: AikenTraceEntry[];
+  label?: string | null;
+}
+
+type AikenTraceKind =
+  | {
+      expression: string;
+      trace_type: 'BooleanCheck';
+    }
+  | {
+      pattern?: string | null;
+      trace_type: 'ExpectFailure';
+    }
+  | {
+      label: string;
+      trace_type: 'TestLabel';
+    }
+  | {
+      trace_type: 'Plain';
+    };
+
+interface AikenTraceReport {
+  boolean_checks: number;
+  expect_failures: number;
+  groups: AikenTraceGroup[];
+}
+
 interface Anchor {
   data_hash: string;
   url: string;
@@ -14,13 +63,145 @@ interface Asset {
   tokens: Token[];
 }
 
+interface AuditDivergence {
+  
+  field: string;
+  first: string;
+  second: string;
+  step: number;
+}
+
+interface BlueprintValidationReport {
+  results: BlueprintValidationResult[];
+  valid: boolean;
+}
+
+interface BlueprintValidationResult {
+  datum_mismatches: SchemaMismatch[];
+  redeemer: string;
+  redeemer_mismatches: SchemaMismatch[];
+  script_hash: string;
+  
+  validator?: string | null;
+}
+
+interface Bookmark {
+  id: number;
+  name: string;
+  note?: string | null;
+  
+  step: number;
+  term_id: number;
+}
+
 interface Budget {
   exUnitsAvailable: number;
   exUnitsSpent: number;
+  
+  exUnitsTxLimitPercent?: number | null;
+  
+  lovelaceRemaining?: number | null;
+  
+  lovelaceSpent?: number | null;
   memoryUnitsAvailable: number;
   memoryUnitsSpent: number;
+  memoryUnitsTxLimitPercent?: number | null;
 }
 
+interface BudgetBreakdown {
+  builtin_cpu: number;
+  builtin_mem: number;
+  
+  builtins: BuiltinBudget[];
+  
+  machine_cpu: number;
+  machine_mem: number;
+}
+
+interface BudgetCrossing {
+  
+  exhausted: string;
+  spent_cpu: number;
+  spent_mem: number;
+  state: StateSummary;
+  
+  step: number;
+  term_id: number;
+}
+
+interface BudgetExhaustionReport {
+  
+  builtins: HotBuiltin[];
+  
+  cycle: number[];
+  cycle_repetitions: number;
+  
+  step: number;
+  term_id: number;
+  
+  terms: HotTerm[];
+  window_cpu: number;
+  window_mem: number;
+  
+  window_steps: number;
+}
+
+type BudgetMode =
+  | {
+      mode: 'Declared';
+    }
+  | {
+      mode: 'Max';
+    }
+  | {
+      cpu: number;
+      mem: number;
+      mode: 'Custom';
+    };
+
+interface BudgetProjection {
+  checks: LimitCheck[];
+  cpu_spent: number;
+  mem_spent: number;
+  
+  violated: string[];
+}
+
+interface BuiltinBudget {
+  builtin: string;
+  calls: number;
+  cpu: number;
+  mem: number;
+}
+
+interface BuiltinForceSite {
+  applied_forces: number;
+  builtin: string;
+  required_forces: number;
+  term_id: number;
+}
+
+interface BuiltinMisuse {
+  
+  arguments?: string[];
+  builtin: string;
+  
+  builtin_term_id: number;
+  kind: BuiltinMisuseKind;
+  message: string;
+  
+  step?: number | null;
+  
+  term_id?: number | null;
+}
+
+type BuiltinMisuseKind =
+  | 'MissingForce'
+  | 'ExcessForce'
+  | 'ExcessArgument'
+  | 'DivisionByZero'
+  | 'IntegerOverflow';
+
 interface BuiltinRuntime {
   args: Value[];
   arity: number;
@@ -35,6 +216,34 @@ interface BuiltinRuntimeLazy {
   fun: string;
 }
 
+interface BuiltinWarnings {
+  
+  runtime_warnings: BuiltinMisuse[];
+  
+  static_warnings: BuiltinMisuse[];
+}
+
+interface ByteStringRendering {
+  
+  base64?: boolean;
+  
+  utf8?: boolean;
+}
+
+interface CallStackFrame {
+  
+  depth: number;
+  
+  description: string;
+  
+  frame_type: string;
+  
+  parameter_name?: string | null;
+  source?: AikenSourceLocation | null;
+  
+  term_id?: number | null;
+}
+
 type CardanoValue =
   | {
       amount: number;
@@ -46,6 +255,28 @@ type CardanoValue =
       value_type: 'Multiasset';
     };
 
+interface CaseBranchPreview {
+  index: number;
+  
+  selected: boolean;
+  term_id: number;
+  
+  term_type: string;
+}
+
+interface CasePreview {
+  branches: CaseBranchPreview[];
+  
+  case_term_id?: number | null;
+  
+  note?: string | null;
+  
+  scrutinee?: string | null;
+  
+  scrutinee_path?: string[] | null;
+  selected_branch?: number | null;
+}
+
 type Certificate =
   | {
       certificate_type: 'StakeRegistration';
@@ -136,16 +367,174 @@ type Certificate =
       drep_credential: StakeCredential;
     };
 
+interface CertificateEncoding {
+  certificate: Certificate;
+  
+  encoding: string;
+  
+  index: number;
+  
+  plutus_data: 
+    | {
+        any_constructor?: number | null;
+        fields: PlutusData[];
+        tag: number;
+        type: 'Constr';
+      }
+    | {
+        key_value_pairs: KeyValuePair[];
+        type: 'Map';
+      }
+    | (
+        | {
+            Int: string;
+          }
+        | {
+            BigUInt: string;
+          }
+        | {
+            BigNInt: string;
+          }
+      )
+    | {
+        type: 'BoundedBytes';
+        value: string;
+      }
+    | {
+        type: 'Array';
+        values: PlutusData[];
+      };
+  plutus_data_cbor: string;
+  redeemer: string;
+}
+
+interface CheckpointInfo {
+  
+  created_at: number;
+  name: string;
+  spent_cpu: number;
+  spent_mem: number;
+  
+  step: number;
+  term_id: number;
+  trace_count: number;
+}
+
+type CipFee = number | CardanoValue;
+
+type CipScriptContext =
+  | {
+      purpose: CipScriptPurpose;
+      txInfo: CipTxInfo;
+      version: 'V1V2';
+    }
+  | {
+      redeemer: PlutusData;
+      scriptInfo: CipScriptInfo;
+      txInfo: CipTxInfo;
+      version: 'V3';
+    };
+
+type CipScriptInfo =
+  | {
+      currencySymbol: string;
+      type: 'MintingScript';
+    }
+  | {
+      datum?: PlutusData | null;
+      txOutRef: TransactionInput;
+      type: 'SpendingScript';
+    }
+  | {
+      credential: StakeCredential;
+      type: 'RewardingScript';
+    }
+  | {
+      index: number;
+      txCert: Certificate;
+      type: 'CertifyingScript';
+    }
+  | {
+      type: 'VotingScript';
+      voter: Voter;
+    }
+  | {
+      index: number;
+      proposalProcedure: ProposalProcedure;
+      type: 'ProposingScript';
+    };
+
+type CipScriptPurpose =
+  | {
+      currencySymbol: string;
+      type: 'Minting';
+    }
+  | {
+      txOutRef: TransactionInput;
+      type: 'Spending';
+    }
+  | {
+      credential: StakeCredential;
+      type: 'Rewarding';
+    }
+  | {
+      index: number;
+      txCert: Certificate;
+      type: 'Certifying';
+    }
+  | {
+      type: 'Voting';
+      voter: Voter;
+    }
+  | {
+      index: number;
+      proposalProcedure: ProposalProcedure;
+      type: 'Proposing';
+    };
+
+interface CipTxInfo {
+  currentTreasuryAmount?: number | null;
+  data: [string, PlutusData][];
+  fee: CipFee;
+  id: string;
+  inputs: TxInInfo[];
+  mint: MintValue;
+  outputs: TransactionOutput[];
+  proposalProcedures?: ProposalProcedure[] | null;
+  redeemers: [ScriptPurpose, Redeemer][];
+  referenceInputs?: TxInInfo[] | null;
+  signatories: string[];
+  treasuryDonation?: number | null;
+  
+  txCerts: Certificate[];
+  validRange: TimeRange;
+  votes?: any[][] | null;
+  wdrl: any[][];
+}
+
 type Constant =
   | {
       type: 'Integer';
       value: string;
     }
   | {
+      base64?: string | null;
       type: 'ByteString';
+      
+      utf8?: string | null;
       value: string;
     }
   | {
+      
+      _type: string;
+      
+      preview: string;
+      
+      total_length: number;
+      truncated: boolean;
+      type: 'Truncated';
+    }
+  | {
       type: 'String';
       value: string;
     }
@@ -173,12 +562,22 @@ type Constant =
       type: 'Data';
     }
   | {
-      serialized: string;
+      
+      compressed?: string;
+      
+      serialized?: string;
       type: 'Bls12_381G1Element';
+      
+      uncompressed?: string;
     }
   | {
-      serialized: string;
+      
+      compressed?: string;
+      
+      serialized?: string;
       type: 'Bls12_381G2Element';
+      
+      uncompressed?: string;
     }
   | {
       bytes: string;
@@ -191,9 +590,18 @@ type ConstantLazy =
       value: string;
     }
   | {
+      base64?: string | null;
       type: 'ByteString';
+      utf8?: string | null;
       value: string;
     }
+  | {
+      hash: string;
+      head: string;
+      length: number;
+      tail: string;
+      type: 'ByteStringPreview';
+    }
   | {
       type: 'String';
       value: string;
@@ -222,12 +630,22 @@ type ConstantLazy =
       type: 'Data';
     }
   | {
+      
+      compressed?: string;
+      
       serialized: string;
       type: 'Bls12_381G1Element';
+      
+      uncompressed?: string;
     }
   | {
+      
+      compressed?: string;
+      
       serialized: string;
       type: 'Bls12_381G2Element';
+      
+      uncompressed?: string;
     }
   | {
       bytes: string;
@@ -239,12 +657,123 @@ interface Constitution {
   guardrail_script?: string | null;
 }
 
+interface ConstructionReport {
+  
+  completed: boolean;
+  failed_redeemers: RedeemerFailure[];
+  stages: ConstructionStage[];
+}
+
+interface ConstructionStage {
+  duration_ms: number;
+  error?: string | null;
+  
+  stage: string;
+  status: StageStatus;
+}
+
+interface ContextCacheStats {
+  
+  cached_redeemers: number;
+  
+  hits: number;
+  
+  tx_info_clones: number;
+}
+
+interface ContextComparison {
+  differences: ContextDifference[];
+  
+  differing_fields: string[];
+  
+  differing_purpose_fields: string[];
+  redeemer_a: string;
+  redeemer_b: string;
+  
+  shared_fields: string[];
+  
+  truncated: boolean;
+  
+  tx_info_version_a?: string | null;
+  tx_info_version_b?: string | null;
+}
+
+interface ContextDifference {
+  a?: any;
+  b?: any;
+  
+  path: string;
+}
+
+interface CostModelParameter {
+  index: number;
+  name?: string | null;
+  value: number;
+}
+
+interface CostModelReport {
+  language: string;
+  parameter_count: number;
+  parameters: CostModelParameter[];
+  resolved: ResolvedCosts;
+}
+
 interface CostModels {
   plutus_v1?: number[] | null;
   plutus_v2?: number[] | null;
   plutus_v3?: number[] | null;
 }
 
+interface CostModelUpgrade {
+  
+  copied: number;
+  cost_model: number[];
+  
+  defaulted: string[];
+}
+
+interface CoverageMap {
+  executed_term_ids: number[];
+  
+  label?: string | null;
+  script_hash: string;
+}
+
+interface CoverageReport {
+  executed_terms: number;
+  percent: number;
+  
+  subtrees: SubtreeCoverage[];
+  total_terms: number;
+  
+  unexecuted_roots: number[];
+}
+
+type DatumLocation =
+  | {
+      inline: boolean;
+      input_index: number;
+      location_type: 'Input';
+      output_index: number;
+      transaction_id: string;
+    }
+  | {
+      inline: boolean;
+      input_index: number;
+      location_type: 'ReferenceInput';
+      output_index: number;
+      transaction_id: string;
+    }
+  | {
+      inline: boolean;
+      location_type: 'Output';
+      output_index: number;
+    }
+  | {
+      location_type: 'Witness';
+      witness_index: number;
+    };
+
 type DatumOption =
   | {
       datum_type: 'Hash';
@@ -255,6 +784,45 @@ type DatumOption =
       datum_type: 'Data';
     };
 
+interface DecisionPoint {
+  
+  branch_index: number;
+  
+  kind: string;
+  
+  reason: string;
+  step: number;
+  
+  term_id: number;
+}
+
+interface DecisionTrace {
+  decisions: DecisionPoint[];
+  
+  truncated: boolean;
+}
+
+interface DecodedDatum {
+  cbor_hex: string;
+  data: PlutusData;
+  hash: string;
+  locations: DatumLocation[];
+}
+
+interface DecodedDatums {
+  datums: DecodedDatum[];
+  unresolved: UnresolvedDatum[];
+}
+
+interface DeterminismAudit {
+  budget: Budget;
+  deterministic: boolean;
+  first_divergence?: AuditDivergence | null;
+  redeemer: string;
+  result: ExecutionStatus;
+  steps: number;
+}
+
 type DRep =
   | {
       drep_type: 'Key';
@@ -304,10 +872,56 @@ type EitherTermOrIdLazy =
       type: 'Id';
     };
 
+interface EngineSettings {
+  
+  arithmetic_guard?: boolean;
+  
+  bytestring_preview_threshold?: number | null;
+  bytestring_rendering?: ByteStringRendering;
+  
+  compact_json?: boolean;
+  
+  condition_breakpoints?: string[];
+  
+  constant_truncation_threshold?: number | null;
+  
+  json_envelope?: boolean;
+  quota?: QuotaLimits;
+  
+  record_execution_trace?: boolean;
+  sandbox?: SandboxLimits;
+  
+  script_hash_format?: 'hex' | 'bech32';
+  
+  step_history?: number;
+  
+  step_limit?: number | null;
+}
+
 interface Env {
   values: Value[];
 }
 
+interface EnvChange {
+  current?: any;
+  
+  index: number;
+  kind: EnvChangeKind;
+  pinned?: any;
+}
+
+type EnvChangeKind = 'Added' | 'Removed' | 'Changed';
+
+interface EnvIdentity {
+  
+  changed_since_last_step: boolean;
+  
+  current_env_id?: string | null;
+  
+  frame_env_ids: (string | null)[];
+  generation: number;
+}
+
 interface EnvLazy {
   
   displayed_count?: number | null;
@@ -318,6 +932,32 @@ interface EnvLazy {
   values: LazyLoadableValue[];
 }
 
+interface EnvPinComparison {
+  changes: EnvChange[];
+  current_size: number;
+  current_step: number;
+  label: string;
+  pinned_size: number;
+  pinned_step: number;
+  
+  unchanged: number;
+}
+
+interface EnvWatch {
+  id: number;
+  target: EnvWatchTarget;
+}
+
+type EnvWatchTarget =
+  | {
+      by: 'Slot';
+      index: number;
+    }
+  | {
+      by: 'Binder';
+      term_id: number;
+    };
+
 type ExecutionStatus =
   | {
       status_type: 'Ready';
@@ -329,8 +969,50 @@ type ExecutionStatus =
   | {
       message: string;
       status_type: 'Error';
+    }
+  | {
+      reason: PauseReason;
+      status_type: 'Paused';
+      term_id: number;
+    }
+  | {
+      budget: Budget;
+      status_type: 'BudgetExceeded';
+    }
+  | {
+      status_type: 'StepLimitReached';
+      steps: number;
+    }
+  | {
+      status_type: 'Running';
+      steps: number;
     };
 
+interface ExecutionTrace {
+  entries: TraceEntry[];
+  
+  first_step: number;
+  
+  truncated: boolean;
+}
+
+interface ExportedFixture {
+  fixture: ScriptFixture;
+  
+  rust_test: string;
+}
+
+interface ExportHandle {
+  chunk_count: number;
+  
+  chunk_size: number;
+  export_id: number;
+  
+  kind: string;
+  
+  total_bytes: number;
+}
+
 interface ExUnitPrices {
   mem_price: Rational;
   step_price: Rational;
@@ -341,6 +1023,70 @@ interface ExUnits {
   steps: number;
 }
 
+interface FixtureBudget {
+  cpu: number;
+  mem: number;
+}
+
+interface FixtureOutcome {
+  budget: FixtureBudget;
+  error?: string | null;
+  success: boolean;
+}
+
+interface ForceDelayIssue {
+  kind: ForceDelayIssueKind;
+  message: string;
+  term_id: number;
+}
+
+type ForceDelayIssueKind = 'ForceNonDelay' | 'ApplyNonFunction';
+
+interface ForceDelayReport {
+  
+  builtin_misuses: BuiltinMisuse[];
+  builtin_sites: BuiltinForceSite[];
+  delays: number;
+  forces: number;
+  
+  issues: ForceDelayIssue[];
+}
+
+interface ForwardingSpend {
+  link: ObserverLink;
+  
+  redeemer: string;
+  script_hash: string;
+}
+
+interface Frame {
+  
+  depth: number;
+  
+  frame_id: number;
+  
+  kind: string;
+  payload: MachineContext;
+  
+  summary: string;
+  
+  term_id?: number | null;
+}
+
+interface FrameLazy {
+  
+  depth: number;
+  
+  frame_id: number;
+  
+  kind: string;
+  payload: MachineContextLazy;
+  
+  summary: string;
+  
+  term_id?: number | null;
+}
+
 type GovAction =
   | {
       action_type: 'ParameterChange';
@@ -387,6 +1133,55 @@ interface GovActionId {
   transaction_id: string;
 }
 
+interface GraphEdge {
+  from: string;
+  
+  relation: string;
+  to: string;
+}
+
+interface GraphNode {
+  
+  details?: string | null;
+  
+  id: string;
+  kind: GraphNodeKind;
+  label: string;
+}
+
+type GraphNodeKind =
+  | ('Redeemer' | 'Script' | 'Policy' | 'Certificate' | 'Voter' | 'Proposal' | 'Datum')
+  | 'Input'
+  | 'Withdrawal';
+
+interface HashForms {
+  bech32: string;
+  hex: string;
+}
+
+interface HotBuiltin {
+  builtin: string;
+  calls: number;
+  cpu: number;
+  mem: number;
+}
+
+interface HotTerm {
+  cpu: number;
+  hits: number;
+  mem: number;
+  source?: AikenSourceLocation | null;
+  term_id: number;
+}
+
+interface IndexMapping {
+  body_index: number;
+  
+  context_index?: number | null;
+  
+  entry: string;
+}
+
 interface KeyValuePair {
   key: PlutusData;
   value: PlutusData;
@@ -403,6 +1198,8 @@ type LazyLoadableBuiltinRuntime =
 type LazyLoadableConstant =
   | ConstantLazy
   | {
+      
+      _hash?: string | null;
       _kind: string;
       _length?: number | null;
       _type: string;
@@ -419,6 +1216,8 @@ type LazyLoadableContext =
 type LazyLoadableData =
   | PlutusData
   | {
+      
+      _hash?: string | null;
       _kind: string;
       _length?: number | null;
       _type: string;
@@ -451,11 +1250,42 @@ type LazyLoadableTermOrId =
 type LazyLoadableValue =
   | ValueLazy
   | {
+      
+      _hash?: string | null;
       _kind: string;
       _length?: number | null;
       _type: string;
     };
 
+interface LimitCheck {
+  
+  cpu_limit?: number | null;
+  cpu_percent?: number | null;
+  exceeded: boolean;
+  
+  limit: string;
+  mem_limit?: number | null;
+  mem_percent?: number | null;
+}
+
+interface LogMatch {
+  after: string[];
+  
+  before: string[];
+  
+  index: number;
+  trace: string;
+}
+
+interface LogSearchResult {
+  matches: LogMatch[];
+  
+  next_index?: number | null;
+  pattern: string;
+  
+  total_traces: number;
+}
+
 type MachineContext =
   | {
       context_type: 'FrameAwaitArg';
@@ -524,9 +1354,22 @@ type MachineContextLazy =
       context_type: 'NoFrame';
     };
 
+interface MachineContextPage {
+  
+  frames: Frame[];
+  
+  start_index: number;
+  
+  total_depth: number;
+  
+  truncated: boolean;
+}
+
 type MachineState =
   | {
       context: MachineContext;
+      
+      context_chain?: Frame[] | null;
       machine_state_type: 'Return';
       value: Value;
     }
@@ -537,6 +1380,8 @@ type MachineState =
       term: EitherTermOrId;
     }
   | {
+      
+      budget?: Budget | null;
       machine_state_type: 'Done';
       term: EitherTermOrId;
     };
@@ -558,10 +1403,102 @@ type MachineStateLazy =
       term: LazyLoadableTermOrId;
     };
 
+interface MintPolicy {
+  policy_id: string;
+  
+  redeemer?: string | null;
+  
+  script: string;
+  tokens: Token[];
+}
+
 interface MintValue {
   mint_value: Asset[];
 }
 
+type ObserverLink = 'SameScript' | 'EmbeddedHash';
+
+interface OptimizationHint {
+  
+  estimated_cpu_savings: number;
+  estimated_mem_savings: number;
+  kind: OptimizationKind;
+  message: string;
+  
+  term_ids: number[];
+}
+
+type OptimizationKind = 'ForceDelay' | 'ConstantFold' | 'RepeatedApplication' | 'IdenticalEquality';
+
+interface OptimizationReport {
+  hints: OptimizationHint[];
+  total_cpu_savings: number;
+  total_mem_savings: number;
+}
+
+interface OrderingCheck {
+  inputs: IndexMapping[];
+  issues: string[];
+  outputs: IndexMapping[];
+  reference_inputs: IndexMapping[];
+  
+  version: string;
+}
+
+interface OvershootAnalysis {
+  declared_cpu: number;
+  declared_mem: number;
+  
+  finished: boolean;
+  first_exhausted: BudgetCrossing;
+  
+  overshoot_cpu: number;
+  
+  overshoot_cpu_percent: number;
+  overshoot_mem: number;
+  overshoot_mem_percent: number;
+  spent_cpu: number;
+  spent_mem: number;
+}
+
+type PauseReason =
+  | {
+      kind: 'Breakpoint';
+    }
+  | {
+      kind: 'Condition';
+    }
+  | {
+      kind: 'Watch';
+    }
+  | {
+      kind: 'Cancelled';
+    }
+  | {
+      kind: 'Target';
+    }
+  | {
+      kind: 'Prelude';
+    }
+  | {
+      kind: 'Builtin';
+      name: string;
+    };
+
+type PinMatch = 'Identical' | 'Equal';
+
+interface PinOccurrence {
+  
+  env_id?: string | null;
+  index?: number | null;
+  
+  location: string;
+  matched: PinMatch;
+  step: number;
+  
+  term_id: number;
+}
+
 type PlutusData =
   | {
       any_constructor?: number | null;
@@ -618,6 +1555,20 @@ interface PoolVotingThresholds {
   security_voting_threshold: Rational;
 }
 
+interface PreludeReport {
+  
+  builtin?: string | null;
+  cpu: number;
+  
+  cpu_percent: number;
+  mem: number;
+  mem_percent: number;
+  steps: number;
+  
+  stopped_at: string;
+  term_id: number;
+}
+
 interface ProposalProcedure {
   anchor: Anchor;
   deposit: number;
@@ -663,6 +1614,20 @@ interface ProtocolVersion {
   minor: number;
 }
 
+interface QuotaLimits {
+  max_serialized_bytes?: number | null;
+  max_steps?: number | null;
+}
+
+interface QuotaUsage {
+  limits: QuotaLimits;
+  serialized_bytes: number;
+  serialized_bytes_limit_exceeded: boolean;
+  steps: number;
+  steps_limit_exceeded: boolean;
+  warnings: string[];
+}
+
 interface Rational {
   denominator: number;
   numerator: number;
@@ -675,6 +1640,34 @@ interface Redeemer {
   tag: RedeemerTag;
 }
 
+interface RedeemerFailure {
+  error: string;
+  redeemer: string;
+}
+
+interface RedeemerGraph {
+  edges: GraphEdge[];
+  nodes: GraphNode[];
+}
+
+interface RedeemerRun {
+  declared_cpu: number;
+  declared_mem: number;
+  error?: string | null;
+  
+  outcome: string;
+  redeemer: string;
+  
+  script_hash?: string | null;
+  spent_cpu: number;
+  spent_mem: number;
+  
+  status?: ExecutionStatus | null;
+  steps: number;
+  traces: string[];
+  within_budget: boolean;
+}
+
 type RedeemerTag =
   | {
       tag: 'Spend';
@@ -695,6 +1688,26 @@ type RedeemerTag =
       tag: 'Propose';
     };
 
+interface RefScriptFee {
+  
+  fee_contribution?: number | null;
+  script_hash: string;
+  script_type: string;
+  size: number;
+  
+  source: string;
+  
+  utxo: string;
+}
+
+interface RefScriptFeeBreakdown {
+  
+  cost_per_byte?: number | null;
+  scripts: RefScriptFee[];
+  total_fee?: number | null;
+  total_size: number;
+}
+
 type Relay =
   | {
       ipv4?: string | null;
@@ -712,6 +1725,113 @@ type Relay =
       relay_type: 'MultiHostName';
     };
 
+interface RepriceReport {
+  
+  builtin_cpu: number;
+  builtin_mem: number;
+  cpu: number;
+  declared_cpu: number;
+  declared_mem: number;
+  
+  machine_cpu: number;
+  machine_mem: number;
+  mem: number;
+  
+  original_cpu: number;
+  original_mem: number;
+  redeemer: string;
+  session_id: string;
+  
+  steps: number;
+  within_declared: boolean;
+}
+
+interface ResolvedCosts {
+  
+  builtins: {
+    [k: string]: {
+      [k: string]: {
+        [k: string]: number;
+      };
+    };
+  };
+  
+  machine: {
+    [k: string]: StepCost;
+  };
+}
+
+interface RunCoverage {
+  executed_terms: number;
+  label?: string | null;
+  percent: number;
+}
+
+interface RunProgress {
+  
+  cpu_percent: number;
+  cpu_spent: number;
+  mem_percent: number;
+  mem_spent: number;
+  
+  run_steps: number;
+  
+  step_count: number;
+  term_id: number;
+}
+
+interface SandboxLimits {
+  cpu?: number;
+  
+  max_memory_bytes?: number;
+  
+  max_result_bytes?: number;
+  max_steps?: number;
+  mem?: number;
+}
+
+type SandboxOutcome =
+  | {
+      outcome_type: 'Done';
+      result: Term;
+    }
+  | {
+      message: string;
+      outcome_type: 'Error';
+    }
+  | {
+      message: string;
+      outcome_type: 'ParseError';
+    }
+  | {
+      outcome_type: 'BudgetExceeded';
+    }
+  | {
+      outcome_type: 'StepLimitExceeded';
+    }
+  | {
+      bytes: number;
+      outcome_type: 'MemoryLimitExceeded';
+    }
+  | {
+      bytes: number;
+      outcome_type: 'ResultTooLarge';
+    };
+
+interface SandboxResult {
+  
+  allocated_bytes: number;
+  budget: Budget;
+  outcome: SandboxOutcome;
+  steps: number;
+}
+
+interface SchemaMismatch {
+  message: string;
+  
+  path: string;
+}
+
 type ScriptContext =
   | {
       purpose: ScriptPurpose;
@@ -725,6 +1845,25 @@ type ScriptContext =
       tx_info: TxInfo;
     };
 
+interface ScriptFixture {
+  
+  arguments: string[];
+  budget_limit: FixtureBudget;
+  
+  cost_model: number[];
+  expected: FixtureOutcome;
+  name: string;
+  
+  plutus_version: string;
+  
+  program_flat: string;
+  redeemer: string;
+  script_hash: string;
+  transaction_id: string;
+}
+
+type ScriptHashFormat = 'hex' | 'bech32';
+
 type ScriptInfo =
   | {
       policy_id: string;
@@ -800,6 +1939,138 @@ type ScriptRef =
       script_type: 'PlutusV3Script';
     };
 
+interface SessionInfo {
+  
+  created_at: number;
+  
+  language: string;
+  
+  last_activity: number;
+  redeemer: string;
+  
+  script_hash: string;
+  session_id: string;
+  step_count: number;
+  version: number;
+}
+
+interface SessionOptions {
+  
+  budget_mode?: 
+    | {
+        mode: 'Declared';
+      }
+    | {
+        mode: 'Max';
+      }
+    | {
+        cpu: number;
+        mem: number;
+        mode: 'Custom';
+      };
+  
+  slippage?: number;
+}
+
+interface SharedContext {
+  envs: {
+    [k: string]: SharedEnv;
+  };
+  frames: SharedFrame[];
+}
+
+interface SharedEnv {
+  
+  references: number;
+  values: SharedValue[];
+}
+
+type SharedFrame =
+  | {
+      context_type: 'FrameAwaitArg';
+      value: SharedValue;
+    }
+  | {
+      context_type: 'FrameAwaitFunTerm';
+      env_id: string;
+      term: EitherTermOrId;
+    }
+  | {
+      context_type: 'FrameAwaitFunValue';
+      value: SharedValue;
+    }
+  | {
+      context_type: 'FrameForce';
+    }
+  | {
+      context_type: 'FrameConstr';
+      env_id: string;
+      tag: number;
+      term_id: number;
+      terms: EitherTermOrId[];
+      values: SharedValue[];
+    }
+  | {
+      context_type: 'FrameCases';
+      env_id: string;
+      terms: EitherTermOrId[];
+    }
+  | {
+      context_type: 'NoFrame';
+    };
+
+type SharedValue =
+  | {
+      constant: Constant;
+      value_type: 'Con';
+    }
+  | {
+      body: EitherTermOrId;
+      env_id: string;
+      term_id: number;
+      value_type: 'Delay';
+    }
+  | {
+      body: EitherTermOrId;
+      env_id: string;
+      parameterName: string;
+      term_id: number;
+      value_type: 'Lambda';
+    }
+  | {
+      args: SharedValue[];
+      arity: number;
+      forces: number;
+      fun: string;
+      term_id: number;
+      value_type: 'Builtin';
+    }
+  | {
+      fields: SharedValue[];
+      tag: number;
+      term_id: number;
+      value_type: 'Constr';
+    };
+
+interface ShrinkReport {
+  
+  arguments: PlutusData[];
+  
+  attempts: number;
+  
+  error: string;
+  
+  exhausted: boolean;
+  
+  original_error: string;
+  
+  original_size: number;
+  reductions: number;
+  shrunk_size: number;
+}
+
+type StageStatus = ('Ok' | 'Failed') | 'Partial';
+
 type StakeCredential =
   | {
       credential_type: 'KeyHash';
@@ -810,11 +2081,144 @@ type StakeCredential =
       hash: string;
     };
 
+interface StakeCredentialForms {
+  credential: StakeCredential;
+  reward_account: string;
+  stake_address: string;
+}
+
+interface StakeObserver {
+  
+  forwarding_spends: ForwardingSpend[];
+  
+  label: string;
+  
+  purpose: string;
+  
+  redeemer: string;
+  
+  reward_account: string;
+  reward_account_bech32?: string | null;
+  script_hash: string;
+  withdrawal_amount: number;
+  
+  zero_withdrawal: boolean;
+}
+
+interface StateSummary {
+  case_preview?: CasePreview | null;
+  
+  frame_type?: string | null;
+  
+  state_type: string;
+  
+  term_id: number;
+}
+
+interface StepBackResult {
+  
+  remaining_history: number;
+  
+  step: number;
+  
+  steps_back: number;
+  term_id: number;
+}
+
+interface StepBatchResult {
+  
+  status: 
+    | {
+        status_type: 'Ready';
+      }
+    | {
+        result: Term;
+        status_type: 'Done';
+      }
+    | {
+        message: string;
+        status_type: 'Error';
+      }
+    | {
+        reason: PauseReason;
+        status_type: 'Paused';
+        term_id: number;
+      }
+    | {
+        budget: Budget;
+        status_type: 'BudgetExceeded';
+      }
+    | {
+        status_type: 'StepLimitReached';
+        steps: number;
+      }
+    | {
+        status_type: 'Running';
+        steps: number;
+      };
+  steps: StepSummary[];
+  term_id: number;
+}
+
+interface StepCost {
+  cpu: number;
+  mem: number;
+}
+
 interface StepResult {
+  
+  cpu_delta?: number | null;
+  
+  mem_delta?: number | null;
   status: ExecutionStatus;
   term_id: number;
 }
 
+interface StepSummary {
+  
+  kind: 'Compute' | 'Return' | 'Done';
+  term_id: number;
+}
+
+interface StopReason {
+  
+  annotation?: TermAnnotation | null;
+  
+  breakpoint_id?: number | null;
+  budget: Budget;
+  description?: string | null;
+  kind: StopReasonKind;
+  
+  matched_trace?: string | null;
+  step: number;
+  term_id: number;
+}
+
+type StopReasonKind =
+  | (
+      | 'Step'
+      | 'Breakpoint'
+      | 'Watch'
+      | 'Condition'
+      | 'Cancelled'
+      | 'BuiltinBreakpoint'
+      | 'BudgetExceeded'
+      | 'StepLimit'
+      | 'RunLimit'
+      | 'Done'
+      | 'Error'
+    )
+  | 'Target';
+
+interface SubtreeCoverage {
+  executed_terms: number;
+  
+  kind: string;
+  percent: number;
+  term_id: number;
+  total_terms: number;
+}
+
 type Term =
   | {
       id: number;
@@ -870,6 +2274,54 @@ type Term =
       term_type: 'Case';
     };
 
+interface TermAnnotation {
+  
+  color?: string | null;
+  
+  data?: any;
+  label?: string | null;
+  note?: string | null;
+}
+
+interface TermCost {
+  cpu: number;
+  
+  hits: number;
+  mem: number;
+}
+
+interface TermCostMap {
+  
+  from_step: number;
+  terms: {
+    [k: string]: TermCost;
+  };
+}
+
+interface TermIdInfo {
+  
+  known_programs: string[];
+  
+  layout_hash: string;
+  max_term_id: number;
+  min_term_id: number;
+  
+  program_hash: string;
+  term_count: number;
+}
+
+interface TermIdRemapping {
+  from_program_hash: string;
+  
+  remapped: {
+    
+    [k: string]: number;
+  };
+  to_program_hash: string;
+  
+  unmapped: number[];
+}
+
 type TermLazy =
   | {
       id: number;
@@ -935,6 +2387,24 @@ interface Token {
   quantity: number;
 }
 
+interface TraceEntry {
+  cpu: number;
+  kind: TraceStateKind;
+  mem: number;
+  
+  term_id: number;
+}
+
+interface TraceInjection {
+  
+  inserted_term_ids: number[];
+  message: string;
+  
+  term_id: number;
+}
+
+type TraceStateKind = 'Compute' | 'Return' | 'Done';
+
 interface TransactionInput {
   index: number;
   transaction_id: string;
@@ -954,6 +2424,16 @@ type TransactionOutput =
       value: CardanoValue;
     };
 
+interface TransactionRun {
+  redeemers: RedeemerRun[];
+  total_declared_cpu: number;
+  total_declared_mem: number;
+  total_spent_cpu: number;
+  total_spent_mem: number;
+  
+  valid: boolean;
+}
+
 type TxInfo =
   | {
       V1: TxInfoV1;
@@ -965,6 +2445,20 @@ type TxInfo =
       V3: TxInfoV3;
     };
 
+interface TxInfoFieldComparison {
+  
+  encoding_a?: string | null;
+  encoding_b?: string | null;
+  
+  encoding_equal?: boolean | null;
+  field: string;
+  in_a: boolean;
+  in_b: boolean;
+  note?: string | null;
+  
+  view_equal: boolean;
+}
+
 interface TxInfoV1 {
   certificates: Certificate[];
   data: [string, PlutusData][];
@@ -1013,6 +2507,17 @@ interface TxInfoV3 {
   withdrawals: [string, number][];
 }
 
+interface TxInfoVersionComparison {
+  differences: ContextDifference[];
+  fields: TxInfoFieldComparison[];
+  
+  representation_changes: string[];
+  
+  truncated: boolean;
+  version_a: string;
+  version_b: string;
+}
+
 interface TxInInfo {
   out_ref: TransactionInput;
   resolved: TransactionOutput;
@@ -1056,6 +2561,11 @@ type Type =
       type: 'Bls12_381MlResult';
     };
 
+interface UnresolvedDatum {
+  hash: string;
+  locations: DatumLocation[];
+}
+
 type Value =
   | {
       constant: Constant;
@@ -1118,6 +2628,47 @@ type ValueLazy =
       value_type: 'Constr';
     };
 
+interface ValueMatch {
+  
+  kind: string;
+  
+  path: string[];
+  
+  preview: string;
+  
+  root: string;
+}
+
+interface ValuePinReport {
+  label: string;
+  occurrences: PinOccurrence[];
+  pinned_step: number;
+  
+  preview: string;
+  
+  structural_hash: string;
+  target: ValuePinTarget;
+  
+  truncated: boolean;
+}
+
+type ValuePinTarget =
+  | {
+      by: 'EnvSlot';
+      env_id: string;
+      index: number;
+    }
+  | {
+      by: 'Binder';
+      term_id: number;
+    };
+
+interface ValueSearchResult {
+  matches: ValueMatch[];
+  
+  truncated: boolean;
+}
+
 type Vote =
   | {
       vote_type: 'No';
@@ -1174,52 +2725,196 @@ type PolicyId = string;
 
 // Exports
 
+export type { AggregatedCoverage };
+
+export type { AikenSourceLocation };
+
+export type { AikenTraceEntry };
+
+export type { AikenTraceGroup };
+
+export type { AikenTraceKind };
+
+export type { AikenTraceReport };
+
 export type { Anchor };
 
 export type { Asset };
 
+export type { AuditDivergence };
+
+export type { BlueprintValidationReport };
+
+export type { BlueprintValidationResult };
+
+export type { Bookmark };
+
 export type { Budget };
 
+export type { BudgetBreakdown };
+
+export type { BudgetCrossing };
+
+export type { BudgetExhaustionReport };
+
+export type { BudgetMode };
+
+export type { BudgetProjection };
+
+export type { BuiltinBudget };
+
+export type { BuiltinForceSite };
+
+export type { BuiltinMisuse };
+
+export type { BuiltinMisuseKind };
+
 export type { BuiltinRuntime };
 
 export type { BuiltinRuntimeLazy };
 
+export type { BuiltinWarnings };
+
+export type { ByteStringRendering };
+
+export type { CallStackFrame };
+
 export type { CardanoValue };
 
+export type { CaseBranchPreview };
+
+export type { CasePreview };
+
 export type { Certificate };
 
+export type { CertificateEncoding };
+
+export type { CheckpointInfo };
+
+export type { CipFee };
+
+export type { CipScriptContext };
+
+export type { CipScriptInfo };
+
+export type { CipScriptPurpose };
+
+export type { CipTxInfo };
+
 export type { Constant };
 
 export type { ConstantLazy };
 
 export type { Constitution };
 
+export type { ConstructionReport };
+
+export type { ConstructionStage };
+
+export type { ContextCacheStats };
+
+export type { ContextComparison };
+
+export type { ContextDifference };
+
+export type { CostModelParameter };
+
+export type { CostModelReport };
+
+export type { CostModelUpgrade };
+
 export type { CostModels };
 
+export type { CoverageMap };
+
+export type { CoverageReport };
+
 export type { DRep };
 
 export type { DRepVotingThresholds };
 
+export type { DatumLocation };
+
 export type { DatumOption };
 
+export type { DecisionPoint };
+
+export type { DecisionTrace };
+
+export type { DecodedDatum };
+
+export type { DecodedDatums };
+
+export type { DeterminismAudit };
+
 export type { EitherTermOrId };
 
 export type { EitherTermOrIdLazy };
 
+export type { EngineSettings };
+
 export type { Env };
 
+export type { EnvChange };
+
+export type { EnvChangeKind };
+
+export type { EnvIdentity };
+
 export type { EnvLazy };
 
+export type { EnvPinComparison };
+
+export type { EnvWatch };
+
+export type { EnvWatchTarget };
+
 export type { ExUnitPrices };
 
 export type { ExUnits };
 
 export type { ExecutionStatus };
 
+export type { ExecutionTrace };
+
+export type { ExportHandle };
+
+export type { ExportedFixture };
+
+export type { FixtureBudget };
+
+export type { FixtureOutcome };
+
+export type { ForceDelayIssue };
+
+export type { ForceDelayIssueKind };
+
+export type { ForceDelayReport };
+
+export type { ForwardingSpend };
+
+export type { Frame };
+
+export type { FrameLazy };
+
 export type { GovAction };
 
 export type { GovActionId };
 
+export type { GraphEdge };
+
+export type { GraphNode };
+
+export type { GraphNodeKind };
+
+export type { HashForms };
+
+export type { HotBuiltin };
+
+export type { HotTerm };
+
+export type { IndexMapping };
+
 export type { KeyValuePair };
 
 export type { LazyLoadableBuiltinRuntime };
@@ -1238,16 +2933,44 @@ export type { LazyLoadableTermOrId };
 
 export type { LazyLoadableValue };
 
+export type { LimitCheck };
+
+export type { LogMatch };
+
+export type { LogSearchResult };
+
 export type { MachineContext };
 
 export type { MachineContextLazy };
 
+export type { MachineContextPage };
+
 export type { MachineState };
 
 export type { MachineStateLazy };
 
+export type { MintPolicy };
+
 export type { MintValue };
 
+export type { ObserverLink };
+
+export type { OptimizationHint };
+
+export type { OptimizationKind };
+
+export type { OptimizationReport };
+
+export type { OrderingCheck };
+
+export type { OvershootAnalysis };
+
+export type { PauseReason };
+
+export type { PinMatch };
+
+export type { PinOccurrence };
+
 export type { PlutusData };
 
 export type { PoolMetadata };
@@ -1256,60 +2979,164 @@ export type { PoolParams };
 
 export type { PoolVotingThresholds };
 
+export type { PreludeReport };
+
 export type { ProposalProcedure };
 
 export type { ProtocolParamsUpdate };
 
 export type { ProtocolVersion };
 
+export type { QuotaLimits };
+
+export type { QuotaUsage };
+
 export type { Rational };
 
 export type { Redeemer };
 
+export type { RedeemerFailure };
+
+export type { RedeemerGraph };
+
+export type { RedeemerRun };
+
 export type { RedeemerTag };
 
+export type { RefScriptFee };
+
+export type { RefScriptFeeBreakdown };
+
 export type { Relay };
 
+export type { RepriceReport };
+
+export type { ResolvedCosts };
+
+export type { RunCoverage };
+
+export type { RunProgress };
+
+export type { SandboxLimits };
+
+export type { SandboxOutcome };
+
+export type { SandboxResult };
+
+export type { SchemaMismatch };
+
 export type { ScriptContext };
 
+export type { ScriptFixture };
+
+export type { ScriptHashFormat };
+
 export type { ScriptInfo };
 
 export type { ScriptPurpose };
 
 export type { ScriptRef };
 
+export type { SessionInfo };
+
+export type { SessionOptions };
+
+export type { SharedContext };
+
+export type { SharedEnv };
+
+export type { SharedFrame };
+
+export type { SharedValue };
+
+export type { ShrinkReport };
+
+export type { StageStatus };
+
 export type { StakeCredential };
 
+export type { StakeCredentialForms };
+
+export type { StakeObserver };
+
+export type { StateSummary };
+
+export type { StepBackResult };
+
+export type { StepBatchResult };
+
+export type { StepCost };
+
 export type { StepResult };
 
+export type { StepSummary };
+
+export type { StopReason };
+
+export type { StopReasonKind };
+
+export type { SubtreeCoverage };
+
 export type { Term };
 
+export type { TermAnnotation };
+
+export type { TermCost };
+
+export type { TermCostMap };
+
+export type { TermIdInfo };
+
+export type { TermIdRemapping };
+
 export type { TermLazy };
 
 export type { TimeRange };
 
 export type { Token };
 
+export type { TraceEntry };
+
+export type { TraceInjection };
+
+export type { TraceStateKind };
+
 export type { TransactionInput };
 
 export type { TransactionOutput };
 
+export type { TransactionRun };
+
 export type { TxInInfo };
 
 export type { TxInfo };
 
+export type { TxInfoFieldComparison };
+
 export type { TxInfoV1 };
 
 export type { TxInfoV2 };
 
 export type { TxInfoV3 };
 
+export type { TxInfoVersionComparison };
+
 export type { Type };
 
+export type { UnresolvedDatum };
+
 export type { Value };
 
 export type { ValueLazy };
 
+export type { ValueMatch };
+
+export type { ValuePinReport };
+
+export type { ValuePinTarget };
+
+export type { ValueSearchResult };
+
 export type { Vote };
 
 export type { Voter };
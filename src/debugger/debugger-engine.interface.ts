@@ -1,7 +1,7 @@
 import { Budget, DebuggerContext, UtxoReference } from "../common";
 import { 
-    MachineContext, MachineState, Term, Env, ScriptContext,
-    MachineContextLazy, MachineStateLazy, EnvLazy, ValueLazy
+    Frame, MachineState, Term, Env, ScriptContext,
+    FrameLazy, MachineContextLazy, MachineStateLazy, EnvLazy, ValueLazy
 } from "../debugger-types";
 
 export interface IDebuggerEngine {
@@ -20,7 +20,7 @@ export interface IDebuggerEngine {
     getPlutusCoreVersion(sessionId: string): Promise<string>;
     getPlutusLanguageVersion(sessionId: string): Promise<string | undefined>;
     getScriptHash(sessionId: string): Promise<string>;
-    getMachineContext(sessionId: string): Promise<MachineContext[]>;
+    getMachineContext(sessionId: string): Promise<Frame[]>;
     getLogs(sessionId: string): Promise<string[]>;
     getMachineState(sessionId: string): Promise<MachineState | undefined>;
     getBudget(sessionId: string): Promise<Budget | undefined>;
@@ -31,10 +31,10 @@ export interface IDebuggerEngine {
     // Lazy loading methods
     // Note: Return type depends on the path:
     // - getMachineStateLazy: MachineStateLazy (path=""), ValueLazy (path="value.*"), EnvLazy (path="env.*")
-    // - getMachineContextLazy: MachineContextLazy[] (path=""), MachineContextLazy (path="[i]"), ValueLazy/EnvLazy (path="[i].field.*")
+    // - getMachineContextLazy: FrameLazy[] (path=""), MachineContextLazy (path="[i]"), ValueLazy/EnvLazy (path="[i].field.*")
     // - getCurrentEnvLazy: EnvLazy (path=""), ValueLazy (path="values[i].*")
     getMachineStateLazy(sessionId: string, path: string, returnFullObject: boolean): Promise<MachineStateLazy | ValueLazy | EnvLazy>;
-    getMachineContextLazy(sessionId: string, path: string, returnFullObject: boolean): Promise<MachineContextLazy[] | MachineContextLazy | ValueLazy | EnvLazy>;
+    getMachineContextLazy(sessionId: string, path: string, returnFullObject: boolean): Promise<FrameLazy[] | MachineContextLazy | ValueLazy | EnvLazy>;
     getCurrentEnvLazy(sessionId: string, path: string, returnFullObject: boolean): Promise<EnvLazy | ValueLazy>;
     
     start(sessionId: string): Promise<void>;
@@ -5,7 +5,7 @@ import { DataProvider } from '../data-providers/data-provider.interface';
 import { getOnlineProvider, getOfflineDataProvider, KoiosClient } from '../data-providers';
 import { DebuggerContext, Network, UtxoOutput, UtxoReference, ProtocolParameters } from '../common';
 import { EventEmitter } from '../events/event-emitter';
-import { ExecutionStatus, Term } from '../debugger-types';
+import { ExecutionStatus } from '../debugger-types';
 import { WasmDebuggerEngineWorker } from './wasm-engine-worker';
 import { WasmEngineHostRunner } from './wasm-engine-host-runner';
 
@@ -33,15 +33,33 @@ export class DebuggerManager {
         };
 
         engine.onExecutionComplete = (result: ExecutionStatus, termId: number) => {
-            if (result.status_type === 'Done') {
-                const term = (result as { status_type: 'Done'; result: Term }).result;
-                EventEmitter.debuggerCaughtFinished(term, termId);
-            } else if (result.status_type === 'Error') {
-                const message = (result as { status_type: 'Error'; message: string }).message;
-                EventEmitter.debuggerCaughtError(message, termId);
-            } else {
-                console.warn('[DebuggerManager] Unexpected execution status:', result);
-                EventEmitter.debuggerCaughtError(`Unexpected execution status: ${JSON.stringify(result)}`, termId);
+            switch (result.status_type) {
+                case 'Done':
+                    EventEmitter.debuggerCaughtFinished(result.result, termId);
+                    break;
+                case 'Error':
+                    EventEmitter.debuggerCaughtError(result.message, termId);
+                    break;
+                case 'BudgetExceeded':
+                    EventEmitter.debuggerCaughtError(
+                        `Budget exceeded: ${result.budget.exUnitsSpent} CPU, ${result.budget.memoryUnitsSpent} memory spent`,
+                        termId
+                    );
+                    break;
+                case 'StepLimitReached':
+                    EventEmitter.debuggerCaughtError(`Step limit reached after ${result.steps} steps`, termId);
+                    break;
+                case 'Paused':
+                    EventEmitter.debuggerCaughtBreakpoint(result.term_id);
+                    break;
+                case 'Ready':
+                case 'Running':
+                    console.warn('[DebuggerManager] Unexpected execution status:', result);
+                    EventEmitter.debuggerCaughtError(`Unexpected execution status: ${JSON.stringify(result)}`, termId);
+                    break;
+                default:
+                    const _exhaustiveCheck: never = result;
+                    throw new Error(`Unknown execution status: ${JSON.stringify(_exhaustiveCheck)}`);
             }
         };
     }
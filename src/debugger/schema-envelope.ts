@@ -0,0 +1,40 @@
+/**
+ * Version of the response shapes src/debugger-types was generated for. Keep it
+ * in step with SCHEMA_VERSION in rust-src/core/src/wasm_tools.rs.
+ */
+export const EXPECTED_SCHEMA_VERSION = 1;
+
+interface SchemaEnvelope {
+  schema_version: number;
+  payload: unknown;
+}
+
+function isSchemaEnvelope(value: unknown): value is SchemaEnvelope {
+  return typeof value === 'object'
+    && value !== null
+    && typeof (value as SchemaEnvelope).schema_version === 'number'
+    && 'payload' in value;
+}
+
+/**
+ * Returns the payload of a parsed WASM response, failing when the build
+ * produces a schema version the TS types don't describe
+ */
+export function unwrapEnvelope(value: unknown): any {
+  if (!isSchemaEnvelope(value)) {
+    throw new Error('WASM response is not in a schema version envelope');
+  }
+  if (value.schema_version !== EXPECTED_SCHEMA_VERSION) {
+    throw new Error(
+      `WASM build produces schema version ${value.schema_version}, but the extension's types were generated for version ${EXPECTED_SCHEMA_VERSION}`
+    );
+  }
+  return value.payload;
+}
+
+/**
+ * Parses a JSON response of the WASM build and returns its payload
+ */
+export function parseEnvelopedJson(json: string): any {
+  return unwrapEnvelope(JSON.parse(json));
+}
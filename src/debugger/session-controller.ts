@@ -1,7 +1,7 @@
 import { Budget } from "../common";
 import { 
     Env, EnvLazy, 
-    MachineContext, MachineContextLazy, 
+    Frame, FrameLazy, MachineContextLazy, 
     MachineState, MachineStateLazy, 
     Term, ValueLazy
 } from "../debugger-types";
@@ -39,7 +39,7 @@ export class SessionController {
         return this.debuggerEngine.getScriptHash(this.sessionId);
     }
 
-    public async getMachineContext(): Promise<MachineContext[]> {
+    public async getMachineContext(): Promise<Frame[]> {
         return this.debuggerEngine.getMachineContext(this.sessionId);
     }
 
@@ -72,7 +72,7 @@ export class SessionController {
         return this.debuggerEngine.getMachineStateLazy(this.sessionId, path, returnFullObject);
     }
 
-    public async getMachineContextLazy(path: string = "", returnFullObject: boolean = false): Promise<MachineContextLazy[] | MachineContextLazy | ValueLazy | EnvLazy> {
+    public async getMachineContextLazy(path: string = "", returnFullObject: boolean = false): Promise<FrameLazy[] | MachineContextLazy | ValueLazy | EnvLazy> {
         return this.debuggerEngine.getMachineContextLazy(this.sessionId, path, returnFullObject);
     }
 
@@ -1,7 +1,7 @@
 import { Worker, TransferListItem } from 'worker_threads';
 import { Budget, DebuggerContext, UtxoReference } from "../common";
 import {
-  MachineContext,
+  Frame,
   MachineState,
   Term,
   Env,
@@ -9,6 +9,7 @@ import {
   ScriptContext,
 } from "../debugger-types";
 import { IDebuggerEngine } from "./debugger-engine.interface";
+import { parseEnvelopedJson } from './schema-envelope';
 import * as path from 'path';
 
 interface WorkerMessage {
@@ -32,12 +33,12 @@ interface WorkerEvent {
 }
 
 /**
- * Helper function to parse JSON from ArrayBuffer
+ * Helper function to parse an enveloped JSON response from ArrayBuffer
  */
 function parseJsonFromBuffer(buffer: ArrayBuffer): any {
   const decoder = new TextDecoder();
   const jsonString = decoder.decode(buffer);
-  return JSON.parse(jsonString);
+  return parseEnvelopedJson(jsonString);
 }
 
 export class WasmEngineHostRunner implements IDebuggerEngine {
@@ -103,7 +104,7 @@ export class WasmEngineHostRunner implements IDebuggerEngine {
             pending.resolve(parsedResult);
           } catch (error) {
             console.error('[Host] Failed to parse JSON from ArrayBuffer:', error);
-            pending.reject(new Error('Failed to parse worker response'));
+            pending.reject(new Error(`Failed to parse worker response: ${error}`));
           }
         } else {
           pending.resolve(response.result);
@@ -188,12 +189,12 @@ export class WasmEngineHostRunner implements IDebuggerEngine {
     return this.callWorker('getScriptHash', sessionId);
   }
 
-  async getMachineContext(sessionId: string): Promise<MachineContext[]> {
+  async getMachineContext(sessionId: string): Promise<Frame[]> {
     if (this.isExecuting) {
       return [];
     }
     const result = await this.callWorker('getMachineContext', sessionId);
-    return result as MachineContext[];
+    return result as Frame[];
   }
 
   async getLogs(sessionId: string): Promise<string[]> {
@@ -367,7 +368,7 @@ export class WasmEngineHostRunner implements IDebuggerEngine {
             break;
           }
 
-          if (status.status_type === 'Done' || status.status_type === 'Error') {
+          if (status.status_type !== 'Ready' && status.status_type !== 'Running') {
             this.needStop = true;
 
             if (this.onExecutionComplete) {
@@ -1,7 +1,7 @@
 import { Worker } from 'worker_threads';
 import { Budget, DebuggerContext, UtxoReference } from '../common';
 import {
-  MachineContext,
+  Frame,
   MachineState,
   Term,
   Env,
@@ -202,7 +202,7 @@ export class WasmDebuggerEngineWorker implements IDebuggerEngine {
     return this.callWorker('getScriptHash', sessionId);
   }
 
-  async getMachineContext(sessionId: string): Promise<MachineContext[]> {
+  async getMachineContext(sessionId: string): Promise<Frame[]> {
     return this.callWorker('getMachineContext', sessionId);
   }
 
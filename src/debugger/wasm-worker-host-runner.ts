@@ -1,6 +1,7 @@
 import { parentPort, TransferListItem } from 'worker_threads';
 import * as wasm from 'de-uplc';
 import { DebuggerContext } from '../common';
+import { parseEnvelopedJson } from './schema-envelope';
 
 // Worker state
 let engine: wasm.DebuggerEngine | undefined;
@@ -303,7 +304,7 @@ const handlers: Record<string, (...args: any[]) => any> = {
     }
     try {
       const stepResultJson = currentSession.step();
-      const stepResult = JSON.parse(stepResultJson);
+      const stepResult = parseEnvelopedJson(stepResultJson);
       return { result: { termId: stepResult.term_id, status: stepResult.status }, transferables: [] };
     } catch (error) {
       throw new Error(`Failed to step and get status: ${error}`);
@@ -1,7 +1,7 @@
 import * as vscode from 'vscode';
 import { 
     MachineState, MachineStateLazy,
-    MachineContext, MachineContextLazy, 
+    MachineContext, FrameLazy, 
     Env, EnvLazy 
 } from '../../debugger-types';
 import { 
@@ -17,7 +17,7 @@ export class UplcTreeDataProvider implements vscode.TreeDataProvider<UplcNode> {
     readonly onDidChangeTreeData: vscode.Event<UplcNode | undefined | void> = this._onDidChangeTreeData.event;
 
     private machineStateLazy: MachineStateLazy | undefined;
-    private contextsLazy: MachineContextLazy[] = [];
+    private contextsLazy: FrameLazy[] = [];
     private currentEnvLazy: EnvLazy | undefined;
     private useLazyLoading: boolean = false;
     private sessionController: SessionController | undefined;
@@ -69,7 +69,7 @@ export class UplcTreeDataProvider implements vscode.TreeDataProvider<UplcNode> {
         this._onDidChangeTreeData.fire(undefined); // undefined = refresh all
     }
 
-    setContextsLazy(contexts: MachineContextLazy[]) {
+    setContextsLazy(contexts: FrameLazy[]) {
         // Explicitly overwrite root with new data
         this.contextsLazy = contexts;
         this.treeGeneration++; // Force VS Code to treat nodes as new
@@ -106,8 +106,8 @@ export class UplcTreeDataProvider implements vscode.TreeDataProvider<UplcNode> {
                 nodes.push(new MachineStateNodeLazy(this.machineStateLazy, this.sessionController, [], 'machineState', this.treeGeneration));
                 }
             // Create new ContextNodeLazy for each context
-                this.contextsLazy.forEach((c, i) => {
-                nodes.push(new ContextNodeLazy(c, `Context ${i}`, [String(i)], 'context', this.sessionController!, this.treeGeneration));
+                this.contextsLazy.forEach(frame => {
+                nodes.push(new ContextNodeLazy(frame.payload, `Context ${frame.depth}: ${frame.summary}`, [String(frame.depth)], 'context', this.sessionController!, this.treeGeneration));
                 });
                 if (this.currentEnvLazy) {
                 nodes.push(new EnvNodeLazy(this.currentEnvLazy, 'Environment', [], 'env', this.sessionController, this.treeGeneration));
@@ -613,7 +613,13 @@ export class TermViewerProvider {
                     type: constant.type,
                     value: constant.value
                 };
-            
+
+            case 'Truncated':
+                return {
+                    type: constant._type,
+                    value: `${constant.preview}... (${constant.total_length} ${constant._type === 'ByteString' ? 'bytes' : 'digits'})`
+                };
+
             case 'Unit':
                 return {
                     type: constant.type,